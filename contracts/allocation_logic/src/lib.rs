@@ -1,12 +1,33 @@
 // Allocation Strategies Contract
 #![no_std]
 
-use shared_utils::RateLimiter;
+use shared_utils::{call, RateLimiter};
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Map, Symbol, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Map,
+    String, Symbol, TryFromVal, Val, Vec,
 };
 
-pub const CURRENT_VERSION: u32 = 1;
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Basis-point denominator for concentration caps (10000 = 100%, i.e. uncapped).
+pub const MAX_CONCENTRATION_BPS: u32 = 10_000;
+
+/// Basis-point denominator for pool reserve ratios (10000 = fully reserved).
+pub const MAX_RESERVE_BPS: u32 = 10_000;
+
+/// Seconds in the 365-day year pool APYs are quoted over.
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+/// Notice LPs get before a pool capacity reduction applies, until the admin
+/// configures it (1 day).
+pub const DEFAULT_CAPACITY_REDUCTION_DELAY: u64 = 86_400;
+
+/// First internal id handed to `allocate_for_commitment`. Linked ids count up
+/// from here, clear of the ids callers usually pick for plain `allocate`.
+pub const LINKED_ID_BASE: u64 = 1 << 63;
+
+/// Fixed-point scale for the per-period growth factor of compounding pools.
+const YIELD_SCALE: i128 = 1_000_000_000_000;
 
 // ============================================================================
 // ERROR CODES - Error Handling
@@ -31,6 +52,15 @@ pub enum Error {
     InvalidWasmHash = 15,
     InvalidVersion = 16,
     AlreadyMigrated = 17,
+    InvalidConcentration = 18,
+    ConcentrationLimitExceeded = 19,
+    PoolClosed = 20,
+    PoolNotEmpty = 21,
+    InvalidReserve = 22,
+    InvalidCompoundingPeriod = 23,
+    NoPendingCapacityChange = 24,
+    CapacityTimelockActive = 25,
+    CommitmentNotFound = 26,
 }
 
 // ============================================================================
@@ -61,11 +91,67 @@ pub struct Pool {
     pub apy: u32,
     pub total_liquidity: i128,
     pub max_capacity: i128,
+    pub reserve_bps: u32,
+    pub compounding: bool,
+    pub compounding_period: u64,
     pub active: bool,
+    pub closed: bool,
     pub created_at: u64,
     pub updated_at: u64,
 }
 
+/// `Pool` as stored by version 1, before reserves, compounding and closing.
+/// Only read by `migrate`.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PoolV1 {
+    pub pool_id: u32,
+    pub risk_level: RiskLevel,
+    pub apy: u32,
+    pub total_liquidity: i128,
+    pub max_capacity: i128,
+    pub active: bool,
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl From<PoolV1> for Pool {
+    fn from(pool: PoolV1) -> Self {
+        Pool {
+            pool_id: pool.pool_id,
+            risk_level: pool.risk_level,
+            apy: pool.apy,
+            total_liquidity: pool.total_liquidity,
+            max_capacity: pool.max_capacity,
+            reserve_bps: 0,
+            compounding: false,
+            compounding_period: 0,
+            active: pool.active,
+            closed: false,
+            created_at: pool.created_at,
+            updated_at: pool.updated_at,
+        }
+    }
+}
+
+/// A queued pool capacity reduction, applied by `execute_capacity_reduction`
+/// once `executable_at` is reached.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingCapacity {
+    pub new_capacity: i128,
+    pub executable_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PoolRegistration {
+    pub pool_id: u32,
+    pub risk_level: RiskLevel,
+    pub apy: u32,
+    pub max_capacity: i128,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct Allocation {
@@ -84,6 +170,25 @@ pub struct AllocationSummary {
     pub allocations: Vec<Allocation>,
 }
 
+/// An allocation with its pool's current APY and the yield accrued on it to date.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AllocationYield {
+    pub allocation: Allocation,
+    pub apy: u32,
+    pub accrued_yield: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct AllocationDetail {
+    pub commitment_id: u64,
+    pub strategy: Strategy,
+    pub total_allocated: i128,
+    pub total_accrued_yield: i128,
+    pub positions: Vec<AllocationYield>,
+}
+
 // ============================================================================
 // STORAGE KEYS
 // ============================================================================
@@ -102,6 +207,16 @@ pub enum DataKey {
     TotalAllocated(u64),   // Total amount allocated per commitment
     AllocationOwner(u64),  // Track allocation ownership
     Version,               // Contract version
+    StrategyExposure(Strategy), // Running total allocated under a strategy
+    MaxConcentration(Strategy), // Max share of one allocation in a single pool (bps)
+    CoreCommitment(String), // commitment_core String id -> internal u64 commitment id
+    PoolEventSequence,      // u64 sequence of the last PoolLiquidity event
+    StrategyForType(String), // commitment_type -> admin-set Strategy override
+    PoolCommitments(u32),   // Vec<u64> of commitments holding a position in a pool
+    CapacityBufferBps,      // u32 headroom over liquidity a reduced capacity must keep
+    CapacityReductionDelay, // u64 seconds a capacity reduction waits before it applies
+    PendingCapacity(u32),   // PendingCapacity queued for a pool
+    NextLinkedId,           // u64 next candidate id for allocate_for_commitment
 }
 
 // ============================================================================
@@ -159,52 +274,63 @@ impl AllocationStrategiesContract {
         Self::require_admin(&env, &admin)?;
         Self::require_no_reentrancy(&env)?;
 
-        // Input validation
-        if max_capacity <= 0 {
-            return Err(Error::InvalidCapacity);
-        }
+        Self::validate_pool_registration(&env, pool_id, apy, max_capacity)?;
+        Self::store_new_pool(&env, pool_id, risk_level, apy, max_capacity);
 
-        if apy > 100_000 {
-            // Max 1000% APY (10000 basis points = 100%)
-            return Err(Error::InvalidAPY);
-        }
+        // Add to registry
+        let mut registry: Vec<u32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::PoolRegistry)
+            .unwrap_or(Vec::new(&env));
+        registry.push_back(pool_id);
+        env.storage()
+            .instance()
+            .set(&DataKey::PoolRegistry, &registry);
 
-        // Check if pool already exists
-        if env.storage().persistent().has(&DataKey::Pool(pool_id)) {
-            return Err(Error::InvalidPoolId);
-        }
+        Ok(())
+    }
 
-        let pool = Pool {
-            pool_id,
-            risk_level,
-            apy,
-            total_liquidity: 0,
-            max_capacity,
-            active: true,
-            created_at: env.ledger().timestamp(),
-            updated_at: env.ledger().timestamp(),
-        };
+    /// Register several pools at once. Every entry is validated before any
+    /// pool is stored, so a single bad entry rejects the whole batch.
+    pub fn batch_register_pools(
+        env: Env,
+        admin: Address,
+        pools: Vec<PoolRegistration>,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+        Self::require_no_reentrancy(&env)?;
 
-        // Store pool
-        env.storage()
-            .persistent()
-            .set(&DataKey::Pool(pool_id), &pool);
+        let mut batch_ids: Vec<u32> = Vec::new(&env);
+        for entry in pools.iter() {
+            Self::validate_pool_registration(&env, entry.pool_id, entry.apy, entry.max_capacity)?;
+            if batch_ids.contains(entry.pool_id) {
+                return Err(Error::InvalidPoolId);
+            }
+            batch_ids.push_back(entry.pool_id);
+        }
 
-        // Add to registry
         let mut registry: Vec<u32> = env
             .storage()
             .instance()
             .get(&DataKey::PoolRegistry)
             .unwrap_or(Vec::new(&env));
-        registry.push_back(pool_id);
+        for entry in pools.iter() {
+            Self::store_new_pool(
+                &env,
+                entry.pool_id,
+                entry.risk_level,
+                entry.apy,
+                entry.max_capacity,
+            );
+            registry.push_back(entry.pool_id);
+        }
         env.storage()
             .instance()
             .set(&DataKey::PoolRegistry, &registry);
 
-        // Emit event
-        env.events()
-            .publish((symbol_short!("pool_reg"), pool_id), risk_level);
-
         Ok(())
     }
 
@@ -220,6 +346,9 @@ impl AllocationStrategiesContract {
         Self::require_no_reentrancy(&env)?;
 
         let mut pool = Self::get_pool_internal(&env, pool_id)?;
+        if pool.closed && active {
+            return Err(Error::PoolClosed);
+        }
         pool.active = active;
         pool.updated_at = env.ledger().timestamp();
 
@@ -233,6 +362,10 @@ impl AllocationStrategiesContract {
         Ok(())
     }
 
+    /// Change a pool's `max_capacity`. Increases apply immediately and drop
+    /// any queued reduction. A reduction must leave at least the configured
+    /// buffer above current liquidity and, unless the reduction delay is zero,
+    /// is queued for `execute_capacity_reduction` to give LPs notice.
     pub fn update_pool_capacity(
         env: Env,
         admin: Address,
@@ -247,19 +380,408 @@ impl AllocationStrategiesContract {
             return Err(Error::InvalidCapacity);
         }
 
+        let pool = Self::get_pool_internal(&env, pool_id)?;
+
+        if new_capacity >= pool.max_capacity {
+            env.storage()
+                .persistent()
+                .remove(&DataKey::PendingCapacity(pool_id));
+            return Self::apply_pool_capacity(&env, pool, new_capacity);
+        }
+
+        // Ensure new capacity keeps current liquidity plus the buffer
+        Self::check_capacity_buffer(&env, &pool, new_capacity)?;
+
+        let (_, delay) = Self::get_capacity_policy(env.clone());
+        if delay == 0 {
+            return Self::apply_pool_capacity(&env, pool, new_capacity);
+        }
+
+        let pending = PendingCapacity {
+            new_capacity,
+            executable_at: env.ledger().timestamp().saturating_add(delay),
+        };
+        env.storage()
+            .persistent()
+            .set(&DataKey::PendingCapacity(pool_id), &pending);
+
+        env.events().publish(
+            (symbol_short!("cap_queue"), pool_id),
+            (pool.max_capacity, new_capacity, pending.executable_at),
+        );
+
+        Ok(())
+    }
+
+    /// Apply a queued capacity reduction once its timelock has passed. The
+    /// buffer is checked again against the pool's liquidity at this point.
+    pub fn execute_capacity_reduction(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        let pending = Self::get_pending_capacity(env.clone(), pool_id)
+            .ok_or(Error::NoPendingCapacityChange)?;
+        if env.ledger().timestamp() < pending.executable_at {
+            return Err(Error::CapacityTimelockActive);
+        }
+
+        let pool = Self::get_pool_internal(&env, pool_id)?;
+        Self::check_capacity_buffer(&env, &pool, pending.new_capacity)?;
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingCapacity(pool_id));
+        Self::apply_pool_capacity(&env, pool, pending.new_capacity)
+    }
+
+    /// Drop a queued capacity reduction.
+    pub fn cancel_capacity_reduction(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        if Self::get_pending_capacity(env.clone(), pool_id).is_none() {
+            return Err(Error::NoPendingCapacityChange);
+        }
+        env.storage()
+            .persistent()
+            .remove(&DataKey::PendingCapacity(pool_id));
+
+        env.events()
+            .publish((symbol_short!("cap_cancl"), pool_id), ());
+
+        Ok(())
+    }
+
+    /// Require reduced capacities to stay `buffer_bps` above liquidity and
+    /// hold reductions back for `reduction_delay` seconds.
+    pub fn set_capacity_policy(
+        env: Env,
+        admin: Address,
+        buffer_bps: u32,
+        reduction_delay: u64,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        if buffer_bps > MAX_RESERVE_BPS {
+            return Err(Error::InvalidCapacity);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::CapacityBufferBps, &buffer_bps);
+        env.storage()
+            .instance()
+            .set(&DataKey::CapacityReductionDelay, &reduction_delay);
+
+        env.events()
+            .publish((symbol_short!("cap_pol"),), (buffer_bps, reduction_delay));
+
+        Ok(())
+    }
+
+    /// Hold back `reserve_bps` of a pool's `max_capacity` as a withdrawal
+    /// buffer that new allocations cannot use.
+    pub fn set_pool_reserve(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        reserve_bps: u32,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        if reserve_bps > MAX_RESERVE_BPS {
+            return Err(Error::InvalidReserve);
+        }
+
+        let mut pool = Self::get_pool_internal(&env, pool_id)?;
+        pool.reserve_bps = reserve_bps;
+        pool.updated_at = env.ledger().timestamp();
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Pool(pool_id), &pool);
+
+        env.events()
+            .publish((symbol_short!("pool_rsv"), pool_id), reserve_bps);
+
+        Ok(())
+    }
+
+    /// Accrue a pool's yield with compound interest every `period_secs`
+    /// (its APY is then the nominal rate) or, when `compounding` is false,
+    /// as simple interest. The period must be between 1 second and a year.
+    pub fn set_pool_compounding(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        compounding: bool,
+        period_secs: u64,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        if compounding && (period_secs == 0 || period_secs > SECONDS_PER_YEAR) {
+            return Err(Error::InvalidCompoundingPeriod);
+        }
+
         let mut pool = Self::get_pool_internal(&env, pool_id)?;
+        pool.compounding = compounding;
+        pool.compounding_period = if compounding { period_secs } else { 0 };
+        pool.updated_at = env.ledger().timestamp();
 
-        // Ensure new capacity is not less than current liquidity
-        if new_capacity < pool.total_liquidity {
+        env.storage()
+            .persistent()
+            .set(&DataKey::Pool(pool_id), &pool);
+
+        env.events().publish(
+            (symbol_short!("pool_cmp"), pool_id),
+            (compounding, pool.compounding_period),
+        );
+
+        Ok(())
+    }
+
+    /// Permanently retire a pool. A closed pool is inactive and cannot be
+    /// reactivated.
+    ///
+    /// A pool still holding liquidity can only be closed with a `migrate_to`
+    /// target: each commitment's position is moved there and its allocation
+    /// record updated. Positions that do not fit in the target stay in the
+    /// closed pool and are flagged for manual deallocation (`dealloc` event,
+    /// listed by `get_pool_commitments`). Returns the amount migrated.
+    pub fn close_pool(
+        env: Env,
+        admin: Address,
+        pool_id: u32,
+        migrate_to: Option<u32>,
+    ) -> Result<i128, Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+        Self::require_no_reentrancy(&env)?;
+
+        let mut pool = Self::get_pool_internal(&env, pool_id)?;
+        if pool.closed {
+            return Err(Error::PoolClosed);
+        }
+
+        let mut target = match migrate_to {
+            Some(target_id) => {
+                if target_id == pool_id {
+                    return Err(Error::InvalidPoolId);
+                }
+                let target = Self::get_pool_internal(&env, target_id)?;
+                if !target.active {
+                    return Err(Error::PoolInactive);
+                }
+                Some(target)
+            }
+            None => {
+                if pool.total_liquidity > 0 {
+                    return Err(Error::PoolNotEmpty);
+                }
+                None
+            }
+        };
+
+        Self::set_reentrancy_guard(&env, true);
+
+        let now = env.ledger().timestamp();
+        let mut migrated = 0i128;
+
+        if let Some(target) = target.as_mut() {
+            let members = Self::get_pool_commitments(env.clone(), pool_id);
+            let mut stranded = Vec::new(&env);
+
+            for commitment_id in members.iter() {
+                let allocations: Vec<Allocation> = env
+                    .storage()
+                    .persistent()
+                    .get(&DataKey::Allocations(commitment_id))
+                    .unwrap_or(Vec::new(&env));
+
+                let mut amount = 0i128;
+                for allocation in allocations.iter() {
+                    if allocation.pool_id == pool_id {
+                        amount = amount
+                            .checked_add(allocation.amount)
+                            .ok_or(Error::ArithmeticOverflow)?;
+                    }
+                }
+                if amount <= 0 {
+                    continue;
+                }
+
+                let new_target_liquidity = target
+                    .total_liquidity
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                if new_target_liquidity > Self::allocation_ceiling(target)? {
+                    stranded.push_back(commitment_id);
+                    env.events()
+                        .publish((symbol_short!("dealloc"), commitment_id), pool_id);
+                    continue;
+                }
+
+                // Fold the position into the commitment's target allocation
+                let mut updated = Vec::new(&env);
+                let mut merged = false;
+                for mut allocation in allocations.iter() {
+                    if allocation.pool_id == pool_id {
+                        continue;
+                    }
+                    if allocation.pool_id == target.pool_id {
+                        allocation.amount = allocation
+                            .amount
+                            .checked_add(amount)
+                            .ok_or(Error::ArithmeticOverflow)?;
+                        allocation.timestamp = now;
+                        merged = true;
+                    }
+                    updated.push_back(allocation);
+                }
+                if !merged {
+                    updated.push_back(Allocation {
+                        commitment_id,
+                        pool_id: target.pool_id,
+                        amount,
+                        timestamp: now,
+                    });
+                }
+                env.storage()
+                    .persistent()
+                    .set(&DataKey::Allocations(commitment_id), &updated);
+                Self::add_pool_commitment(&env, target.pool_id, commitment_id);
+
+                pool.total_liquidity = pool
+                    .total_liquidity
+                    .checked_sub(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+                target.total_liquidity = new_target_liquidity;
+                Self::emit_pool_liquidity(&env, pool_id, -amount, pool.total_liquidity);
+                Self::emit_pool_liquidity(&env, target.pool_id, amount, target.total_liquidity);
+
+                migrated = migrated
+                    .checked_add(amount)
+                    .ok_or(Error::ArithmeticOverflow)?;
+            }
+
+            env.storage()
+                .persistent()
+                .set(&DataKey::PoolCommitments(pool_id), &stranded);
+
+            target.updated_at = now;
+            env.storage()
+                .persistent()
+                .set(&DataKey::Pool(target.pool_id), &*target);
+        }
+
+        pool.active = false;
+        pool.closed = true;
+        pool.updated_at = now;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Pool(pool_id), &pool);
+
+        Self::set_reentrancy_guard(&env, false);
+
+        env.events().publish(
+            (symbol_short!("pool_cls"), pool_id),
+            (migrate_to, migrated),
+        );
+
+        Ok(migrated)
+    }
+
+    fn check_capacity_buffer(env: &Env, pool: &Pool, new_capacity: i128) -> Result<(), Error> {
+        let (buffer_bps, _) = Self::get_capacity_policy(env.clone());
+        let buffer = pool
+            .total_liquidity
+            .checked_mul(buffer_bps as i128)
+            .ok_or(Error::ArithmeticOverflow)?
+            / 10_000;
+        if new_capacity < pool.total_liquidity.saturating_add(buffer) {
             return Err(Error::PoolCapacityExceeded);
         }
+        Ok(())
+    }
 
+    fn apply_pool_capacity(env: &Env, mut pool: Pool, new_capacity: i128) -> Result<(), Error> {
+        let old_capacity = pool.max_capacity;
         pool.max_capacity = new_capacity;
         pool.updated_at = env.ledger().timestamp();
 
         env.storage()
             .persistent()
-            .set(&DataKey::Pool(pool_id), &pool);
+            .set(&DataKey::Pool(pool.pool_id), &pool);
+
+        env.events().publish(
+            (symbol_short!("pool_cap"), pool.pool_id),
+            (old_capacity, new_capacity),
+        );
+
+        Ok(())
+    }
+
+    /// Cap the share of any single allocation that may land in one pool for
+    /// `strategy`. `max_bps` of 10000 disables the cap.
+    pub fn set_max_concentration(
+        env: Env,
+        admin: Address,
+        strategy: Strategy,
+        max_bps: u32,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        if max_bps == 0 || max_bps > MAX_CONCENTRATION_BPS {
+            return Err(Error::InvalidConcentration);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxConcentration(strategy), &max_bps);
+
+        env.events()
+            .publish((symbol_short!("conc_cap"), strategy), max_bps);
+
+        Ok(())
+    }
+
+    /// Map a commitment type to the strategy recommended for it, overriding
+    /// the built-in safe/balanced/aggressive mapping.
+    pub fn set_strategy_for_type(
+        env: Env,
+        admin: Address,
+        commitment_type: String,
+        strategy: Strategy,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        Self::require_initialized(&env)?;
+        Self::require_admin(&env, &admin)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::StrategyForType(commitment_type.clone()), &strategy);
+
+        env.events()
+            .publish((symbol_short!("strat_map"), commitment_type), strategy);
 
         Ok(())
     }
@@ -279,7 +801,7 @@ impl AllocationStrategiesContract {
     ///
     /// **Postconditions:**
     /// - `get_allocation(commitment_id).total_allocated == amount`
-    /// - For all pools P: `P.total_liquidity <= P.max_capacity`
+    /// - For all pools P: `P.total_liquidity <= P.max_capacity * (10000 - P.reserve_bps) / 10000`
     /// - `reentrancy_guard == false`
     ///
     /// **Invariants Maintained:**
@@ -297,6 +819,53 @@ impl AllocationStrategiesContract {
         strategy: Strategy,
     ) -> Result<AllocationSummary, Error> {
         caller.require_auth();
+        Self::allocate_internal(env, caller, commitment_id, amount, strategy)
+    }
+
+    /// Allocate funds for a commitment_core commitment, keyed by its String id.
+    ///
+    /// `caller` must own the commitment in commitment_core. The internal u64
+    /// id comes from a counter starting at `LINKED_ID_BASE`, skipping any id
+    /// already holding an allocation, and the link is stored so both
+    /// contracts' records can be correlated.
+    pub fn allocate_for_commitment(
+        env: Env,
+        caller: Address,
+        core_commitment_id: String,
+        amount: i128,
+        strategy: Strategy,
+    ) -> Result<AllocationSummary, Error> {
+        caller.require_auth();
+        Self::require_initialized(&env)?;
+
+        let link_key = DataKey::CoreCommitment(core_commitment_id.clone());
+        if env.storage().persistent().has(&link_key) {
+            return Err(Error::AlreadyInitialized);
+        }
+        if Self::core_commitment_owner(&env, &core_commitment_id)? != caller {
+            return Err(Error::Unauthorized);
+        }
+
+        let commitment_id = Self::next_linked_id(&env);
+        let summary =
+            Self::allocate_internal(env.clone(), caller, commitment_id, amount, strategy)?;
+
+        env.storage().persistent().set(&link_key, &commitment_id);
+        env.events().publish(
+            (symbol_short!("alloc_lnk"), commitment_id),
+            core_commitment_id,
+        );
+
+        Ok(summary)
+    }
+
+    fn allocate_internal(
+        env: Env,
+        caller: Address,
+        commitment_id: u64,
+        amount: i128,
+        strategy: Strategy,
+    ) -> Result<AllocationSummary, Error> {
         Self::require_initialized(&env)?;
         Self::require_no_reentrancy(&env)?;
 
@@ -368,7 +937,7 @@ impl AllocationStrategiesContract {
                 .checked_add(alloc_amount)
                 .ok_or(Error::ArithmeticOverflow)?;
 
-            if new_liquidity > pool.max_capacity {
+            if new_liquidity > Self::allocation_ceiling(&pool)? {
                 Self::set_reentrancy_guard(&env, false);
                 return Err(Error::PoolCapacityExceeded);
             }
@@ -378,6 +947,7 @@ impl AllocationStrategiesContract {
             env.storage()
                 .persistent()
                 .set(&DataKey::Pool(pool_id), &pool);
+            Self::emit_pool_liquidity(&env, pool_id, alloc_amount, new_liquidity);
 
             // Record allocation
             let allocation = Allocation {
@@ -388,6 +958,7 @@ impl AllocationStrategiesContract {
             };
 
             allocations.push_back(allocation);
+            Self::add_pool_commitment(&env, pool_id, commitment_id);
 
             // Safe addition
             total_allocated = total_allocated
@@ -408,6 +979,7 @@ impl AllocationStrategiesContract {
         env.storage()
             .persistent()
             .set(&DataKey::TotalAllocated(commitment_id), &total_allocated);
+        Self::adjust_strategy_exposure(&env, strategy, total_allocated)?;
 
         // Clear reentrancy guard
         Self::set_reentrancy_guard(&env, false);
@@ -466,14 +1038,11 @@ impl AllocationStrategiesContract {
             .get(&DataKey::Strategy(commitment_id))
             .ok_or(Error::AllocationNotFound)?;
 
-        let mut total_amount = 0i128;
+        let (total_amount, new_allocations) =
+            Self::plan_rebalance(&env, commitment_id, &current_allocations, strategy)?;
 
         // Remove old allocations from pools with overflow protection
         for allocation in current_allocations.iter() {
-            total_amount = total_amount
-                .checked_add(allocation.amount)
-                .ok_or(Error::ArithmeticOverflow)?;
-
             let mut pool = Self::get_pool_internal(&env, allocation.pool_id)?;
             pool.total_liquidity = pool
                 .total_liquidity
@@ -483,21 +1052,118 @@ impl AllocationStrategiesContract {
             env.storage()
                 .persistent()
                 .set(&DataKey::Pool(allocation.pool_id), &pool);
+            Self::emit_pool_liquidity(&env, allocation.pool_id, -allocation.amount, pool.total_liquidity);
+            Self::remove_pool_commitment(&env, allocation.pool_id, commitment_id);
         }
 
         // Reallocate with current strategy
-        let pools = Self::select_pools(&env, strategy)?;
-        let allocation_plan = Self::calculate_allocation(&env, total_amount, &pools, strategy)?;
-
-        let mut new_allocations = Vec::new(&env);
         let mut new_total = 0i128;
+        for allocation in new_allocations.iter() {
+            let mut pool = Self::get_pool_internal(&env, allocation.pool_id)?;
+            let new_liquidity = pool
+                .total_liquidity
+                .checked_add(allocation.amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            pool.total_liquidity = new_liquidity;
+            pool.updated_at = env.ledger().timestamp();
+            env.storage()
+                .persistent()
+                .set(&DataKey::Pool(allocation.pool_id), &pool);
+            Self::emit_pool_liquidity(&env, allocation.pool_id, allocation.amount, new_liquidity);
 
+            Self::add_pool_commitment(&env, allocation.pool_id, commitment_id);
+            new_total = new_total
+                .checked_add(allocation.amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Allocations(commitment_id), &new_allocations);
+        env.storage()
+            .persistent()
+            .set(&DataKey::TotalAllocated(commitment_id), &new_total);
+        let exposure_delta = new_total
+            .checked_sub(total_amount)
+            .ok_or(Error::ArithmeticOverflow)?;
+        Self::adjust_strategy_exposure(&env, strategy, exposure_delta)?;
+
+        Self::set_reentrancy_guard(&env, false);
+
+        env.events()
+            .publish((symbol_short!("rebalance"), commitment_id), new_total);
+
+        Ok(AllocationSummary {
+            commitment_id,
+            strategy,
+            total_allocated: new_total,
+            allocations: new_allocations,
+        })
+    }
+
+    /// Dry run of `rebalance`: the commitment's current allocations and the
+    /// ones `rebalance` would make now, under the stored strategy and current
+    /// pool states. Nothing is written.
+    pub fn preview_rebalance(
+        env: Env,
+        commitment_id: u64,
+    ) -> Result<(Vec<Allocation>, Vec<Allocation>), Error> {
+        let current_allocations: Vec<Allocation> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Allocations(commitment_id))
+            .ok_or(Error::AllocationNotFound)?;
+        let strategy: Strategy = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Strategy(commitment_id))
+            .ok_or(Error::AllocationNotFound)?;
+
+        let (_, proposed) =
+            Self::plan_rebalance(&env, commitment_id, &current_allocations, strategy)?;
+        Ok((current_allocations, proposed))
+    }
+
+    /// Work out where a rebalance moves a commitment: its positions are
+    /// released from their pools, then the total is spread under `strategy`
+    /// over the pools as they would then stand, skipping inactive and full
+    /// pools. Returns the total released and the new allocations. Reads only.
+    fn plan_rebalance(
+        env: &Env,
+        commitment_id: u64,
+        current_allocations: &Vec<Allocation>,
+        strategy: Strategy,
+    ) -> Result<(i128, Vec<Allocation>), Error> {
+        let mut total_amount = 0i128;
+        let mut released: Map<u32, i128> = Map::new(env);
+        for allocation in current_allocations.iter() {
+            total_amount = total_amount
+                .checked_add(allocation.amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            let pool_released = released.get(allocation.pool_id).unwrap_or(0);
+            released.set(allocation.pool_id, pool_released + allocation.amount);
+        }
+        let release = |mut pool: Pool| -> Result<Pool, Error> {
+            pool.total_liquidity = pool
+                .total_liquidity
+                .checked_sub(released.get(pool.pool_id).unwrap_or(0))
+                .ok_or(Error::ArithmeticOverflow)?;
+            Ok(pool)
+        };
+
+        let mut pools = Vec::new(env);
+        for pool in Self::select_pools(env, strategy)?.iter() {
+            pools.push_back(release(pool)?);
+        }
+        let allocation_plan = Self::calculate_allocation(env, total_amount, &pools, strategy)?;
+
+        let mut new_allocations = Vec::new(env);
         for (pool_id, alloc_amount) in allocation_plan.iter() {
             if alloc_amount <= 0 {
                 continue;
             }
 
-            let mut pool = Self::get_pool_internal(&env, pool_id)?;
+            let pool = release(Self::get_pool_internal(env, pool_id)?)?;
 
             if !pool.active {
                 continue; // Skip inactive pools during rebalancing
@@ -506,47 +1172,52 @@ impl AllocationStrategiesContract {
             let new_liquidity = pool
                 .total_liquidity
                 .checked_add(alloc_amount)
-                .ok_or(Error::ArithmeticOverflow)?;
-
-            if new_liquidity <= pool.max_capacity {
-                pool.total_liquidity = new_liquidity;
-                pool.updated_at = env.ledger().timestamp();
-                env.storage()
-                    .persistent()
-                    .set(&DataKey::Pool(pool_id), &pool);
+                .ok_or(Error::ArithmeticOverflow)?;
 
-                let allocation = Allocation {
+            if new_liquidity <= Self::allocation_ceiling(&pool)? {
+                new_allocations.push_back(Allocation {
                     commitment_id,
                     pool_id,
                     amount: alloc_amount,
                     timestamp: env.ledger().timestamp(),
-                };
-
-                new_allocations.push_back(allocation);
-                new_total = new_total
-                    .checked_add(alloc_amount)
-                    .ok_or(Error::ArithmeticOverflow)?;
+                });
             }
         }
 
-        env.storage()
-            .persistent()
-            .set(&DataKey::Allocations(commitment_id), &new_allocations);
-        env.storage()
-            .persistent()
-            .set(&DataKey::TotalAllocated(commitment_id), &new_total);
+        Ok((total_amount, new_allocations))
+    }
+
+    /// Hook invoked by commitment_core when a commitment settles or exits
+    /// early. Releases every pool position held for it.
+    pub fn on_commitment_closed(env: Env, core_commitment_id: String) -> Result<(), Error> {
+        Self::require_initialized(&env)?;
+        let commitment_core: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::CommitmentCore)
+            .ok_or(Error::NotInitialized)?;
+        commitment_core.require_auth();
+
+        // Nothing was allocated through allocate_for_commitment
+        let commitment_id =
+            match Self::get_linked_commitment_id(env.clone(), core_commitment_id.clone()) {
+                Some(id) => id,
+                None => return Ok(()),
+            };
+
+        Self::require_no_reentrancy(&env)?;
+        Self::set_reentrancy_guard(&env, true);
+
+        let released = Self::release_allocations(&env, commitment_id)?;
 
         Self::set_reentrancy_guard(&env, false);
 
-        env.events()
-            .publish((symbol_short!("rebalance"), commitment_id), new_total);
+        env.events().publish(
+            (symbol_short!("closed"), commitment_id),
+            (core_commitment_id, released),
+        );
 
-        Ok(AllocationSummary {
-            commitment_id,
-            strategy,
-            total_allocated: new_total,
-            allocations: new_allocations,
-        })
+        Ok(())
     }
 
     // ========================================================================
@@ -580,10 +1251,113 @@ impl AllocationStrategiesContract {
         }
     }
 
+    /// Internal u64 id linked to a commitment_core id, if it was allocated
+    /// through `allocate_for_commitment`.
+    pub fn get_linked_commitment_id(env: Env, core_commitment_id: String) -> Option<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CoreCommitment(core_commitment_id))
+    }
+
+    pub fn get_allocation_by_core_id(
+        env: Env,
+        core_commitment_id: String,
+    ) -> Result<AllocationSummary, Error> {
+        let commitment_id = Self::get_linked_commitment_id(env.clone(), core_commitment_id)
+            .ok_or(Error::AllocationNotFound)?;
+        Ok(Self::get_allocation(env, commitment_id))
+    }
+
     pub fn get_pool(env: Env, pool_id: u32) -> Result<Pool, Error> {
         Self::get_pool_internal(&env, pool_id)
     }
 
+    /// Yield `principal` would earn in a pool over `elapsed_secs`.
+    pub fn preview_yield(
+        env: Env,
+        pool_id: u32,
+        principal: i128,
+        elapsed_secs: u64,
+    ) -> Result<i128, Error> {
+        if principal < 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let pool = Self::get_pool_internal(&env, pool_id)?;
+        Self::accrue_yield(&pool, principal, elapsed_secs)
+    }
+
+    /// Yield accrued so far on a commitment's allocations, each counted from
+    /// when it was placed at its pool's current rate.
+    pub fn get_accrued_yield(env: Env, commitment_id: u64) -> Result<i128, Error> {
+        Ok(Self::get_allocation_detail(env, commitment_id)?.total_accrued_yield)
+    }
+
+    /// A commitment's allocations, each with its pool's current APY and the
+    /// yield accrued since it was made, plus the totals. Read-only.
+    pub fn get_allocation_detail(env: Env, commitment_id: u64) -> Result<AllocationDetail, Error> {
+        let summary = Self::get_allocation(env.clone(), commitment_id);
+        let now = env.ledger().timestamp();
+        let mut positions = Vec::new(&env);
+        let mut total_accrued_yield: i128 = 0;
+        for allocation in summary.allocations.iter() {
+            let pool = Self::get_pool_internal(&env, allocation.pool_id)?;
+            let elapsed = now.saturating_sub(allocation.timestamp);
+            let accrued_yield = Self::accrue_yield(&pool, allocation.amount, elapsed)?;
+            total_accrued_yield = total_accrued_yield
+                .checked_add(accrued_yield)
+                .ok_or(Error::ArithmeticOverflow)?;
+            positions.push_back(AllocationYield {
+                allocation,
+                apy: pool.apy,
+                accrued_yield,
+            });
+        }
+        Ok(AllocationDetail {
+            commitment_id,
+            strategy: summary.strategy,
+            total_allocated: summary.total_allocated,
+            total_accrued_yield,
+            positions,
+        })
+    }
+
+    /// Amount new allocations can still place in a pool after its reserve.
+    pub fn get_allocatable_capacity(env: Env, pool_id: u32) -> Result<i128, Error> {
+        let pool = Self::get_pool_internal(&env, pool_id)?;
+        Ok(Self::available_capacity(&pool)?.max(0))
+    }
+
+    /// The capacity reduction queued for a pool, if any.
+    pub fn get_pending_capacity(env: Env, pool_id: u32) -> Option<PendingCapacity> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PendingCapacity(pool_id))
+    }
+
+    /// `(buffer_bps, reduction_delay)` applied to capacity reductions.
+    pub fn get_capacity_policy(env: Env) -> (u32, u64) {
+        let buffer_bps = env
+            .storage()
+            .instance()
+            .get(&DataKey::CapacityBufferBps)
+            .unwrap_or(0);
+        let delay = env
+            .storage()
+            .instance()
+            .get(&DataKey::CapacityReductionDelay)
+            .unwrap_or(DEFAULT_CAPACITY_REDUCTION_DELAY);
+        (buffer_bps, delay)
+    }
+
+    /// Commitments holding a position in `pool_id`. For a closed pool these
+    /// are the positions flagged for manual deallocation.
+    pub fn get_pool_commitments(env: Env, pool_id: u32) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PoolCommitments(pool_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
     pub fn get_all_pools(env: Env) -> Vec<Pool> {
         let registry: Vec<u32> = env
             .storage()
@@ -600,6 +1374,69 @@ impl AllocationStrategiesContract {
         pools
     }
 
+    /// Total amount currently allocated under `strategy` across all commitments.
+    pub fn get_strategy_exposure(env: Env, strategy: Strategy) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::StrategyExposure(strategy))
+            .unwrap_or(0i128)
+    }
+
+    /// Allocated totals for every strategy.
+    pub fn get_all_strategy_exposure(env: Env) -> Map<Strategy, i128> {
+        let mut exposure = Map::new(&env);
+        for strategy in [Strategy::Safe, Strategy::Balanced, Strategy::Aggressive] {
+            exposure.set(
+                strategy,
+                Self::get_strategy_exposure(env.clone(), strategy),
+            );
+        }
+        exposure
+    }
+
+    /// Sequence number of the last `PoolLiquidity` event (0 before any).
+    pub fn get_pool_event_sequence(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::PoolEventSequence)
+            .unwrap_or(0)
+    }
+
+    /// Per-pool concentration cap for `strategy` in basis points.
+    pub fn get_max_concentration(env: Env, strategy: Strategy) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxConcentration(strategy))
+            .unwrap_or(MAX_CONCENTRATION_BPS)
+    }
+
+    /// Strategy mapped to `commitment_type`: the admin override if set,
+    /// otherwise the built-in mapping. `None` for unmapped types.
+    pub fn get_strategy_for_type(env: Env, commitment_type: String) -> Option<Strategy> {
+        if let Some(strategy) = env
+            .storage()
+            .instance()
+            .get(&DataKey::StrategyForType(commitment_type.clone()))
+        {
+            return Some(strategy);
+        }
+        if commitment_type == String::from_str(&env, "safe") {
+            Some(Strategy::Safe)
+        } else if commitment_type == String::from_str(&env, "balanced") {
+            Some(Strategy::Balanced)
+        } else if commitment_type == String::from_str(&env, "aggressive") {
+            Some(Strategy::Aggressive)
+        } else {
+            None
+        }
+    }
+
+    /// Recommended allocation strategy for a commitment type. Unmapped types
+    /// fall back to `Strategy::Safe`.
+    pub fn recommend_strategy(env: Env, commitment_type: String) -> Strategy {
+        Self::get_strategy_for_type(env, commitment_type).unwrap_or(Strategy::Safe)
+    }
+
     pub fn is_initialized(env: Env) -> bool {
         env.storage()
             .instance()
@@ -640,6 +1477,10 @@ impl AllocationStrategiesContract {
     }
 
     /// Migrate storage from a previous version to CURRENT_VERSION (admin-only).
+    ///
+    /// Version 2 added `reserve_bps`, `compounding`, `compounding_period` and
+    /// `closed` to `Pool`: every registered pool is rewritten unreserved,
+    /// simple-interest and open, matching its version 1 behavior.
     pub fn migrate(
         env: Env,
         caller: Address,
@@ -669,6 +1510,20 @@ impl AllocationStrategiesContract {
                 .set(&DataKey::ReentrancyGuard, &false);
         }
 
+        if from_version < 2 {
+            let registry: Vec<u32> = env
+                .storage()
+                .instance()
+                .get(&DataKey::PoolRegistry)
+                .unwrap_or(Vec::new(&env));
+            for pool_id in registry.iter() {
+                let key = DataKey::Pool(pool_id);
+                if let Some(legacy) = env.storage().persistent().get::<_, PoolV1>(&key) {
+                    env.storage().persistent().set(&key, &Pool::from(legacy));
+                }
+            }
+        }
+
         env.storage().instance().set(&DataKey::Version, &CURRENT_VERSION);
         Ok(())
     }
@@ -756,6 +1611,263 @@ impl AllocationStrategiesContract {
             .set(&DataKey::ReentrancyGuard, &value);
     }
 
+    /// Return a commitment's allocated amounts to their pools and clear its
+    /// allocation record. Returns the total released.
+    fn release_allocations(env: &Env, commitment_id: u64) -> Result<i128, Error> {
+        let allocations: Vec<Allocation> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Allocations(commitment_id))
+            .unwrap_or(Vec::new(env));
+
+        let mut released = 0i128;
+        for allocation in allocations.iter() {
+            let mut pool = Self::get_pool_internal(env, allocation.pool_id)?;
+            pool.total_liquidity = pool
+                .total_liquidity
+                .checked_sub(allocation.amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+            pool.updated_at = env.ledger().timestamp();
+            env.storage()
+                .persistent()
+                .set(&DataKey::Pool(allocation.pool_id), &pool);
+            Self::emit_pool_liquidity(env, allocation.pool_id, -allocation.amount, pool.total_liquidity);
+            Self::remove_pool_commitment(env, allocation.pool_id, commitment_id);
+
+            released = released
+                .checked_add(allocation.amount)
+                .ok_or(Error::ArithmeticOverflow)?;
+        }
+
+        if let Some(strategy) = env
+            .storage()
+            .persistent()
+            .get::<_, Strategy>(&DataKey::Strategy(commitment_id))
+        {
+            Self::adjust_strategy_exposure(env, strategy, -released)?;
+        }
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Allocations(commitment_id), &Vec::<Allocation>::new(env));
+        env.storage()
+            .persistent()
+            .set(&DataKey::TotalAllocated(commitment_id), &0i128);
+
+        Ok(released)
+    }
+
+    fn add_pool_commitment(env: &Env, pool_id: u32, commitment_id: u64) {
+        let mut members = Self::get_pool_commitments(env.clone(), pool_id);
+        if !members.contains(commitment_id) {
+            members.push_back(commitment_id);
+            env.storage()
+                .persistent()
+                .set(&DataKey::PoolCommitments(pool_id), &members);
+        }
+    }
+
+    fn remove_pool_commitment(env: &Env, pool_id: u32, commitment_id: u64) {
+        let mut members = Self::get_pool_commitments(env.clone(), pool_id);
+        if let Some(index) = members.first_index_of(commitment_id) {
+            members.remove(index);
+            env.storage()
+                .persistent()
+                .set(&DataKey::PoolCommitments(pool_id), &members);
+        }
+    }
+
+    /// Emit `PoolLiquidity` for one pool liquidity change.
+    ///
+    /// Topics are ("PoolLiquidity", pool_id, sequence) and data is
+    /// (amount, new_total, direction) with direction `in` or `out`; the
+    /// sequence increases by one per event so consumers can detect gaps.
+    fn emit_pool_liquidity(env: &Env, pool_id: u32, delta: i128, new_total: i128) {
+        let sequence = Self::get_pool_event_sequence(env.clone()) + 1;
+        env.storage()
+            .instance()
+            .set(&DataKey::PoolEventSequence, &sequence);
+        let direction = if delta >= 0 {
+            symbol_short!("in")
+        } else {
+            symbol_short!("out")
+        };
+        env.events().publish(
+            (Symbol::new(env, "PoolLiquidity"), pool_id, sequence),
+            (delta.abs(), new_total, direction),
+        );
+    }
+
+    fn adjust_strategy_exposure(env: &Env, strategy: Strategy, delta: i128) -> Result<(), Error> {
+        let key = DataKey::StrategyExposure(strategy);
+        let current: i128 = env.storage().instance().get(&key).unwrap_or(0);
+        let updated = current.checked_add(delta).ok_or(Error::ArithmeticOverflow)?;
+        env.storage().instance().set(&key, &updated);
+        Ok(())
+    }
+
+    /// Owner of a commitment_core commitment, read from the configured core
+    /// contract.
+    fn core_commitment_owner(env: &Env, core_commitment_id: &String) -> Result<Address, Error> {
+        let commitment_core: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::CommitmentCore)
+            .ok_or(Error::NotInitialized)?;
+        let commitment: Val = call::try_invoke1(
+            env,
+            &commitment_core,
+            "get_commitment",
+            core_commitment_id.clone(),
+        )
+        .map_err(|_| Error::CommitmentNotFound)?;
+        Map::<Symbol, Val>::try_from_val(env, &commitment)
+            .ok()
+            .and_then(|fields| fields.get(Symbol::new(env, "owner")))
+            .and_then(|owner| Address::try_from_val(env, &owner).ok())
+            .ok_or(Error::CommitmentNotFound)
+    }
+
+    /// Claim the next free linked id. Ids already taken through plain
+    /// `allocate` are skipped, so they cannot block a core commitment's link.
+    fn next_linked_id(env: &Env) -> u64 {
+        let mut id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextLinkedId)
+            .unwrap_or(LINKED_ID_BASE);
+        while env.storage().persistent().has(&DataKey::Allocations(id))
+            || env.storage().persistent().has(&DataKey::AllocationOwner(id))
+        {
+            id += 1;
+        }
+        env.storage().instance().set(&DataKey::NextLinkedId, &(id + 1));
+        id
+    }
+
+    fn validate_pool_registration(
+        env: &Env,
+        pool_id: u32,
+        apy: u32,
+        max_capacity: i128,
+    ) -> Result<(), Error> {
+        if max_capacity <= 0 {
+            return Err(Error::InvalidCapacity);
+        }
+
+        if apy > 100_000 {
+            // Max 1000% APY (10000 basis points = 100%)
+            return Err(Error::InvalidAPY);
+        }
+
+        // Check if pool already exists
+        if env.storage().persistent().has(&DataKey::Pool(pool_id)) {
+            return Err(Error::InvalidPoolId);
+        }
+
+        Ok(())
+    }
+
+    /// Store a freshly registered pool and emit its registration event.
+    /// Callers are responsible for validation and the pool registry.
+    fn store_new_pool(
+        env: &Env,
+        pool_id: u32,
+        risk_level: RiskLevel,
+        apy: u32,
+        max_capacity: i128,
+    ) {
+        let pool = Pool {
+            pool_id,
+            risk_level,
+            apy,
+            total_liquidity: 0,
+            max_capacity,
+            reserve_bps: 0,
+            compounding: false,
+            compounding_period: 0,
+            active: true,
+            closed: false,
+            created_at: env.ledger().timestamp(),
+            updated_at: env.ledger().timestamp(),
+        };
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Pool(pool_id), &pool);
+
+        env.events()
+            .publish((symbol_short!("pool_reg"), pool_id), risk_level);
+    }
+
+    /// Highest `total_liquidity` allocations may bring a pool to: its
+    /// `max_capacity` less the reserve.
+    fn allocation_ceiling(pool: &Pool) -> Result<i128, Error> {
+        pool.max_capacity
+            .checked_mul((MAX_RESERVE_BPS - pool.reserve_bps) as i128)
+            .and_then(|x| x.checked_div(MAX_RESERVE_BPS as i128))
+            .ok_or(Error::ArithmeticOverflow)
+    }
+
+    /// Room left under the allocation ceiling; negative once a raised reserve
+    /// puts existing liquidity above it.
+    fn available_capacity(pool: &Pool) -> Result<i128, Error> {
+        Self::allocation_ceiling(pool)?
+            .checked_sub(pool.total_liquidity)
+            .ok_or(Error::ArithmeticOverflow)
+    }
+
+    /// Simple interest on `principal` at the pool's APY (bps) over `elapsed_secs`.
+    fn simple_yield(apy: u32, principal: i128, elapsed_secs: u64) -> Result<i128, Error> {
+        principal
+            .checked_mul(apy as i128)
+            .and_then(|x| x.checked_mul(elapsed_secs as i128))
+            .and_then(|x| x.checked_div(MAX_RESERVE_BPS as i128 * SECONDS_PER_YEAR as i128))
+            .ok_or(Error::ArithmeticOverflow)
+    }
+
+    /// Yield on `principal` over `elapsed_secs`: compounded once per whole
+    /// period for compounding pools (the partial period accrues simply on the
+    /// compounded balance), otherwise simple interest.
+    ///
+    /// The growth factor is raised by squaring, so the work is logarithmic in
+    /// the number of periods.
+    fn accrue_yield(pool: &Pool, principal: i128, elapsed_secs: u64) -> Result<i128, Error> {
+        if !pool.compounding || pool.compounding_period == 0 {
+            return Self::simple_yield(pool.apy, principal, elapsed_secs);
+        }
+
+        let period = pool.compounding_period;
+        let mul = |a: i128, b: i128| {
+            a.checked_mul(b)
+                .map(|x| x / YIELD_SCALE)
+                .ok_or(Error::ArithmeticOverflow)
+        };
+
+        let mut base = YIELD_SCALE
+            .checked_add(Self::simple_yield(pool.apy, YIELD_SCALE, period)?)
+            .ok_or(Error::ArithmeticOverflow)?;
+        let mut factor = YIELD_SCALE;
+        let mut periods = elapsed_secs / period;
+        while periods > 0 {
+            if periods & 1 == 1 {
+                factor = mul(factor, base)?;
+            }
+            periods >>= 1;
+            if periods > 0 {
+                base = mul(base, base)?;
+            }
+        }
+
+        let balance = mul(principal, factor)?;
+        let balance = balance
+            .checked_add(Self::simple_yield(pool.apy, balance, elapsed_secs % period)?)
+            .ok_or(Error::ArithmeticOverflow)?;
+        balance
+            .checked_sub(principal)
+            .ok_or(Error::ArithmeticOverflow)
+    }
+
     fn get_pool_internal(env: &Env, pool_id: u32) -> Result<Pool, Error> {
         env.storage()
             .persistent()
@@ -885,9 +1997,65 @@ impl AllocationStrategiesContract {
             }
         }
 
+        Self::apply_concentration_cap(env, &mut allocation_map, pools, total_amount, strategy)?;
+
         Ok(allocation_map)
     }
 
+    /// Clip every pool to the strategy's concentration cap and spread the
+    /// excess over the other eligible pools that still have headroom.
+    fn apply_concentration_cap(
+        env: &Env,
+        allocation_map: &mut Map<u32, i128>,
+        pools: &Vec<Pool>,
+        total_amount: i128,
+        strategy: Strategy,
+    ) -> Result<(), Error> {
+        let max_bps = Self::get_max_concentration(env.clone(), strategy);
+        if max_bps >= MAX_CONCENTRATION_BPS {
+            return Ok(());
+        }
+
+        let cap = total_amount
+            .checked_mul(max_bps as i128)
+            .and_then(|x| x.checked_div(MAX_CONCENTRATION_BPS as i128))
+            .ok_or(Error::ArithmeticOverflow)?;
+
+        let mut excess = 0i128;
+        for (pool_id, amount) in allocation_map.iter() {
+            if amount > cap {
+                allocation_map.set(pool_id, cap);
+                excess = excess
+                    .checked_add(amount - cap)
+                    .ok_or(Error::ArithmeticOverflow)?;
+            }
+        }
+
+        for pool in pools.iter() {
+            if excess == 0 {
+                break;
+            }
+
+            let current = allocation_map.get(pool.pool_id).unwrap_or(0);
+            let capacity_room = Self::available_capacity(&pool)?
+                .checked_sub(current)
+                .ok_or(Error::ArithmeticOverflow)?;
+            let room = (cap - current).min(capacity_room);
+
+            if room > 0 {
+                let moved = room.min(excess);
+                allocation_map.set(pool.pool_id, current + moved);
+                excess -= moved;
+            }
+        }
+
+        if excess > 0 {
+            return Err(Error::ConcentrationLimitExceeded);
+        }
+
+        Ok(())
+    }
+
     fn distribute_to_pools(
         _env: &Env,
         allocation_map: &mut Map<u32, i128>,
@@ -902,10 +2070,7 @@ impl AllocationStrategiesContract {
         let amount_per_pool = amount / pool_count as i128;
 
         for pool in pools.iter() {
-            let available_capacity = pool
-                .max_capacity
-                .checked_sub(pool.total_liquidity)
-                .ok_or(Error::ArithmeticOverflow)?;
+            let available_capacity = Self::available_capacity(&pool)?;
 
             let alloc_amount = if amount_per_pool > available_capacity {
                 available_capacity
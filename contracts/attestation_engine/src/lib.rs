@@ -1,12 +1,31 @@
 #![no_std]
-use shared_utils::RateLimiter;
+use shared_utils::{call, BatchError, BatchMode, BatchProcessor, BatchResultVoid, RateLimiter};
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, IntoVal,
-    Map, String, Symbol, TryIntoVal, Val, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, xdr::ToXdr, Address,
+    Bytes, BytesN, Env, IntoVal, Map, String, Symbol, TryIntoVal, Val, Vec,
 };
 
 pub const CURRENT_VERSION: u32 = 1;
 
+/// Maximum number of alert subscribers per commitment
+pub const MAX_ALERT_SUBSCRIBERS: u32 = 20;
+
+/// Maximum number of commitments `batch_verify_compliance` checks per call
+pub const MAX_COMPLIANCE_BATCH: u32 = 50;
+
+/// Minimum compliance score `verify_compliance` accepts until the admin sets one
+pub const DEFAULT_MIN_COMPLIANCE_SCORE: u32 = 80;
+
+/// Attestation data map limits applied until the admin sets them
+pub const DEFAULT_MAX_DATA_ENTRIES: u32 = 32;
+pub const DEFAULT_MAX_DATA_KEY_LEN: u32 = 64;
+pub const DEFAULT_MAX_DATA_VALUE_LEN: u32 = 256;
+
+/// Compliance score penalties by severity
+pub const SEVERITY_PENALTY_HIGH: u32 = 30;
+pub const SEVERITY_PENALTY_MEDIUM: u32 = 20;
+pub const SEVERITY_PENALTY_LOW: u32 = 10;
+
 // ============================================================================
 // Error Types
 // ============================================================================
@@ -38,6 +57,32 @@ pub enum AttestationError {
     FeeRecipientNotSet = 10,
     /// Insufficient collected fees to withdraw
     InsufficientFees = 11,
+    /// Alert subscriber limit reached for this commitment
+    TooManySubscribers = 12,
+    /// Storage is already at the current version
+    AlreadyMigrated = 13,
+    /// Migration source version does not match the stored version
+    InvalidVersion = 14,
+    /// Upgrade WASM hash must not be zero
+    InvalidWasmHash = 15,
+    /// Compliance score must be between 0 and 100
+    InvalidComplianceScore = 16,
+    /// Drawdown severity bands must be non-zero and ascending
+    InvalidSeverityBands = 17,
+    /// Caller exceeded the configured rate limit
+    RateLimited = 18,
+    /// Verifier has no accrued rewards to claim
+    NoRewardsToClaim = 19,
+    /// Verifier reward token not configured
+    RewardAssetNotSet = 20,
+    /// Historical import window has been closed
+    ImportWindowClosed = 21,
+    /// Timestamp lies in the future
+    InvalidTimestamp = 22,
+    /// Attestation data map has too many entries or an oversized key or value
+    DataTooLarge = 23,
+    /// Data limits must all be non-zero
+    InvalidDataLimits = 24,
 }
 
 // ============================================================================
@@ -51,8 +96,12 @@ pub enum DataKey {
     Admin,
     /// Core contract address
     CoreContract,
+    /// Contract registry used to resolve the core contract when none is configured
+    Registry,
     /// Verifier whitelist (Address -> bool)
     Verifier(Address),
+    /// Enumerable verifier whitelist (Vec<Address>)
+    Verifiers,
     /// Attestations for a commitment (commitment_id -> Vec<Attestation>)
     Attestations(String),
     /// Health metrics for a commitment (commitment_id -> HealthMetrics)
@@ -77,6 +126,26 @@ pub enum DataKey {
     AttestationFeeAsset,
     /// Collected fees per asset (asset -> i128)
     CollectedFees(Address),
+    /// Drawdown alert subscribers for a commitment (commitment_id -> Vec<Address>)
+    AlertSubscribers(String),
+    /// Stored contract version
+    Version,
+    /// Minimum compliance score accepted by verify_compliance (u32)
+    MinComplianceScore,
+    /// Global attestation event sequence (u64), last topic of attestation events
+    EventSequence,
+    /// Drawdown severity bands (DrawdownSeverityBands)
+    DrawdownSeverityBands,
+    /// Verifier reward: amount accrued per attestation (0 = no reward)
+    VerifierRewardAmount,
+    /// Verifier reward: token rewards are paid in
+    VerifierRewardAsset,
+    /// Unclaimed rewards per verifier (Address -> i128)
+    VerifierRewards(Address),
+    /// Set once the admin closes the historical import window (bool)
+    ImportClosed,
+    /// Size limits on attestation data maps (DataLimits)
+    DataLimits,
 }
 
 #[contracttype]
@@ -90,6 +159,17 @@ pub struct Attestation {
     pub verified_by: Address,
 }
 
+/// Size limits on the `data` map of an attestation
+///
+/// Lengths are in bytes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DataLimits {
+    pub max_entries: u32,
+    pub max_key_len: u32,
+    pub max_value_len: u32,
+}
+
 /// Parameters for batch attestation operations
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -110,6 +190,7 @@ pub struct CommitmentRules {
     pub early_exit_penalty: u32,
     pub min_fee_threshold: i128,
     pub grace_period_days: u32,
+    pub max_loss_bps: u32,
 }
 
 #[contracttype]
@@ -129,46 +210,42 @@ pub struct Commitment {
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub enum DataKey {
-    Admin,
-    CommitmentCore,
-    HealthState(String),
-    Attestations(String),
-}
-
-#[contracttype]
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct HealthState {
+pub struct HealthMetrics {
+    pub commitment_id: String,
+    pub current_value: i128,
+    pub initial_value: i128,
+    pub drawdown_percent: i128,
     pub fees_generated: i128,
     pub volatility_exposure: i128,
     pub last_attestation: u64,
-    pub compliance_score: u32, // 0-100; 0 means "unknown / not calculated"
+    pub compliance_score: u32, // 0-100
 }
 
+/// Drawdown severity thresholds, as a percentage of the commitment's `max_loss_percent`
+///
+/// A drawdown at or above `high` is high severity, at or above `medium` is medium,
+/// at or above `low` is low; anything below `low` carries no penalty.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Attestation {
-    pub commitment_id: String,
-    pub timestamp: u64,
-    pub attestation_type: String, // "health_check", "violation", "fee_generation", "drawdown"
-    pub data: Map<String, String>, // Flexible data structure
-    pub is_compliant: bool,
-    pub verified_by: Address,
+pub struct DrawdownSeverityBands {
+    pub low: u32,
+    pub medium: u32,
+    pub high: u32,
 }
 
-// Import Commitment types from commitment_core (define locally for cross-contract calls)
-
+/// Exportable compliance bundle for a single commitment
+///
+/// `hash` is the sha256 of the XDR-encoded attestation records concatenated in
+/// storage order, so any change to the attestation history changes the hash.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct HealthMetrics {
+pub struct ComplianceCertificate {
     pub commitment_id: String,
-    pub current_value: i128,
-    pub initial_value: i128,
-    pub drawdown_percent: i128,
-    pub fees_generated: i128,
-    pub volatility_exposure: i128,
+    pub compliance_score: u32,
+    pub total_attestations: u32,
+    pub violation_attestations: u32,
     pub last_attestation: u64,
-    pub compliance_score: u32, // 0-100
+    pub hash: BytesN<32>,
 }
 
 #[contract]
@@ -204,6 +281,60 @@ impl AttestationEngineContract {
         Ok(())
     }
 
+    /// Initialize the attestation engine without a direct core address
+    ///
+    /// The core contract is resolved from `registry` under the name `core` on each
+    /// use, so re-pointing the registry entry follows a core redeployment.
+    pub fn initialize_with_registry(
+        e: Env,
+        admin: Address,
+        registry: Address,
+    ) -> Result<(), AttestationError> {
+        if e.storage().instance().has(&DataKey::Admin) {
+            return Err(AttestationError::AlreadyInitialized);
+        }
+
+        e.storage().instance().set(&DataKey::Admin, &admin);
+        e.storage().instance().set(&DataKey::Registry, &registry);
+
+        Ok(())
+    }
+
+    /// Set the contract registry used when no direct core address is configured (admin only)
+    pub fn set_registry(e: Env, caller: Address, registry: Address) -> Result<(), AttestationError> {
+        require_admin(&e, &caller)?;
+        e.storage().instance().set(&DataKey::Registry, &registry);
+        e.events().publish(
+            (Symbol::new(&e, "RegistrySet"), caller),
+            (registry, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Get the contract registry address, if one is configured
+    pub fn get_registry(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::Registry)
+    }
+
+    /// Resolve the core contract: the direct address if configured, else the registry's `core` entry
+    fn core_contract(e: &Env) -> Option<Address> {
+        if let Some(core) = e.storage().instance().get(&DataKey::CoreContract) {
+            return Some(core);
+        }
+
+        let registry: Address = e.storage().instance().get(&DataKey::Registry)?;
+        let mut args = Vec::new(e);
+        args.push_back(symbol_short!("core").into_val(e));
+        match e.try_invoke_contract::<Option<Address>, soroban_sdk::Error>(
+            &registry,
+            &Symbol::new(e, "get_contract"),
+            args,
+        ) {
+            Ok(Ok(core)) => core,
+            _ => None,
+        }
+    }
+
     // ========================================================================
     // Verifier Whitelist Management
     // ========================================================================
@@ -232,9 +363,13 @@ impl AttestationEngineContract {
         }
 
         // Add verifier to whitelist
-        e.storage()
-            .instance()
-            .set(&DataKey::Verifier(verifier.clone()), &true);
+        let key = DataKey::Verifier(verifier.clone());
+        if !e.storage().instance().has(&key) {
+            let mut verifiers = Self::get_verifiers(e.clone());
+            verifiers.push_back(verifier.clone());
+            e.storage().instance().set(&DataKey::Verifiers, &verifiers);
+        }
+        e.storage().instance().set(&key, &true);
 
         // Emit event
         e.events()
@@ -271,6 +406,14 @@ impl AttestationEngineContract {
             .instance()
             .remove(&DataKey::Verifier(verifier.clone()));
 
+        let mut remaining = Vec::new(&e);
+        for existing in Self::get_verifiers(e.clone()).iter() {
+            if existing != verifier {
+                remaining.push_back(existing);
+            }
+        }
+        e.storage().instance().set(&DataKey::Verifiers, &remaining);
+
         // Emit event
         e.events()
             .publish((Symbol::new(&e, "VerifierRemoved"),), (verifier,));
@@ -303,6 +446,16 @@ impl AttestationEngineContract {
         Self::is_authorized_verifier(&e, &address)
     }
 
+    /// List whitelisted verifiers in the order they were added
+    ///
+    /// The admin is implicitly authorized and is not listed unless explicitly added.
+    pub fn get_verifiers(e: Env) -> Vec<Address> {
+        e.storage()
+            .instance()
+            .get(&DataKey::Verifiers)
+            .unwrap_or(Vec::new(&e))
+    }
+
     /// Get the admin address
     pub fn get_admin(e: Env) -> Result<Address, AttestationError> {
         e.storage()
@@ -311,12 +464,9 @@ impl AttestationEngineContract {
             .ok_or(AttestationError::NotInitialized)
     }
 
-    /// Get the core contract address
+    /// Get the core contract address (direct, or resolved through the registry)
     pub fn get_core_contract(e: Env) -> Result<Address, AttestationError> {
-        e.storage()
-            .instance()
-            .get(&DataKey::CoreContract)
-            .ok_or(AttestationError::NotInitialized)
+        Self::core_contract(&e).ok_or(AttestationError::NotInitialized)
     }
 
     /// Get current on-chain version (0 if legacy/uninitialized).
@@ -390,6 +540,11 @@ impl AttestationEngineContract {
     }
 
     /// Get stored health metrics for a commitment (without recalculation)
+    ///
+    /// Makes no cross-contract calls, so it works while the core contract is
+    /// down, but values are as of `last_attestation`: `current_value` and
+    /// `initial_value` were refreshed from core then, and `drawdown_percent`
+    /// only changes on drawdown attestations.
     pub fn get_stored_health_metrics(e: Env, commitment_id: String) -> Option<HealthMetrics> {
         let key = DataKey::HealthMetrics(commitment_id);
         e.storage().persistent().get(&key)
@@ -412,6 +567,15 @@ impl AttestationEngineContract {
             || *att_type == drawdown
     }
 
+    /// Whether a data map fits the configured entry count and key/value lengths
+    fn data_within_limits(e: &Env, data: &Map<String, String>) -> bool {
+        let limits = Self::get_data_limits(e.clone());
+        data.len() <= limits.max_entries
+            && data.iter().all(|(key, value)| {
+                key.len() <= limits.max_key_len && value.len() <= limits.max_value_len
+            })
+    }
+
     /// Validate attestation data based on type
     fn validate_attestation_data(e: &Env, att_type: &String, data: &Map<String, String>) -> bool {
         let health_check = String::from_str(e, "health_check");
@@ -442,26 +606,69 @@ impl AttestationEngineContract {
 
     /// Check if commitment exists in core contract
     fn commitment_exists(e: &Env, commitment_id: &String) -> bool {
-        let commitment_core: Address = match e.storage().instance().get(&DataKey::CoreContract) {
+        let commitment_core: Address = match Self::core_contract(e) {
             Some(addr) => addr,
             None => return false,
         };
 
-        // Try to get commitment from core contract
+        call::try_invoke1::<_, Val>(e, &commitment_core, "get_commitment", commitment_id.clone())
+            .is_ok()
+    }
+
+    /// Fetch a commitment from the core contract, panicking if it does not exist
+    fn core_get_commitment(e: &Env, commitment_id: &String) -> Commitment {
+        let commitment_core: Address = Self::core_contract(e).unwrap();
+
         let mut args = Vec::new(e);
         args.push_back(commitment_id.clone().into_val(e));
+        let commitment_val: Val =
+            e.invoke_contract(&commitment_core, &Symbol::new(e, "get_commitment"), args);
 
-        // Use try_invoke_contract to handle potential failures
-        let result = e.try_invoke_contract::<Val, soroban_sdk::Error>(
-            &commitment_core,
+        commitment_val.try_into_val(e).unwrap()
+    }
+
+    /// Fetch a commitment from the core contract, or `None` if the core contract
+    /// is unset or the lookup fails
+    fn try_core_get_commitment(e: &Env, commitment_id: &String) -> Option<Commitment> {
+        let commitment_core: Address = Self::core_contract(e)?;
+        Self::try_get_commitment_from(e, &commitment_core, commitment_id)
+    }
+
+    /// Fetch a commitment from a known core contract, or `None` if the lookup fails
+    fn try_get_commitment_from(
+        e: &Env,
+        commitment_core: &Address,
+        commitment_id: &String,
+    ) -> Option<Commitment> {
+        let mut args = Vec::new(e);
+        args.push_back(commitment_id.clone().into_val(e));
+        match e.try_invoke_contract::<Val, soroban_sdk::Error>(
+            commitment_core,
             &Symbol::new(e, "get_commitment"),
             args,
-        );
+        ) {
+            Ok(Ok(val)) => val.try_into_val(e).ok(),
+            _ => None,
+        }
+    }
+
+    /// Ask the core contract whether a commitment currently breaks its rules
+    fn core_check_violations(e: &Env, commitment_core: &Address, commitment_id: &String) -> bool {
+        let mut args = Vec::new(e);
+        args.push_back(commitment_id.clone().into_val(e));
+        e.invoke_contract(commitment_core, &Symbol::new(e, "check_violations"), args)
+    }
 
-        match result {
-            Ok(Ok(_)) => true,
-            _ => false,
+    /// Drawdown as a whole percentage of the initial value (0 when nothing was committed)
+    fn calc_drawdown_percent(initial_value: i128, current_value: i128) -> i128 {
+        if initial_value <= 0 {
+            return 0;
         }
+        let diff = initial_value.checked_sub(current_value).unwrap_or(0);
+        diff.checked_mul(100)
+            .unwrap_or(0)
+            .checked_div(initial_value)
+            .unwrap_or(0)
     }
 
     // ========================================================================
@@ -487,8 +694,15 @@ impl AttestationEngineContract {
                     compliance_score: 100,
                 });
 
-        // Update last_attestation timestamp
-        metrics.last_attestation = attestation.timestamp;
+        // Backfill and refresh value tracking from core
+        let commitment = Self::try_core_get_commitment(e, commitment_id);
+        if let Some(commitment) = &commitment {
+            metrics.initial_value = commitment.amount;
+            metrics.current_value = commitment.current_value;
+        }
+
+        // Update last_attestation timestamp (imported history may be older)
+        metrics.last_attestation = metrics.last_attestation.max(attestation.timestamp);
 
         // Update type-specific metrics
         let fee_generation = String::from_str(e, "fee_generation");
@@ -514,11 +728,21 @@ impl AttestationEngineContract {
                 }
             }
         } else if attestation.attestation_type == drawdown_type {
-            // Update drawdown_percent
+            // Update drawdown_percent; severity is derived from the commitment's loss
+            // limit, any caller-supplied "severity" is ignored
             let drawdown_percent_key = String::from_str(e, "drawdown_percent");
             if let Some(drawdown_str) = attestation.data.get(drawdown_percent_key) {
                 if let Some(drawdown_val) = Self::parse_i128_from_string(e, &drawdown_str) {
                     metrics.drawdown_percent = drawdown_val;
+                    if let Some(commitment) = &commitment {
+                        let penalty = Self::drawdown_penalty(
+                            e,
+                            drawdown_val,
+                            commitment.rules.max_loss_percent,
+                        );
+                        metrics.compliance_score =
+                            metrics.compliance_score.saturating_sub(penalty);
+                    }
                 }
             }
         } else if attestation.attestation_type == violation {
@@ -528,14 +752,14 @@ impl AttestationEngineContract {
                 let high = String::from_str(e, "high");
                 let medium = String::from_str(e, "medium");
                 if severity == high {
-                    30u32
+                    SEVERITY_PENALTY_HIGH
                 } else if severity == medium {
-                    20u32
+                    SEVERITY_PENALTY_MEDIUM
                 } else {
-                    10u32
+                    SEVERITY_PENALTY_LOW
                 }
             } else {
-                20u32 // Default penalty
+                SEVERITY_PENALTY_MEDIUM // Default penalty
             };
 
             metrics.compliance_score = metrics.compliance_score.saturating_sub(penalty);
@@ -552,6 +776,29 @@ impl AttestationEngineContract {
         e.storage().persistent().set(&key, &metrics);
     }
 
+    /// Compliance penalty for a drawdown, banded by its share of the loss limit
+    fn drawdown_penalty(e: &Env, drawdown_percent: i128, max_loss_percent: u32) -> u32 {
+        if drawdown_percent <= 0 {
+            return 0;
+        }
+        if max_loss_percent == 0 {
+            // Any loss breaches a zero-loss commitment
+            return SEVERITY_PENALTY_HIGH;
+        }
+
+        let bands = Self::get_drawdown_severity_bands(e.clone());
+        let ratio = drawdown_percent.saturating_mul(100) / max_loss_percent as i128;
+        if ratio >= bands.high as i128 {
+            SEVERITY_PENALTY_HIGH
+        } else if ratio >= bands.medium as i128 {
+            SEVERITY_PENALTY_MEDIUM
+        } else if ratio >= bands.low as i128 {
+            SEVERITY_PENALTY_LOW
+        } else {
+            0
+        }
+    }
+
     /// Parse i128 from String (optimized implementation)
     fn parse_i128_from_string(_e: &Env, s: &String) -> Option<i128> {
         let len = s.len();
@@ -635,7 +882,10 @@ impl AttestationEngineContract {
 
         // 3b. Rate limit attestations per verifier
         let fn_symbol = Symbol::new(&e, "attest");
-        RateLimiter::check(&e, &caller, &fn_symbol);
+        if !RateLimiter::try_check(&e, &caller, &fn_symbol) {
+            e.storage().instance().remove(&DataKey::ReentrancyGuard);
+            return Err(AttestationError::RateLimited);
+        }
 
         // 4. Validate commitment_id is not empty
         if commitment_id.len() == 0 {
@@ -655,7 +905,11 @@ impl AttestationEngineContract {
             return Err(AttestationError::InvalidAttestationType);
         }
 
-        // 7. Validate data format for the attestation type
+        // 7. Validate data size and format for the attestation type
+        if !Self::data_within_limits(&e, &data) {
+            e.storage().instance().remove(&DataKey::ReentrancyGuard);
+            return Err(AttestationError::DataTooLarge);
+        }
         if !Self::validate_attestation_data(&e, &attestation_type, &data) {
             e.storage().instance().remove(&DataKey::ReentrancyGuard);
             return Err(AttestationError::InvalidAttestationData);
@@ -677,37 +931,63 @@ impl AttestationEngineContract {
         // 8. Create attestation record
         let timestamp = e.ledger().timestamp();
         let attestation = Attestation {
-            commitment_id: commitment_id_clone,
-            attestation_type: attestation_type_clone,
-            data: data_clone,
-            timestamp: e.ledger().timestamp(),
-            verified_by: verified_by_clone,
-            is_compliant: true, // Default to true, can be updated by logic
+            commitment_id: commitment_id.clone(),
+            attestation_type: attestation_type.clone(),
+            data,
+            timestamp,
+            verified_by: caller.clone(),
+            is_compliant,
         };
 
-        // 9. Store attestation in commitment's list
-        let key = DataKey::Attestations(commitment_id.clone());
+        // 9-11. Store the attestation, refresh metrics and bump counters
+        Self::store_attestation(&e, &attestation);
+
+        // 11c. Accrue the per-attestation verifier reward
+        Self::accrue_verifier_rewards(&e, &caller, 1);
+
+        // 12. Emit enhanced AttestationRecorded event
+        e.events().publish(
+            (
+                Symbol::new(&e, "AttestationRecorded"),
+                commitment_id,
+                caller,
+                Self::next_event_sequence(&e),
+            ),
+            (attestation_type, is_compliant, timestamp),
+        );
+
+        // 13. Clear reentrancy guard
+        e.storage().instance().remove(&DataKey::ReentrancyGuard);
+
+        Ok(())
+    }
+
+    /// Append an attestation to its commitment, refresh health metrics and update
+    /// the per-commitment, protocol and per-verifier counters
+    fn store_attestation(e: &Env, attestation: &Attestation) {
+        // Store attestation in commitment's list
+        let key = DataKey::Attestations(attestation.commitment_id.clone());
         let mut attestations: Vec<Attestation> = e
             .storage()
             .persistent()
             .get(&key)
-            .unwrap_or_else(|| Vec::new(&e));
+            .unwrap_or_else(|| Vec::new(e));
 
         // Add new attestation
-        attestations.push_back(attestation);
+        attestations.push_back(attestation.clone());
 
         // Store updated list
         e.storage().persistent().set(&key, &attestations);
 
-        // 10. Update health metrics
-        Self::update_health_metrics(&e, &commitment_id, &attestation);
+        // Update health metrics
+        Self::update_health_metrics(e, &attestation.commitment_id, attestation);
 
-        // 11. Increment attestation counter
-        let counter_key = DataKey::AttestationCounter(commitment_id.clone());
+        // Increment attestation counter
+        let counter_key = DataKey::AttestationCounter(attestation.commitment_id.clone());
         let counter: u64 = e.storage().persistent().get(&counter_key).unwrap_or(0);
         e.storage().persistent().set(&counter_key, &(counter + 1));
 
-        // 11b. OPTIMIZATION: Batch update all analytics counters
+        // OPTIMIZATION: Batch update all analytics counters
         let (total_attestations, total_violations, verifier_count) = {
             let total_att = e
                 .storage()
@@ -719,7 +999,7 @@ impl AttestationEngineContract {
                 .instance()
                 .get(&DataKey::TotalViolations)
                 .unwrap_or(0u64);
-            let verifier_key = DataKey::VerifierAttestationCount(caller.clone());
+            let verifier_key = DataKey::VerifierAttestationCount(attestation.verified_by.clone());
             let ver_count = e.storage().instance().get(&verifier_key).unwrap_or(0u64);
             (total_att, total_viol, ver_count)
         };
@@ -729,7 +1009,7 @@ impl AttestationEngineContract {
             .set(&DataKey::TotalAttestations, &(total_attestations + 1));
 
         // Track violations (explicit or non-compliant)
-        let violation_type = String::from_str(&e, "violation");
+        let violation_type = String::from_str(e, "violation");
         if attestation.attestation_type == violation_type || !attestation.is_compliant {
             e.storage()
                 .instance()
@@ -737,27 +1017,102 @@ impl AttestationEngineContract {
         }
 
         // Track per-verifier attestation count
-        let verifier_key = DataKey::VerifierAttestationCount(caller.clone());
+        let verifier_key = DataKey::VerifierAttestationCount(attestation.verified_by.clone());
         e.storage()
             .instance()
             .set(&verifier_key, &(verifier_count + 1));
+    }
+
+    /// Import a historical attestation with its original timestamp and verifier (admin only)
+    ///
+    /// Used to seed attestations migrated from an off-chain system. The attestation is
+    /// validated like `attest` and updates the same counters and health metrics, but
+    /// charges no fee and accrues no verifier reward. `verifier` need not be a current
+    /// verifier. Only available until the admin calls `close_import_window`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn import_attestation(
+        e: Env,
+        caller: Address,
+        commitment_id: String,
+        attestation_type: String,
+        data: Map<String, String>,
+        is_compliant: bool,
+        original_timestamp: u64,
+        verifier: Address,
+    ) -> Result<(), AttestationError> {
+        require_admin(&e, &caller)?;
+
+        if Self::is_import_window_closed(&e) {
+            return Err(AttestationError::ImportWindowClosed);
+        }
+        if original_timestamp > e.ledger().timestamp() {
+            return Err(AttestationError::InvalidTimestamp);
+        }
+        if commitment_id.is_empty() {
+            return Err(AttestationError::InvalidCommitmentId);
+        }
+        if !Self::commitment_exists(&e, &commitment_id) {
+            return Err(AttestationError::CommitmentNotFound);
+        }
+        if !Self::is_valid_attestation_type(&e, &attestation_type) {
+            return Err(AttestationError::InvalidAttestationType);
+        }
+        if !Self::data_within_limits(&e, &data) {
+            return Err(AttestationError::DataTooLarge);
+        }
+        if !Self::validate_attestation_data(&e, &attestation_type, &data) {
+            return Err(AttestationError::InvalidAttestationData);
+        }
+
+        let attestation = Attestation {
+            commitment_id: commitment_id.clone(),
+            attestation_type: attestation_type.clone(),
+            data,
+            timestamp: original_timestamp,
+            verified_by: verifier.clone(),
+            is_compliant,
+        };
+        Self::store_attestation(&e, &attestation);
 
-        // 12. Emit enhanced AttestationRecorded event
         e.events().publish(
             (
-                Symbol::new(&e, "AttestationRecorded"),
+                Symbol::new(&e, "AttestationImported"),
                 commitment_id,
-                caller,
+                verifier,
+                Self::next_event_sequence(&e),
             ),
-            (attestation_type, is_compliant, timestamp),
+            (attestation_type, is_compliant, original_timestamp),
         );
 
-        // 13. Clear reentrancy guard
-        e.storage().instance().remove(&DataKey::ReentrancyGuard);
+        Ok(())
+    }
+
+    /// Permanently close the historical import window (admin only)
+    pub fn close_import_window(e: Env, caller: Address) -> Result<(), AttestationError> {
+        require_admin(&e, &caller)?;
+
+        e.storage().instance().set(&DataKey::ImportClosed, &true);
+
+        e.events().publish(
+            (Symbol::new(&e, "ImportWindowClosed"),),
+            e.ledger().timestamp(),
+        );
 
         Ok(())
     }
 
+    /// Whether `import_attestation` is still accepted
+    pub fn is_import_window_open(e: Env) -> bool {
+        !Self::is_import_window_closed(&e)
+    }
+
+    fn is_import_window_closed(e: &Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::ImportClosed)
+            .unwrap_or(false)
+    }
+
     /// Get all attestations for a commitment
     pub fn get_attestations(e: Env, commitment_id: String) -> Vec<Attestation> {
         // Retrieve attestations from persistent storage using commitment_id as key
@@ -768,94 +1123,98 @@ impl AttestationEngineContract {
             .unwrap_or_else(|| Vec::new(&e))
     }
 
+    /// Get the attestations for a commitment whose timestamp lies in `[from, to]`
+    pub fn get_attestations_in_range(
+        e: Env,
+        commitment_id: String,
+        from: u64,
+        to: u64,
+    ) -> Vec<Attestation> {
+        let mut matching = Vec::new(&e);
+        for attestation in Self::get_attestations(e.clone(), commitment_id).iter() {
+            if attestation.timestamp >= from && attestation.timestamp <= to {
+                matching.push_back(attestation);
+            }
+        }
+        matching
+    }
+
     /// Get attestation count for a commitment
     pub fn get_attestation_count(e: Env, commitment_id: String) -> u64 {
         let key = DataKey::AttestationCounter(commitment_id);
         e.storage().persistent().get(&key).unwrap_or(0)
     }
 
-    /// Get current health metrics for a commitment
-    pub fn get_health_metrics(e: Env, commitment_id: String) -> HealthMetrics {
-        let core = Self::get_commitment_core(&e);
-        let commitment = Self::core_get_commitment(&e, &core, &commitment_id);
-
-        let initial_value = commitment.amount;
-        let current_value = commitment.current_value;
-        let drawdown_percent = Self::calc_drawdown_percent(initial_value, current_value);
-
-        let state = Self::get_health_state_or_default(&e, &commitment_id);
-
-        // Get commitment from core contract
-        let commitment_core: Address = e.storage().instance().get(&DataKey::CoreContract).unwrap();
-
-        // Call get_commitment on commitment_core contract
-        // Using Symbol::new() for function name longer than 9 characters
-        let mut args = Vec::new(&e);
-        args.push_back(commitment_id.clone().into_val(&e));
-        let commitment_val: Val =
-            e.invoke_contract(&commitment_core, &Symbol::new(&e, "get_commitment"), args);
-
-        // Convert Val to Commitment
-        let commitment: Commitment = commitment_val.try_into_val(&e).unwrap();
-
-        // Get all attestations
+    /// Export a tamper-evident compliance certificate for a commitment
+    ///
+    /// Panics if the commitment has no stored score and the core contract does
+    /// not know it (see `calculate_compliance_score`).
+    pub fn get_compliance_certificate(e: Env, commitment_id: String) -> ComplianceCertificate {
         let attestations = Self::get_attestations(e.clone(), commitment_id.clone());
+        let violation = String::from_str(&e, "violation");
 
-        // Extract values from commitment
-        let initial_value = commitment.amount; // Using amount as initial value
-        let current_value = commitment.current_value;
-
-        // Calculate drawdown percentage: ((initial - current) / initial) * 100
-        // Handle zero initial value to prevent division by zero
-        let drawdown_percent = if initial_value > 0 {
-            let diff = initial_value.checked_sub(current_value).unwrap_or(0);
-            diff.checked_mul(100)
-                .unwrap_or(0)
-                .checked_div(initial_value)
-                .unwrap_or(0)
-        } else {
-            0
-        };
-
-        // Sum fees from fee attestations
-        // Extract fee_amount from data map where key is "fee_amount"
-        let fees_generated: i128 = 0;
-        let fee_key = String::from_str(&e, "fee_amount");
+        let mut violation_attestations: u32 = 0;
+        let mut last_attestation: u64 = 0;
         for att in attestations.iter() {
-            if att.attestation_type == String::from_str(&e, "fee_generation") {
-                // Try to get fee_amount from data map
-                if let Some(_fee_val) = att.data.get(fee_key.clone()) {
-                    // The value is stored as String, we need to parse it
-                    // For simplicity, we'll use a helper to extract numeric value
-                    // In a real implementation, fees would be stored as i128 directly
-                    // For now, we'll track fees in a separate storage or use a different approach
-                    // Since Map<String, String> stores strings, we'll need parsing
-                    // Simplified: assume fee is stored as string representation of number
-                }
+            if !att.is_compliant || att.attestation_type == violation {
+                violation_attestations += 1;
+            }
+            if att.timestamp > last_attestation {
+                last_attestation = att.timestamp;
             }
         }
 
-        // For now, fees_generated will be 0 until we implement proper fee tracking
-        // This is acceptable as the requirement is to sum from fee attestations
-        // which requires the attest() function to properly store fees
+        ComplianceCertificate {
+            compliance_score: Self::calculate_compliance_score(e.clone(), commitment_id.clone()),
+            total_attestations: attestations.len(),
+            violation_attestations,
+            last_attestation,
+            hash: Self::hash_attestations(&e, &attestations),
+            commitment_id,
+        }
+    }
+
+    /// Check a certificate's hash against the commitment's current attestation records
+    pub fn verify_certificate(e: Env, certificate: ComplianceCertificate) -> bool {
+        let attestations = Self::get_attestations(e.clone(), certificate.commitment_id.clone());
+        attestations.len() == certificate.total_attestations
+            && Self::hash_attestations(&e, &attestations) == certificate.hash
+    }
 
-        // Calculate volatility exposure from attestations
-        // Simplified: use variance of price changes from attestations
-        let mut volatility_exposure: i128 = 0;
-        if attestations.len() > 1 {
-            // Calculate variance from price data in attestations
-            // For now, return 0 as placeholder - would need price history
-            volatility_exposure = 0;
+    /// sha256 over the concatenated XDR encoding of each attestation record
+    fn hash_attestations(e: &Env, attestations: &Vec<Attestation>) -> BytesN<32> {
+        let mut payload = Bytes::new(e);
+        for att in attestations.iter() {
+            payload.append(&att.to_xdr(e));
         }
+        e.crypto().sha256(&payload).into()
+    }
 
-        // Get last attestation timestamp
-        let last_attestation = attestations
-            .iter()
-            .map(|att| att.timestamp)
-            .max()
-            .unwrap_or(0);
+    /// Get current health metrics for a commitment
+    ///
+    /// Value and drawdown are read live from the core contract; fees, volatility
+    /// and the last attestation time come from the metrics stored by `attest`.
+    /// Panics if the core contract does not know the commitment.
+    ///
+    /// This is the refreshing read: it calls the core contract and recomputes the
+    /// compliance score when none is stored. Use `get_stored_health_metrics` for
+    /// routine reads.
+    pub fn get_health_metrics(e: Env, commitment_id: String) -> HealthMetrics {
+        let commitment = Self::core_get_commitment(&e, &commitment_id);
+
+        let initial_value = commitment.amount;
+        let current_value = commitment.current_value;
+        let drawdown_percent = Self::calc_drawdown_percent(initial_value, current_value);
 
-        // Calculate compliance score
+        let (fees_generated, volatility_exposure, last_attestation) =
+            match Self::get_stored_health_metrics(e.clone(), commitment_id.clone()) {
+                Some(stored) => (
+                    stored.fees_generated,
+                    stored.volatility_exposure,
+                    stored.last_attestation,
+                ),
+                None => (0, 0, 0),
+            };
         let compliance_score = Self::calculate_compliance_score(e.clone(), commitment_id.clone());
 
         HealthMetrics {
@@ -863,31 +1222,83 @@ impl AttestationEngineContract {
             current_value,
             initial_value,
             drawdown_percent,
-            fees_generated: state.fees_generated,
-            volatility_exposure: state.volatility_exposure,
-            last_attestation: state.last_attestation,
-            compliance_score: state.compliance_score,
+            fees_generated,
+            volatility_exposure,
+            last_attestation,
+            compliance_score,
         }
     }
 
-    /// Record fee generation
+    /// Verify commitment compliance
     ///
-    /// Convenience function that creates a fee_generation attestation
+    /// A commitment is compliant when all of the following hold:
+    /// - drawdown is within `max_loss_percent`
+    /// - it has not expired (skipped when `duration_days` is 0)
+    /// - recorded fees meet `min_fee_threshold` (skipped when the threshold is not positive)
+    /// - the compliance score is unknown (0) or at least `get_min_compliance_score`
+    /// - the core contract reports no violations and the status is not "violated"
     ///
-    /// # Arguments
-    /// * `caller` - Must be authorized verifier
-    /// * `commitment_id` - The commitment generating fees
-    /// * `fee_amount` - The fee amount generated
-    /// Verify commitment compliance
+    /// Returns `false` instead of panicking when the core contract is unset or does
+    /// not know the commitment.
     pub fn verify_compliance(e: Env, commitment_id: String) -> bool {
-        let core = Self::get_commitment_core(&e);
-        let commitment = Self::core_get_commitment(&e, &core, &commitment_id);
-        let health = Self::get_health_metrics(e.clone(), commitment_id.clone());
-        let has_violations = Self::core_check_violations(&e, &core, &commitment_id);
+        let commitment_core = match Self::core_contract(&e) {
+            Some(address) => address,
+            None => return false,
+        };
+        let min_score = Self::get_min_compliance_score(e.clone());
+        Self::check_compliance(&e, &commitment_core, &commitment_id, min_score)
+    }
+
+    /// Verify compliance for several commitments at once
+    ///
+    /// Returns one result per id, in input order, with the same semantics as
+    /// `verify_compliance`. Panics if more than `MAX_COMPLIANCE_BATCH` ids are given.
+    pub fn batch_verify_compliance(e: Env, commitment_ids: Vec<String>) -> Vec<bool> {
+        if commitment_ids.len() > MAX_COMPLIANCE_BATCH {
+            panic!("Batch too large");
+        }
+        let mut results = Vec::new(&e);
+        let commitment_core = match Self::core_contract(&e) {
+            Some(address) => address,
+            None => {
+                for _ in commitment_ids.iter() {
+                    results.push_back(false);
+                }
+                return results;
+            }
+        };
+        let min_score = Self::get_min_compliance_score(e.clone());
+        for commitment_id in commitment_ids.iter() {
+            results.push_back(Self::check_compliance(
+                &e,
+                &commitment_core,
+                &commitment_id,
+                min_score,
+            ));
+        }
+        results
+    }
+
+    /// Compliance check shared by `verify_compliance` and `batch_verify_compliance`,
+    /// reading the commitment from the core contract once
+    fn check_compliance(
+        e: &Env,
+        commitment_core: &Address,
+        commitment_id: &String,
+        min_score: u32,
+    ) -> bool {
+        let commitment = match Self::try_get_commitment_from(e, commitment_core, commitment_id) {
+            Some(commitment) => commitment,
+            None => return false,
+        };
+        let stored = Self::get_stored_health_metrics(e.clone(), commitment_id.clone());
+        let has_violations = Self::core_check_violations(e, commitment_core, commitment_id);
 
         // Loss limit compliance
         let max_loss = commitment.rules.max_loss_percent as i128;
-        let loss_ok = health.drawdown_percent <= max_loss;
+        let drawdown_percent =
+            Self::calc_drawdown_percent(commitment.amount, commitment.current_value);
+        let loss_ok = drawdown_percent <= max_loss;
 
         // Duration compliance (if applicable)
         let now = e.ledger().timestamp();
@@ -898,75 +1309,142 @@ impl AttestationEngineContract {
         };
 
         // Fee threshold compliance (if applicable)
+        let fees_generated = stored.as_ref().map(|m| m.fees_generated).unwrap_or(0);
         let fee_ok = if commitment.rules.min_fee_threshold <= 0 {
             true
         } else {
-            health.fees_generated >= commitment.rules.min_fee_threshold
+            fees_generated >= commitment.rules.min_fee_threshold
         };
 
-        // Overall health compliance (if score is present; 0 means unknown)
-        let overall_health_ok = health.compliance_score == 0 || health.compliance_score >= 80;
+        // Overall health compliance (0 means unknown)
+        let compliance_score = match &stored {
+            Some(metrics) => metrics.compliance_score,
+            None => Self::score_commitment(e, commitment_id, &commitment),
+        };
+        let overall_health_ok = compliance_score == 0 || compliance_score >= min_score;
 
-        // Status-based sanity checks
-        let status_violated = commitment.status == String::from_str(&e, "violated");
-        let status_ok = !status_violated;
+        let status_ok = commitment.status != String::from_str(e, "violated");
 
         loss_ok && duration_ok && fee_ok && overall_health_ok && !has_violations && status_ok
     }
 
-    /// Record fee generation
-    ///
-    /// # Arguments
-    /// * `commitment_id` - The commitment to verify
-    ///
-    /// # Returns
-    /// `true` if compliant, `false` otherwise
-    pub fn verify_compliance(e: Env, commitment_id: String) -> bool {
-        // Get commitment from core contract
-        let commitment_core: Address = match e.storage().instance().get(&DataKey::CoreContract) {
-            Some(addr) => addr,
-            None => return false,
-        };
+    /// Set the minimum compliance score `verify_compliance` accepts (admin only)
+    pub fn set_min_compliance_score(
+        e: Env,
+        caller: Address,
+        min_score: u32,
+    ) -> Result<(), AttestationError> {
+        require_admin(&e, &caller)?;
+        if min_score > 100 {
+            return Err(AttestationError::InvalidComplianceScore);
+        }
 
-        // Get commitment details
-        let mut args = Vec::new(&e);
-        args.push_back(commitment_id.clone().into_val(&e));
-        let commitment_val: Val = match e.try_invoke_contract::<Val, soroban_sdk::Error>(
-            &commitment_core,
-            &Symbol::new(&e, "get_commitment"),
-            args,
-        ) {
-            Ok(Ok(val)) => val,
-            _ => return false,
-        };
+        e.storage()
+            .instance()
+            .set(&DataKey::MinComplianceScore, &min_score);
+        e.events().publish(
+            (Symbol::new(&e, "MinComplianceSet"), caller),
+            (min_score, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
 
-        let commitment: Commitment = match commitment_val.try_into_val(&e) {
-            Ok(c) => c,
-            Err(_) => return false,
-        };
+    /// Get the minimum compliance score (defaults to `DEFAULT_MIN_COMPLIANCE_SCORE`)
+    pub fn get_min_compliance_score(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::MinComplianceScore)
+            .unwrap_or(DEFAULT_MIN_COMPLIANCE_SCORE)
+    }
 
-        // Get health metrics
-        let metrics = Self::get_health_metrics(e.clone(), commitment_id);
+    /// Set the size limits on attestation data maps (admin only)
+    pub fn set_data_limits(
+        e: Env,
+        caller: Address,
+        limits: DataLimits,
+    ) -> Result<(), AttestationError> {
+        require_admin(&e, &caller)?;
+        if limits.max_entries == 0 || limits.max_key_len == 0 || limits.max_value_len == 0 {
+            return Err(AttestationError::InvalidDataLimits);
+        }
 
-        // Check compliance rules
-        let max_loss = commitment.rules.max_loss_percent as i128;
+        e.storage().instance().set(&DataKey::DataLimits, &limits);
+        e.events().publish(
+            (Symbol::new(&e, "DataLimitsSet"), caller),
+            (limits.max_entries, limits.max_key_len, limits.max_value_len),
+        );
+        Ok(())
+    }
 
-        // Check if drawdown exceeds max loss
-        if metrics.drawdown_percent > max_loss {
-            return false;
-        }
+    /// Get the attestation data map limits (defaults to the `DEFAULT_MAX_DATA_*` values)
+    pub fn get_data_limits(e: Env) -> DataLimits {
+        e.storage()
+            .instance()
+            .get(&DataKey::DataLimits)
+            .unwrap_or(DataLimits {
+                max_entries: DEFAULT_MAX_DATA_ENTRIES,
+                max_key_len: DEFAULT_MAX_DATA_KEY_LEN,
+                max_value_len: DEFAULT_MAX_DATA_VALUE_LEN,
+            })
+    }
+
+    /// Reconcile attested fees against the commitment's fee threshold
+    ///
+    /// Returns `(attested_fees, min_fee_threshold, meets_threshold)`, where
+    /// `attested_fees` is the sum of this commitment's fee_generation attestations and
+    /// the threshold is read from the core contract. Panics if the core contract does
+    /// not know the commitment.
+    pub fn reconcile_fees(e: Env, commitment_id: String) -> (i128, i128, bool) {
+        let commitment = Self::core_get_commitment(&e, &commitment_id);
+        let attested_fees = Self::get_stored_health_metrics(e.clone(), commitment_id)
+            .map(|metrics| metrics.fees_generated)
+            .unwrap_or(0);
+        let min_threshold = commitment.rules.min_fee_threshold;
 
-        // Check compliance score threshold (below 50 is non-compliant)
-        if metrics.compliance_score < 50 {
-            return false;
+        (attested_fees, min_threshold, attested_fees >= min_threshold)
+    }
+
+    /// Set the drawdown severity bands (admin only)
+    ///
+    /// Thresholds are percentages of the commitment's `max_loss_percent` and must
+    /// satisfy `0 < low <= medium <= high`.
+    pub fn set_drawdown_severity_bands(
+        e: Env,
+        caller: Address,
+        bands: DrawdownSeverityBands,
+    ) -> Result<(), AttestationError> {
+        require_admin(&e, &caller)?;
+        if bands.low == 0 || bands.low > bands.medium || bands.medium > bands.high {
+            return Err(AttestationError::InvalidSeverityBands);
         }
 
-        true
+        e.storage()
+            .instance()
+            .set(&DataKey::DrawdownSeverityBands, &bands);
+        e.events().publish(
+            (Symbol::new(&e, "SeverityBandsSet"), caller),
+            (bands.low, bands.medium, bands.high),
+        );
+        Ok(())
+    }
+
+    /// Get the drawdown severity bands (defaults to 50/75/100)
+    pub fn get_drawdown_severity_bands(e: Env) -> DrawdownSeverityBands {
+        e.storage()
+            .instance()
+            .get(&DataKey::DrawdownSeverityBands)
+            .unwrap_or(DrawdownSeverityBands {
+                low: 50,
+                medium: 75,
+                high: 100,
+            })
     }
 
     /// Record fee generation
     ///
-    /// Convenience function that creates a fee_generation attestation
+    /// Convenience function that creates a fee_generation attestation through
+    /// `attest`. The amount is stored as a decimal string under `fee_amount`,
+    /// which `attest` parses into the commitment's `fees_generated`.
     ///
     /// # Arguments
     /// * `caller` - Must be authorized verifier
@@ -992,36 +1470,16 @@ impl AttestationEngineContract {
             commitment_id.clone(),
             String::from_str(&e, "fee_generation"),
             data,
-            timestamp: e.ledger().timestamp(),
-            verified_by: caller.clone(),
-            is_compliant: true,
-        };
+            true,
+        )?;
 
-        // Store attestation
-        let atts_key = (symbol_short!("ATTS"), commitment_id.clone());
-        let mut attestations: Vec<Attestation> = e
-            .storage()
-            .persistent()
-            .get(&atts_key)
-            .unwrap_or_else(|| Vec::new(&e));
-        attestations.push_back(attestation);
-        e.storage().persistent().set(&atts_key, &attestations);
-        
-        // Recalculate compliance score (may call external contract)
-        metrics.compliance_score = Self::calculate_compliance_score(e.clone(), commitment_id.clone());
-        
-        // Update last attestation timestamp
-        metrics.last_attestation = e.ledger().timestamp();
-        
-        // Store updated health metrics
-        Self::store_health_metrics(&e, &metrics);
-        
-        // Clear reentrancy guard
-        e.storage().instance().set(&guard_key, &false);
-        
         // Emit FeeRecorded event
         e.events().publish(
-            (Symbol::new(&e, "FeeRecorded"), commitment_id),
+            (
+                Symbol::new(&e, "FeeRecorded"),
+                commitment_id,
+                Self::next_event_sequence(&e),
+            ),
             (fee_amount, e.ledger().timestamp()),
         );
 
@@ -1041,20 +1499,10 @@ impl AttestationEngineContract {
         drawdown_percent: i128,
     ) -> Result<(), AttestationError> {
         // Get commitment to check max_loss_percent
-        let commitment_core: Address = e
-            .storage()
-            .instance()
-            .get(&DataKey::CoreContract)
-            .ok_or(AttestationError::NotInitialized)?;
-
-        let mut args = Vec::new(&e);
-        args.push_back(commitment_id.clone().into_val(&e));
-        let commitment_val: Val =
-            e.invoke_contract(&commitment_core, &Symbol::new(&e, "get_commitment"), args);
-
-        let commitment: Commitment = commitment_val
-            .try_into_val(&e)
-            .map_err(|_| AttestationError::CommitmentNotFound)?;
+        let commitment_core =
+            Self::core_contract(&e).ok_or(AttestationError::NotInitialized)?;
+        let commitment = Self::try_get_commitment_from(&e, &commitment_core, &commitment_id)
+            .ok_or(AttestationError::CommitmentNotFound)?;
 
         let max_loss = commitment.rules.max_loss_percent as i128;
         let is_compliant = drawdown_percent <= max_loss;
@@ -1070,59 +1518,38 @@ impl AttestationEngineContract {
             Self::i128_to_string(&e, max_loss),
         );
 
-            // Store violation attestation
-            let atts_key = (symbol_short!("ATTS"), commitment_id.clone());
-            let mut attestations: Vec<Attestation> = e
-                .storage()
-                .persistent()
-                .get(&atts_key)
-                .unwrap_or_else(|| Vec::new(&e));
-            attestations.push_back(violation_attestation);
-            e.storage().persistent().set(&atts_key, &attestations);
+        // Store a single drawdown record; a breach is flagged by is_compliant
+        Self::attest(
+            e.clone(),
+            caller,
+            commitment_id.clone(),
+            String::from_str(&e, "drawdown"),
+            data,
+            is_compliant,
+        )?;
 
+        if !is_compliant {
             // Emit ViolationDetected event
             e.events().publish(
-                (Symbol::new(&e, "ViolationDetected"), commitment_id.clone()),
-                (drawdown_percent, max_loss_percent, e.ledger().timestamp()),
+                (
+                    Symbol::new(&e, "ViolationDetected"),
+                    commitment_id.clone(),
+                    Self::next_event_sequence(&e),
+                ),
+                (drawdown_percent, max_loss, e.ledger().timestamp()),
             );
+
+            // Notify alert subscribers of the breach
+            Self::fire_drawdown_alert(&e, &commitment_id, drawdown_percent, max_loss);
         }
-        
-        // Create drawdown attestation
-        let drawdown_data = Map::new(&e);
-        let drawdown_attestation = Attestation {
-            commitment_id: commitment_id.clone(),
-            attestation_type: String::from_str(&e, "drawdown"),
-            data: drawdown_data,
-            timestamp: e.ledger().timestamp(),
-            verified_by: caller.clone(),
-            is_compliant: !is_violation,
-        };
 
-        // Store drawdown attestation
-        let atts_key = (symbol_short!("ATTS"), commitment_id.clone());
-        let mut attestations: Vec<Attestation> = e
-            .storage()
-            .persistent()
-            .get(&atts_key)
-            .unwrap_or_else(|| Vec::new(&e));
-        attestations.push_back(drawdown_attestation);
-        e.storage().persistent().set(&atts_key, &attestations);
-        
-        // Recalculate compliance score (may call external contract)
-        metrics.compliance_score = Self::calculate_compliance_score(e.clone(), commitment_id.clone());
-        
-        // Update last attestation timestamp
-        metrics.last_attestation = e.ledger().timestamp();
-        
-        // Store updated health metrics
-        Self::store_health_metrics(&e, &metrics);
-        
-        // Clear reentrancy guard
-        e.storage().instance().set(&guard_key, &false);
-        
         // Emit DrawdownRecorded event
         e.events().publish(
-            (Symbol::new(&e, "DrawdownRecorded"), commitment_id),
+            (
+                Symbol::new(&e, "DrawdownRecorded"),
+                commitment_id,
+                Self::next_event_sequence(&e),
+            ),
             (drawdown_percent, is_compliant, e.ledger().timestamp()),
         );
 
@@ -1197,19 +1624,12 @@ impl AttestationEngineContract {
             return stored_metrics.compliance_score;
         }
 
-        // Get commitment from core contract
-        let commitment_core: Address = e.storage().instance().get(&DataKey::CoreContract).unwrap();
-
-        // Call get_commitment on commitment_core contract
-        // Using Symbol::new() for function name longer than 9 characters
-        let mut args = Vec::new(&e);
-        args.push_back(commitment_id.clone().into_val(&e));
-        let commitment_val: Val =
-            e.invoke_contract(&commitment_core, &Symbol::new(&e, "get_commitment"), args);
-
-        // Convert Val to Commitment
-        let commitment: Commitment = commitment_val.try_into_val(&e).unwrap();
+        let commitment = Self::core_get_commitment(&e, &commitment_id);
+        Self::score_commitment(&e, &commitment_id, &commitment)
+    }
 
+    /// Score a commitment from its attestations and core state, ignoring any stored score
+    fn score_commitment(e: &Env, commitment_id: &String, commitment: &Commitment) -> u32 {
         // Get all attestations
         let attestations = Self::get_attestations(e.clone(), commitment_id.clone());
 
@@ -1220,7 +1640,7 @@ impl AttestationEngineContract {
         let violation_count = attestations
             .iter()
             .filter(|att| {
-                !att.is_compliant || att.attestation_type == String::from_str(&e, "violation")
+                !att.is_compliant || att.attestation_type == String::from_str(e, "violation")
             })
             .count() as i32;
         score = score
@@ -1233,13 +1653,7 @@ impl AttestationEngineContract {
         let max_loss_percent = commitment.rules.max_loss_percent as i128;
 
         if initial_value > 0 {
-            let drawdown_percent = {
-                let diff = initial_value.checked_sub(current_value).unwrap_or(0);
-                diff.checked_mul(100)
-                    .unwrap_or(0)
-                    .checked_div(initial_value)
-                    .unwrap_or(0)
-            };
+            let drawdown_percent = Self::calc_drawdown_percent(initial_value, current_value);
 
             if drawdown_percent > max_loss_percent {
                 let over_threshold = drawdown_percent.checked_sub(max_loss_percent).unwrap_or(0);
@@ -1252,10 +1666,10 @@ impl AttestationEngineContract {
         // Get fees from health metrics (which sums from attestations)
         // We'll calculate this from the attestations directly
         let total_fees: i128 = 0;
-        let fee_key = String::from_str(&e, "fee_amount");
+        let fee_key = String::from_str(e, "fee_amount");
 
         for att in attestations.iter() {
-            if att.attestation_type == String::from_str(&e, "fee_generation") {
+            if att.attestation_type == String::from_str(e, "fee_generation") {
                 // Extract fee from data map
                 // Since Map<String, String> stores strings, we need to parse
                 // For this implementation, we'll use a simplified approach:
@@ -1313,13 +1727,83 @@ impl AttestationEngineContract {
 
         // Emit compliance score update event
         e.events().publish(
-            (symbol_short!("ScoreUpd"), commitment_id),
+            (symbol_short!("ScoreUpd"), commitment_id.clone()),
             (score as u32, e.ledger().timestamp()),
         );
 
         score as u32
     }
 
+    /// Rescore commitments and overwrite their stored health metrics (admin only)
+    ///
+    /// Scores come from `calculate_compliance_score`'s formula applied to current core
+    /// state; stored fee, volatility and last-attestation figures are kept. Commitments
+    /// the core contract does not know are skipped and reported in the error list.
+    pub fn recompute_health_metrics(
+        e: Env,
+        caller: Address,
+        commitment_ids: Vec<String>,
+    ) -> Result<BatchResultVoid, AttestationError> {
+        require_admin(&e, &caller)?;
+
+        // Reentrancy protection
+        if e.storage().instance().has(&DataKey::ReentrancyGuard) {
+            panic!("Reentrancy detected");
+        }
+        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+
+        let mut errors = Vec::new(&e);
+        let mut updated: u32 = 0;
+        for (index, commitment_id) in commitment_ids.iter().enumerate() {
+            let commitment = match Self::try_core_get_commitment(&e, &commitment_id) {
+                Some(commitment) => commitment,
+                None => {
+                    errors.push_back(BatchError {
+                        index: index as u32,
+                        error_code: AttestationError::CommitmentNotFound as u32,
+                        context: String::from_str(&e, "commitment_not_found"),
+                    });
+                    continue;
+                }
+            };
+
+            let key = DataKey::HealthMetrics(commitment_id.clone());
+            let (fees_generated, volatility_exposure, last_attestation) =
+                match e.storage().persistent().get::<_, HealthMetrics>(&key) {
+                    Some(stored) => (
+                        stored.fees_generated,
+                        stored.volatility_exposure,
+                        stored.last_attestation,
+                    ),
+                    None => (0, 0, 0),
+                };
+            let metrics = HealthMetrics {
+                commitment_id: commitment_id.clone(),
+                current_value: commitment.current_value,
+                initial_value: commitment.amount,
+                drawdown_percent: Self::calc_drawdown_percent(
+                    commitment.amount,
+                    commitment.current_value,
+                ),
+                fees_generated,
+                volatility_exposure,
+                last_attestation,
+                compliance_score: Self::score_commitment(&e, &commitment_id, &commitment),
+            };
+            e.storage().persistent().set(&key, &metrics);
+            updated += 1;
+        }
+
+        e.storage().instance().remove(&DataKey::ReentrancyGuard);
+
+        e.events().publish(
+            (Symbol::new(&e, "HealthRecomputed"), caller),
+            (updated, errors.len(), e.ledger().timestamp()),
+        );
+
+        Ok(BatchResultVoid::partial(updated, errors))
+    }
+
     /// Get high-level protocol analytics combining commitment and attestation data.
     ///
     /// Returns:
@@ -1329,7 +1813,7 @@ impl AttestationEngineContract {
     /// - total_fees_generated
     pub fn get_protocol_statistics(e: Env) -> (u64, u64, u64, i128) {
         // Read commitment_core statistics
-        let commitment_core: Address = e.storage().instance().get(&DataKey::CoreContract).unwrap();
+        let commitment_core: Address = Self::core_contract(&e).unwrap();
 
         // get_total_commitments() on core contract
         let args = Vec::new(&e);
@@ -1368,6 +1852,162 @@ impl AttestationEngineContract {
         e.storage().instance().get(&key).unwrap_or(0)
     }
 
+    // ========================================================================
+    // Drawdown Alert Subscriptions
+    // ========================================================================
+
+    /// Subscribe to drawdown alerts for a commitment
+    ///
+    /// Subscribed addresses are included in the `AlertFired` event emitted when
+    /// `record_drawdown` detects a breach of the commitment's max loss.
+    /// Subscribing twice is a no-op. The commitment must exist in the core
+    /// contract.
+    pub fn subscribe_alerts(
+        e: Env,
+        subscriber: Address,
+        commitment_id: String,
+    ) -> Result<(), AttestationError> {
+        subscriber.require_auth();
+
+        if commitment_id.is_empty() {
+            return Err(AttestationError::InvalidCommitmentId);
+        }
+        if Self::try_core_get_commitment(&e, &commitment_id).is_none() {
+            return Err(AttestationError::CommitmentNotFound);
+        }
+
+        let key = DataKey::AlertSubscribers(commitment_id.clone());
+        let mut subscribers: Vec<Address> = e
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(&e));
+
+        if subscribers.contains(&subscriber) {
+            return Ok(());
+        }
+        if subscribers.len() >= MAX_ALERT_SUBSCRIBERS {
+            return Err(AttestationError::TooManySubscribers);
+        }
+
+        subscribers.push_back(subscriber.clone());
+        e.storage().persistent().set(&key, &subscribers);
+
+        e.events().publish(
+            (Symbol::new(&e, "AlertSubscribed"), commitment_id),
+            (subscriber, e.ledger().timestamp()),
+        );
+
+        Ok(())
+    }
+
+    /// Unsubscribe from drawdown alerts for a commitment
+    pub fn unsubscribe_alerts(
+        e: Env,
+        subscriber: Address,
+        commitment_id: String,
+    ) -> Result<(), AttestationError> {
+        subscriber.require_auth();
+        Self::remove_subscriber(&e, commitment_id, subscriber);
+        Ok(())
+    }
+
+    /// Remove a subscriber from a commitment's drawdown alerts (commitment owner or admin)
+    ///
+    /// Lets the owner clear unwanted subscribers that fill the
+    /// `MAX_ALERT_SUBSCRIBERS` slots. Removing an address that is not
+    /// subscribed is a no-op.
+    pub fn remove_alert_subscriber(
+        e: Env,
+        caller: Address,
+        commitment_id: String,
+        subscriber: Address,
+    ) -> Result<(), AttestationError> {
+        let is_owner = Self::try_core_get_commitment(&e, &commitment_id)
+            .is_some_and(|commitment| commitment.owner == caller);
+        if is_owner {
+            caller.require_auth();
+        } else {
+            require_admin(&e, &caller)?;
+        }
+
+        Self::remove_subscriber(&e, commitment_id, subscriber);
+        Ok(())
+    }
+
+    /// Drop `subscriber` from the alert list, emitting `AlertUnsubscribed` if it was on it
+    fn remove_subscriber(e: &Env, commitment_id: String, subscriber: Address) {
+        let key = DataKey::AlertSubscribers(commitment_id.clone());
+        let mut subscribers: Vec<Address> = e
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(e));
+
+        if let Some(index) = subscribers.first_index_of(&subscriber) {
+            subscribers.remove(index);
+            if subscribers.is_empty() {
+                e.storage().persistent().remove(&key);
+            } else {
+                e.storage().persistent().set(&key, &subscribers);
+            }
+
+            e.events().publish(
+                (Symbol::new(e, "AlertUnsubscribed"), commitment_id),
+                (subscriber, e.ledger().timestamp()),
+            );
+        }
+    }
+
+    /// Sequence number of the last attestation event (0 before any)
+    ///
+    /// `attest`, `batch_attest`, `record_fees` and `record_drawdown` events carry
+    /// the sequence as their last topic, so indexers can spot gaps.
+    pub fn get_event_sequence(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKey::EventSequence)
+            .unwrap_or(0)
+    }
+
+    /// Advance the event sequence and return the value for the next event
+    fn next_event_sequence(e: &Env) -> u64 {
+        let next = Self::get_event_sequence(e.clone()) + 1;
+        e.storage().instance().set(&DataKey::EventSequence, &next);
+        next
+    }
+
+    /// Get the drawdown alert subscribers for a commitment
+    pub fn get_alert_subscribers(e: Env, commitment_id: String) -> Vec<Address> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::AlertSubscribers(commitment_id))
+            .unwrap_or_else(|| Vec::new(&e))
+    }
+
+    /// Emit an `AlertFired` event carrying the subscriber list as a topic
+    fn fire_drawdown_alert(
+        e: &Env,
+        commitment_id: &String,
+        drawdown_percent: i128,
+        max_loss_percent: i128,
+    ) {
+        let subscribers = Self::get_alert_subscribers(e.clone(), commitment_id.clone());
+        if subscribers.is_empty() {
+            return;
+        }
+
+        e.events().publish(
+            (
+                Symbol::new(e, "AlertFired"),
+                commitment_id.clone(),
+                subscribers,
+                Self::next_event_sequence(e),
+            ),
+            (drawdown_percent, max_loss_percent, e.ledger().timestamp()),
+        );
+    }
+
     // ========================================================================
     // Batch Operations
     // ========================================================================
@@ -1506,6 +2146,26 @@ impl AttestationEngineContract {
                 }
             }
 
+            // Validate data size
+            if !Self::data_within_limits(&e, &params.data) {
+                if mode == BatchMode::Atomic {
+                    e.storage().instance().remove(&DataKey::ReentrancyGuard);
+                    errors.push_back(BatchError {
+                        index: i,
+                        error_code: AttestationError::DataTooLarge as u32,
+                        context: String::from_str(&e, "data_too_large"),
+                    });
+                    return BatchResultVoid::failure(&e, errors);
+                } else {
+                    errors.push_back(BatchError {
+                        index: i,
+                        error_code: AttestationError::DataTooLarge as u32,
+                        context: String::from_str(&e, "data_too_large"),
+                    });
+                    continue;
+                }
+            }
+
             // Validate data format
             if !Self::validate_attestation_data(&e, &params.attestation_type, &params.data) {
                 if mode == BatchMode::Atomic {
@@ -1567,7 +2227,12 @@ impl AttestationEngineContract {
 
             // Emit event
             e.events().publish(
-                (Symbol::new(&e, "AttestationRecorded"), params.commitment_id.clone(), caller.clone()),
+                (
+                    Symbol::new(&e, "AttestationRecorded"),
+                    params.commitment_id.clone(),
+                    caller.clone(),
+                    Self::next_event_sequence(&e),
+                ),
                 (params.attestation_type.clone(), params.is_compliant, timestamp)
             );
         }
@@ -1577,13 +2242,18 @@ impl AttestationEngineContract {
         e.storage().instance().set(&DataKey::TotalViolations, &total_violations);
         let verifier_key = DataKey::VerifierAttestationCount(caller.clone());
         e.storage().instance().set(&verifier_key, &verifier_count);
+        Self::accrue_verifier_rewards(&e, &caller, results.len());
 
         // Clear reentrancy guard
         e.storage().instance().remove(&DataKey::ReentrancyGuard);
 
         // Emit batch event
         e.events().publish(
-            (Symbol::new(&e, "BatchAttest"), batch_size),
+            (
+                Symbol::new(&e, "BatchAttest"),
+                batch_size,
+                Self::next_event_sequence(&e),
+            ),
             (results.len(), errors.len(), timestamp)
         );
 
@@ -1748,6 +2418,86 @@ impl AttestationEngineContract {
             .get(&DataKey::CollectedFees(asset_address))
             .unwrap_or(0)
     }
+
+    // ========================================================================
+    // Verifier rewards
+    // ========================================================================
+
+    /// Set the reward accrued by a verifier per recorded attestation, and the
+    /// token it is paid in. Admin only. Set amount to 0 to stop accruing.
+    ///
+    /// Rewards are paid from this contract's balance of `asset`; the admin is
+    /// responsible for funding it.
+    pub fn set_verifier_reward(
+        e: Env,
+        caller: Address,
+        amount: i128,
+        asset: Address,
+    ) -> Result<(), AttestationError> {
+        require_admin(&e, &caller)?;
+        if amount < 0 {
+            return Err(AttestationError::InvalidFeeAmount);
+        }
+        e.storage().instance().set(&DataKey::VerifierRewardAmount, &amount);
+        e.storage().instance().set(&DataKey::VerifierRewardAsset, &asset);
+        e.events().publish(
+            (Symbol::new(&e, "VerifierRewardSet"), caller),
+            (amount, asset, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
+
+    /// Get verifier reward per attestation (amount, asset). (0, None) if not set.
+    pub fn get_verifier_reward(e: Env) -> (i128, Option<Address>) {
+        let amount: i128 = e.storage().instance().get(&DataKey::VerifierRewardAmount).unwrap_or(0);
+        let asset: Option<Address> = e.storage().instance().get(&DataKey::VerifierRewardAsset);
+        (amount, asset)
+    }
+
+    /// Get unclaimed rewards accrued by a verifier.
+    pub fn get_verifier_rewards(e: Env, verifier: Address) -> i128 {
+        e.storage()
+            .instance()
+            .get(&DataKey::VerifierRewards(verifier))
+            .unwrap_or(0)
+    }
+
+    /// Transfer a verifier's accrued rewards to them and reset the counter.
+    /// Returns the amount paid.
+    pub fn claim_verifier_rewards(e: Env, verifier: Address) -> Result<i128, AttestationError> {
+        verifier.require_auth();
+        let key = DataKey::VerifierRewards(verifier.clone());
+        let accrued: i128 = e.storage().instance().get(&key).unwrap_or(0);
+        if accrued <= 0 {
+            return Err(AttestationError::NoRewardsToClaim);
+        }
+        let asset: Address = e
+            .storage()
+            .instance()
+            .get(&DataKey::VerifierRewardAsset)
+            .ok_or(AttestationError::RewardAssetNotSet)?;
+        e.storage().instance().remove(&key);
+        let token_client = token::Client::new(&e, &asset);
+        token_client.transfer(&e.current_contract_address(), &verifier, &accrued);
+        e.events().publish(
+            (Symbol::new(&e, "VerifierRewardsClaimed"), verifier),
+            (asset, accrued, e.ledger().timestamp()),
+        );
+        Ok(accrued)
+    }
+
+    /// Credit `verifier` with the configured reward for `attestations` attestations.
+    fn accrue_verifier_rewards(e: &Env, verifier: &Address, attestations: u32) {
+        let reward: i128 = e.storage().instance().get(&DataKey::VerifierRewardAmount).unwrap_or(0);
+        if reward <= 0 || attestations == 0 {
+            return;
+        }
+        let key = DataKey::VerifierRewards(verifier.clone());
+        let accrued: i128 = e.storage().instance().get(&key).unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&key, &(accrued + reward * attestations as i128));
+    }
 }
 
 fn read_version(e: &Env) -> u32 {
@@ -33,6 +33,7 @@ fn store_core_commitment(
             early_exit_penalty: 10,
             min_fee_threshold: 1000,
             grace_period_days: 3,
+            max_loss_bps: 0,
         },
         amount,
         asset_address: Address::generate(e),
@@ -55,23 +56,87 @@ fn setup_test_env() -> (Env, Address, Address, Address) {
     let e = Env::default();
     e.mock_all_auths();
     let admin = Address::generate(&e);
-    let commitment_core_id = e.register_contract(None, MockCoreContract);
-    let _contract_id = e.register_contract(None, AttestationEngineContract);
 
-    e.as_contract(&_contract_id, || {
-        AttestationEngineContract::initialize(e.clone(), admin, commitment_core_id);
+    // Register and initialize commitment_core contract
+    let commitment_core_id = e.register_contract(None, CommitmentCoreContract);
+    let nft_contract = Address::generate(&e);
+
+    // Initialize commitment_core contract
+    e.as_contract(&commitment_core_id, || {
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
+    });
+
+    // Register attestation_engine contract
+    let contract_id = e.register_contract(None, AttestationEngineContract);
+
+    // Initialize attestation_engine contract
+    e.as_contract(&contract_id, || {
+        AttestationEngineContract::initialize(e.clone(), admin.clone(), commitment_core_id.clone())
+            .unwrap();
     });
+
+    (e, admin, commitment_core_id, contract_id)
+}
+
+/// Minimal core stand-in whose commitments and violation flags tests can set directly
+#[contract]
+pub struct MockCoreContract;
+
+#[contractimpl]
+impl MockCoreContract {
+    /// Make every read panic, to prove a caller's path makes no core call
+    pub fn set_unavailable(e: Env, unavailable: bool) {
+        e.storage()
+            .instance()
+            .set(&symbol_short!("down"), &unavailable);
+    }
+
+    fn require_available(e: &Env) {
+        if e.storage().instance().get(&symbol_short!("down")).unwrap_or(false) {
+            panic!("core unavailable");
+        }
+    }
+
+    pub fn set_commitment(e: Env, commitment_id: String, commitment: Commitment) {
+        e.storage()
+            .instance()
+            .set(&(symbol_short!("cmt"), commitment_id), &commitment);
+    }
+
+    pub fn get_commitment(e: Env, commitment_id: String) -> Commitment {
+        Self::require_available(&e);
+        e.storage()
+            .instance()
+            .get(&(symbol_short!("cmt"), commitment_id))
+            .expect("Commitment not found")
+    }
+
+    pub fn set_violations(e: Env, commitment_id: String, has_violations: bool) {
+        e.storage()
+            .instance()
+            .set(&(symbol_short!("viol"), commitment_id), &has_violations);
+    }
+
+    pub fn check_violations(e: Env, commitment_id: String) -> bool {
+        Self::require_available(&e);
+        e.storage()
+            .instance()
+            .get(&(symbol_short!("viol"), commitment_id))
+            .unwrap_or(false)
+    }
 }
 
 #[test]
 fn test_attest() {
     let e = Env::default();
+    e.mock_all_auths();
     let verified_by = Address::generate(&e);
     let core_id = e.register_contract(None, MockCoreContract);
     let _contract_id = e.register_contract(None, AttestationEngineContract);
 
     e.as_contract(&_contract_id, || {
-        AttestationEngineContract::initialize(e.clone(), Address::generate(&e), core_id.clone());
+        AttestationEngineContract::initialize(e.clone(), verified_by.clone(), core_id.clone())
+            .unwrap();
     });
 
     let commitment_id = String::from_str(&e, "c1");
@@ -83,6 +148,8 @@ fn test_attest() {
         commitment_type: String::from_str(&e, "safe"),
         early_exit_penalty: 0,
         min_fee_threshold: 0,
+        grace_period_days: 0,
+        max_loss_bps: 0,
     };
     let commitment = Commitment {
         commitment_id: commitment_id.clone(),
@@ -106,11 +173,13 @@ fn test_attest() {
     e.as_contract(&_contract_id, || {
         AttestationEngineContract::attest(
             e.clone(),
+            verified_by,
             commitment_id.clone(),
             String::from_str(&e, "health_check"),
             data,
-            verified_by,
-        );
+            true,
+        )
+        .unwrap();
     });
 
     let atts = e.as_contract(&_contract_id, || {
@@ -122,15 +191,17 @@ fn test_attest() {
 #[test]
 fn test_verify_compliance() {
     let e = Env::default();
+    e.mock_all_auths();
     // Set a deterministic ledger timestamp for duration checks.
     e.ledger().with_mut(|li| {
         li.timestamp = 50;
     });
 
+    let admin = Address::generate(&e);
     let core_id = e.register_contract(None, MockCoreContract);
     let _contract_id = e.register_contract(None, AttestationEngineContract);
     e.as_contract(&_contract_id, || {
-        AttestationEngineContract::initialize(e.clone(), Address::generate(&e), core_id.clone());
+        AttestationEngineContract::initialize(e.clone(), admin.clone(), core_id.clone()).unwrap();
     });
 
     let commitment_id = String::from_str(&e, "c1");
@@ -142,6 +213,8 @@ fn test_verify_compliance() {
         commitment_type: String::from_str(&e, "safe"),
         early_exit_penalty: 0,
         min_fee_threshold: 100,
+        grace_period_days: 0,
+        max_loss_bps: 0,
     };
 
     // Happy path: in-range drawdown, not expired, fees meet threshold, no violations.
@@ -162,7 +235,8 @@ fn test_verify_compliance() {
         MockCoreContract::set_violations(e.clone(), commitment_id.clone(), false);
     });
     e.as_contract(&_contract_id, || {
-        AttestationEngineContract::record_fees(e.clone(), commitment_id.clone(), 100);
+        AttestationEngineContract::record_fees(e.clone(), admin.clone(), commitment_id.clone(), 100)
+            .unwrap();
     });
 
     assert!(e.as_contract(&_contract_id, || {
@@ -245,31 +319,11 @@ fn test_verify_compliance() {
     assert!(e.as_contract(&_contract_id, || {
         AttestationEngineContract::verify_compliance(e.clone(), commitment_id3)
     }));
-
-    // Register and initialize commitment_core contract
-    let commitment_core_id = e.register_contract(None, CommitmentCoreContract);
-    let nft_contract = Address::generate(&e);
-
-    // Initialize commitment_core contract
-    e.as_contract(&commitment_core_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
-    });
-
-    // Register attestation_engine contract
-    let contract_id = e.register_contract(None, AttestationEngineContract);
-
-    // Initialize attestation_engine contract
-    e.as_contract(&contract_id, || {
-        AttestationEngineContract::initialize(e.clone(), admin.clone(), commitment_core_id.clone())
-            .unwrap();
-    });
-
-    (e, admin, commitment_core_id, contract_id)
 }
 
 #[test]
 fn test_initialize() {
-    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    let (e, _admin, _commitment_core, contract_id) = setup_test_env();
 
     // Verify initialization by checking that we can call other functions
     // (indirect verification through storage access)
@@ -330,6 +384,112 @@ fn test_fee_withdraw_requires_recipient() {
     client.withdraw_fees(&admin, &asset, &100i128);
 }
 
+#[test]
+fn test_verifier_rewards_accrue_and_claim() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    let reward_asset = e
+        .register_stellar_asset_contract_v2(admin.clone())
+        .address();
+    token::StellarAssetClient::new(&e, &reward_asset).mint(&contract_id, &1_000);
+    client.set_verifier_reward(&admin, &10i128, &reward_asset);
+
+    let owner = Address::generate(&e);
+    store_core_commitment(&e, &commitment_core, "reward_c", &owner, 1000, 1000, 10, 30, 1000);
+    let commitment_id = String::from_str(&e, "reward_c");
+    let health_check = String::from_str(&e, "health_check");
+
+    client.attest(&admin, &commitment_id, &health_check, &Map::new(&e), &true);
+    client.attest(&admin, &commitment_id, &health_check, &Map::new(&e), &true);
+    let mut params_list = Vec::new(&e);
+    for _ in 0..2 {
+        params_list.push_back(AttestParams {
+            commitment_id: commitment_id.clone(),
+            attestation_type: health_check.clone(),
+            data: Map::new(&e),
+            is_compliant: true,
+        });
+    }
+    client.batch_attest(&admin, &params_list, &BatchMode::Atomic);
+    assert_eq!(client.get_verifier_rewards(&admin), 40);
+
+    assert_eq!(client.claim_verifier_rewards(&admin), 40);
+    assert_eq!(client.get_verifier_rewards(&admin), 0);
+    assert_eq!(token::Client::new(&e, &reward_asset).balance(&admin), 40);
+    assert_eq!(
+        client.try_claim_verifier_rewards(&admin),
+        Err(Ok(AttestationError::NoRewardsToClaim))
+    );
+}
+
+#[test]
+fn test_verifier_rewards_default_to_zero() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    assert_eq!(client.get_verifier_reward(), (0, None));
+
+    let owner = Address::generate(&e);
+    store_core_commitment(&e, &commitment_core, "no_reward", &owner, 1000, 1000, 10, 30, 1000);
+    client.attest(
+        &admin,
+        &String::from_str(&e, "no_reward"),
+        &String::from_str(&e, "health_check"),
+        &Map::new(&e),
+        &true,
+    );
+    assert_eq!(client.get_verifier_rewards(&admin), 0);
+}
+
+#[test]
+fn test_compliance_certificate_hash_changes_with_new_attestation() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    let owner = Address::generate(&e);
+    store_core_commitment(&e, &commitment_core, "cert_c", &owner, 1000, 1000, 10, 30, 1000);
+    let commitment_id = String::from_str(&e, "cert_c");
+    let health_check = String::from_str(&e, "health_check");
+
+    e.ledger().with_mut(|l| l.timestamp = 100);
+    client.attest(&admin, &commitment_id, &health_check, &Map::new(&e), &true);
+    let first = client.get_compliance_certificate(&commitment_id);
+    assert_eq!(first.total_attestations, 1);
+    assert_eq!(first.violation_attestations, 0);
+    assert_eq!(first.last_attestation, 100);
+    assert!(client.verify_certificate(&first));
+
+    e.ledger().with_mut(|l| l.timestamp = 200);
+    client.attest(&admin, &commitment_id, &health_check, &Map::new(&e), &false);
+    let second = client.get_compliance_certificate(&commitment_id);
+    assert_eq!(second.total_attestations, 2);
+    assert_eq!(second.violation_attestations, 1);
+    assert_eq!(second.last_attestation, 200);
+    assert_ne!(second.hash, first.hash);
+
+    // The old bundle no longer matches the attestation history
+    assert!(!client.verify_certificate(&first));
+    assert!(client.verify_certificate(&second));
+}
+
+#[test]
+fn test_verify_certificate_rejects_tampered_hash() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    let owner = Address::generate(&e);
+    store_core_commitment(&e, &commitment_core, "cert_t", &owner, 1000, 1000, 10, 30, 1000);
+    let commitment_id = String::from_str(&e, "cert_t");
+    client.attest(
+        &admin,
+        &commitment_id,
+        &String::from_str(&e, "health_check"),
+        &Map::new(&e),
+        &true,
+    );
+
+    let mut certificate = client.get_compliance_certificate(&commitment_id);
+    certificate.hash = BytesN::from_array(&e, &[0u8; 32]);
+    assert!(!client.verify_certificate(&certificate));
+}
+
 #[test]
 fn test_get_attestations_empty() {
     let (e, _admin, _commitment_core, contract_id) = setup_test_env();
@@ -634,7 +794,7 @@ fn test_attest_and_get_metrics() {
         30,
         1000,
     );
-    let attestation_type = String::from_str(&e, "general");
+    let attestation_type = String::from_str(&e, "health_check");
     let mut data = Map::new(&e);
     data.set(
         String::from_str(&e, "note"),
@@ -716,6 +876,45 @@ fn test_attest_reentrancy_protection() {
     });
 }
 
+#[test]
+fn test_attest_non_compliant_is_stored_and_counted() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let commitment_id = String::from_str(&e, "test_commitment");
+    let owner = Address::generate(&e);
+    store_core_commitment(
+        &e,
+        &commitment_core,
+        "test_commitment",
+        &owner,
+        1000,
+        1000,
+        10,
+        30,
+        1000,
+    );
+
+    client.attest(
+        &admin,
+        &commitment_id,
+        &String::from_str(&e, "health_check"),
+        &Map::new(&e),
+        &false,
+    );
+
+    let attestations = client.get_attestations(&commitment_id);
+    assert_eq!(attestations.len(), 1);
+    let stored = attestations.get(0).unwrap();
+    assert!(!stored.is_compliant);
+    assert_eq!(stored.commitment_id, commitment_id);
+    assert_eq!(stored.verified_by, admin);
+
+    let (_, total_attestations, total_violations, _) = client.get_protocol_statistics();
+    assert_eq!(total_attestations, 1);
+    assert_eq!(total_violations, 1);
+}
+
 // ============================================================================
 // Access Control Tests
 // ============================================================================
@@ -779,6 +978,32 @@ fn test_remove_verifier_success() {
     assert!(!is_verifier);
 }
 
+#[test]
+fn test_get_verifiers_tracks_whitelist() {
+    let (e, admin, _commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    assert_eq!(client.get_verifiers().len(), 0);
+
+    let first = Address::generate(&e);
+    let second = Address::generate(&e);
+    let third = Address::generate(&e);
+    client.add_verifier(&admin, &first);
+    client.add_verifier(&admin, &second);
+    client.add_verifier(&admin, &third);
+    // Re-adding must not duplicate the entry
+    client.add_verifier(&admin, &second);
+    assert_eq!(
+        client.get_verifiers(),
+        vec![&e, first.clone(), second.clone(), third.clone()]
+    );
+
+    client.remove_verifier(&admin, &second);
+    assert_eq!(client.get_verifiers(), vec![&e, first, third]);
+    assert!(!client.is_verifier(&second));
+}
+
 #[test]
 fn test_attest_unauthorized_caller() {
     let (e, _admin, _commitment_core, contract_id) = setup_test_env();
@@ -837,25 +1062,12 @@ fn test_attest_authorized_verifier() {
         1000,
     );
 
-    // record_fees requires caller (admin)
-    client.record_fees(&admin, &commitment_id, &100);
-
     // Add verifier
     e.as_contract(&contract_id, || {
         AttestationEngineContract::add_verifier(e.clone(), admin.clone(), verifier.clone())
             .unwrap();
     });
 
-    assert_eq!(last_event.0, contract_id);
-    assert_eq!(
-        last_event.1,
-        vec![
-            &e,
-            symbol_short!("FeeRec").into_val(&e),
-            commitment_id.into_val(&e)
-        ]
-    );
-
     // Use invalid attestation type
     let attestation_type = String::from_str(&e, "invalid_type");
     let data = Map::new(&e);
@@ -908,15 +1120,7 @@ fn test_attest_invalid_data_violation() {
         )
     });
 
-    assert_eq!(last_event.0, contract_id);
-    assert_eq!(
-        last_event.1,
-        vec![
-            &e,
-            symbol_short!("Drawdown").into_val(&e),
-            commitment_id.into_val(&e)
-        ]
-    );
+    assert_eq!(result, Err(AttestationError::InvalidAttestationData));
 
     // fee_generation requires "fee_amount" field
     let attestation_type = String::from_str(&e, "fee_generation");
@@ -1254,6 +1458,68 @@ fn test_health_metrics_updated_after_attestation() {
     assert_eq!(metrics.last_attestation, 10000);
 }
 
+#[test]
+fn test_stored_health_metrics_track_core_values() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.ledger().with_mut(|li| li.timestamp = 10000);
+
+    let commitment_id = String::from_str(&e, "test_commitment");
+    let owner = Address::generate(&e);
+    store_core_commitment(
+        &e,
+        &commitment_core,
+        "test_commitment",
+        &owner,
+        1000,
+        950,
+        10,
+        30,
+        1000,
+    );
+
+    let attest = |e: &Env| {
+        e.as_contract(&contract_id, || {
+            AttestationEngineContract::attest(
+                e.clone(),
+                admin.clone(),
+                commitment_id.clone(),
+                String::from_str(e, "health_check"),
+                Map::new(e),
+                true,
+            )
+            .unwrap();
+        });
+    };
+    let stored = |e: &Env| {
+        e.as_contract(&contract_id, || {
+            AttestationEngineContract::get_stored_health_metrics(e.clone(), commitment_id.clone())
+        })
+        .unwrap()
+    };
+
+    attest(&e);
+    let metrics = stored(&e);
+    assert_eq!(metrics.initial_value, 1000);
+    assert_eq!(metrics.current_value, 950);
+
+    // Later attestations pick up the core's latest value
+    store_core_commitment(
+        &e,
+        &commitment_core,
+        "test_commitment",
+        &owner,
+        1000,
+        1100,
+        10,
+        30,
+        1000,
+    );
+    attest(&e);
+    let metrics = stored(&e);
+    assert_eq!(metrics.initial_value, 1000);
+    assert_eq!(metrics.current_value, 1100);
+}
+
 #[test]
 fn test_compliance_score_decreases_on_violation() {
     let (e, admin, _commitment_core, contract_id) = setup_test_env();
@@ -1523,13 +1789,13 @@ fn test_attest_event() {
             &e,
             Symbol::new(&e, "AttestationRecorded").into_val(&e),
             commitment_id.into_val(&e),
-            verified_by.into_val(&e)
+            verified_by.into_val(&e),
+            client.get_event_sequence().into_val(&e)
         ]
     );
 }
 
 #[test]
-#[should_panic(expected = "Rate limit exceeded")]
 fn test_attest_rate_limit_enforced() {
     let (e, admin, _commitment_core, contract_id) = setup_test_env();
 
@@ -1577,17 +1843,33 @@ fn test_attest_rate_limit_enforced() {
         .unwrap();
     });
 
-    // Second attestation within same window should panic
-    e.as_contract(&contract_id, || {
-        let _ = AttestationEngineContract::attest(
+    // Second attestation within same window is rejected
+    let result = e.as_contract(&contract_id, || {
+        AttestationEngineContract::attest(
             e.clone(),
             verifier.clone(),
             commitment_id.clone(),
             attestation_type.clone(),
             data.clone(),
             true,
-        );
+        )
     });
+    assert_eq!(result, Err(AttestationError::RateLimited));
+
+    // Called in-context, so the event is visible here; a real invocation
+    // returning this error would roll it back
+    let events = e.events().all();
+    let last_event = events.last().unwrap();
+    assert_eq!(last_event.0, contract_id);
+    assert_eq!(
+        last_event.1,
+        vec![
+            &e,
+            Symbol::new(&e, "RateLimited").into_val(&e),
+            verifier.into_val(&e),
+            Symbol::new(&e, "attest").into_val(&e)
+        ]
+    );
 }
 
 #[test]
@@ -1673,7 +1955,8 @@ fn test_record_fees_event() {
         vec![
             &e,
             Symbol::new(&e, "FeeRecorded").into_val(&e),
-            commitment_id.into_val(&e)
+            commitment_id.into_val(&e),
+            client.get_event_sequence().into_val(&e)
         ]
     );
     let event_data: (i128, u64) = last_event.2.into_val(&e);
@@ -1681,12 +1964,11 @@ fn test_record_fees_event() {
 }
 
 #[test]
-fn test_record_drawdown_event() {
+fn test_record_fees_stores_fee_attestation() {
     let (e, admin, commitment_core, contract_id) = setup_test_env();
     e.mock_all_auths();
     let client = AttestationEngineContractClient::new(&e, &contract_id);
 
-    // Need to store a commitment first because record_drawdown fetches it
     let commitment_id = String::from_str(&e, "test_id");
     let owner = Address::generate(&e);
     store_core_commitment(
@@ -1701,27 +1983,119 @@ fn test_record_drawdown_event() {
         1000,
     );
 
-    // record_drawdown requires caller (admin) and drawdown_percent
-    client.record_drawdown(&admin, &commitment_id, &5); // 5% drawdown
-
-    let events = e.events().all();
-    let last_event = events.last().unwrap();
+    client.record_fees(&admin, &commitment_id, &250);
 
-    assert_eq!(last_event.0, contract_id);
+    let attestations = client.get_attestations(&commitment_id);
+    assert_eq!(attestations.len(), 1);
+    let attestation = attestations.get(0).unwrap();
+    assert_eq!(attestation.attestation_type, String::from_str(&e, "fee_generation"));
     assert_eq!(
-        last_event.1,
-        vec![
-            &e,
-            Symbol::new(&e, "DrawdownRecorded").into_val(&e),
-            commitment_id.into_val(&e)
-        ]
+        attestation.data.get(String::from_str(&e, "fee_amount")),
+        Some(String::from_str(&e, "250"))
     );
-    let event_data: (i128, bool, u64) = last_event.2.into_val(&e);
+    assert!(attestation.is_compliant);
+    assert_eq!(client.get_attestation_count(&commitment_id), 1);
+    assert_eq!(client.get_health_metrics(&commitment_id).fees_generated, 250);
+}
+
+#[test]
+fn test_record_drawdown_event() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    // Need to store a commitment first because record_drawdown fetches it
+    let commitment_id = String::from_str(&e, "test_id");
+    let owner = Address::generate(&e);
+    store_core_commitment(
+        &e,
+        &commitment_core,
+        "test_id",
+        &owner,
+        1000,
+        1000,
+        10,
+        30,
+        1000,
+    );
+
+    // record_drawdown requires caller (admin) and drawdown_percent
+    client.record_drawdown(&admin, &commitment_id, &5); // 5% drawdown
+
+    let events = e.events().all();
+    let last_event = events.last().unwrap();
+
+    assert_eq!(last_event.0, contract_id);
+    assert_eq!(
+        last_event.1,
+        vec![
+            &e,
+            Symbol::new(&e, "DrawdownRecorded").into_val(&e),
+            commitment_id.into_val(&e),
+            client.get_event_sequence().into_val(&e)
+        ]
+    );
+    let event_data: (i128, bool, u64) = last_event.2.into_val(&e);
     // (drawdown_percent, is_compliant, timestamp)
     assert_eq!(event_data.0, 5);
     assert_eq!(event_data.1, true);
 }
 
+#[test]
+fn test_record_drawdown_unknown_commitment() {
+    let (e, admin, _commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    assert_eq!(
+        client.try_record_drawdown(&admin, &String::from_str(&e, "missing"), &5),
+        Err(Ok(AttestationError::CommitmentNotFound))
+    );
+}
+
+#[test]
+fn test_record_drawdown_breach_stores_single_attestation() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let commitment_id = String::from_str(&e, "test_id");
+    let owner = Address::generate(&e);
+    store_core_commitment(
+        &e,
+        &commitment_core,
+        "test_id",
+        &owner,
+        1000,
+        1000,
+        10,
+        30,
+        1000,
+    );
+
+    // 15% drawdown breaches the 10% loss limit
+    client.record_drawdown(&admin, &commitment_id, &15);
+
+    let attestations = client.get_attestations(&commitment_id);
+    assert_eq!(attestations.len(), 1);
+    let attestation = attestations.get(0).unwrap();
+    assert_eq!(attestation.attestation_type, String::from_str(&e, "drawdown"));
+    assert!(!attestation.is_compliant);
+    assert_eq!(client.get_attestation_count(&commitment_id), 1);
+
+    // The breach is reported as an event, not as a second stored record
+    let violation_events = e
+        .events()
+        .all()
+        .iter()
+        .filter(|event| {
+            let name: Option<Symbol> = event.1.get(0).and_then(|topic| topic.try_into_val(&e).ok());
+            name == Some(Symbol::new(&e, "ViolationDetected"))
+        })
+        .count();
+    assert_eq!(violation_events, 1);
+}
+
 #[test]
 fn test_calculate_compliance_score_event() {
     let (e, _admin, commitment_core, contract_id) = setup_test_env();
@@ -1759,3 +2133,1047 @@ fn test_calculate_compliance_score_event() {
     let event_data: (u32, u64) = last_event.2.into_val(&e);
     assert_eq!(event_data.0, 100);
 }
+
+// ============================================================================
+// Drawdown Alert Subscription Tests
+// ============================================================================
+
+fn find_alert_fired(e: &Env) -> Option<Vec<Address>> {
+    let alert_topic = Symbol::new(e, "AlertFired");
+    e.events().all().iter().find_map(|ev| {
+        let topic: Result<Symbol, _> = ev.1.get(0).unwrap().try_into_val(e);
+        match topic {
+            Ok(topic) if topic == alert_topic => Some(ev.1.get(2).unwrap().into_val(e)),
+            _ => None,
+        }
+    })
+}
+
+#[test]
+fn test_alert_fired_includes_only_current_subscribers() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let commitment_id = String::from_str(&e, "test_id");
+    let owner = Address::generate(&e);
+    store_core_commitment(
+        &e,
+        &commitment_core,
+        "test_id",
+        &owner,
+        1000,
+        500,
+        10,
+        30,
+        1000,
+    );
+
+    let subscribed = Address::generate(&e);
+    let unsubscribed = Address::generate(&e);
+    client.subscribe_alerts(&subscribed, &commitment_id);
+    client.subscribe_alerts(&unsubscribed, &commitment_id);
+    client.unsubscribe_alerts(&unsubscribed, &commitment_id);
+
+    let subscribers = client.get_alert_subscribers(&commitment_id);
+    assert_eq!(subscribers, vec![&e, subscribed.clone()]);
+
+    // 50% drawdown breaches the 10% max loss
+    client.record_drawdown(&admin, &commitment_id, &50);
+
+    let fired_to = find_alert_fired(&e).expect("AlertFired event not emitted");
+    assert!(fired_to.contains(&subscribed));
+    assert!(!fired_to.contains(&unsubscribed));
+}
+
+#[test]
+fn test_no_alert_without_breach() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let commitment_id = String::from_str(&e, "test_id");
+    let owner = Address::generate(&e);
+    store_core_commitment(
+        &e,
+        &commitment_core,
+        "test_id",
+        &owner,
+        1000,
+        1000,
+        10,
+        30,
+        1000,
+    );
+
+    client.subscribe_alerts(&Address::generate(&e), &commitment_id);
+    client.record_drawdown(&admin, &commitment_id, &5);
+
+    assert!(find_alert_fired(&e).is_none());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")] // TooManySubscribers
+fn test_subscribe_alerts_cap() {
+    let (e, _admin, commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let commitment_id = String::from_str(&e, "test_id");
+    let owner = Address::generate(&e);
+    store_core_commitment(&e, &commitment_core, "test_id", &owner, 1000, 1000, 10, 30, 1000);
+    for _ in 0..=MAX_ALERT_SUBSCRIBERS {
+        client.subscribe_alerts(&Address::generate(&e), &commitment_id);
+    }
+}
+
+#[test]
+fn test_subscribe_alerts_requires_existing_commitment() {
+    let (e, _admin, _commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let commitment_id = String::from_str(&e, "missing");
+    assert_eq!(
+        client.try_subscribe_alerts(&Address::generate(&e), &commitment_id),
+        Err(Ok(AttestationError::CommitmentNotFound))
+    );
+    assert_eq!(client.get_alert_subscribers(&commitment_id).len(), 0);
+}
+
+#[test]
+fn test_remove_alert_subscriber_owner_or_admin_only() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let commitment_id = String::from_str(&e, "test_id");
+    let owner = Address::generate(&e);
+    store_core_commitment(&e, &commitment_core, "test_id", &owner, 1000, 1000, 10, 30, 1000);
+
+    let spammer = Address::generate(&e);
+    let other = Address::generate(&e);
+    client.subscribe_alerts(&spammer, &commitment_id);
+    client.subscribe_alerts(&other, &commitment_id);
+
+    assert_eq!(
+        client.try_remove_alert_subscriber(&other, &commitment_id, &spammer),
+        Err(Ok(AttestationError::Unauthorized))
+    );
+
+    client.remove_alert_subscriber(&owner, &commitment_id, &spammer);
+    assert_eq!(client.get_alert_subscribers(&commitment_id), vec![&e, other.clone()]);
+
+    client.remove_alert_subscriber(&admin, &commitment_id, &other);
+    assert_eq!(client.get_alert_subscribers(&commitment_id).len(), 0);
+}
+
+// ============================================================================
+// Compliance Semantics Tests
+// ============================================================================
+
+#[test]
+fn test_verify_compliance_unknown_commitment_is_false() {
+    let (e, _admin, _commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    assert!(!client.verify_compliance(&String::from_str(&e, "missing")));
+}
+
+#[test]
+fn test_verify_compliance_flags_core_violation() {
+    let e = Env::default();
+    let core_id = e.register_contract(None, MockCoreContract);
+    let contract_id = e.register_contract(None, AttestationEngineContract);
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    client.initialize(&Address::generate(&e), &core_id);
+
+    let commitment_id = String::from_str(&e, "c1");
+    let commitment = Commitment {
+        commitment_id: commitment_id.clone(),
+        owner: Address::generate(&e),
+        nft_token_id: 1,
+        rules: CommitmentRules {
+            duration_days: 0,
+            max_loss_percent: 20,
+            commitment_type: String::from_str(&e, "safe"),
+            early_exit_penalty: 0,
+            min_fee_threshold: 0,
+            grace_period_days: 0,
+            max_loss_bps: 0,
+        },
+        amount: 1_000,
+        asset_address: Address::generate(&e),
+        created_at: 0,
+        expires_at: 0,
+        current_value: 1_000,
+        status: String::from_str(&e, "active"),
+    };
+    e.as_contract(&core_id, || {
+        MockCoreContract::set_commitment(e.clone(), commitment_id.clone(), commitment);
+    });
+    assert!(client.verify_compliance(&commitment_id));
+
+    e.as_contract(&core_id, || {
+        MockCoreContract::set_violations(e.clone(), commitment_id.clone(), true);
+    });
+    assert!(!client.verify_compliance(&commitment_id));
+}
+
+/// Store an active no-expiry commitment in the mock core with the given current value
+fn set_mock_commitment(e: &Env, core_id: &Address, id: &str, current_value: i128) -> String {
+    let commitment_id = String::from_str(e, id);
+    let commitment = Commitment {
+        commitment_id: commitment_id.clone(),
+        owner: Address::generate(e),
+        nft_token_id: 1,
+        rules: CommitmentRules {
+            duration_days: 0,
+            max_loss_percent: 20,
+            commitment_type: String::from_str(e, "safe"),
+            early_exit_penalty: 0,
+            min_fee_threshold: 0,
+            grace_period_days: 0,
+            max_loss_bps: 0,
+        },
+        amount: 1_000,
+        asset_address: Address::generate(e),
+        created_at: 0,
+        expires_at: 0,
+        current_value,
+        status: String::from_str(e, "active"),
+    };
+    e.as_contract(core_id, || {
+        MockCoreContract::set_commitment(e.clone(), commitment_id.clone(), commitment);
+    });
+    commitment_id
+}
+
+#[test]
+fn test_batch_verify_compliance_preserves_input_order() {
+    let e = Env::default();
+    let core_id = e.register_contract(None, MockCoreContract);
+    let contract_id = e.register_contract(None, AttestationEngineContract);
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    client.initialize(&Address::generate(&e), &core_id);
+
+    let healthy = set_mock_commitment(&e, &core_id, "healthy", 1_000);
+    let flagged = set_mock_commitment(&e, &core_id, "flagged", 1_000);
+    let over_loss = set_mock_commitment(&e, &core_id, "over_loss", 700);
+    e.as_contract(&core_id, || {
+        MockCoreContract::set_violations(e.clone(), flagged.clone(), true);
+    });
+    let missing = String::from_str(&e, "missing");
+
+    let ids = vec![
+        &e,
+        flagged.clone(),
+        healthy.clone(),
+        missing,
+        over_loss.clone(),
+        healthy.clone(),
+    ];
+    let results = client.batch_verify_compliance(&ids);
+    assert_eq!(results, vec![&e, false, true, false, false, true]);
+    for (id, result) in ids.iter().zip(results.iter()) {
+        assert_eq!(client.verify_compliance(&id), result);
+    }
+    assert!(client.batch_verify_compliance(&Vec::new(&e)).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "Batch too large")]
+fn test_batch_verify_compliance_rejects_oversized_batch() {
+    let (e, _admin, _commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let mut ids = Vec::new(&e);
+    for _ in 0..=MAX_COMPLIANCE_BATCH {
+        ids.push_back(String::from_str(&e, "c1"));
+    }
+    client.batch_verify_compliance(&ids);
+}
+
+#[test]
+fn test_get_stored_health_metrics_makes_no_core_call() {
+    let e = Env::default();
+    let core_id = e.register_contract(None, MockCoreContract);
+    let contract_id = e.register_contract(None, AttestationEngineContract);
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    client.initialize(&Address::generate(&e), &core_id);
+    e.as_contract(&core_id, || MockCoreContract::set_unavailable(e.clone(), true));
+
+    let commitment_id = String::from_str(&e, "c1");
+    assert_eq!(client.get_stored_health_metrics(&commitment_id), None);
+
+    let metrics = HealthMetrics {
+        commitment_id: commitment_id.clone(),
+        current_value: 900,
+        initial_value: 1_000,
+        drawdown_percent: 10,
+        fees_generated: 25,
+        volatility_exposure: 0,
+        last_attestation: 5_000,
+        compliance_score: 90,
+    };
+    e.as_contract(&contract_id, || {
+        e.storage()
+            .persistent()
+            .set(&crate::DataKey::HealthMetrics(commitment_id.clone()), &metrics);
+    });
+
+    assert_eq!(client.get_stored_health_metrics(&commitment_id), Some(metrics));
+    // The refreshing read does go to core
+    assert!(client.try_get_health_metrics(&commitment_id).is_err());
+}
+
+#[test]
+#[should_panic]
+fn test_get_health_metrics_unknown_commitment_panics() {
+    let (e, _admin, _commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    client.get_health_metrics(&String::from_str(&e, "missing"));
+}
+
+// ============================================================================
+// Compliance Score Floor Tests
+// ============================================================================
+
+/// Mock-core commitment whose only stored attestation is a high severity violation (score 70)
+fn setup_scored_commitment(e: &Env) -> (AttestationEngineContractClient<'_>, Address, String) {
+    e.mock_all_auths();
+    let admin = Address::generate(e);
+    let core_id = e.register_contract(None, MockCoreContract);
+    let contract_id = e.register_contract(None, AttestationEngineContract);
+    let client = AttestationEngineContractClient::new(e, &contract_id);
+    client.initialize(&admin, &core_id);
+
+    let commitment_id = String::from_str(e, "c1");
+    let commitment = Commitment {
+        commitment_id: commitment_id.clone(),
+        owner: Address::generate(e),
+        nft_token_id: 1,
+        rules: CommitmentRules {
+            duration_days: 0,
+            max_loss_percent: 20,
+            commitment_type: String::from_str(e, "safe"),
+            early_exit_penalty: 0,
+            min_fee_threshold: 0,
+            grace_period_days: 0,
+            max_loss_bps: 0,
+        },
+        amount: 1_000,
+        asset_address: Address::generate(e),
+        created_at: 0,
+        expires_at: 0,
+        current_value: 1_000,
+        status: String::from_str(e, "active"),
+    };
+    e.as_contract(&core_id, || {
+        MockCoreContract::set_commitment(e.clone(), commitment_id.clone(), commitment);
+    });
+
+    let mut data = Map::new(e);
+    data.set(
+        String::from_str(e, "violation_type"),
+        String::from_str(e, "excessive_drawdown"),
+    );
+    data.set(String::from_str(e, "severity"), String::from_str(e, "high"));
+    client.attest(
+        &admin,
+        &commitment_id,
+        &String::from_str(e, "violation"),
+        &data,
+        &false,
+    );
+
+    (client, admin, commitment_id)
+}
+
+#[test]
+fn test_verify_compliance_uses_configured_score_floor() {
+    let e = Env::default();
+    let (client, admin, commitment_id) = setup_scored_commitment(&e);
+    assert_eq!(client.get_health_metrics(&commitment_id).compliance_score, 70);
+
+    // Default floor of 80 rejects a score of 70
+    assert_eq!(client.get_min_compliance_score(), DEFAULT_MIN_COMPLIANCE_SCORE);
+    assert!(!client.verify_compliance(&commitment_id));
+
+    client.set_min_compliance_score(&admin, &70);
+    assert!(client.verify_compliance(&commitment_id));
+
+    client.set_min_compliance_score(&admin, &71);
+    assert!(!client.verify_compliance(&commitment_id));
+}
+
+#[test]
+fn test_set_min_compliance_score_validation() {
+    let e = Env::default();
+    let (client, admin, _commitment_id) = setup_scored_commitment(&e);
+
+    assert_eq!(
+        client.try_set_min_compliance_score(&admin, &101),
+        Err(Ok(AttestationError::InvalidComplianceScore))
+    );
+    assert_eq!(
+        client.try_set_min_compliance_score(&Address::generate(&e), &50),
+        Err(Ok(AttestationError::Unauthorized))
+    );
+    assert_eq!(client.get_min_compliance_score(), DEFAULT_MIN_COMPLIANCE_SCORE);
+}
+
+// ============================================================================
+// Health Metrics Recompute Tests
+// ============================================================================
+
+#[test]
+fn test_recompute_health_metrics_replaces_stale_scores() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let commitment_id = String::from_str(&e, "test_commitment");
+    let owner = Address::generate(&e);
+    store_core_commitment(
+        &e,
+        &commitment_core,
+        "test_commitment",
+        &owner,
+        1000,
+        1000,
+        10,
+        30,
+        1000,
+    );
+
+    // attest applies the incremental severity penalty: 100 - 30
+    let mut data = Map::new(&e);
+    data.set(
+        String::from_str(&e, "violation_type"),
+        String::from_str(&e, "excessive_drawdown"),
+    );
+    data.set(String::from_str(&e, "severity"), String::from_str(&e, "high"));
+    client.attest(
+        &admin,
+        &commitment_id,
+        &String::from_str(&e, "violation"),
+        &data,
+        &false,
+    );
+    assert_eq!(
+        client.get_stored_health_metrics(&commitment_id).unwrap().compliance_score,
+        70
+    );
+
+    // The commitment then loses 40% against a 10% max loss
+    store_core_commitment(
+        &e,
+        &commitment_core,
+        "test_commitment",
+        &owner,
+        1000,
+        600,
+        10,
+        30,
+        1000,
+    );
+
+    let missing = String::from_str(&e, "missing");
+    let result = client.recompute_health_metrics(
+        &admin,
+        &vec![&e, commitment_id.clone(), missing.clone()],
+    );
+    assert_eq!(result.success_count, 1);
+    assert_eq!(result.errors.len(), 1);
+    let error = result.errors.get(0).unwrap();
+    assert_eq!(error.index, 1);
+    assert_eq!(error.error_code, AttestationError::CommitmentNotFound as u32);
+
+    // 100 - 20 (one violation) - 30 (points over max loss) + 10 (on schedule)
+    let metrics = client.get_stored_health_metrics(&commitment_id).unwrap();
+    assert_eq!(metrics.compliance_score, 60);
+    assert_eq!(metrics.current_value, 600);
+    assert_eq!(metrics.drawdown_percent, 40);
+    assert_eq!(metrics.last_attestation, e.ledger().timestamp());
+    assert!(client.get_stored_health_metrics(&missing).is_none());
+}
+
+#[test]
+fn test_recompute_health_metrics_admin_only() {
+    let (e, _admin, _commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    assert_eq!(
+        client.try_recompute_health_metrics(&Address::generate(&e), &Vec::new(&e)),
+        Err(Ok(AttestationError::Unauthorized))
+    );
+}
+
+fn count_contract_events(e: &Env, contract_id: &Address) -> u64 {
+    e.events()
+        .all()
+        .iter()
+        .filter(|event| event.0 == *contract_id)
+        .count() as u64
+}
+
+#[test]
+fn test_event_sequence_starts_at_zero() {
+    let (e, _admin, _commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    assert_eq!(client.get_event_sequence(), 0);
+}
+
+#[test]
+fn test_event_sequence_tracks_emitted_events() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let commitment_id = String::from_str(&e, "test_id");
+    let owner = Address::generate(&e);
+    store_core_commitment(
+        &e,
+        &commitment_core,
+        "test_id",
+        &owner,
+        1000,
+        1000,
+        10,
+        30,
+        1000,
+    );
+
+    let before = client.get_event_sequence();
+    let events_before = count_contract_events(&e, &contract_id);
+    client.attest(
+        &admin,
+        &commitment_id,
+        &String::from_str(&e, "health_check"),
+        &Map::new(&e),
+        &true,
+    );
+    let emitted = count_contract_events(&e, &contract_id) - events_before;
+    assert_eq!(client.get_event_sequence() - before, emitted);
+
+    let before = client.get_event_sequence();
+    let events_before = count_contract_events(&e, &contract_id);
+    client.record_fees(&admin, &commitment_id, &100);
+    let emitted = count_contract_events(&e, &contract_id) - events_before;
+    assert_eq!(client.get_event_sequence() - before, emitted);
+
+    let before = client.get_event_sequence();
+    let events_before = count_contract_events(&e, &contract_id);
+    client.record_drawdown(&admin, &commitment_id, &5);
+    let emitted = count_contract_events(&e, &contract_id) - events_before;
+    assert_eq!(client.get_event_sequence() - before, emitted);
+
+    let before = client.get_event_sequence();
+    let events_before = count_contract_events(&e, &contract_id);
+    let mut params_list = Vec::new(&e);
+    for _ in 0..2 {
+        params_list.push_back(AttestParams {
+            commitment_id: commitment_id.clone(),
+            attestation_type: String::from_str(&e, "health_check"),
+            data: Map::new(&e),
+            is_compliant: true,
+        });
+    }
+    client.batch_attest(&admin, &params_list, &BatchMode::Atomic);
+    let emitted = count_contract_events(&e, &contract_id) - events_before;
+    // One AttestationRecorded per item plus the BatchAttest summary
+    assert_eq!(emitted, 3);
+    assert_eq!(client.get_event_sequence() - before, emitted);
+}
+
+fn drawdown_score(
+    e: &Env,
+    client: &AttestationEngineContractClient,
+    admin: &Address,
+    commitment_core: &Address,
+    id: &str,
+    drawdown_percent: i128,
+) -> u32 {
+    let owner = Address::generate(e);
+    store_core_commitment(e, commitment_core, id, &owner, 1000, 1000, 10, 30, 1000);
+    let commitment_id = String::from_str(e, id);
+    client.record_drawdown(admin, &commitment_id, &drawdown_percent);
+    client
+        .get_stored_health_metrics(&commitment_id)
+        .unwrap()
+        .compliance_score
+}
+
+#[test]
+fn test_drawdown_penalty_follows_default_bands() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    // max_loss_percent is 10; compliant drawdowns also earn the +1 bonus
+    assert_eq!(
+        drawdown_score(&e, &client, &admin, &commitment_core, "dd_20", 2),
+        100
+    );
+    assert_eq!(
+        drawdown_score(&e, &client, &admin, &commitment_core, "dd_50", 5),
+        91
+    );
+    assert_eq!(
+        drawdown_score(&e, &client, &admin, &commitment_core, "dd_80", 8),
+        81
+    );
+    assert_eq!(
+        drawdown_score(&e, &client, &admin, &commitment_core, "dd_100", 10),
+        71
+    );
+    assert_eq!(
+        drawdown_score(&e, &client, &admin, &commitment_core, "dd_150", 15),
+        70
+    );
+}
+
+#[test]
+fn test_drawdown_penalty_follows_configured_bands() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let bands = DrawdownSeverityBands {
+        low: 10,
+        medium: 20,
+        high: 40,
+    };
+    client.set_drawdown_severity_bands(&admin, &bands);
+    assert_eq!(client.get_drawdown_severity_bands(), bands);
+
+    assert_eq!(
+        drawdown_score(&e, &client, &admin, &commitment_core, "dd_0", 0),
+        100
+    );
+    assert_eq!(
+        drawdown_score(&e, &client, &admin, &commitment_core, "dd_10", 1),
+        91
+    );
+    assert_eq!(
+        drawdown_score(&e, &client, &admin, &commitment_core, "dd_30", 3),
+        81
+    );
+    assert_eq!(
+        drawdown_score(&e, &client, &admin, &commitment_core, "dd_50", 5),
+        71
+    );
+}
+
+#[test]
+fn test_drawdown_ignores_caller_severity() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let owner = Address::generate(&e);
+    store_core_commitment(
+        &e,
+        &commitment_core,
+        "test_id",
+        &owner,
+        1000,
+        1000,
+        10,
+        30,
+        1000,
+    );
+    let commitment_id = String::from_str(&e, "test_id");
+
+    let mut data = Map::new(&e);
+    data.set(
+        String::from_str(&e, "drawdown_percent"),
+        String::from_str(&e, "2"),
+    );
+    data.set(
+        String::from_str(&e, "severity"),
+        String::from_str(&e, "high"),
+    );
+    client.attest(
+        &admin,
+        &commitment_id,
+        &String::from_str(&e, "drawdown"),
+        &data,
+        &false,
+    );
+
+    let metrics = client.get_stored_health_metrics(&commitment_id).unwrap();
+    assert_eq!(metrics.compliance_score, 100);
+}
+
+#[test]
+fn test_set_drawdown_severity_bands_validation() {
+    let (e, admin, _commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let defaults = client.get_drawdown_severity_bands();
+    assert_eq!(
+        defaults,
+        DrawdownSeverityBands {
+            low: 50,
+            medium: 75,
+            high: 100,
+        }
+    );
+
+    let zero_low = DrawdownSeverityBands {
+        low: 0,
+        medium: 75,
+        high: 100,
+    };
+    assert_eq!(
+        client.try_set_drawdown_severity_bands(&admin, &zero_low),
+        Err(Ok(AttestationError::InvalidSeverityBands))
+    );
+
+    let descending = DrawdownSeverityBands {
+        low: 50,
+        medium: 100,
+        high: 75,
+    };
+    assert_eq!(
+        client.try_set_drawdown_severity_bands(&admin, &descending),
+        Err(Ok(AttestationError::InvalidSeverityBands))
+    );
+
+    let not_admin = Address::generate(&e);
+    let valid = DrawdownSeverityBands {
+        low: 25,
+        medium: 50,
+        high: 75,
+    };
+    assert_eq!(
+        client.try_set_drawdown_severity_bands(&not_admin, &valid),
+        Err(Ok(AttestationError::Unauthorized))
+    );
+    assert_eq!(client.get_drawdown_severity_bands(), defaults);
+}
+
+#[test]
+fn test_reconcile_fees_against_core_threshold() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let core_id = e.register_contract(None, MockCoreContract);
+    let contract_id = e.register_contract(None, AttestationEngineContract);
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &core_id);
+
+    let commitment_id = String::from_str(&e, "c1");
+    let commitment = Commitment {
+        commitment_id: commitment_id.clone(),
+        owner: Address::generate(&e),
+        nft_token_id: 1,
+        rules: CommitmentRules {
+            duration_days: 30,
+            max_loss_percent: 20,
+            commitment_type: String::from_str(&e, "balanced"),
+            early_exit_penalty: 0,
+            min_fee_threshold: 500,
+            grace_period_days: 0,
+            max_loss_bps: 0,
+        },
+        amount: 1_000,
+        asset_address: Address::generate(&e),
+        created_at: 0,
+        expires_at: 30 * 86400,
+        current_value: 1_000,
+        status: String::from_str(&e, "active"),
+    };
+    e.as_contract(&core_id, || {
+        MockCoreContract::set_commitment(e.clone(), commitment_id.clone(), commitment);
+    });
+
+    assert_eq!(client.reconcile_fees(&commitment_id), (0, 500, false));
+
+    client.record_fees(&admin, &commitment_id, &200);
+    assert_eq!(client.reconcile_fees(&commitment_id), (200, 500, false));
+
+    client.record_fees(&admin, &commitment_id, &300);
+    assert_eq!(client.reconcile_fees(&commitment_id), (500, 500, true));
+}
+
+#[test]
+fn test_core_contract_resolved_through_registry() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+
+    let registry_id = e.register_contract(None, contract_registry::ContractRegistry);
+    let registry = contract_registry::ContractRegistryClient::new(&e, &registry_id);
+    registry.initialize(&admin);
+
+    let contract_id = e.register_contract(None, AttestationEngineContract);
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+    client.initialize_with_registry(&admin, &registry_id);
+    assert_eq!(client.get_registry(), Some(registry_id.clone()));
+
+    // Nothing registered yet
+    assert_eq!(
+        client.try_get_core_contract(),
+        Err(Ok(AttestationError::NotInitialized))
+    );
+
+    let core_id = e.register_contract(None, MockCoreContract);
+    registry.set_contract(&admin, &symbol_short!("core"), &core_id);
+    assert_eq!(client.get_core_contract(), core_id);
+
+    let commitment_id = String::from_str(&e, "c1");
+    let commitment = Commitment {
+        commitment_id: commitment_id.clone(),
+        owner: Address::generate(&e),
+        nft_token_id: 1,
+        rules: CommitmentRules {
+            duration_days: 30,
+            max_loss_percent: 20,
+            commitment_type: String::from_str(&e, "balanced"),
+            early_exit_penalty: 0,
+            min_fee_threshold: 0,
+            grace_period_days: 0,
+            max_loss_bps: 0,
+        },
+        amount: 1_000,
+        asset_address: Address::generate(&e),
+        created_at: 0,
+        expires_at: 30 * 86400,
+        current_value: 1_000,
+        status: String::from_str(&e, "active"),
+    };
+    e.as_contract(&core_id, || {
+        MockCoreContract::set_commitment(e.clone(), commitment_id.clone(), commitment);
+    });
+    client.attest(
+        &admin,
+        &commitment_id,
+        &String::from_str(&e, "health_check"),
+        &Map::new(&e),
+        &true,
+    );
+    client.record_drawdown(&admin, &commitment_id, &5);
+    assert_eq!(client.get_attestation_count(&commitment_id), 2);
+
+    // Re-pointing the registry entry moves the engine to the new core
+    let upgraded_core = e.register_contract(None, MockCoreContract);
+    registry.set_contract(&admin, &symbol_short!("core"), &upgraded_core);
+    assert_eq!(client.get_core_contract(), upgraded_core);
+    assert!(!client.verify_compliance(&commitment_id));
+}
+
+#[test]
+fn test_direct_core_address_takes_precedence_over_registry() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.mock_all_auths();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let registry_id = e.register_contract(None, contract_registry::ContractRegistry);
+    let registry = contract_registry::ContractRegistryClient::new(&e, &registry_id);
+    registry.initialize(&admin);
+    registry.set_contract(&admin, &symbol_short!("core"), &Address::generate(&e));
+
+    client.set_registry(&admin, &registry_id);
+    assert_eq!(client.get_core_contract(), commitment_core);
+
+    let not_admin = Address::generate(&e);
+    assert_eq!(
+        client.try_set_registry(&not_admin, &registry_id),
+        Err(Ok(AttestationError::Unauthorized))
+    );
+}
+
+#[test]
+fn test_import_backdated_attestation() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.ledger().with_mut(|li| li.timestamp = 100_000);
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let owner = Address::generate(&e);
+    store_core_commitment(&e, &commitment_core, "c1", &owner, 1000, 1000, 10, 30, 1000);
+    let commitment_id = String::from_str(&e, "c1");
+    let health_check = String::from_str(&e, "health_check");
+
+    client.attest(&admin, &commitment_id, &health_check, &Map::new(&e), &true);
+
+    // A former off-chain verifier, no longer whitelisted
+    let legacy_verifier = Address::generate(&e);
+    client.import_attestation(
+        &admin,
+        &commitment_id,
+        &health_check,
+        &Map::new(&e),
+        &false,
+        &5_000,
+        &legacy_verifier,
+    );
+
+    let imported = client.get_attestations_in_range(&commitment_id, &4_000, &6_000);
+    assert_eq!(imported.len(), 1);
+    let attestation = imported.get(0).unwrap();
+    assert_eq!(attestation.timestamp, 5_000);
+    assert_eq!(attestation.verified_by, legacy_verifier);
+    assert_eq!(client.get_attestations_in_range(&commitment_id, &0, &200_000).len(), 2);
+
+    // Counters include the import; the newer live attestation stays the latest
+    assert_eq!(client.get_attestation_count(&commitment_id), 2);
+    assert_eq!(client.get_verifier_statistics(&legacy_verifier), 1);
+    let (_, total_attestations, total_violations, _) = client.get_protocol_statistics();
+    assert_eq!((total_attestations, total_violations), (2, 1));
+    assert_eq!(
+        client.get_stored_health_metrics(&commitment_id).unwrap().last_attestation,
+        100_000
+    );
+}
+
+#[test]
+fn test_import_attestation_admin_only_and_window_closes() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    e.ledger().with_mut(|li| li.timestamp = 100_000);
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let owner = Address::generate(&e);
+    store_core_commitment(&e, &commitment_core, "c1", &owner, 1000, 1000, 10, 30, 1000);
+    let commitment_id = String::from_str(&e, "c1");
+    let health_check = String::from_str(&e, "health_check");
+    let verifier = Address::generate(&e);
+
+    let result = client.try_import_attestation(
+        &verifier,
+        &commitment_id,
+        &health_check,
+        &Map::new(&e),
+        &true,
+        &5_000,
+        &verifier,
+    );
+    assert_eq!(result, Err(Ok(AttestationError::Unauthorized)));
+
+    let result = client.try_import_attestation(
+        &admin,
+        &commitment_id,
+        &health_check,
+        &Map::new(&e),
+        &true,
+        &200_000,
+        &verifier,
+    );
+    assert_eq!(result, Err(Ok(AttestationError::InvalidTimestamp)));
+
+    assert!(client.is_import_window_open());
+    client.close_import_window(&admin);
+    assert!(!client.is_import_window_open());
+
+    let result = client.try_import_attestation(
+        &admin,
+        &commitment_id,
+        &health_check,
+        &Map::new(&e),
+        &true,
+        &5_000,
+        &verifier,
+    );
+    assert_eq!(result, Err(Ok(AttestationError::ImportWindowClosed)));
+    assert_eq!(client.get_attestation_count(&commitment_id), 0);
+}
+
+#[test]
+fn test_attest_data_map_at_and_over_limits() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let owner = Address::generate(&e);
+    store_core_commitment(&e, &commitment_core, "c1", &owner, 1000, 1000, 10, 30, 1000);
+    let commitment_id = String::from_str(&e, "c1");
+    let health_check = String::from_str(&e, "health_check");
+
+    assert_eq!(client.get_data_limits().max_entries, DEFAULT_MAX_DATA_ENTRIES);
+    let limits = DataLimits {
+        max_entries: 2,
+        max_key_len: 4,
+        max_value_len: 8,
+    };
+    client.set_data_limits(&admin, &limits);
+    assert_eq!(client.get_data_limits(), limits);
+
+    // Exactly at every limit
+    let mut data = Map::new(&e);
+    data.set(String::from_str(&e, "key1"), String::from_str(&e, "12345678"));
+    data.set(String::from_str(&e, "key2"), String::from_str(&e, "v"));
+    client.attest(&admin, &commitment_id, &health_check, &data, &true);
+
+    // One entry too many
+    let mut too_many = data.clone();
+    too_many.set(String::from_str(&e, "key3"), String::from_str(&e, "v"));
+    assert_eq!(
+        client.try_attest(&admin, &commitment_id, &health_check, &too_many, &true),
+        Err(Ok(AttestationError::DataTooLarge))
+    );
+
+    // Key and value one byte too long
+    let mut long_key = Map::new(&e);
+    long_key.set(String::from_str(&e, "key10"), String::from_str(&e, "v"));
+    assert_eq!(
+        client.try_attest(&admin, &commitment_id, &health_check, &long_key, &true),
+        Err(Ok(AttestationError::DataTooLarge))
+    );
+    let mut long_value = Map::new(&e);
+    long_value.set(String::from_str(&e, "key"), String::from_str(&e, "123456789"));
+    assert_eq!(
+        client.try_attest(&admin, &commitment_id, &health_check, &long_value, &true),
+        Err(Ok(AttestationError::DataTooLarge))
+    );
+
+    assert_eq!(client.get_attestation_count(&commitment_id), 1);
+    assert_eq!(
+        client.try_set_data_limits(&admin, &DataLimits { max_entries: 0, ..limits }),
+        Err(Ok(AttestationError::InvalidDataLimits))
+    );
+}
+
+#[test]
+fn test_batch_attest_rejects_oversized_data() {
+    let (e, admin, commitment_core, contract_id) = setup_test_env();
+    let client = AttestationEngineContractClient::new(&e, &contract_id);
+
+    let owner = Address::generate(&e);
+    store_core_commitment(&e, &commitment_core, "c1", &owner, 1000, 1000, 10, 30, 1000);
+    let commitment_id = String::from_str(&e, "c1");
+    let health_check = String::from_str(&e, "health_check");
+
+    client.set_data_limits(
+        &admin,
+        &DataLimits {
+            max_entries: 1,
+            max_key_len: 64,
+            max_value_len: 256,
+        },
+    );
+
+    let mut at_limit = Map::new(&e);
+    at_limit.set(String::from_str(&e, "a"), String::from_str(&e, "1"));
+    let mut over_limit = at_limit.clone();
+    over_limit.set(String::from_str(&e, "b"), String::from_str(&e, "2"));
+
+    let mut params_list = Vec::new(&e);
+    for data in [at_limit, over_limit] {
+        params_list.push_back(AttestParams {
+            commitment_id: commitment_id.clone(),
+            attestation_type: health_check.clone(),
+            data,
+            is_compliant: true,
+        });
+    }
+
+    let result = client.batch_attest(&admin, &params_list, &BatchMode::BestEffort);
+    assert_eq!(result.success_count, 1);
+    let error = result.errors.get(0).unwrap();
+    assert_eq!(error.index, 1);
+    assert_eq!(error.error_code, AttestationError::DataTooLarge as u32);
+    assert_eq!(client.get_attestation_count(&commitment_id), 1);
+}
@@ -49,7 +49,7 @@ fn setup_test_env(e: &Env) -> (Address, Address, Address) {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 
     (contract_id, admin, owner)
@@ -66,7 +66,7 @@ fn benchmark_initialize() {
 
     e.as_contract(&contract_id, || {
         let start = e.ledger().sequence();
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
         let end = e.ledger().sequence();
         metrics.record_gas(start, end);
     });
@@ -92,12 +92,14 @@ fn benchmark_create_commitment() {
 
     e.as_contract(&contract_id, || {
         let start = e.ledger().sequence();
-        CommitmentCoreContract::create_commitment(
+        let _ = CommitmentCoreContract::create_commitment(
             e.clone(),
             owner.clone(),
             1000_0000000,
             asset_address.clone(),
             rules.clone(),
+            None,
+            0,
         );
         let end = e.ledger().sequence();
         metrics.record_gas(start, end);
@@ -122,12 +124,14 @@ fn benchmark_get_commitment() {
     };
 
     let commitment_id = e.as_contract(&contract_id, || {
-        CommitmentCoreContract::create_commitment(
+        let _ = CommitmentCoreContract::create_commitment(
             e.clone(),
             owner.clone(),
             1000_0000000,
             asset_address.clone(),
             rules.clone(),
+            None,
+            0,
         )
     });
 
@@ -159,12 +163,14 @@ fn benchmark_check_violations() {
     };
 
     let commitment_id = e.as_contract(&contract_id, || {
-        CommitmentCoreContract::create_commitment(
+        let _ = CommitmentCoreContract::create_commitment(
             e.clone(),
             owner.clone(),
             1000_0000000,
             asset_address.clone(),
             rules.clone(),
+            None,
+            0,
         )
     });
 
@@ -239,6 +245,8 @@ fn benchmark_batch_create_commitments() {
                 1000_0000000 + (i as i128),
                 asset_address.clone(),
                 rules.clone(),
+                None,
+                0,
             );
         }
         let end = e.ledger().sequence();
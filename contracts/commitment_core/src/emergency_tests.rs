@@ -12,7 +12,7 @@ fn test_emergency_mode_toggle() {
     let admin = Address::generate(&e);
     let nft_contract = Address::generate(&e);
 
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
 
     assert!(!client.is_emergency_mode());
 
@@ -37,7 +37,7 @@ fn test_create_commitment_forbidden_in_emergency() {
     let owner = Address::generate(&e);
     let asset = Address::generate(&e);
 
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     client.set_emergency_mode(&admin, &true);
 
     let rules = CommitmentRules {
@@ -46,10 +46,12 @@ fn test_create_commitment_forbidden_in_emergency() {
         commitment_type: String::from_str(&e, "safe"),
         early_exit_penalty: 5,
         min_fee_threshold: 100,
+        grace_period_days: 0,
+        max_loss_bps: 0,
     };
 
     // This should panic because of emergency mode
-    client.create_commitment(&owner, &1000, &asset, &rules);
+    client.create_commitment(&owner, &1000, &asset, &rules, &None, &0);
 }
 
 #[test]
@@ -64,7 +66,7 @@ fn test_emergency_withdraw_forbidden_in_normal_mode() {
     let to = Address::generate(&e);
     let asset = Address::generate(&e);
 
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
 
     // Normal mode, should panic
     client.emergency_withdraw(&admin, &asset, &to, &1000);
@@ -81,8 +83,83 @@ fn test_set_emergency_mode_unauthorized() {
     let nft_contract = Address::generate(&e);
     let attacker = Address::generate(&e);
 
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
 
     // Using attacker address should fail the require_admin check
     client.set_emergency_mode(&attacker, &true);
 }
+
+fn setup_reassignable_commitment(
+    e: &Env,
+) -> (CommitmentCoreContractClient<'_>, Address, Address, String) {
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(e, &contract_id);
+    let admin = Address::generate(e);
+    let owner = Address::generate(e);
+    let commitment_id = String::from_str(e, "c_0");
+
+    client.initialize(&admin, &Address::generate(e), &None);
+    e.as_contract(&contract_id, || {
+        let commitment = Commitment {
+            commitment_id: commitment_id.clone(),
+            owner: owner.clone(),
+            nft_token_id: 0,
+            rules: CommitmentRules {
+                duration_days: 30,
+                max_loss_percent: 10,
+                commitment_type: String::from_str(e, "safe"),
+                early_exit_penalty: 5,
+                min_fee_threshold: 100,
+                grace_period_days: 0,
+                max_loss_bps: 0,
+            },
+            amount: 1000,
+            asset_address: Address::generate(e),
+            created_at: 0,
+            expires_at: 30 * 86400,
+            current_value: 1000,
+            status: String::from_str(e, "active"),
+        };
+        set_commitment(e, &commitment);
+        add_owner_commitment(e, &owner, &commitment_id);
+    });
+
+    (client, admin, owner, commitment_id)
+}
+
+#[test]
+fn test_emergency_reassign_owner() {
+    let e = Env::default();
+    let (client, admin, owner, commitment_id) = setup_reassignable_commitment(&e);
+    let new_owner = Address::generate(&e);
+
+    client.set_emergency_mode(&admin, &true);
+    client.emergency_reassign_owner(&admin, &commitment_id, &new_owner, &false);
+
+    assert_eq!(client.get_commitment(&commitment_id).owner, new_owner);
+    assert_eq!(client.get_owner_commitments(&owner).len(), 0);
+    assert_eq!(
+        client.get_owner_commitments(&new_owner),
+        soroban_sdk::vec![&e, commitment_id]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Action only allowed in emergency mode")]
+fn test_emergency_reassign_owner_forbidden_in_normal_mode() {
+    let e = Env::default();
+    let (client, admin, _owner, commitment_id) = setup_reassignable_commitment(&e);
+
+    client.emergency_reassign_owner(&admin, &commitment_id, &Address::generate(&e), &false);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: caller not allowed")]
+fn test_emergency_reassign_owner_unauthorized() {
+    let e = Env::default();
+    let (client, admin, owner, commitment_id) = setup_reassignable_commitment(&e);
+
+    client.set_emergency_mode(&admin, &true);
+    client.emergency_reassign_owner(&owner, &commitment_id, &Address::generate(&e), &false);
+}
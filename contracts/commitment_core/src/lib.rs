@@ -1,22 +1,42 @@
 #![no_std]
-use soroban_sdk::{
-    contract, contractimpl, contracttype, symbol_short, Address, Env, String, Vec, Map,
-    Val, BytesN, IntoVal,
-};
-use soroban_sdk::storage::Storage;
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String};
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env, String, symbol_short, Symbol};
-
 use shared_utils::{
-    emit_error_event, fee_from_bps, BPS_MAX, EmergencyControl, RateLimiter, SafeMath, TimeUtils,
-    Validation,
+    emit_error_event, fee_from_bps, safe_transfer, split_fee, BatchError, BatchMode,
+    BatchProcessor, BatchResultVoid, BPS_MAX, EmergencyControl, RateLimiter, SafeMath, TimeUtils,
 };
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, log, symbol_short, token, Address, BytesN,
-    Env, IntoVal, String, Symbol, Vec,
+    Env, IntoVal, Map, String, Symbol, Vec,
 };
 
-pub const CURRENT_VERSION: u32 = 1;
+pub const CURRENT_VERSION: u32 = 2;
+
+/// Default prefix for generated commitment ids.
+const DEFAULT_ID_PREFIX: &[u8] = b"c_";
+
+/// Longest id prefix that still leaves room for a u64 counter (20 digits)
+/// in the 32-byte id buffer.
+pub const MAX_ID_PREFIX_LEN: u32 = 12;
+
+/// Default lifetime of an idempotency key, in ledgers (~24h at 5s ledgers).
+pub const DEFAULT_IDEMPOTENCY_TTL_LEDGERS: u32 = 17_280;
+
+/// Commitment types accepted until the admin configures the list.
+pub const DEFAULT_COMMITMENT_TYPES: [&str; 3] = ["safe", "balanced", "aggressive"];
+
+/// Status transitions kept per commitment; the oldest entries are dropped first.
+pub const MAX_HISTORY_ENTRIES: u32 = 20;
+
+/// Most commitment records one index rebuild call may scan.
+pub const MAX_INDEX_REBUILD_BATCH: u32 = 100;
+
+/// Settlement log entries stored per persistent page.
+pub const SETTLEMENT_LOG_PAGE_SIZE: u32 = 50;
+
+/// Most settlement log entries one `get_settlements_paged` call returns.
+pub const MAX_SETTLEMENT_QUERY: u32 = 100;
+
+/// Settlement log entry: (commitment_id, amount paid out, timestamp).
+pub type SettlementLogEntry = (String, i128, u64);
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -41,6 +61,23 @@ pub enum CommitmentError {
     InvalidFeeBps = 17,
     InvalidFeeRecipient = 18,
     InsufficientFees = 19,
+    InvalidIdPrefix = 20,
+    AmountBelowMinimum = 21,
+    AmountAboveMaximum = 22,
+    AllocationCapExceeded = 23,
+    InvalidAllocationCap = 24,
+    OutstandingAllocations = 25,
+    CommitmentFrozen = 26,
+    DeadlineExceeded = 27,
+    NothingToSweep = 28,
+    TemplateNotFound = 29,
+    AutoRenewDisabled = 30,
+    FeeThresholdMet = 31,
+    AssetMismatch = 32,
+    InvalidShares = 33,
+    DuplicateCommitmentId = 34,
+    AlreadyMigrated = 35,
+    InvalidVersion = 36,
 }
 
 impl CommitmentError {
@@ -66,14 +103,36 @@ impl CommitmentError {
             CommitmentError::InvalidFeeBps => "Invalid fee: basis points must be 0-10000",
             CommitmentError::InvalidFeeRecipient => "Invalid fee recipient address",
             CommitmentError::InsufficientFees => "Insufficient collected fees to withdraw",
+            CommitmentError::InvalidIdPrefix => "Invalid id prefix: must be 1-12 bytes",
+            CommitmentError::AmountBelowMinimum => "Amount is below the minimum commitment for this asset",
+            CommitmentError::AmountAboveMaximum => "Amount is above the maximum commitment for this asset",
+            CommitmentError::AllocationCapExceeded => "Allocation would exceed the commitment allocation cap",
+            CommitmentError::InvalidAllocationCap => "Invalid allocation cap: basis points must be 1-10000",
+            CommitmentError::OutstandingAllocations => "Commitment has outstanding allocations; recall them first",
+            CommitmentError::CommitmentFrozen => "Commitment is frozen for allocations",
+            CommitmentError::DeadlineExceeded => "Deadline exceeded: transaction submitted too late",
+            CommitmentError::NothingToSweep => "No unaccounted balance to sweep",
+            CommitmentError::TemplateNotFound => "Commitment template not found",
+            CommitmentError::AutoRenewDisabled => "Commitment is not opted in to auto-renewal",
+            CommitmentError::FeeThresholdMet => "Attested fees meet the commitment's fee threshold",
+            CommitmentError::AssetMismatch => "Commitment asset differs from the asset recorded at creation",
+            CommitmentError::InvalidShares => "Co-owner shares must be positive and sum to 10000 bps",
+            CommitmentError::DuplicateCommitmentId => "Commitment id appears more than once in the batch",
+            CommitmentError::AlreadyMigrated => "Storage is already at the current version",
+            CommitmentError::InvalidVersion => "Migration source version does not match the stored version",
         }
     }
 }
 
+/// Emit error event and return the error, for functions that return `Result`.
+fn raise(e: &Env, err: CommitmentError, context: &str) -> CommitmentError {
+    emit_error_event(e, err as u32, context);
+    err
+}
+
 /// Emit error event and panic with standardized message (for indexers and UX).
 fn fail(e: &Env, err: CommitmentError, context: &str) -> ! {
-    emit_error_event(e, err as u32, context);
-    panic!("{}", err.message());
+    panic!("{}", raise(e, err, context).message());
 }
 
 #[contracttype]
@@ -94,10 +153,37 @@ pub struct CommitmentCreatedEvent {
 pub struct CommitmentRules {
     pub duration_days: u32,
     pub max_loss_percent: u32,
-    pub commitment_type: String, // "safe", "balanced", "aggressive"
+    pub commitment_type: String, // "safe", "balanced", "aggressive", or an admin-added type
     pub early_exit_penalty: u32,
     pub min_fee_threshold: i128,
     pub grace_period_days: u32,
+    pub max_loss_bps: u32, // 0 = compare whole-percent loss to max_loss_percent; else bps loss to this
+}
+
+/// `CommitmentRules` as stored by version 1, before `max_loss_bps`. Only read by `migrate`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommitmentRulesV1 {
+    pub duration_days: u32,
+    pub max_loss_percent: u32,
+    pub commitment_type: String,
+    pub early_exit_penalty: u32,
+    pub min_fee_threshold: i128,
+    pub grace_period_days: u32,
+}
+
+impl From<CommitmentRulesV1> for CommitmentRules {
+    fn from(rules: CommitmentRulesV1) -> Self {
+        CommitmentRules {
+            duration_days: rules.duration_days,
+            max_loss_percent: rules.max_loss_percent,
+            commitment_type: rules.commitment_type,
+            early_exit_penalty: rules.early_exit_penalty,
+            min_fee_threshold: rules.min_fee_threshold,
+            grace_period_days: rules.grace_period_days,
+            max_loss_bps: 0,
+        }
+    }
 }
 
 /// Metadata for a supported asset (symbol, decimals).
@@ -123,6 +209,63 @@ pub struct Commitment {
     pub status: String, // "active", "settled", "violated", "early_exit"
 }
 
+/// `Commitment` as stored by version 1, with `CommitmentRulesV1`. Only read by `migrate`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommitmentV1 {
+    pub commitment_id: String,
+    pub owner: Address,
+    pub nft_token_id: u32,
+    pub rules: CommitmentRulesV1,
+    pub amount: i128,
+    pub asset_address: Address,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub current_value: i128,
+    pub status: String,
+}
+
+impl From<CommitmentV1> for Commitment {
+    fn from(commitment: CommitmentV1) -> Self {
+        Commitment {
+            commitment_id: commitment.commitment_id,
+            owner: commitment.owner,
+            nft_token_id: commitment.nft_token_id,
+            rules: commitment.rules.into(),
+            amount: commitment.amount,
+            asset_address: commitment.asset_address,
+            created_at: commitment.created_at,
+            expires_at: commitment.expires_at,
+            current_value: commitment.current_value,
+            status: commitment.status,
+        }
+    }
+}
+
+/// Health metrics as stored by attestation_engine (mirrored for cross-contract decoding)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HealthMetrics {
+    pub commitment_id: String,
+    pub current_value: i128,
+    pub initial_value: i128,
+    pub drawdown_percent: i128,
+    pub fees_generated: i128,
+    pub volatility_exposure: i128,
+    pub last_attestation: u64,
+    pub compliance_score: u32,
+}
+
+/// Number of commitment_transformation instruments attached to a commitment
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransformationCounts {
+    pub tranche_sets: u32,
+    pub collateral: u32,
+    pub instruments: u32,
+    pub guarantees: u32,
+}
+
 /// Parameters for creating a commitment (used in batch operations)
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -141,6 +284,14 @@ pub struct UpdateValueParams {
     pub new_value: i128,
 }
 
+/// One co-owner's share of a commitment's proceeds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CoOwner {
+    pub owner: Address,
+    pub share_bps: u32,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Allocation {
@@ -157,154 +308,15 @@ pub struct AllocationTracking {
     pub allocations: Vec<Allocation>,
 }
 
-// Storage Data Keys
 #[contracttype]
 #[derive(Clone)]
-pub enum DataKey {
-    Admin,
-    AuthorizedAllocator(Address),
-    Commitment(String),
-    CommitmentBalance(String),
-    AllocationTracking(String),
-    InitFlag,
-}
-
-// Error helper functions using panic with error codes
-fn panic_unauthorized() -> ! {
-    panic!("Unauthorized: caller is not an authorized allocation contract");
-}
-
-fn panic_insufficient_balance() -> ! {
-    panic!("InsufficientBalance: commitment does not have enough balance");
-}
-
-fn panic_inactive_commitment() -> ! {
-    panic!("InactiveCommitment: commitment is not active or does not exist");
-}
-
-fn panic_transfer_failed() -> ! {
-    panic!("TransferFailed: asset transfer failed");
-}
-
-fn panic_already_initialized() -> ! {
-    panic!("AlreadyInitialized: contract is already initialized");
-}
-
-fn panic_invalid_amount() -> ! {
-    panic!("InvalidAmount: amount must be greater than zero");
-}
-
-// Helper functions for storage operations
-fn has_admin(e: &Env) -> bool {
-    let key = DataKey::Admin;
-    e.storage().instance().has(&key)
-}
-
-fn get_admin(e: &Env) -> Address {
-    let key = DataKey::Admin;
-    e.storage().instance().get(&key).unwrap()
-}
-
-fn set_admin(e: &Env, admin: &Address) {
-    let key = DataKey::Admin;
-    e.storage().instance().set(&key, admin);
-}
-
-fn is_authorized_allocator(e: &Env, allocator: &Address) -> bool {
-    let key = DataKey::AuthorizedAllocator(allocator.clone());
-    if e.storage().instance().has(&key) {
-        e.storage().instance().get::<DataKey, bool>(&key).unwrap_or(false)
-    } else {
-        false
-    }
-}
-
-fn set_authorized_allocator(e: &Env, allocator: &Address, authorized: bool) {
-    let key = DataKey::AuthorizedAllocator(allocator.clone());
-    e.storage().instance().set(&key, &authorized);
-}
-
-fn get_commitment(e: &Env, commitment_id: &String) -> Option<Commitment> {
-    let key = DataKey::Commitment(commitment_id.clone());
-    e.storage().persistent().get(&key)
-}
-
-fn set_commitment(e: &Env, commitment: &Commitment) {
-    let key = DataKey::Commitment(commitment.commitment_id.clone());
-    e.storage().persistent().set(&key, commitment);
-}
-
-fn get_commitment_balance(e: &Env, commitment_id: &String) -> i128 {
-    let key = DataKey::CommitmentBalance(commitment_id.clone());
-    e.storage().persistent().get(&key).unwrap_or(0)
-}
-
-fn set_commitment_balance(e: &Env, commitment_id: &String, balance: i128) {
-    let key = DataKey::CommitmentBalance(commitment_id.clone());
-    e.storage().persistent().set(&key, &balance);
-}
-
-fn get_allocation_tracking(e: &Env, commitment_id: &String) -> AllocationTracking {
-    let key = DataKey::AllocationTracking(commitment_id.clone());
-    e.storage().persistent().get(&key).unwrap_or(AllocationTracking {
-        total_allocated: 0,
-        allocations: Vec::new(&e),
-    })
-}
-
-fn set_allocation_tracking(e: &Env, commitment_id: &String, tracking: &AllocationTracking) {
-    let key = DataKey::AllocationTracking(commitment_id.clone());
-    e.storage().persistent().set(&key, tracking);
-}
-
-fn is_initialized(e: &Env) -> bool {
-    let key = DataKey::InitFlag;
-    if e.storage().instance().has(&key) {
-        e.storage().instance().get::<DataKey, bool>(&key).unwrap_or(false)
-    } else {
-        false
-    }
-}
-
-fn set_initialized(e: &Env) {
-    let key = DataKey::InitFlag;
-    e.storage().instance().set(&key, &true);
-}
-
-// Asset transfer helper function using Stellar asset contract
-fn transfer_asset(e: &Env, asset: &Address, from: &Address, to: &Address, amount: i128) {
-    if amount <= 0 {
-        panic_invalid_amount();
-    }
-
-    // Call the asset contract's transfer function
-    // The asset contract should have a transfer function with signature:
-    // transfer(from: Address, to: Address, amount: i128)
-    // Using invoke_contract to call the asset contract's transfer function
-    let transfer_symbol = symbol_short!("transfer");
-    
-    // Invoke the contract's transfer function
-    // Note: This assumes the asset contract follows the standard token interface
-    let _: () = e.invoke_contract(
-        asset,
-        &transfer_symbol,
-        soroban_sdk::vec![e, from.clone().into_val(e), to.clone().into_val(e), amount.into_val(e)],
-    );
-}
-
-#[contract]
-pub struct CommitmentCoreContract;
-
-// Storage keys - using Symbol for efficient storage (max 9 chars)
-fn commitment_key(_e: &Env) -> Symbol {
-    symbol_short!("Commit")
-#[derive(Clone)]
 pub enum DataKey {
     Admin,
     NftContract,
     Commitment(String),        // commitment_id -> Commitment
     OwnerCommitments(Address), // owner -> Vec<commitment_id>
     ActiveCommitments,         // Vec<commitment_id>
+    AllCommitmentIds,          // Vec<commitment_id> of every commitment, in creation order
     TotalCommitments,          // counter
     ReentrancyGuard,           // reentrancy protection flag
     TotalValueLocked,          // aggregate value locked across active commitments
@@ -312,42 +324,67 @@ pub enum DataKey {
     FeeRecipient,              // protocol treasury address for fee withdrawals
     CreationFeeBps,            // commitment creation fee in basis points (0-10000)
     CollectedFees(Address),    // asset -> accumulated fee balance
+    SettlementFeeBps,          // protocol fee on normal settlement in basis points (0-10000)
     SupportedAssets,          // Vec<Address> — whitelist; empty = allow all
     AssetMetadata(Address),   // asset -> AssetMetadata (optional)
+    MinCommitmentAmount(Address), // asset -> i128 (unset = no lower bound)
+    MaxCommitmentAmount(Address), // asset -> i128 (unset = no upper bound)
+    MinPenaltyAmount,             // i128: early exit penalty floor (unset = none)
+    MaxPenaltyAmount,             // i128: early exit penalty cap (unset = none)
     TotalValueLockedByAsset(Address), // asset -> i128
+    LockedPrincipal(Address), // asset -> i128 deposited principal of open commitments
     Version,
+    SeenKey(Address, String), // (owner, idempotency key) -> commitment_id (temporary storage)
+    IdempotencyTtl,           // lifetime of idempotency keys in ledgers
+    IdPrefix,                 // String prefix for generated commitment ids
+    AllocationContract,       // allocation_logic contract notified when commitments close
+    BusinessDayExpiry,        // bool: count duration_days as business days
+    AuthorizedAllocator(Address), // allocator -> bool
+    AuthorizedAllocators,         // Vec<Address> of authorized allocators, for enumeration
+    AllocationTracking(String),   // commitment_id -> AllocationTracking, persistent
+    MaxAllocationBps,             // u32: cap on allocated share of a commitment (default 10000)
+    CommitmentTypes,              // Vec<String> of accepted commitment types
+    InsuranceFund,                // Address receiving the insurance share of penalties
+    InsuranceBps,                 // u32: share of penalties routed to the insurance fund
+    CommitmentFrozen(String),     // commitment_id -> bool: allocations blocked
+    Template(String),             // template name -> CommitmentRules
+    TemplateNames,                // Vec<String> of registered template names
+    AutoRenew(String),            // commitment_id -> bool: roll into a new term on settlement
+    AttestationEngine,            // attestation_engine contract read for attested fees
+    UnderperformanceSlashBps,     // u32: share of the payout slashed for missed fee thresholds
+    AuthorizedUpdater(Address),   // updater -> bool: may call batch_update_value
+    OriginalAsset(String),        // commitment_id -> Address locked at creation; never rewritten
+    CommitmentHistory(String),    // commitment_id -> Vec<(timestamp, from_status, to_status)>
+    CoOwners(String),             // commitment_id -> Vec<CoOwner> sharing settlement proceeds
+    SettlementRecipient(String),  // commitment_id -> (owner who set it, payout address)
+    CommitmentByNft(u32),         // nft token id -> commitment_id
+    SettlementLogCount,           // u64: entries appended to the settlement log
+    SettlementLogPage(u32),       // page -> Vec<(commitment_id, amount, timestamp)>, persistent
+    OwnerPinned(String),          // commitment_id -> bool: owner reassigned without the NFT; no NFT sync
 }
 
 /// Transfer assets from owner to contract
-fn transfer_assets(e: &Env, from: &Address, to: &Address, asset_address: &Address, amount: i128) {
+fn transfer_assets(
+    e: &Env,
+    from: &Address,
+    to: &Address,
+    asset_address: &Address,
+    amount: i128,
+) -> Result<(), CommitmentError> {
     let token_client = token::Client::new(e, asset_address);
 
     // Check balance first
     let balance = token_client.balance(from);
     if balance < amount {
         log!(e, "Insufficient balance: {} < {}", balance, amount);
-        fail(e, CommitmentError::InsufficientBalance, "transfer_assets");
+        return Err(raise(e, CommitmentError::InsufficientBalance, "transfer_assets"));
     }
 
-    // Transfer tokens (fails transaction if unsuccessful)
-    token_client.transfer(from, to, &amount);
+    safe_transfer(e, asset_address, from, to, amount)
+        .map_err(|_| raise(e, CommitmentError::TransferFailed, "transfer_assets"))
 }
 
 
-// Error types for better error handling
-#[contracterror]
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
-#[repr(u32)]
-pub enum CommitmentError {
-    NotFound = 1,
-    AlreadySettled = 2,
-    NotExpired = 3,
-    Unauthorized = 4,
-    InvalidRules = 5,
-    InsufficientBalance = 6,
-    TransferFailed = 7,
-    InvalidAmount = 8,
-    AssetNotFound = 9,
 
 /// Helper function to call NFT contract mint function
 fn call_nft_mint(
@@ -361,7 +398,8 @@ fn call_nft_mint(
     initial_amount: i128,
     asset_address: &Address,
     early_exit_penalty: u32,
-) -> u32 {
+    expires_at: u64,
+) -> Result<u32, CommitmentError> {
     let mut args = Vec::new(e);
     args.push_back(owner.clone().into_val(e));
     args.push_back(commitment_id.clone().into_val(e));
@@ -371,11 +409,17 @@ fn call_nft_mint(
     args.push_back(initial_amount.into_val(e));
     args.push_back(asset_address.clone().into_val(e));
     args.push_back(early_exit_penalty.into_val(e));
+    // Pass core's expiry so both contracts store the same timestamp
+    args.push_back(Some(expires_at).into_val(e));
 
-    // In Soroban, contract calls return the value directly
-    // Failures cause the entire transaction to fail
-    e.invoke_contract::<u32>(nft_contract, &Symbol::new(e, "mint"), args)
-
+    match e.try_invoke_contract::<u32, soroban_sdk::Error>(
+        nft_contract,
+        &Symbol::new(e, "mint"),
+        args,
+    ) {
+        Ok(Ok(token_id)) => Ok(token_id),
+        _ => Err(raise(e, CommitmentError::MintingFailed, "call_nft_mint")),
+    }
 }
 
 // Storage helpers
@@ -416,6 +460,42 @@ fn set_commitment(e: &Env, commitment: &Commitment) {
     );
 }
 
+/// Append a status transition to the commitment's bounded audit log.
+fn record_status_change(e: &Env, commitment_id: &String, from_status: String, to_status: String) {
+    let key = DataKey::CommitmentHistory(commitment_id.clone());
+    let mut history = e
+        .storage()
+        .instance()
+        .get::<_, Vec<(u64, String, String)>>(&key)
+        .unwrap_or(Vec::new(e));
+    history.push_back((e.ledger().timestamp(), from_status, to_status));
+    while history.len() > MAX_HISTORY_ENTRIES {
+        history.pop_front();
+    }
+    e.storage().instance().set(&key, &history);
+}
+
+/// Append a settlement to the log, starting a new persistent page every
+/// `SETTLEMENT_LOG_PAGE_SIZE` entries so no single key grows without bound.
+fn append_settlement_log(e: &Env, commitment_id: &String, amount: i128) {
+    let count = e
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKey::SettlementLogCount)
+        .unwrap_or(0);
+    let key = DataKey::SettlementLogPage((count / SETTLEMENT_LOG_PAGE_SIZE as u64) as u32);
+    let mut page = e
+        .storage()
+        .persistent()
+        .get::<_, Vec<SettlementLogEntry>>(&key)
+        .unwrap_or(Vec::new(e));
+    page.push_back((commitment_id.clone(), amount, e.ledger().timestamp()));
+    e.storage().persistent().set(&key, &page);
+    e.storage()
+        .instance()
+        .set(&DataKey::SettlementLogCount, &(count + 1));
+}
+
 fn has_commitment(e: &Env, commitment_id: &String) -> bool {
     e.storage()
         .instance()
@@ -437,6 +517,16 @@ fn add_owner_commitment(e: &Env, owner: &Address, commitment_id: &String) {
         .set(&DataKey::OwnerCommitments(owner.clone()), &commitments);
 }
 
+fn remove_owner_commitment(e: &Env, owner: &Address, commitment_id: &String) {
+    let mut commitments = get_owner_commitments(e, owner);
+    if let Some(index) = commitments.first_index_of(commitment_id) {
+        commitments.remove(index);
+        e.storage()
+            .instance()
+            .set(&DataKey::OwnerCommitments(owner.clone()), &commitments);
+    }
+}
+
 fn get_active_commitments(e: &Env) -> Vec<String> {
     e.storage()
         .instance()
@@ -444,6 +534,49 @@ fn get_active_commitments(e: &Env) -> Vec<String> {
         .unwrap_or(Vec::new(e))
 }
 
+/// Accepted commitment types; defaults to the built-in safe/balanced/aggressive set.
+fn get_commitment_types(e: &Env) -> Vec<String> {
+    e.storage()
+        .instance()
+        .get::<_, Vec<String>>(&DataKey::CommitmentTypes)
+        .unwrap_or_else(|| {
+            let mut types = Vec::new(e);
+            for t in DEFAULT_COMMITMENT_TYPES.iter() {
+                types.push_back(String::from_str(e, t));
+            }
+            types
+        })
+}
+
+fn get_all_commitment_ids(e: &Env) -> Vec<String> {
+    e.storage()
+        .instance()
+        .get::<_, Vec<String>>(&DataKey::AllCommitmentIds)
+        .unwrap_or(Vec::new(e))
+}
+
+fn add_to_all_commitment_ids(e: &Env, commitment_id: &String) {
+    let mut ids = get_all_commitment_ids(e);
+    ids.push_back(commitment_id.clone());
+    e.storage().instance().set(&DataKey::AllCommitmentIds, &ids);
+}
+
+/// Deposited principal still held for open commitments in `asset`. Unlike TVL
+/// it ignores `update_value` markdowns, so it is what the contract owes users.
+fn get_locked_principal(e: &Env, asset: &Address) -> i128 {
+    e.storage()
+        .instance()
+        .get::<_, i128>(&DataKey::LockedPrincipal(asset.clone()))
+        .unwrap_or(0)
+}
+
+fn adjust_locked_principal(e: &Env, asset: &Address, delta: i128) {
+    let principal = get_locked_principal(e, asset) + delta;
+    e.storage()
+        .instance()
+        .set(&DataKey::LockedPrincipal(asset.clone()), &principal.max(0));
+}
+
 fn add_active_commitment(e: &Env, commitment_id: &String) {
     let mut active = get_active_commitments(e);
     active.push_back(commitment_id.clone());
@@ -484,8 +617,125 @@ fn increment_total_commitments(e: &Env) -> u64 {
     total
 }
 
+fn is_authorized_allocator(e: &Env, allocator: &Address) -> bool {
+    e.storage()
+        .instance()
+        .get::<_, bool>(&DataKey::AuthorizedAllocator(allocator.clone()))
+        .unwrap_or(false)
+}
+
+fn set_authorized_allocator(e: &Env, allocator: &Address, authorized: bool) {
+    e.storage()
+        .instance()
+        .set(&DataKey::AuthorizedAllocator(allocator.clone()), &authorized);
+
+    // Keep the enumerable list in sync with the per-address flags
+    let allocators = read_authorized_allocators(e);
+    let updated = if authorized {
+        let mut allocators = allocators;
+        if !allocators.contains(allocator) {
+            allocators.push_back(allocator.clone());
+        }
+        allocators
+    } else {
+        let mut remaining = Vec::new(e);
+        for existing in allocators.iter() {
+            if existing != *allocator {
+                remaining.push_back(existing);
+            }
+        }
+        remaining
+    };
+    e.storage()
+        .instance()
+        .set(&DataKey::AuthorizedAllocators, &updated);
+}
+
+fn read_authorized_allocators(e: &Env) -> Vec<Address> {
+    e.storage()
+        .instance()
+        .get::<_, Vec<Address>>(&DataKey::AuthorizedAllocators)
+        .unwrap_or(Vec::new(e))
+}
+
+fn is_authorized_updater(e: &Env, updater: &Address) -> bool {
+    e.storage()
+        .instance()
+        .get::<_, bool>(&DataKey::AuthorizedUpdater(updater.clone()))
+        .unwrap_or(false)
+}
+
+fn set_authorized_updater(e: &Env, updater: &Address, authorized: bool) {
+    e.storage()
+        .instance()
+        .set(&DataKey::AuthorizedUpdater(updater.clone()), &authorized);
+}
+
+fn get_allocation_tracking(e: &Env, commitment_id: &String) -> AllocationTracking {
+    e.storage()
+        .persistent()
+        .get::<_, AllocationTracking>(&DataKey::AllocationTracking(commitment_id.clone()))
+        .unwrap_or(AllocationTracking {
+            total_allocated: 0,
+            allocations: Vec::new(e),
+        })
+}
+
+fn is_commitment_frozen(e: &Env, commitment_id: &String) -> bool {
+    e.storage()
+        .instance()
+        .get::<_, bool>(&DataKey::CommitmentFrozen(commitment_id.clone()))
+        .unwrap_or(false)
+}
+
+fn is_auto_renew(e: &Env, commitment_id: &String) -> bool {
+    e.storage()
+        .instance()
+        .get::<_, bool>(&DataKey::AutoRenew(commitment_id.clone()))
+        .unwrap_or(false)
+}
+
+fn set_allocation_tracking(e: &Env, commitment_id: &String, tracking: &AllocationTracking) {
+    e.storage()
+        .persistent()
+        .set(&DataKey::AllocationTracking(commitment_id.clone()), tracking);
+}
+
+/// Split a protocol fee into (treasury, insurance) parts. Everything stays with
+/// the treasury until an insurance fund is configured.
+fn insurance_split(e: &Env, total: i128) -> (i128, i128) {
+    if !e.storage().instance().has(&DataKey::InsuranceFund) {
+        return (total, 0);
+    }
+    let insurance_bps = e
+        .storage()
+        .instance()
+        .get::<_, u32>(&DataKey::InsuranceBps)
+        .unwrap_or(0);
+    split_fee(total, insurance_bps)
+}
+
+/// Net a deallocation against a pool's recorded allocations, newest first, so
+/// `allocations` only lists what is still outstanding.
+fn net_pool_allocations(e: &Env, tracking: &mut AllocationTracking, pool: &Address, amount: i128) {
+    let mut remaining = amount;
+    let mut outstanding = Vec::new(e);
+    for mut allocation in tracking.allocations.iter().rev() {
+        if remaining > 0 && allocation.target_pool == *pool {
+            let netted = allocation.amount.min(remaining);
+            remaining -= netted;
+            allocation.amount -= netted;
+            if allocation.amount == 0 {
+                continue;
+            }
+        }
+        outstanding.push_front(allocation);
+    }
+    tracking.allocations = outstanding;
+}
+
 /// Reentrancy protection helpers
-fn require_no_reentrancy(e: &Env) {
+fn check_no_reentrancy(e: &Env) -> Result<(), CommitmentError> {
     let guard: bool = e
         .storage()
         .instance()
@@ -493,11 +743,18 @@ fn require_no_reentrancy(e: &Env) {
         .unwrap_or(false);
 
     if guard {
-        fail(
+        return Err(raise(
             e,
             CommitmentError::ReentrancyDetected,
             "require_no_reentrancy",
-        );
+        ));
+    }
+    Ok(())
+}
+
+fn require_no_reentrancy(e: &Env) {
+    if let Err(err) = check_no_reentrancy(e) {
+        panic!("{}", err.message());
     }
 }
 
@@ -507,8 +764,25 @@ fn set_reentrancy_guard(e: &Env, value: bool) {
         .set(&DataKey::ReentrancyGuard, &value);
 }
 
+/// Require that the amount is within the per-asset commitment limits.
+fn require_amount_within_limits(
+    e: &Env,
+    asset_address: &Address,
+    amount: i128,
+) -> Result<(), CommitmentError> {
+    let (min_amount, max_amount) =
+        CommitmentCoreContract::get_commitment_amount_limits(e.clone(), asset_address.clone());
+    if matches!(min_amount, Some(min) if amount < min) {
+        return Err(raise(e, CommitmentError::AmountBelowMinimum, "create_commitment"));
+    }
+    if matches!(max_amount, Some(max) if amount > max) {
+        return Err(raise(e, CommitmentError::AmountAboveMaximum, "create_commitment"));
+    }
+    Ok(())
+}
+
 /// Require that the asset is in the supported whitelist (if whitelist is non-empty).
-fn require_asset_supported(e: &Env, asset_address: &Address) {
+fn require_asset_supported(e: &Env, asset_address: &Address) -> Result<(), CommitmentError> {
     let supported = e
         .storage()
         .instance()
@@ -523,29 +797,323 @@ fn require_asset_supported(e: &Env, asset_address: &Address) {
             }
         }
         if !found {
-            fail(e, CommitmentError::AssetNotSupported, "require_asset_supported");
+            return Err(raise(e, CommitmentError::AssetNotSupported, "require_asset_supported"));
         }
     }
+    Ok(())
 }
 
-/// Require that the caller is the admin stored in this contract.
-fn require_admin(e: &Env, caller: &Address) {
-    caller.require_auth();
-    let admin = e
+/// Require that a commitment still pays out in the asset it was created with.
+/// Commitments created before the original asset was recorded are not checked.
+fn require_original_asset(
+    e: &Env,
+    commitment: &Commitment,
+    context: &str,
+) -> Result<(), CommitmentError> {
+    let original = e
         .storage()
         .instance()
-        .get::<_, Address>(&DataKey::Admin)
-        .unwrap_or_else(|| fail(e, CommitmentError::NotInitialized, "require_admin"));
-    if *caller != admin {
-        fail(e, CommitmentError::Unauthorized, "require_admin");
+        .get::<_, Address>(&DataKey::OriginalAsset(commitment.commitment_id.clone()));
+    if matches!(original, Some(asset) if asset != commitment.asset_address) {
+        return Err(raise(e, CommitmentError::AssetMismatch, context));
     }
+    Ok(())
 }
 
-fn read_version(e: &Env) -> u32 {
-    e.storage()
+/// Address that receives the owner's payouts: the settlement recipient if the
+/// current owner set one, otherwise the owner. A recipient set by a previous
+/// owner is ignored.
+fn settlement_recipient(e: &Env, commitment: &Commitment) -> Address {
+    match e
+        .storage()
         .instance()
-        .get::<_, u32>(&DataKey::Version)
-        .unwrap_or(0)
+        .get::<_, (Address, Address)>(&DataKey::SettlementRecipient(commitment.commitment_id.clone()))
+    {
+        Some((set_by, recipient)) if set_by == commitment.owner => recipient,
+        _ => commitment.owner.clone(),
+    }
+}
+
+/// Pay `amount` of the commitment's asset out of the contract: all of it to the
+/// owner, or split across the co-owners by `share_bps` with the last co-owner
+/// taking the rounding remainder. The owner's part goes to its settlement
+/// recipient.
+fn pay_owners(e: &Env, commitment: &Commitment, amount: i128) -> Result<(), u32> {
+    let contract_address = e.current_contract_address();
+    let recipient = settlement_recipient(e, commitment);
+    let co_owners = e
+        .storage()
+        .instance()
+        .get::<_, Vec<CoOwner>>(&DataKey::CoOwners(commitment.commitment_id.clone()))
+        .unwrap_or(Vec::new(e));
+    if co_owners.is_empty() {
+        return safe_transfer(e, &commitment.asset_address, &contract_address, &recipient, amount);
+    }
+
+    let mut remaining = amount;
+    let last = co_owners.len() - 1;
+    for (i, co_owner) in co_owners.iter().enumerate() {
+        let share = if i as u32 == last {
+            remaining
+        } else {
+            fee_from_bps(amount, co_owner.share_bps)
+        };
+        if share > 0 {
+            let payee = if co_owner.owner == commitment.owner {
+                &recipient
+            } else {
+                &co_owner.owner
+            };
+            safe_transfer(e, &commitment.asset_address, &contract_address, payee, share)?;
+        }
+        remaining -= share;
+    }
+    Ok(())
+}
+
+/// Hand `previous_owner`'s co-owner share to `new_owner` when the commitment
+/// changes hands, merging it into `new_owner`'s entry if they already hold one.
+fn move_co_owner_share(
+    e: &Env,
+    commitment_id: &String,
+    previous_owner: &Address,
+    new_owner: &Address,
+) {
+    let key = DataKey::CoOwners(commitment_id.clone());
+    let co_owners = match e.storage().instance().get::<_, Vec<CoOwner>>(&key) {
+        Some(co_owners) => co_owners,
+        None => return,
+    };
+    let moved: u32 = co_owners
+        .iter()
+        .filter(|co_owner| co_owner.owner == *previous_owner)
+        .map(|co_owner| co_owner.share_bps)
+        .sum();
+    if moved == 0 {
+        return;
+    }
+
+    let mut updated = Vec::new(e);
+    let mut merged = false;
+    for co_owner in co_owners.iter() {
+        if co_owner.owner == *previous_owner {
+            continue;
+        }
+        if co_owner.owner == *new_owner {
+            updated.push_back(CoOwner {
+                owner: co_owner.owner,
+                share_bps: co_owner.share_bps + moved,
+            });
+            merged = true;
+        } else {
+            updated.push_back(co_owner);
+        }
+    }
+    if !merged {
+        updated.push_back(CoOwner {
+            owner: new_owner.clone(),
+            share_bps: moved,
+        });
+    }
+    e.storage().instance().set(&key, &updated);
+}
+
+/// Require that the caller is the admin stored in this contract.
+fn require_admin(e: &Env, caller: &Address) {
+    caller.require_auth();
+    let admin = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::Admin)
+        .unwrap_or_else(|| fail(e, CommitmentError::NotInitialized, "require_admin"));
+    if *caller != admin {
+        fail(e, CommitmentError::Unauthorized, "require_admin");
+    }
+}
+
+/// Tell the configured allocation contract that a commitment closed so it can
+/// release its pool positions. Failures are reported as an event and never
+/// block the settlement itself.
+fn notify_allocation_closed(e: &Env, commitment_id: &String) {
+    let allocation_contract = match e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::AllocationContract)
+    {
+        Some(address) => address,
+        None => return,
+    };
+
+    let mut args = Vec::new(e);
+    args.push_back(commitment_id.clone().into_val(e));
+    let result = e.try_invoke_contract::<(), soroban_sdk::Error>(
+        &allocation_contract,
+        &Symbol::new(e, "on_commitment_closed"),
+        args,
+    );
+
+    if !matches!(result, Ok(Ok(()))) {
+        e.events().publish(
+            (Symbol::new(e, "AllocHookFailed"), commitment_id.clone()),
+            (allocation_contract, e.ledger().timestamp()),
+        );
+    }
+}
+
+/// Length of an id list returned by a satellite contract, or None if the call failed.
+fn satellite_id_count(
+    e: &Env,
+    contract: &Address,
+    function: &str,
+    commitment_id: &String,
+) -> Option<u32> {
+    let mut args = Vec::new(e);
+    args.push_back(commitment_id.clone().into_val(e));
+    match e.try_invoke_contract::<Vec<String>, soroban_sdk::Error>(
+        contract,
+        &Symbol::new(e, function),
+        args,
+    ) {
+        Ok(Ok(ids)) => Some(ids.len()),
+        _ => None,
+    }
+}
+
+/// Instrument counts for a commitment from the transformation contract, or None
+/// if any of its getters failed.
+fn read_transformation_counts(
+    e: &Env,
+    transformation: &Address,
+    commitment_id: &String,
+) -> Option<TransformationCounts> {
+    Some(TransformationCounts {
+        tranche_sets: satellite_id_count(e, transformation, "get_commitment_tranche_sets", commitment_id)?,
+        collateral: satellite_id_count(e, transformation, "get_commitment_collateral", commitment_id)?,
+        instruments: satellite_id_count(e, transformation, "get_commitment_instruments", commitment_id)?,
+        guarantees: satellite_id_count(e, transformation, "get_commitment_guarantees", commitment_id)?,
+    })
+}
+
+/// Split a settlement into (gross, settlement fee, net payout to the owner).
+fn settlement_breakdown(e: &Env, commitment: &Commitment) -> (i128, i128, i128) {
+    let gross = commitment.current_value;
+    let fee = if gross > 0 {
+        fee_from_bps(gross, CommitmentCoreContract::get_settlement_fee_bps(e.clone()))
+    } else {
+        0
+    };
+    (gross, fee, gross - fee)
+}
+
+/// Load the active commitment a value update targets, or the error rejecting it.
+fn value_update_target(
+    e: &Env,
+    commitment_id: &String,
+    new_value: i128,
+) -> Result<Commitment, CommitmentError> {
+    if new_value < 0 {
+        return Err(CommitmentError::InvalidAmount);
+    }
+    let commitment = read_commitment(e, commitment_id).ok_or(CommitmentError::CommitmentNotFound)?;
+    if commitment.status != String::from_str(e, "active") {
+        return Err(CommitmentError::NotActive);
+    }
+    Ok(commitment)
+}
+
+/// Store a commitment's new value, move TVL by the difference and emit `ValUpd`.
+fn apply_value_update(e: &Env, mut commitment: Commitment, new_value: i128) {
+    let old_value = commitment.current_value;
+    let asset = commitment.asset_address.clone();
+    commitment.current_value = new_value;
+    set_commitment(e, &commitment);
+
+    // Adjust TotalValueLocked: TVL -= old_value, TVL += new_value
+    let current_tvl = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&DataKey::TotalValueLocked)
+        .unwrap_or(0);
+    let new_tvl = current_tvl - old_value + new_value;
+    e.storage()
+        .instance()
+        .set(&DataKey::TotalValueLocked, &new_tvl);
+
+    // Per-asset TVL
+    let asset_tvl = e
+        .storage()
+        .instance()
+        .get::<_, i128>(&DataKey::TotalValueLockedByAsset(asset.clone()))
+        .unwrap_or(0);
+    e.storage()
+        .instance()
+        .set(&DataKey::TotalValueLockedByAsset(asset), &(asset_tvl - old_value + new_value));
+
+    e.events().publish(
+        (symbol_short!("ValUpd"), commitment.commitment_id),
+        (new_value, e.ledger().timestamp()),
+    );
+}
+
+/// Whether a commitment's loss exceeds its limit: in basis points against
+/// `max_loss_bps` when set, otherwise in whole percent against `max_loss_percent`.
+/// A zero-amount commitment cannot meaningfully breach a loss limit.
+fn loss_limit_breached(commitment: &Commitment) -> bool {
+    if commitment.amount <= 0 {
+        return false;
+    }
+    if commitment.rules.max_loss_bps > 0 {
+        SafeMath::loss_bps(commitment.amount, commitment.current_value)
+            > commitment.rules.max_loss_bps as i128
+    } else {
+        SafeMath::loss_percent(commitment.amount, commitment.current_value)
+            > commitment.rules.max_loss_percent as i128
+    }
+}
+
+/// Split an early exit into (penalty, amount returned to the owner), clamping
+/// the percentage penalty to the configured floor and cap.
+fn early_exit_breakdown(e: &Env, commitment: &Commitment) -> (i128, i128) {
+    let (min_penalty, max_penalty) = CommitmentCoreContract::get_penalty_limits(e.clone());
+    let penalty = SafeMath::penalty_amount_bounded(
+        commitment.current_value,
+        commitment.rules.early_exit_penalty,
+        min_penalty,
+        max_penalty,
+    );
+    (penalty, SafeMath::sub(commitment.current_value, penalty))
+}
+
+/// Look up the commitment `owner` created under an idempotency key, if the
+/// key is still live and the commitment is still theirs.
+fn read_seen_key(e: &Env, owner: &Address, key: &String) -> Option<String> {
+    let commitment_id = e
+        .storage()
+        .temporary()
+        .get::<_, String>(&DataKey::SeenKey(owner.clone(), key.clone()))?;
+    match read_commitment(e, &commitment_id) {
+        Some(commitment) if commitment.owner == *owner => Some(commitment_id),
+        _ => None,
+    }
+}
+
+/// Remember which commitment an owner's idempotency key produced until its TTL lapses.
+fn record_seen_key(e: &Env, owner: &Address, key: &String, commitment_id: &String) {
+    let ttl = e
+        .storage()
+        .instance()
+        .get::<_, u32>(&DataKey::IdempotencyTtl)
+        .unwrap_or(DEFAULT_IDEMPOTENCY_TTL_LEDGERS);
+    let storage_key = DataKey::SeenKey(owner.clone(), key.clone());
+    e.storage().temporary().set(&storage_key, commitment_id);
+    e.storage().temporary().extend_ttl(&storage_key, ttl, ttl);
+}
+
+fn read_version(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get::<_, u32>(&DataKey::Version)
+        .unwrap_or(0)
 }
 
 fn write_version(e: &Env, version: u32) {
@@ -565,32 +1133,259 @@ pub struct CommitmentCoreContract;
 
 #[contractimpl]
 impl CommitmentCoreContract {
-    /// Validate commitment rules using shared utilities
-    fn validate_rules(e: &Env, rules: &CommitmentRules) {
+    /// Validate commitment rules
+    fn validate_rules(e: &Env, rules: &CommitmentRules) -> Result<(), CommitmentError> {
         // Duration must be > 0
-        Validation::require_valid_duration(rules.duration_days);
+        if rules.duration_days == 0 {
+            return Err(raise(e, CommitmentError::InvalidDuration, "validate_rules"));
+        }
 
-        // Max loss percent must be between 0 and 100
-        Validation::require_valid_percent(rules.max_loss_percent);
+        // Max loss percent must be between 0 and 100, and the bps variant up to 10000
+        if rules.max_loss_percent > 100 || rules.max_loss_bps > BPS_MAX {
+            return Err(raise(e, CommitmentError::InvalidMaxLossPercent, "validate_rules"));
+        }
 
-        // Commitment type must be valid
-        let valid_types = ["safe", "balanced", "aggressive"];
-        Validation::require_valid_commitment_type(e, &rules.commitment_type, &valid_types);
+        // Commitment type must be one of the configured types
+        if !get_commitment_types(e).contains(&rules.commitment_type) {
+            return Err(raise(e, CommitmentError::InvalidCommitmentType, "validate_rules"));
+        }
+        Ok(())
     }
 
     /// Generate unique commitment ID
     /// Optimized: Uses counter to create unique ID efficiently
+    fn read_active_commitment(e: &Env, commitment_id: &String, context: &str) -> Commitment {
+        let commitment = read_commitment(e, commitment_id)
+            .unwrap_or_else(|| fail(e, CommitmentError::CommitmentNotFound, context));
+        if commitment.status != String::from_str(e, "active") {
+            fail(e, CommitmentError::NotActive, context);
+        }
+        commitment
+    }
+
+    /// Validate that a commitment has matured and close it for settlement:
+    /// mark it settled, drop it from the active list and TVL, and book the
+    /// settlement fee. Clears the reentrancy guard on error.
+    ///
+    /// Returns (commitment, settlement fee, payout owed to the owner).
+    fn close_matured_commitment(
+        e: &Env,
+        commitment_id: &String,
+        context: &str,
+    ) -> Result<(Commitment, i128, i128), CommitmentError> {
+        // CHECKS: Get and validate commitment
+        let mut commitment = read_commitment(e, commitment_id).ok_or_else(|| {
+            set_reentrancy_guard(e, false);
+            raise(e, CommitmentError::CommitmentNotFound, context)
+        })?;
+
+        // Verify commitment is expired or within grace period
+        let current_time = e.ledger().timestamp();
+        // Requirement: Allow settlement if expired or within grace period
+        // Note: Settlement is allowed if current_time >= expires_at
+        if current_time < commitment.expires_at {
+            set_reentrancy_guard(e, false);
+            return Err(raise(e, CommitmentError::NotExpired, context));
+        }
+
+        // Verify commitment is active
+        let active_status = String::from_str(e, "active");
+        if commitment.status != active_status {
+            set_reentrancy_guard(e, false);
+            return Err(raise(e, CommitmentError::NotActive, context));
+        }
+
+        // Allocated funds sit in pools; deallocate_all must bring them back first
+        if get_allocation_tracking(e, commitment_id).total_allocated > 0 {
+            set_reentrancy_guard(e, false);
+            return Err(raise(e, CommitmentError::OutstandingAllocations, context));
+        }
+
+        // Never pay out in a token other than the one deposited
+        if let Err(err) = require_original_asset(e, &commitment, context) {
+            set_reentrancy_guard(e, false);
+            return Err(err);
+        }
+
+        // EFFECTS: Update state before external calls
+        let (settlement_amount, settlement_fee, payout) = settlement_breakdown(e, &commitment);
+        let settled_status = String::from_str(e, "settled");
+        record_status_change(e, commitment_id, commitment.status.clone(), settled_status.clone());
+        commitment.status = settled_status;
+        set_commitment(e, &commitment);
+
+        // Remove from active commitments list
+        remove_active_commitment(e, commitment_id);
+        adjust_locked_principal(e, &commitment.asset_address, -commitment.amount);
+
+        // Decrease total value locked
+        let current_tvl = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLocked)
+            .unwrap_or(0);
+        let new_tvl = current_tvl - settlement_amount;
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLocked, &new_tvl);
+
+        // Per-asset TVL
+        let asset = commitment.asset_address.clone();
+        let asset_tvl = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLockedByAsset(asset.clone()))
+            .unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLockedByAsset(asset), &(asset_tvl - settlement_amount));
+
+        // Settlement fee stays in the contract as protocol revenue
+        if settlement_fee > 0 {
+            let key = DataKey::CollectedFees(commitment.asset_address.clone());
+            let current_fees = e.storage().instance().get::<_, i128>(&key).unwrap_or(0);
+            e.storage()
+                .instance()
+                .set(&key, &(current_fees + settlement_fee));
+        }
+
+        Ok((commitment, settlement_fee, payout))
+    }
+
+    /// Mark a settled commitment's NFT as settled and release its pool positions
+    fn finish_settlement(
+        e: &Env,
+        commitment_id: &String,
+        commitment: &Commitment,
+        context: &str,
+    ) -> Result<(), CommitmentError> {
+        // Call NFT contract to mark NFT as settled
+        let nft_contract = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::NftContract)
+            .ok_or_else(|| {
+                set_reentrancy_guard(e, false);
+                raise(e, CommitmentError::NotInitialized, context)
+            })?;
+
+        let mut args = Vec::new(e);
+        args.push_back(commitment.nft_token_id.into_val(e));
+        e.invoke_contract::<()>(&nft_contract, &Symbol::new(e, "settle"), args);
+
+        // Release any pool positions held for this commitment
+        notify_allocation_closed(e, commitment_id);
+
+        Ok(())
+    }
+
+    /// Store a new active commitment holding `amount_locked` (already in the
+    /// contract), update the counters and TVL, and mint its NFT.
+    ///
+    /// Returns the minted NFT token id.
+    fn open_commitment(
+        e: &Env,
+        nft_contract: &Address,
+        commitment_id: &String,
+        owner: &Address,
+        asset_address: &Address,
+        rules: &CommitmentRules,
+        amount_locked: i128,
+    ) -> Result<u32, CommitmentError> {
+        // Calculate expiration timestamp using shared utilities
+        let current_timestamp = TimeUtils::now(e);
+        let expires_at = if Self::is_business_day_expiry(e.clone()) {
+            TimeUtils::add_business_days(e, current_timestamp, rules.duration_days)
+        } else {
+            TimeUtils::calculate_expiration(e, rules.duration_days)
+        };
+
+        let mut commitment = Commitment {
+            commitment_id: commitment_id.clone(),
+            owner: owner.clone(),
+            nft_token_id: 0, // Will be set after NFT mint
+            rules: rules.clone(),
+            amount: amount_locked,
+            asset_address: asset_address.clone(),
+            created_at: current_timestamp,
+            expires_at,
+            current_value: amount_locked, // Initially same as locked amount
+            status: String::from_str(e, "active"),
+        };
+
+        // Store commitment data (before external calls)
+        set_commitment(e, &commitment);
+        record_status_change(e, commitment_id, String::from_str(e, ""), commitment.status.clone());
+        e.storage()
+            .instance()
+            .set(&DataKey::OriginalAsset(commitment_id.clone()), asset_address);
+        add_owner_commitment(e, owner, commitment_id);
+        add_active_commitment(e, commitment_id);
+        add_to_all_commitment_ids(e, commitment_id);
+        increment_total_commitments(e);
+
+        let current_tvl = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLocked)
+            .unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLocked, &(current_tvl + amount_locked));
+
+        // Per-asset TVL tracking
+        let asset_tvl = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLockedByAsset(asset_address.clone()))
+            .unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLockedByAsset(asset_address.clone()), &(asset_tvl + amount_locked));
+        adjust_locked_principal(e, asset_address, amount_locked);
+
+        // INTERACTIONS: NFT mint (use locked amount for display)
+        let nft_token_id = call_nft_mint(
+            e,
+            nft_contract,
+            owner,
+            commitment_id,
+            rules.duration_days,
+            rules.max_loss_percent,
+            &rules.commitment_type,
+            amount_locked,
+            asset_address,
+            rules.early_exit_penalty,
+            expires_at,
+        )?;
+
+        commitment.nft_token_id = nft_token_id;
+        set_commitment(e, &commitment);
+        e.storage()
+            .instance()
+            .set(&DataKey::CommitmentByNft(nft_token_id), commitment_id);
+        Ok(nft_token_id)
+    }
+
     fn generate_commitment_id(e: &Env, counter: u64) -> String {
         // OPTIMIZATION: Use counter directly as string to minimize allocations
         // This is more gas-efficient than string concatenation
         let mut buf = [0u8; 32];
-        let prefix = b"c_";
-        buf[0] = prefix[0];
-        buf[1] = prefix[1];
+        let prefix_len = match e.storage().instance().get::<_, String>(&DataKey::IdPrefix) {
+            Some(prefix) => {
+                let len = prefix.len() as usize;
+                prefix.copy_into_slice(&mut buf[..len]);
+                len
+            }
+            None => {
+                buf[..DEFAULT_ID_PREFIX.len()].copy_from_slice(DEFAULT_ID_PREFIX);
+                DEFAULT_ID_PREFIX.len()
+            }
+        };
 
         // Convert counter to string representation
         let mut n = counter;
-        let mut i = 2;
+        let mut i = prefix_len;
         if n == 0 {
             buf[i] = b'0';
             i += 1;
@@ -613,51 +1408,22 @@ impl CommitmentCoreContract {
     }
 
     /// Initialize the core commitment contract
-    pub fn initialize(e: Env, admin: Address, _nft_contract: Address) {
-        if is_initialized(&e) {
-            panic_already_initialized();
-        }
-        
-        set_admin(&e, &admin);
-        set_initialized(&e);
-    }
-
-    /// Add an authorized allocation contract
-    pub fn add_authorized_allocator(e: Env, allocator: Address) {
-        let admin = get_admin(&e);
-        admin.require_auth();
-        
-        set_authorized_allocator(&e, &allocator, true);
-    }
-
-    /// Remove an authorized allocation contract
-    pub fn remove_authorized_allocator(e: Env, allocator: Address) {
-        let admin = get_admin(&e);
-        admin.require_auth();
-        
-        set_authorized_allocator(&e, &allocator, false);
-    }
-
-    /// Check if an address is an authorized allocator
-    pub fn is_authorized_allocator(e: Env, allocator: Address) -> bool {
-        is_authorized_allocator(&e, &allocator)
-    pub fn initialize(_e: Env, _admin: Address, _nft_contract: Address) {
-        // TODO: Store admin and NFT contract address
-        // TODO: Initialize storage
-    pub fn initialize(e: Env, admin: Address, nft_contract: Address) {
-
-        // Store admin
-        e.storage().instance().set(&admin_key(&e), &admin);
-        // Store NFT contract address
-        e.storage()
-            .instance()
-            .set(&nft_contract_key(&e), &nft_contract);
-
+    ///
+    /// `id_prefix` namespaces generated commitment ids (e.g. "mainnet_") so
+    /// several deployments on one network don't collide. Defaults to "c_".
+    pub fn initialize(e: Env, admin: Address, nft_contract: Address, id_prefix: Option<String>) {
         // Check if already initialized
         if e.storage().instance().has(&DataKey::Admin) {
             fail(&e, CommitmentError::AlreadyInitialized, "initialize");
         }
 
+        if let Some(prefix) = id_prefix.as_ref() {
+            if prefix.is_empty() || prefix.len() > MAX_ID_PREFIX_LEN {
+                fail(&e, CommitmentError::InvalidIdPrefix, "initialize");
+            }
+            e.storage().instance().set(&DataKey::IdPrefix, prefix);
+        }
+
         // Store admin and NFT contract address
         set_admin(&e, &admin);
         set_nft_contract(&e, &nft_contract);
@@ -683,28 +1449,92 @@ impl CommitmentCoreContract {
         write_version(&e, CURRENT_VERSION);
     }
 
+    /// Add an authorized allocation contract (admin only)
+    pub fn add_authorized_allocator(e: Env, allocator: Address) {
+        let admin = get_admin(&e);
+        admin.require_auth();
+
+        set_authorized_allocator(&e, &allocator, true);
+    }
+
+    /// Remove an authorized allocation contract (admin only)
+    pub fn remove_authorized_allocator(e: Env, allocator: Address) {
+        let admin = get_admin(&e);
+        admin.require_auth();
+
+        set_authorized_allocator(&e, &allocator, false);
+    }
+
+    /// Check if an address is an authorized allocator
+    pub fn is_authorized_allocator(e: Env, allocator: Address) -> bool {
+        is_authorized_allocator(&e, &allocator)
+    }
+
+    /// List all authorized allocators, in the order they were added
+    pub fn get_authorized_allocators(e: Env) -> Vec<Address> {
+        read_authorized_allocators(&e)
+    }
+
+    /// Authorize an address to call `batch_update_value` (admin only)
+    pub fn add_authorized_updater(e: Env, updater: Address) {
+        let admin = get_admin(&e);
+        admin.require_auth();
+
+        set_authorized_updater(&e, &updater, true);
+    }
+
+    /// Revoke an address's `batch_update_value` access (admin only)
+    pub fn remove_authorized_updater(e: Env, updater: Address) {
+        let admin = get_admin(&e);
+        admin.require_auth();
+
+        set_authorized_updater(&e, &updater, false);
+    }
+
+    /// Check if an address is an authorized value updater
+    pub fn is_authorized_updater(e: Env, updater: Address) -> bool {
+        is_authorized_updater(&e, &updater)
+    }
+
     /// Create a new commitment
     ///
+    /// # Idempotency
+    /// If `idempotency_key` is set, `owner` must authorize the call. When the
+    /// same owner already used the key within its TTL, the commitment created
+    /// by the earlier call is returned and nothing is transferred or minted
+    /// again. Keys are scoped per owner, so another owner's key never matches.
+    ///
+    /// # Deadline
+    /// A nonzero `deadline` (ledger timestamp) rejects the call once the ledger
+    /// has moved past it, so a delayed submission cannot lock funds under
+    /// stale terms. Pass `0` to disable the check.
+    ///
+    /// # Fee-bearing assets
+    /// The committed amount is what the contract actually received (balance
+    /// delta), which is less than `amount` for transfer-fee or rebasing tokens.
+    ///
     /// # Reentrancy Protection
     /// This function uses checks-effects-interactions pattern:
     /// 1. Checks: Validate inputs
-    /// 2. Effects: Update state (commitment storage, counters)
-    /// 3. Interactions: External calls (token transfer, NFT mint)
+    /// 2. Token transfer in, measured by balance delta
+    /// 3. Effects: Update state (commitment storage, counters)
+    /// 4. Interactions: NFT mint
     /// Reentrancy guard prevents recursive calls.
     ///
     /// # Formal Verification
     /// **Preconditions:**
+    /// - `deadline == 0 || ledger.timestamp <= deadline`
     /// - `amount > 0`
     /// - `rules.duration_days > 0`
-    /// - `rules.max_loss_percent <= 100`
-    /// - `rules.commitment_type ∈ {"safe", "balanced", "aggressive"}`
+    /// - `rules.max_loss_percent <= 100` and `rules.max_loss_bps <= 10000`
+    /// - `rules.commitment_type ∈ get_commitment_types()`
     /// - Contract is initialized
     /// - `reentrancy_guard == false`
     ///
     /// **Postconditions:**
     /// - Returns unique `commitment_id`
     /// - `get_commitment(commitment_id).owner == owner`
-    /// - `get_commitment(commitment_id).amount == amount`
+    /// - `get_commitment(commitment_id).amount == received - creation_fee` (`received <= amount`)
     /// - `get_commitment(commitment_id).status == "active"`
     /// - `get_total_commitments() == old(get_total_commitments()) + 1`
     /// - `reentrancy_guard == false`
@@ -726,97 +1556,67 @@ impl CommitmentCoreContract {
         amount: i128,
         asset_address: Address,
         rules: CommitmentRules,
-
+        idempotency_key: Option<String>,
+        deadline: u64,
     ) -> Result<String, CommitmentError> {
-        // Require authorization from owner
-        owner.require_auth();
-
-        // Validate rules
-        if rules.duration_days == 0 {
-            return Err(CommitmentError::InvalidRules);
-        }
-        if rules.max_loss_percent > 100 {
-            return Err(CommitmentError::InvalidRules);
+        if deadline != 0 && e.ledger().timestamp() > deadline {
+            return Err(raise(&e, CommitmentError::DeadlineExceeded, "create_commitment"));
         }
-        if amount <= 0 {
-            return Err(CommitmentError::InvalidAmount);
-        }
-
-        // Verify user has sufficient balance
-        verify_sufficient_balance(&e, &asset_address, &owner, amount)?;
-
-        // Transfer assets from owner to contract
-        transfer_from_user_to_contract(&e, &asset_address, &owner, amount);
-
-        // Generate unique commitment ID based on timestamp
-        let timestamp = e.ledger().timestamp();
-        let commitment_id = String::from_str(&e, "commitment_");
-        // In production, would append timestamp/hash for uniqueness
-
-        // Calculate expiration
-        let duration_seconds = (rules.duration_days as u64) * 24 * 60 * 60;
-        let expires_at = timestamp + duration_seconds;
 
-        // Get NFT contract and mint NFT
-        let nft_contract: Address = e.storage().instance().get(&nft_contract_key(&e)).unwrap();
-        let nft_token_id: u32 = 1; // This will be returned from NFT contract mint call
-                                   // TODO: Call NFT contract to mint (requires cross-contract call implementation)
-
-        // Create commitment
-
-    ) -> String {
         // Reentrancy protection
-        require_no_reentrancy(&e);
+        check_no_reentrancy(&e)?;
         set_reentrancy_guard(&e, true);
         EmergencyControl::require_not_emergency(&e);
 
+        // Retried call: hand back the commitment this owner created the first time
+        if let Some(key) = idempotency_key.as_ref() {
+            owner.require_auth();
+            if let Some(existing_id) = read_seen_key(&e, &owner, key) {
+                set_reentrancy_guard(&e, false);
+                return Ok(existing_id);
+            }
+        }
+
         // Rate limit: per-owner commitment creation
         let fn_symbol = symbol_short!("create");
         RateLimiter::check(&e, &owner, &fn_symbol);
 
-        // Validate amount > 0 using shared utilities
-        Validation::require_positive(amount);
+        if amount <= 0 {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::InvalidAmount, "create_commitment"));
+        }
 
         // Validate rules
-        Self::validate_rules(&e, &rules);
-
-        // Fee: creation fee in basis points (0 = no fee)
-        let creation_fee_bps: u32 = e
-            .storage()
-            .instance()
-            .get::<_, u32>(&DataKey::CreationFeeBps)
-            .unwrap_or(0);
-        let creation_fee = if creation_fee_bps > 0 && creation_fee_bps <= BPS_MAX {
-            fee_from_bps(amount, creation_fee_bps)
-        } else {
-            0
-        };
-        let amount_locked = amount - creation_fee;
+        if let Err(err) = Self::validate_rules(&e, &rules) {
+            set_reentrancy_guard(&e, false);
+            return Err(err);
+        }
 
         // Require asset is in supported whitelist (if whitelist is set)
-        require_asset_supported(&e, &asset_address);
+        if let Err(err) = require_asset_supported(&e, &asset_address) {
+            set_reentrancy_guard(&e, false);
+            return Err(err);
+        }
 
-        // OPTIMIZATION: Read both counters and NFT contract once to minimize storage operations
-        let (current_total, current_tvl, nft_contract) = {
+        // OPTIMIZATION: Read the counter and NFT contract once to minimize storage operations
+        let (current_total, nft_contract) = {
             let total = e
                 .storage()
                 .instance()
                 .get::<_, u64>(&DataKey::TotalCommitments)
                 .unwrap_or(0);
-            let tvl = e
-                .storage()
-                .instance()
-                .get::<_, i128>(&DataKey::TotalValueLocked)
-                .unwrap_or(0);
             let nft = e
                 .storage()
                 .instance()
-                .get::<_, Address>(&DataKey::NftContract)
-                .unwrap_or_else(|| {
-                    set_reentrancy_guard(&e, false);
-                    fail(&e, CommitmentError::NotInitialized, "create_commitment")
-                });
-            (total, tvl, nft)
+                .get::<_, Address>(&DataKey::NftContract);
+            (total, nft)
+        };
+        let nft_contract = match nft_contract {
+            Some(nft) => nft,
+            None => {
+                set_reentrancy_guard(&e, false);
+                return Err(raise(&e, CommitmentError::NotInitialized, "create_commitment"));
+            }
         };
 
         // Generate unique commitment ID using counter
@@ -825,42 +1625,41 @@ impl CommitmentCoreContract {
         // CHECKS: Validate commitment doesn't already exist
         if has_commitment(&e, &commitment_id) {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::InvalidStatus, "create_commitment");
+            return Err(raise(&e, CommitmentError::InvalidStatus, "create_commitment"));
         }
 
-        // EFFECTS: Update state before external calls
-        // Calculate expiration timestamp using shared utilities
-        let current_timestamp = TimeUtils::now(&e);
-        let expires_at = TimeUtils::calculate_expiration(&e, rules.duration_days);
-
-        // Create commitment data (amount locked = user amount minus creation fee)
-        let commitment = Commitment {
-            commitment_id: commitment_id.clone(),
-            owner: owner.clone(),
-            nft_token_id: 0, // Will be set after NFT mint
-            rules: rules.clone(),
-            amount: amount_locked,
-            asset_address: asset_address.clone(),
-            created_at: current_timestamp,
-            expires_at,
-            current_value: amount_locked, // Initially same as locked amount
-            status: String::from_str(&e, "active"),
-        };
-
-        // Store commitment data (before external calls)
-        set_commitment(&e, &commitment);
-
-        // Update owner's commitment list
-        add_owner_commitment(&e, &owner, &commitment_id);
-
-        // Update active commitments list
-        add_active_commitment(&e, &commitment_id);
+        // INTERACTIONS: Pull funds first. Fee-bearing or rebasing tokens can deliver
+        // less than `amount`, so everything below is sized from the balance delta.
+        let contract_address = e.current_contract_address();
+        let token_client = token::Client::new(&e, &asset_address);
+        let balance_before = token_client.balance(&contract_address);
+        if let Err(err) = transfer_assets(&e, &owner, &contract_address, &asset_address, amount) {
+            set_reentrancy_guard(&e, false);
+            return Err(err);
+        }
+        let received = token_client.balance(&contract_address) - balance_before;
+        if received <= 0 {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::InvalidAmount, "create_commitment"));
+        }
+        // Per-asset bounds apply to what will actually be locked
+        if let Err(err) = require_amount_within_limits(&e, &asset_address, received) {
+            set_reentrancy_guard(&e, false);
+            return Err(err);
+        }
 
-        // OPTIMIZATION: Increment both counters using already-read values
-        increment_total_commitments(&e);
-        e.storage()
+        // Fee: creation fee in basis points (0 = no fee)
+        let creation_fee_bps: u32 = e
+            .storage()
             .instance()
-            .set(&DataKey::TotalValueLocked, &(current_tvl + amount_locked));
+            .get::<_, u32>(&DataKey::CreationFeeBps)
+            .unwrap_or(0);
+        let creation_fee = if creation_fee_bps > 0 && creation_fee_bps <= BPS_MAX {
+            fee_from_bps(received, creation_fee_bps)
+        } else {
+            0
+        };
+        let amount_locked = received - creation_fee;
 
         // Track creation fee for protocol (collected in contract, withdrawable by admin)
         if creation_fee > 0 {
@@ -871,39 +1670,21 @@ impl CommitmentCoreContract {
                 .set(&key, &(current_fees + creation_fee));
         }
 
-        // Per-asset TVL tracking
-        let asset_tvl = e
-            .storage()
-            .instance()
-            .get::<_, i128>(&DataKey::TotalValueLockedByAsset(asset_address.clone()))
-            .unwrap_or(0);
-        e.storage()
-            .instance()
-            .set(&DataKey::TotalValueLockedByAsset(asset_address.clone()), &(asset_tvl + amount));
-
-        // INTERACTIONS: External calls (token transfer, NFT mint)
-        // Transfer full amount from owner to contract (fee portion stays as protocol revenue)
-        let contract_address = e.current_contract_address();
-        transfer_assets(&e, &owner, &contract_address, &asset_address, amount);
+        if let Some(key) = idempotency_key.as_ref() {
+            record_seen_key(&e, &owner, key, &commitment_id);
+        }
 
-        // Mint NFT (use locked amount for display)
-        let nft_token_id = call_nft_mint(
+        // EFFECTS and NFT mint. A failed mint returns `Err`, which rolls back
+        // the transfer and effects above.
+        let nft_token_id = Self::open_commitment(
             &e,
             &nft_contract,
-            &owner,
             &commitment_id,
-            rules.duration_days,
-            rules.max_loss_percent,
-            &rules.commitment_type,
-            amount_locked,
+            &owner,
             &asset_address,
-            rules.early_exit_penalty,
-        );
-
-        // Update commitment with NFT token ID
-        let mut updated_commitment = commitment;
-        updated_commitment.nft_token_id = nft_token_id;
-        set_commitment(&e, &updated_commitment);
+            &rules,
+            amount_locked,
+        )?;
 
         // Clear reentrancy guard
         set_reentrancy_guard(&e, false);
@@ -915,19 +1696,110 @@ impl CommitmentCoreContract {
                 commitment_id.clone(),
                 owner.clone(),
             ),
-            (amount, rules, nft_token_id, e.ledger().timestamp()),
+            (received, rules, nft_token_id, e.ledger().timestamp()),
         );
-        commitment_id
+        Ok(commitment_id)
     }
 
     /// Get commitment details
-    pub fn get_commitment(e: Env, commitment_id: String) -> Option<Commitment> {
-        get_commitment(&e, &commitment_id)
+    ///
+    /// Panics with `CommitmentNotFound` for an unknown id. Callers that need to
+    /// probe for existence should use the generated `try_get_commitment` client
+    /// method (or `try_invoke_contract` cross-contract) and treat an error as absent.
     pub fn get_commitment(e: Env, commitment_id: String) -> Commitment {
         read_commitment(&e, &commitment_id)
             .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "get_commitment"))
     }
 
+    /// Get a commitment's status transitions as (timestamp, from_status, to_status),
+    /// oldest first. Creation is recorded with an empty `from_status`. Only the
+    /// last `MAX_HISTORY_ENTRIES` transitions are kept.
+    pub fn get_commitment_history(e: Env, commitment_id: String) -> Vec<(u64, String, String)> {
+        e.storage()
+            .instance()
+            .get(&DataKey::CommitmentHistory(commitment_id))
+            .unwrap_or(Vec::new(&e))
+    }
+
+    /// Get settlement log entries `start..start + limit` as (commitment_id,
+    /// amount paid out, timestamp), oldest first. Covers `settle`,
+    /// `settle_and_renew` and `emergency_settle`; the amount is net of fees.
+    /// `limit` must be 1..=`MAX_SETTLEMENT_QUERY`.
+    pub fn get_settlements_paged(e: Env, start: u64, limit: u32) -> Vec<SettlementLogEntry> {
+        if limit == 0 || limit > MAX_SETTLEMENT_QUERY {
+            fail(&e, CommitmentError::InvalidAmount, "get_settlements_paged");
+        }
+        let end = start
+            .saturating_add(limit as u64)
+            .min(Self::get_settlement_count(e.clone()));
+        let page_size = SETTLEMENT_LOG_PAGE_SIZE as u64;
+        let mut entries = Vec::new(&e);
+        let mut loaded: Option<(u64, Vec<SettlementLogEntry>)> = None;
+        for index in start..end {
+            let page_number = index / page_size;
+            if loaded.as_ref().map(|(number, _)| *number) != Some(page_number) {
+                let page = e
+                    .storage()
+                    .persistent()
+                    .get(&DataKey::SettlementLogPage(page_number as u32))
+                    .unwrap_or(Vec::new(&e));
+                loaded = Some((page_number, page));
+            }
+            if let Some((_, page)) = &loaded {
+                if let Some(entry) = page.get((index % page_size) as u32) {
+                    entries.push_back(entry);
+                }
+            }
+        }
+        entries
+    }
+
+    /// Number of entries in the settlement log.
+    pub fn get_settlement_count(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKey::SettlementLogCount)
+            .unwrap_or(0)
+    }
+
+    /// Get the commitment an NFT token id was minted for, if any.
+    pub fn get_commitment_by_nft(e: Env, token_id: u32) -> Option<Commitment> {
+        e.storage()
+            .instance()
+            .get::<_, String>(&DataKey::CommitmentByNft(token_id))
+            .and_then(|commitment_id| read_commitment(&e, &commitment_id))
+    }
+
+    /// Get a commitment together with its latest attested health metrics and
+    /// transformation instrument counts, for detail pages.
+    ///
+    /// Returns (commitment, health, transformations). Reads the metrics stored by
+    /// `attestation_engine` (no recalculation, which would call back into core)
+    /// and the id lists of `transformation`. A satellite that is missing or fails
+    /// yields `None` for its part.
+    pub fn get_commitment_full(
+        e: Env,
+        commitment_id: String,
+        attestation_engine: Address,
+        transformation: Address,
+    ) -> (Commitment, Option<HealthMetrics>, Option<TransformationCounts>) {
+        let commitment = Self::get_commitment(e.clone(), commitment_id.clone());
+
+        let mut args = Vec::new(&e);
+        args.push_back(commitment_id.clone().into_val(&e));
+        let health = match e.try_invoke_contract::<Option<HealthMetrics>, soroban_sdk::Error>(
+            &attestation_engine,
+            &Symbol::new(&e, "get_stored_health_metrics"),
+            args,
+        ) {
+            Ok(Ok(metrics)) => metrics,
+            _ => None,
+        };
+
+        let transformations = read_transformation_counts(&e, &transformation, &commitment_id);
+        (commitment, health, transformations)
+    }
+
     /// Get all commitments for an owner
     pub fn get_owner_commitments(e: Env, owner: Address) -> Vec<String> {
         get_owner_commitments(&e, &owner)
@@ -938,6 +1810,44 @@ impl CommitmentCoreContract {
         get_active_commitments(&e)
     }
 
+    /// Get the id of every commitment ever created, in creation order
+    pub fn get_all_commitment_ids(e: Env) -> Vec<String> {
+        get_all_commitment_ids(&e)
+    }
+
+    /// Get active commitments that expire at or before `timestamp`, for keepers
+    /// staging settlements.
+    ///
+    /// Scans only the active commitments index. Skips the first `start` matches
+    /// and returns at most `limit` ids, in index order.
+    pub fn get_commitments_expiring_before(
+        e: Env,
+        timestamp: u64,
+        start: u32,
+        limit: u32,
+    ) -> Vec<String> {
+        let mut expiring = Vec::new(&e);
+        let mut skipped = 0u32;
+        for commitment_id in get_active_commitments(&e).iter() {
+            if expiring.len() >= limit {
+                break;
+            }
+            let expires_at = match read_commitment(&e, &commitment_id) {
+                Some(commitment) => commitment.expires_at,
+                None => continue,
+            };
+            if expires_at > timestamp {
+                continue;
+            }
+            if skipped < start {
+                skipped += 1;
+                continue;
+            }
+            expiring.push_back(commitment_id);
+        }
+        expiring
+    }
+
     /// Get total number of commitments
     pub fn get_total_commitments(e: Env) -> u64 {
         get_total_commitments(&e)
@@ -959,6 +1869,52 @@ impl CommitmentCoreContract {
             .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "get_admin"))
     }
 
+    /// Get the on-chain storage version (0 if legacy/uninitialized).
+    pub fn get_version(e: Env) -> u32 {
+        read_version(&e)
+    }
+
+    /// Upgrade the contract WASM in place (admin only).
+    pub fn upgrade(e: Env, caller: Address, new_wasm_hash: BytesN<32>) {
+        require_admin(&e, &caller);
+        require_valid_wasm_hash(&e, &new_wasm_hash);
+        e.deployer().update_current_contract_wasm(new_wasm_hash);
+    }
+
+    /// Migrate storage from a previous version to CURRENT_VERSION (admin only).
+    ///
+    /// Version 2 added `CommitmentRules.max_loss_bps`: every stored commitment
+    /// and template is rewritten with `max_loss_bps = 0`, which keeps the
+    /// version 1 whole-percent loss check.
+    pub fn migrate(e: Env, caller: Address, from_version: u32) {
+        require_admin(&e, &caller);
+
+        let stored_version = read_version(&e);
+        if stored_version == CURRENT_VERSION {
+            fail(&e, CommitmentError::AlreadyMigrated, "migrate");
+        }
+        if from_version != stored_version || from_version > CURRENT_VERSION {
+            fail(&e, CommitmentError::InvalidVersion, "migrate");
+        }
+
+        if from_version < 2 {
+            for counter in 0..get_total_commitments(&e) {
+                let key = DataKey::Commitment(Self::generate_commitment_id(&e, counter));
+                if let Some(legacy) = e.storage().instance().get::<_, CommitmentV1>(&key) {
+                    e.storage().instance().set(&key, &Commitment::from(legacy));
+                }
+            }
+            for name in Self::list_templates(e.clone()).iter() {
+                let key = DataKey::Template(name);
+                if let Some(legacy) = e.storage().instance().get::<_, CommitmentRulesV1>(&key) {
+                    e.storage().instance().set(&key, &CommitmentRules::from(legacy));
+                }
+            }
+        }
+
+        write_version(&e, CURRENT_VERSION);
+    }
+
     /// Get NFT contract address
     pub fn get_nft_contract(e: Env) -> Address {
         e.storage()
@@ -967,55 +1923,109 @@ impl CommitmentCoreContract {
             .unwrap_or_else(|| fail(&e, CommitmentError::NotInitialized, "get_nft_contract"))
     }
 
-    /// Update commitment value (called by allocation logic or oracle-fed keeper).
-    /// Persists new_value to commitment.current_value and updates TotalValueLocked.
-    pub fn update_value(e: Env, commitment_id: String, new_value: i128) {
-        // Global per-function rate limit (per contract instance)
+    /// Update commitment value (called by allocation logic or oracle-fed keeper).
+    /// Persists new_value to commitment.current_value and updates TotalValueLocked.
+    /// Admin or an authorized updater, as for `batch_update_value`.
+    pub fn update_value(
+        e: Env,
+        caller: Address,
+        commitment_id: String,
+        new_value: i128,
+    ) -> Result<(), CommitmentError> {
+        caller.require_auth();
+        if caller != get_admin(&e) && !is_authorized_updater(&e, &caller) {
+            return Err(raise(&e, CommitmentError::Unauthorized, "update_value"));
+        }
+
+        // Global per-function rate limit (per contract instance)
+        let fn_symbol = symbol_short!("upd_val");
+        let contract_address = e.current_contract_address();
+        RateLimiter::check(&e, &contract_address, &fn_symbol);
+        EmergencyControl::require_not_emergency(&e);
+
+        let commitment = value_update_target(&e, &commitment_id, new_value)
+            .map_err(|err| raise(&e, err, "update_value"))?;
+        apply_value_update(&e, commitment, new_value);
+        Ok(())
+    }
+
+    /// Update the values of several commitments in one call, adjusting TVL for
+    /// each. Admin or an authorized updater.
+    ///
+    /// Every entry is validated before any is applied. In `Atomic` mode a single
+    /// invalid entry rejects the whole batch; in `BestEffort` mode valid entries
+    /// are applied and the rest reported in `errors` by index.
+    pub fn batch_update_value(
+        e: Env,
+        caller: Address,
+        updates: Vec<UpdateValueParams>,
+        mode: BatchMode,
+    ) -> BatchResultVoid {
+        caller.require_auth();
+        if caller != get_admin(&e) && !is_authorized_updater(&e, &caller) {
+            fail(&e, CommitmentError::Unauthorized, "batch_update_value");
+        }
+
+        // Reentrancy protection
+        if let Err(err) = check_no_reentrancy(&e) {
+            fail(&e, err, "batch_update_value");
+        }
+        set_reentrancy_guard(&e, true);
+        EmergencyControl::require_not_emergency(&e);
+
+        // One rate limit slot per batch, shared with update_value
         let fn_symbol = symbol_short!("upd_val");
         let contract_address = e.current_contract_address();
         RateLimiter::check(&e, &contract_address, &fn_symbol);
-        EmergencyControl::require_not_emergency(&e);
 
-        Validation::require_non_negative(new_value);
-
-        let mut commitment = read_commitment(&e, &commitment_id)
-            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "update_value"));
-
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
-            fail(&e, CommitmentError::NotActive, "update_value");
+        let contract_name = String::from_str(&e, "commitment_core");
+        if let Err(error_code) =
+            BatchProcessor::enforce_batch_limits(&e, updates.len(), Some(contract_name))
+        {
+            set_reentrancy_guard(&e, false);
+            let mut errors = Vec::new(&e);
+            errors.push_back(BatchError {
+                index: 0,
+                error_code,
+                context: String::from_str(&e, "batch_size_validation"),
+            });
+            return BatchResultVoid::failure(&e, errors);
         }
 
-        let old_value = commitment.current_value;
-        let asset = commitment.asset_address.clone();
-        commitment.current_value = new_value;
-        set_commitment(&e, &commitment);
+        // Validate everything first so Atomic mode never applies a partial batch
+        // An id may appear once: every entry is checked against the stored value,
+        // so a repeat would move TVL from a stale snapshot
+        let mut errors = Vec::new(&e);
+        let mut targets = Vec::new(&e);
+        let mut seen: Vec<String> = Vec::new(&e);
+        for (index, update) in updates.iter().enumerate() {
+            let target = if seen.contains(&update.commitment_id) {
+                Err(CommitmentError::DuplicateCommitmentId)
+            } else {
+                seen.push_back(update.commitment_id.clone());
+                value_update_target(&e, &update.commitment_id, update.new_value)
+            };
+            match target {
+                Ok(commitment) => targets.push_back((commitment, update.new_value)),
+                Err(err) => errors.push_back(BatchError {
+                    index: index as u32,
+                    error_code: err as u32,
+                    context: String::from_str(&e, err.message()),
+                }),
+            }
+        }
 
-        // Adjust TotalValueLocked: TVL -= old_value, TVL += new_value
-        let current_tvl = e
-            .storage()
-            .instance()
-            .get::<_, i128>(&DataKey::TotalValueLocked)
-            .unwrap_or(0);
-        let new_tvl = current_tvl - old_value + new_value;
-        e.storage()
-            .instance()
-            .set(&DataKey::TotalValueLocked, &new_tvl);
+        if mode == BatchMode::Atomic && !errors.is_empty() {
+            set_reentrancy_guard(&e, false);
+            return BatchResultVoid::failure(&e, errors);
+        }
 
-        // Per-asset TVL
-        let asset_tvl = e
-            .storage()
-            .instance()
-            .get::<_, i128>(&DataKey::TotalValueLockedByAsset(asset.clone()))
-            .unwrap_or(0);
-        e.storage()
-            .instance()
-            .set(&DataKey::TotalValueLockedByAsset(asset), &(asset_tvl - old_value + new_value));
+        for (commitment, new_value) in targets.iter() {
+            apply_value_update(&e, commitment, new_value);
+        }
 
-        e.events().publish(
-            (symbol_short!("ValUpd"), commitment_id),
-            (new_value, e.ledger().timestamp()),
-        );
+        set_reentrancy_guard(&e, false);
+        BatchResultVoid::partial(targets.len(), errors)
     }
 
     /// Check if commitment rules are violated
@@ -1026,7 +2036,7 @@ impl CommitmentCoreContract {
     /// - `commitment_id` exists
     ///
     /// **Postconditions:**
-    /// - Returns `true` if `loss_percent > max_loss_percent OR current_time >= expires_at`
+    /// - Returns `true` if the loss exceeds `max_loss_bps` (bps mode) or `max_loss_percent` (percent mode), OR `current_time >= expires_at`
     /// - Returns `false` otherwise
     /// - Pure function (no state changes)
     ///
@@ -1048,18 +2058,7 @@ impl CommitmentCoreContract {
         let current_time = e.ledger().timestamp();
 
         // Check loss limit violation
-        // Calculate loss percentage using shared utilities, but handle zero-amount
-        // commitments gracefully to avoid panics. A zero-amount commitment cannot
-        // meaningfully violate a loss limit, so we treat its loss percent as 0.
-        let loss_percent = if commitment.amount > 0 {
-            SafeMath::loss_percent(commitment.amount, commitment.current_value)
-        } else {
-            0
-        };
-
-        // Convert max_loss_percent (u32) to i128 for comparison
-        let max_loss = commitment.rules.max_loss_percent as i128;
-        let loss_violated = loss_percent > max_loss;
+        let loss_violated = loss_limit_breached(&commitment);
 
         // Check duration violation (expired)
         let duration_violated = current_time >= commitment.expires_at;
@@ -1100,8 +2099,7 @@ impl CommitmentCoreContract {
         };
 
         // Check loss limit violation
-        let max_loss = commitment.rules.max_loss_percent as i128;
-        let loss_violated = loss_percent > max_loss;
+        let loss_violated = loss_limit_breached(&commitment);
 
         // Check duration violation
         let duration_violated = current_time >= commitment.expires_at;
@@ -1128,135 +2126,338 @@ impl CommitmentCoreContract {
     ///
     /// # Reentrancy Protection
     /// Uses checks-effects-interactions pattern with reentrancy guard.
-    pub fn settle(e: Env, commitment_id: String) {
+    pub fn settle(e: Env, commitment_id: String) -> Result<(), CommitmentError> {
         // Reentrancy protection
-        require_no_reentrancy(&e);
+        check_no_reentrancy(&e)?;
         set_reentrancy_guard(&e, true);
         EmergencyControl::require_not_emergency(&e);
 
-        // CHECKS: Get and validate commitment
-        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
-            set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::CommitmentNotFound, "settle")
-        });
+        // CHECKS and EFFECTS
+        let (commitment, settlement_fee, payout) =
+            Self::close_matured_commitment(&e, &commitment_id, "settle")?;
 
-        // Verify commitment is expired or within grace period
-        let current_time = e.ledger().timestamp();
-        // Requirement: Allow settlement if expired or within grace period
-        // Note: Settlement is allowed if current_time >= expires_at
-        if current_time < commitment.expires_at {
+        // INTERACTIONS: External calls (token transfer, NFT settlement)
+        // Transfer assets back to the owner(s), net of the settlement fee
+        if payout > 0 && pay_owners(&e, &commitment, payout).is_err() {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::NotExpired, "settle");
+            return Err(raise(&e, CommitmentError::TransferFailed, "settle"));
         }
 
-        // Verify commitment is active
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
-            set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::NotActive, "settle");
-        }
+        // Mark the NFT settled and release any pool positions
+        Self::finish_settlement(&e, &commitment_id, &commitment, "settle")?;
+        append_settlement_log(&e, &commitment_id, payout);
 
-        // EFFECTS: Update state before external calls
-        let settlement_amount = commitment.current_value;
-        commitment.status = String::from_str(&e, "settled");
-        set_commitment(&e, &commitment);
+        // Clear reentrancy guard
+        set_reentrancy_guard(&e, false);
 
-        // Remove from active commitments list
-        remove_active_commitment(&e, &commitment_id);
+        // Emit settlement event with required fields: commitment_id, owner, payout, fee, timestamp
+        e.events().publish(
+            (symbol_short!("Settled"), commitment_id, commitment.owner),
+            (payout, settlement_fee, e.ledger().timestamp()),
+        );
+        Ok(())
+    }
 
-        // Decrease total value locked
-        let current_tvl = e
-            .storage()
-            .instance()
-            .get::<_, i128>(&DataKey::TotalValueLocked)
-            .unwrap_or(0);
-        let new_tvl = current_tvl - settlement_amount;
+    /// Opt a commitment in or out of auto-renewal. Owner only.
+    ///
+    /// An opted-in commitment can be rolled into a new term with
+    /// `settle_and_renew` once it matures.
+    pub fn set_auto_renew(e: Env, caller: Address, commitment_id: String, enabled: bool) {
+        caller.require_auth();
+        let commitment = Self::read_active_commitment(&e, &commitment_id, "set_auto_renew");
+        if caller != commitment.owner {
+            fail(&e, CommitmentError::Unauthorized, "set_auto_renew");
+        }
         e.storage()
             .instance()
-            .set(&DataKey::TotalValueLocked, &new_tvl);
+            .set(&DataKey::AutoRenew(commitment_id.clone()), &enabled);
+        e.events().publish(
+            (symbol_short!("AutoRenew"), commitment_id, caller),
+            (enabled, e.ledger().timestamp()),
+        );
+    }
 
-        // Per-asset TVL
-        let asset = commitment.asset_address.clone();
-        let asset_tvl = e
-            .storage()
-            .instance()
-            .get::<_, i128>(&DataKey::TotalValueLockedByAsset(asset.clone()))
-            .unwrap_or(0);
+    /// Route the owner's payouts (settlement, early exit, slashed settlement)
+    /// to `recipient`, e.g. a custody address, or back to the owner with
+    /// `None`. Owner only; the NFT and ownership are unchanged.
+    ///
+    /// The setting lapses if the commitment changes owner.
+    pub fn set_settlement_recipient(
+        e: Env,
+        caller: Address,
+        commitment_id: String,
+        recipient: Option<Address>,
+    ) {
+        caller.require_auth();
+        let commitment =
+            Self::read_active_commitment(&e, &commitment_id, "set_settlement_recipient");
+        if caller != commitment.owner {
+            fail(&e, CommitmentError::Unauthorized, "set_settlement_recipient");
+        }
+        let key = DataKey::SettlementRecipient(commitment_id.clone());
+        let payee = match recipient {
+            Some(recipient) => {
+                if recipient == e.current_contract_address() {
+                    fail(&e, CommitmentError::InvalidFeeRecipient, "set_settlement_recipient");
+                }
+                e.storage().instance().set(&key, &(caller.clone(), recipient.clone()));
+                recipient
+            }
+            None => {
+                e.storage().instance().remove(&key);
+                caller.clone()
+            }
+        };
+        e.events().publish(
+            (symbol_short!("SetlRecip"), commitment_id, caller),
+            (payee, e.ledger().timestamp()),
+        );
+    }
+
+    /// Address that receives the owner's payouts; the owner unless a
+    /// settlement recipient is set.
+    pub fn get_settlement_recipient(e: Env, commitment_id: String) -> Address {
+        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            fail(&e, CommitmentError::CommitmentNotFound, "get_settlement_recipient")
+        });
+        settlement_recipient(&e, &commitment)
+    }
+
+    /// Whether a commitment is opted in to auto-renewal.
+    pub fn is_auto_renew(e: Env, commitment_id: String) -> bool {
+        is_auto_renew(&e, &commitment_id)
+    }
+
+    /// Settle a matured auto-renew commitment and roll the payout into a new
+    /// commitment with the same owner, asset and rules. Returns the new id.
+    ///
+    /// The settlement fee is booked as for `settle`; the rest stays in the
+    /// contract as the new principal, so TVL drops only by the fee. No creation
+    /// fee is charged on renewal. The new commitment inherits the auto-renew flag.
+    pub fn settle_and_renew(e: Env, commitment_id: String) -> Result<String, CommitmentError> {
+        // Reentrancy protection
+        check_no_reentrancy(&e)?;
+        set_reentrancy_guard(&e, true);
+        EmergencyControl::require_not_emergency(&e);
+
+        if !is_auto_renew(&e, &commitment_id) {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::AutoRenewDisabled, "settle_and_renew"));
+        }
+
+        // CHECKS and EFFECTS: close the matured term
+        let (commitment, settlement_fee, payout) =
+            Self::close_matured_commitment(&e, &commitment_id, "settle_and_renew")?;
+        if payout <= 0 {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::InvalidAmount, "settle_and_renew"));
+        }
         e.storage()
             .instance()
-            .set(&DataKey::TotalValueLockedByAsset(asset), &(asset_tvl - settlement_amount));
+            .remove(&DataKey::AutoRenew(commitment_id.clone()));
 
-        // INTERACTIONS: External calls (token transfer, NFT settlement)
-        // Transfer assets back to owner
-        let contract_address = e.current_contract_address();
-        let token_client = token::Client::new(&e, &commitment.asset_address);
-        token_client.transfer(&contract_address, &commitment.owner, &settlement_amount);
+        Self::finish_settlement(&e, &commitment_id, &commitment, "settle_and_renew")?;
+        append_settlement_log(&e, &commitment_id, payout);
 
-        // Call NFT contract to mark NFT as settled
+        // Open the next term with the payout as principal
         let nft_contract = e
             .storage()
             .instance()
             .get::<_, Address>(&DataKey::NftContract)
-            .unwrap_or_else(|| {
+            .ok_or_else(|| {
                 set_reentrancy_guard(&e, false);
-                fail(&e, CommitmentError::NotInitialized, "settle")
-            });
+                raise(&e, CommitmentError::NotInitialized, "settle_and_renew")
+            })?;
+        let new_id = Self::generate_commitment_id(&e, get_total_commitments(&e));
+        if has_commitment(&e, &new_id) {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::InvalidStatus, "settle_and_renew"));
+        }
+        let nft_token_id = Self::open_commitment(
+            &e,
+            &nft_contract,
+            &new_id,
+            &commitment.owner,
+            &commitment.asset_address,
+            &commitment.rules,
+            payout,
+        )?;
+        e.storage()
+            .instance()
+            .set(&DataKey::AutoRenew(new_id.clone()), &true);
+        if let Some(co_owners) = e
+            .storage()
+            .instance()
+            .get::<_, Vec<CoOwner>>(&DataKey::CoOwners(commitment_id.clone()))
+        {
+            e.storage()
+                .instance()
+                .set(&DataKey::CoOwners(new_id.clone()), &co_owners);
+        }
+
+        // Clear reentrancy guard
+        set_reentrancy_guard(&e, false);
+
+        let timestamp = e.ledger().timestamp();
+        e.events().publish(
+            (symbol_short!("Settled"), commitment_id.clone(), commitment.owner.clone()),
+            (payout, settlement_fee, timestamp),
+        );
+        e.events().publish(
+            (symbol_short!("Renewed"), commitment_id, new_id.clone()),
+            (commitment.owner, payout, nft_token_id, timestamp),
+        );
+        Ok(new_id)
+    }
+
+    /// Settle a matured commitment whose attested fees fell short of its
+    /// `min_fee_threshold`, diverting the underperformance slash share of the
+    /// payout to the insurance fund. Admin only. Returns the slashed amount.
+    ///
+    /// Fees are read from the configured attestation engine's stored health
+    /// metrics (no metrics counts as zero fees). Fails with `FeeThresholdMet`
+    /// if the commitment has no threshold or the attested fees meet it.
+    pub fn slash_for_underperformance(
+        e: Env,
+        caller: Address,
+        commitment_id: String,
+    ) -> Result<i128, CommitmentError> {
+        require_admin(&e, &caller);
+
+        // Reentrancy protection
+        check_no_reentrancy(&e)?;
+        set_reentrancy_guard(&e, true);
+        EmergencyControl::require_not_emergency(&e);
+
+        let commitment = read_commitment(&e, &commitment_id).ok_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            raise(&e, CommitmentError::CommitmentNotFound, "slash_for_underperformance")
+        })?;
+        let (attestation_engine, insurance_fund) = match (
+            e.storage()
+                .instance()
+                .get::<_, Address>(&DataKey::AttestationEngine),
+            e.storage().instance().get::<_, Address>(&DataKey::InsuranceFund),
+        ) {
+            (Some(engine), Some(fund)) => (engine, fund),
+            _ => {
+                set_reentrancy_guard(&e, false);
+                return Err(raise(&e, CommitmentError::NotInitialized, "slash_for_underperformance"));
+            }
+        };
 
+        // CHECKS: the fee promise must have been missed
+        let threshold = commitment.rules.min_fee_threshold;
         let mut args = Vec::new(&e);
-        args.push_back(commitment.nft_token_id.into_val(&e));
-        e.invoke_contract::<()>(&nft_contract, &Symbol::new(&e, "settle"), args);
+        args.push_back(commitment_id.clone().into_val(&e));
+        let attested_fees = e
+            .invoke_contract::<Option<HealthMetrics>>(
+                &attestation_engine,
+                &Symbol::new(&e, "get_stored_health_metrics"),
+                args,
+            )
+            .map(|metrics| metrics.fees_generated)
+            .unwrap_or(0);
+        if threshold <= 0 || attested_fees >= threshold {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::FeeThresholdMet, "slash_for_underperformance"));
+        }
+
+        // EFFECTS: close the commitment as a normal settlement
+        let (commitment, settlement_fee, payout) =
+            Self::close_matured_commitment(&e, &commitment_id, "slash_for_underperformance")?;
+        let slashed = fee_from_bps(payout, Self::get_underperformance_slash_bps(e.clone()));
+        let returned = payout - slashed;
+
+        // INTERACTIONS: slash to the insurance fund, remainder to the owner
+        let contract_address = e.current_contract_address();
+        let asset = commitment.asset_address.clone();
+        if slashed > 0
+            && safe_transfer(&e, &asset, &contract_address, &insurance_fund, slashed).is_err()
+        {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::TransferFailed, "slash_for_underperformance"));
+        }
+        if returned > 0 && pay_owners(&e, &commitment, returned).is_err() {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::TransferFailed, "slash_for_underperformance"));
+        }
+
+        Self::finish_settlement(&e, &commitment_id, &commitment, "slash_for_underperformance")?;
+        append_settlement_log(&e, &commitment_id, returned);
 
         // Clear reentrancy guard
         set_reentrancy_guard(&e, false);
 
-        // Emit settlement event with required fields: commitment_id, owner, settlement_amount, timestamp
+        let timestamp = e.ledger().timestamp();
         e.events().publish(
-            (symbol_short!("Settled"), commitment_id, commitment.owner),
-            (settlement_amount, e.ledger().timestamp()),
+            (symbol_short!("Settled"), commitment_id.clone(), commitment.owner.clone()),
+            (returned, settlement_fee, timestamp),
+        );
+        e.events().publish(
+            (symbol_short!("Slashed"), commitment_id, commitment.owner),
+            (slashed, attested_fees, threshold, timestamp),
         );
+        Ok(slashed)
     }
 
-    pub fn early_exit(e: Env, commitment_id: String, caller: Address) {
+    pub fn early_exit(
+        e: Env,
+        commitment_id: String,
+        caller: Address,
+    ) -> Result<(), CommitmentError> {
         // Reentrancy protection
-        require_no_reentrancy(&e);
+        check_no_reentrancy(&e)?;
         set_reentrancy_guard(&e, true);
         EmergencyControl::require_not_emergency(&e);
 
         // CHECKS: Get and validate commitment
-        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+        let mut commitment = read_commitment(&e, &commitment_id).ok_or_else(|| {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::CommitmentNotFound, "early_exit")
-        });
+            raise(&e, CommitmentError::CommitmentNotFound, "early_exit")
+        })?;
 
         // Verify caller is owner
         caller.require_auth();
         if commitment.owner != caller {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::Unauthorized, "early_exit");
+            return Err(raise(&e, CommitmentError::Unauthorized, "early_exit"));
         }
 
         // Verify commitment is active
         let active_status = String::from_str(&e, "active");
         if commitment.status != active_status {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::NotActive, "early_exit");
+            return Err(raise(&e, CommitmentError::NotActive, "early_exit"));
+        }
+
+        // Allocated funds sit in pools; deallocate_all must bring them back first
+        if get_allocation_tracking(&e, &commitment_id).total_allocated > 0 {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::OutstandingAllocations, "early_exit"));
+        }
+
+        if let Err(err) = require_original_asset(&e, &commitment, "early_exit") {
+            set_reentrancy_guard(&e, false);
+            return Err(err);
         }
 
         // Save original current value before updating (for TVL and transfers)
         let original_current_value = commitment.current_value;
 
         // EFFECTS: Calculate penalty using shared utilities (early exit fee goes to protocol)
-        let penalty_amount =
-            SafeMath::penalty_amount(original_current_value, commitment.rules.early_exit_penalty);
-        let returned_amount = SafeMath::sub(original_current_value, penalty_amount);
+        let (penalty_amount, returned_amount) = early_exit_breakdown(&e, &commitment);
 
         // Update commitment status to early_exit
-        commitment.status = String::from_str(&e, "early_exit");
+        let exit_status = String::from_str(&e, "early_exit");
+        record_status_change(&e, &commitment_id, commitment.status.clone(), exit_status.clone());
+        commitment.status = exit_status;
         commitment.current_value = 0; // All value has been distributed
         set_commitment(&e, &commitment);
 
         // Remove from active commitments list
         remove_active_commitment(&e, &commitment_id);
+        adjust_locked_principal(&e, &commitment.asset_address, -commitment.amount);
 
         // Decrease total value locked by full current value (no longer locked)
         let current_tvl = e
@@ -1269,13 +2470,15 @@ impl CommitmentCoreContract {
             .instance()
             .set(&DataKey::TotalValueLocked, &new_tvl);
 
-        // Early exit fee (penalty) goes to protocol: add to collected fees
-        if penalty_amount > 0 {
+        // Early exit fee (penalty) goes to protocol: the treasury share is added to
+        // collected fees, the insurance share is paid out below
+        let (treasury_penalty, insurance_penalty) = insurance_split(&e, penalty_amount);
+        if treasury_penalty > 0 {
             let key = DataKey::CollectedFees(commitment.asset_address.clone());
             let current_fees = e.storage().instance().get::<_, i128>(&key).unwrap_or(0);
             e.storage()
                 .instance()
-                .set(&key, &(current_fees + penalty_amount));
+                .set(&key, &(current_fees + treasury_penalty));
         }
 
         // Per-asset TVL
@@ -1292,10 +2495,28 @@ impl CommitmentCoreContract {
         // INTERACTIONS: External calls (token transfer)
         // Transfer remaining amount (after penalty) to owner
         let contract_address = e.current_contract_address();
-        let token_client = token::Client::new(&e, &commitment.asset_address);
+        let asset = commitment.asset_address.clone();
+
+        if returned_amount > 0 && pay_owners(&e, &commitment, returned_amount).is_err() {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::TransferFailed, "early_exit"));
+        }
 
-        if returned_amount > 0 {
-            token_client.transfer(&contract_address, &commitment.owner, &returned_amount);
+        if insurance_penalty > 0 {
+            let insurance_fund = e
+                .storage()
+                .instance()
+                .get::<_, Address>(&DataKey::InsuranceFund)
+                .ok_or_else(|| {
+                    set_reentrancy_guard(&e, false);
+                    raise(&e, CommitmentError::NotInitialized, "early_exit")
+                })?;
+            if safe_transfer(&e, &asset, &contract_address, &insurance_fund, insurance_penalty)
+                .is_err()
+            {
+                set_reentrancy_guard(&e, false);
+                return Err(raise(&e, CommitmentError::TransferFailed, "early_exit"));
+            }
         }
 
         // Call NFT contract to update NFT status (mark as inactive/early_exited)
@@ -1303,16 +2524,19 @@ impl CommitmentCoreContract {
             .storage()
             .instance()
             .get::<_, Address>(&DataKey::NftContract)
-            .unwrap_or_else(|| {
+            .ok_or_else(|| {
                 set_reentrancy_guard(&e, false);
-                fail(&e, CommitmentError::NotInitialized, "early_exit")
-            });
+                raise(&e, CommitmentError::NotInitialized, "early_exit")
+            })?;
 
         // Call settle on NFT to mark it as inactive
         let mut args = Vec::new(&e);
         args.push_back(commitment.nft_token_id.into_val(&e));
         e.invoke_contract::<()>(&nft_contract, &Symbol::new(&e, "settle"), args);
 
+        // Release any pool positions held for this commitment
+        notify_allocation_closed(&e, &commitment_id);
+
         // Clear reentrancy guard
         set_reentrancy_guard(&e, false);
 
@@ -1325,191 +2549,378 @@ impl CommitmentCoreContract {
             ),
             (penalty_amount, returned_amount, e.ledger().timestamp()),
         );
+        Ok(())
+    }
+
+    /// Preview what `settle` would pay out, without changing state.
+    ///
+    /// Returns `(gross, fee, net)` where `net` is the amount transferred to the
+    /// owner after the settlement fee. Does not check that the commitment has expired.
+    pub fn preview_settlement(e: Env, commitment_id: String) -> (i128, i128, i128) {
+        let commitment = Self::read_active_commitment(&e, &commitment_id, "preview_settlement");
+        settlement_breakdown(&e, &commitment)
+    }
+
+    /// Preview what `early_exit` would return, without changing state.
+    ///
+    /// Returns `(penalty, returned)` based on the commitment's current value.
+    pub fn preview_early_exit(e: Env, commitment_id: String) -> (i128, i128) {
+        let commitment = Self::read_active_commitment(&e, &commitment_id, "preview_early_exit");
+        early_exit_breakdown(&e, &commitment)
+    }
+
+    /// Point `Commitment.owner` at the current holder of the commitment's NFT.
+    ///
+    /// After an NFT sale the buyer calls this (anyone may) so core recognizes them
+    /// for owner-only actions such as `early_exit`. Reads `owner_of` from the NFT
+    /// contract, updates the owner indices when the holder changed, and returns
+    /// the synced owner. On a co-owned commitment the previous owner's share
+    /// moves to the new owner.
+    ///
+    /// Only active commitments sync. A commitment reassigned by
+    /// `emergency_reassign_owner` without moving its NFT is pinned to the new
+    /// owner and fails with `InvalidStatus`, so the old NFT holder cannot take
+    /// it back.
+    pub fn sync_owner_from_nft(e: Env, commitment_id: String) -> Address {
+        let mut commitment =
+            Self::read_active_commitment(&e, &commitment_id, "sync_owner_from_nft");
+        if e.storage()
+            .persistent()
+            .get::<_, bool>(&DataKey::OwnerPinned(commitment_id.clone()))
+            .unwrap_or(false)
+        {
+            fail(&e, CommitmentError::InvalidStatus, "sync_owner_from_nft");
+        }
+
+        let nft_contract = get_nft_contract(&e);
+        let mut args = Vec::new(&e);
+        args.push_back(commitment.nft_token_id.into_val(&e));
+        let nft_owner =
+            e.invoke_contract::<Address>(&nft_contract, &Symbol::new(&e, "owner_of"), args);
+
+        let previous_owner = commitment.owner.clone();
+        if nft_owner != previous_owner {
+            commitment.owner = nft_owner.clone();
+            set_commitment(&e, &commitment);
+            remove_owner_commitment(&e, &previous_owner, &commitment_id);
+            add_owner_commitment(&e, &nft_owner, &commitment_id);
+            move_co_owner_share(&e, &commitment_id, &previous_owner, &nft_owner);
+
+            e.events().publish(
+                (symbol_short!("OwnerSync"), commitment_id),
+                (previous_owner, nft_owner.clone(), e.ledger().timestamp()),
+            );
+        }
+
+        nft_owner
     }
 
-    /// Allocate liquidity to a target pool
-    /// 
-    /// # Arguments
-    /// * `caller` - The address of the allocation contract calling this function (must be authorized)
-    /// * `commitment_id` - The ID of the commitment
-    /// * `target_pool` - The address of the target pool to allocate to
-    /// * `amount` - The amount to allocate
-    /// 
-    /// # Errors
-    /// * `Unauthorized` - If caller is not an authorized allocation contract
-    /// * `InactiveCommitment` - If commitment is not active
-    /// * `InsufficientBalance` - If commitment doesn't have enough balance
-    /// * `TransferFailed` - If asset transfer fails
-    /// * `InvalidAmount` - If amount is invalid (<= 0)
-    /// 
-    /// # Note
-    /// The allocation contract should pass its own address as the `caller` parameter.
-    /// This address must be authorized by the admin before calling this function.
-    pub fn allocate(e: Env, caller: Address, commitment_id: String, target_pool: Address, amount: i128) {
-        // Verify caller is authorized allocation contract
-        if !is_authorized_allocator(&e, &caller) {
-            panic_unauthorized();
+    /// Allocate liquidity to a target pool
+    ///
+    /// # Arguments
+    /// * `caller` - The allocation contract calling this function (must be authorized and sign)
+    /// * `commitment_id` - The ID of the commitment
+    /// * `target_pool` - The address of the target pool to allocate to
+    /// * `amount` - The amount to allocate
+    ///
+    /// # Reentrancy Protection
+    /// Uses checks-effects-interactions pattern with reentrancy guard.
+    pub fn allocate(
+        e: Env,
+        caller: Address,
+        commitment_id: String,
+        target_pool: Address,
+        amount: i128,
+    ) -> Result<(), CommitmentError> {
+        // Reentrancy protection
+        check_no_reentrancy(&e)?;
+        set_reentrancy_guard(&e, true);
+        EmergencyControl::require_not_emergency(&e);
+
+        // CHECKS: Caller must be an authorized allocation contract
+        caller.require_auth();
+        if !is_authorized_allocator(&e, &caller) {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::Unauthorized, "allocate"));
+        }
+
+        // Rate limit allocations per target pool address
+        let fn_symbol = symbol_short!("alloc");
+        RateLimiter::check(&e, &target_pool, &fn_symbol);
+
+        if amount <= 0 {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::InvalidAmount, "allocate"));
         }
 
-        // Verify commitment exists and is active
-        let commitment = match get_commitment(&e, &commitment_id) {
-            Some(c) => c,
-            None => panic_inactive_commitment(),
-        };
+        let commitment = read_commitment(&e, &commitment_id).ok_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            raise(&e, CommitmentError::CommitmentNotFound, "allocate")
+        })?;
+
+        if is_commitment_frozen(&e, &commitment_id) {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::CommitmentFrozen, "allocate"));
+        }
 
-        // Check if commitment is active
+        // Verify commitment is active
         let active_status = String::from_str(&e, "active");
         if commitment.status != active_status {
-            panic_inactive_commitment();
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::NotActive, "allocate"));
         }
 
         // Verify sufficient balance
-        let balance = get_commitment_balance(&e, &commitment_id);
-        if balance < amount {
-            panic_insufficient_balance();
+        if commitment.current_value < amount {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::InsufficientBalance, "allocate"));
         }
 
-        // Transfer assets to target pool
-        let contract_address = e.current_contract_address();
-        transfer_asset(&e, &commitment.asset_address, &contract_address, &target_pool, amount);
+        // Cap the cumulative allocated share of the commitment's value
+        let mut tracking = get_allocation_tracking(&e, &commitment_id);
+        let allocatable = commitment.current_value + tracking.total_allocated;
+        let cap = fee_from_bps(allocatable, Self::get_max_allocation_bps(e.clone()));
+        if tracking.total_allocated + amount > cap {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::AllocationCapExceeded, "allocate"));
+        }
+
+        // EFFECTS: Update commitment value before external call
+        let mut updated_commitment = commitment;
+        let asset = updated_commitment.asset_address.clone();
+        updated_commitment.current_value -= amount;
+        set_commitment(&e, &updated_commitment);
 
-        // Update commitment balance
-        let new_balance = balance - amount;
-        set_commitment_balance(&e, &commitment_id, new_balance);
+        // Decrease total value locked and per-asset TVL
+        let current_tvl = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLocked)
+            .unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLocked, &(current_tvl - amount));
+        let asset_tvl = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLockedByAsset(asset.clone()))
+            .unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLockedByAsset(asset.clone()), &(asset_tvl - amount));
 
         // Record allocation
-        let mut tracking = get_allocation_tracking(&e, &commitment_id);
         let timestamp = e.ledger().timestamp();
-        
-        let allocation = Allocation {
+        tracking.allocations.push_back(Allocation {
             commitment_id: commitment_id.clone(),
             target_pool: target_pool.clone(),
             amount,
             timestamp,
-        };
-        
-        tracking.allocations.push_back(allocation.clone());
+        });
         tracking.total_allocated += amount;
         set_allocation_tracking(&e, &commitment_id, &tracking);
 
+        // INTERACTIONS: External call (token transfer)
+        let contract_address = e.current_contract_address();
+        if safe_transfer(&e, &asset, &contract_address, &target_pool, amount).is_err() {
+            set_reentrancy_guard(&e, false);
+            return Err(raise(&e, CommitmentError::TransferFailed, "allocate"));
+        }
+
+        // Clear reentrancy guard
+        set_reentrancy_guard(&e, false);
+
         // Emit allocation event
         e.events().publish(
-            (symbol_short!("alloc"), symbol_short!("cmt_id")),
-            commitment_id,
-        );
-        e.events().publish(
-            (symbol_short!("alloc"), symbol_short!("pool")),
-            target_pool,
+            (symbol_short!("Alloc"), commitment_id, target_pool),
+            (amount, timestamp),
         );
+        Ok(())
+    }
+
+    /// Get allocation tracking for a commitment
+    pub fn get_allocation_tracking(e: Env, commitment_id: String) -> AllocationTracking {
+        get_allocation_tracking(&e, &commitment_id)
+    }
+
+    /// Freeze or unfreeze allocator access to a single commitment. Owner or admin.
+    ///
+    /// While frozen, `allocate`, `deallocate` and `deallocate_all` reject the
+    /// commitment; settlement and early exit stay available to the owner.
+    pub fn set_commitment_frozen(e: Env, caller: Address, commitment_id: String, frozen: bool) {
+        caller.require_auth();
+        let commitment = read_commitment(&e, &commitment_id)
+            .unwrap_or_else(|| fail(&e, CommitmentError::CommitmentNotFound, "set_commitment_frozen"));
+        if caller != commitment.owner && caller != get_admin(&e) {
+            fail(&e, CommitmentError::Unauthorized, "set_commitment_frozen");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::CommitmentFrozen(commitment_id.clone()), &frozen);
         e.events().publish(
-            (symbol_short!("alloc"), symbol_short!("amount")),
-            amount,
+            (symbol_short!("Frozen"), commitment_id, caller),
+            (frozen, e.ledger().timestamp()),
         );
+    }
+
+    /// Whether allocations are frozen for a commitment.
+    pub fn is_commitment_frozen(e: Env, commitment_id: String) -> bool {
+        is_commitment_frozen(&e, &commitment_id)
+    }
+
+    /// Cap the share of a commitment's value that may be allocated at once, in
+    /// basis points (e.g. 8000 = 80%). Admin only.
+    ///
+    /// The cap applies to the cumulative allocated amount, measured against the
+    /// commitment's current value plus what is already allocated.
+    pub fn set_max_allocation_bps(e: Env, caller: Address, max_bps: u32) {
+        require_admin(&e, &caller);
+        if max_bps == 0 || max_bps > BPS_MAX {
+            fail(&e, CommitmentError::InvalidAllocationCap, "set_max_allocation_bps");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::MaxAllocationBps, &max_bps);
         e.events().publish(
-            (symbol_short!("alloc"), symbol_short!("time")),
-            timestamp,
+            (symbol_short!("AllocCap"), caller),
+            (max_bps, e.ledger().timestamp()),
         );
     }
 
-    /// Get allocation tracking for a commitment
-    pub fn get_allocation_tracking(e: Env, commitment_id: String) -> AllocationTracking {
-        get_allocation_tracking(&e, &commitment_id)
+    /// Get the allocation cap in basis points (default 10000, i.e. uncapped).
+    pub fn get_max_allocation_bps(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get::<_, u32>(&DataKey::MaxAllocationBps)
+            .unwrap_or(BPS_MAX)
     }
 
-    /// Deallocate liquidity from a pool (optional functionality)
-    /// This would be called when liquidity is returned from a pool
-    /// 
+    /// Deallocate liquidity returned from a pool
+    ///
     /// # Arguments
-    /// * `caller` - The address of the allocation contract calling this function (must be authorized)
+    /// * `caller` - The allocation contract calling this function (must be authorized and sign)
     /// * `commitment_id` - The ID of the commitment
-    /// * `target_pool` - The address of the pool to deallocate from
+    /// * `target_pool` - The address of the pool returning the funds (must sign the transfer)
     /// * `amount` - The amount to deallocate
-    pub fn deallocate(e: Env, caller: Address, commitment_id: String, target_pool: Address, amount: i128) {
-        // Verify caller is authorized
+    pub fn deallocate(
+        e: Env,
+        caller: Address,
+        commitment_id: String,
+        target_pool: Address,
+        amount: i128,
+    ) {
+        // Reentrancy protection
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        // CHECKS
+        caller.require_auth();
         if !is_authorized_allocator(&e, &caller) {
-            panic_unauthorized();
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::Unauthorized, "deallocate");
         }
 
-        // Get commitment
-        let commitment = match get_commitment(&e, &commitment_id) {
-            Some(c) => c,
-            None => panic_inactive_commitment(),
-        };
+        if amount <= 0 {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::InvalidAmount, "deallocate");
+        }
 
-        // Transfer assets back from pool to commitment contract
-        let contract_address = e.current_contract_address();
-        transfer_asset(&e, &commitment.asset_address, &target_pool, &contract_address, amount);
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentNotFound, "deallocate")
+        });
+
+        if is_commitment_frozen(&e, &commitment_id) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, CommitmentError::CommitmentFrozen, "deallocate");
+        }
+
+        // EFFECTS: Return value to the commitment and reduce tracked allocation
+        let asset = commitment.asset_address.clone();
+        commitment.current_value += amount;
+        set_commitment(&e, &commitment);
 
-        // Update commitment balance
-        let balance = get_commitment_balance(&e, &commitment_id);
-        set_commitment_balance(&e, &commitment_id, balance + amount);
+        let current_tvl = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLocked)
+            .unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLocked, &(current_tvl + amount));
+        let asset_tvl = e
+            .storage()
+            .instance()
+            .get::<_, i128>(&DataKey::TotalValueLockedByAsset(asset.clone()))
+            .unwrap_or(0);
+        e.storage()
+            .instance()
+            .set(&DataKey::TotalValueLockedByAsset(asset.clone()), &(asset_tvl + amount));
 
-        // Update allocation tracking
         let mut tracking = get_allocation_tracking(&e, &commitment_id);
         tracking.total_allocated -= amount;
         if tracking.total_allocated < 0 {
             tracking.total_allocated = 0;
         }
+        net_pool_allocations(&e, &mut tracking, &target_pool, amount);
         set_allocation_tracking(&e, &commitment_id, &tracking);
 
-        // Emit deallocation event
-        e.events().publish(
-            (symbol_short!("dealloc"), symbol_short!("cmt_id")),
-            commitment_id,
-        );
-        e.events().publish(
-            (symbol_short!("dealloc"), symbol_short!("pool")),
-            target_pool,
-        );
+        // INTERACTIONS: Transfer assets back from pool to this contract
+        let contract_address = e.current_contract_address();
+        let token_client = token::Client::new(&e, &asset);
+        token_client.transfer(&target_pool, &contract_address, &amount);
+
+        // Clear reentrancy guard
+        set_reentrancy_guard(&e, false);
+
         e.events().publish(
-            (symbol_short!("dealloc"), symbol_short!("amount")),
-            amount,
+            (symbol_short!("Dealloc"), commitment_id, target_pool),
+            (amount, e.ledger().timestamp()),
         );
-    /// Allocate liquidity (called by allocation strategy)
+    }
+
+    /// Recall every outstanding allocation of a commitment, e.g. before settlement
     ///
-    /// # Reentrancy Protection
-    /// Uses checks-effects-interactions pattern with reentrancy guard.
-    pub fn allocate(e: Env, commitment_id: String, target_pool: Address, amount: i128) {
+    /// Pulls each entry of `AllocationTracking.allocations` back from its target
+    /// pool (each pool must authorize its transfer), restores the commitment value
+    /// and TVL, clears the tracking and returns the amount recalled.
+    ///
+    /// # Arguments
+    /// * `caller` - The allocation contract calling this function (must be authorized and sign)
+    /// * `commitment_id` - The ID of the commitment
+    pub fn deallocate_all(e: Env, caller: Address, commitment_id: String) -> i128 {
         // Reentrancy protection
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
-        EmergencyControl::require_not_emergency(&e);
-
-        // Rate limit allocations per target pool address
-        let fn_symbol = symbol_short!("alloc");
-        RateLimiter::check(&e, &target_pool, &fn_symbol);
 
-        // CHECKS: Validate inputs and commitment
-        if amount <= 0 {
+        // CHECKS
+        caller.require_auth();
+        if !is_authorized_allocator(&e, &caller) {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::InvalidAmount, "allocate");
+            fail(&e, CommitmentError::Unauthorized, "deallocate_all");
         }
 
-        let commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::CommitmentNotFound, "allocate")
+            fail(&e, CommitmentError::CommitmentNotFound, "deallocate_all")
         });
 
-        // Verify commitment is active
-        let active_status = String::from_str(&e, "active");
-        if commitment.status != active_status {
+        if is_commitment_frozen(&e, &commitment_id) {
             set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::NotActive, "allocate");
+            fail(&e, CommitmentError::CommitmentFrozen, "deallocate_all");
         }
 
-        // Verify sufficient balance
-        if commitment.current_value < amount {
-            set_reentrancy_guard(&e, false);
-            fail(&e, CommitmentError::InsufficientBalance, "allocate");
+        let tracking = get_allocation_tracking(&e, &commitment_id);
+        let mut recalled: i128 = 0;
+        for allocation in tracking.allocations.iter() {
+            recalled += allocation.amount;
         }
 
-        // EFFECTS: Update commitment value before external call
-        let mut updated_commitment = commitment;
-        let asset = updated_commitment.asset_address.clone();
-        updated_commitment.current_value = updated_commitment.current_value - amount;
-        set_commitment(&e, &updated_commitment);
+        // EFFECTS: Return value to the commitment and clear tracked allocations
+        let asset = commitment.asset_address.clone();
+        commitment.current_value += recalled;
+        set_commitment(&e, &commitment);
 
-        // Decrease total value locked and per-asset TVL
         let current_tvl = e
             .storage()
             .instance()
@@ -1517,7 +2928,7 @@ impl CommitmentCoreContract {
             .unwrap_or(0);
         e.storage()
             .instance()
-            .set(&DataKey::TotalValueLocked, &(current_tvl - amount));
+            .set(&DataKey::TotalValueLocked, &(current_tvl + recalled));
         let asset_tvl = e
             .storage()
             .instance()
@@ -1525,22 +2936,33 @@ impl CommitmentCoreContract {
             .unwrap_or(0);
         e.storage()
             .instance()
-            .set(&DataKey::TotalValueLockedByAsset(asset), &(asset_tvl - amount));
+            .set(&DataKey::TotalValueLockedByAsset(asset.clone()), &(asset_tvl + recalled));
 
-        // INTERACTIONS: External call (token transfer)
-        // Transfer assets to target pool
+        set_allocation_tracking(
+            &e,
+            &commitment_id,
+            &AllocationTracking {
+                total_allocated: 0,
+                allocations: Vec::new(&e),
+            },
+        );
+
+        // INTERACTIONS: Transfer assets back from each pool to this contract
         let contract_address = e.current_contract_address();
-        let token_client = token::Client::new(&e, &updated_commitment.asset_address);
-        token_client.transfer(&contract_address, &target_pool, &amount);
+        let token_client = token::Client::new(&e, &asset);
+        for allocation in tracking.allocations.iter() {
+            token_client.transfer(&allocation.target_pool, &contract_address, &allocation.amount);
+        }
 
         // Clear reentrancy guard
         set_reentrancy_guard(&e, false);
 
-        // Emit allocation event
         e.events().publish(
-            (symbol_short!("Alloc"), commitment_id, target_pool),
-            (amount, e.ledger().timestamp()),
+            (Symbol::new(&e, "DeallocAll"), commitment_id),
+            (recalled, tracking.allocations.len(), e.ledger().timestamp()),
         );
+
+        recalled
     }
 
     /// Configure rate limits for this contract's functions.
@@ -1565,6 +2987,76 @@ impl CommitmentCoreContract {
         RateLimiter::set_exempt(&e, &address, exempt);
     }
 
+    // ========================================================================
+    // Allocation hooks
+    // ========================================================================
+
+    /// Set the allocation contract notified via `on_commitment_closed` when a
+    /// commitment settles or exits early. Admin only.
+    pub fn set_allocation_contract(e: Env, caller: Address, allocation_contract: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::AllocationContract, &allocation_contract);
+        e.events().publish(
+            (Symbol::new(&e, "AllocContractSet"), caller),
+            (allocation_contract, e.ledger().timestamp()),
+        );
+    }
+
+    /// Get the allocation contract notified on commitment close, if any.
+    pub fn get_allocation_contract(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::AllocationContract)
+    }
+
+    // ========================================================================
+    // Idempotency
+    // ========================================================================
+
+    /// Set how many ledgers an idempotency key stays live. Admin only.
+    pub fn set_idempotency_ttl(e: Env, caller: Address, ttl_ledgers: u32) {
+        require_admin(&e, &caller);
+        if ttl_ledgers == 0 || ttl_ledgers > e.storage().max_ttl() {
+            fail(&e, CommitmentError::InvalidDuration, "set_idempotency_ttl");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::IdempotencyTtl, &ttl_ledgers);
+    }
+
+    /// Get the idempotency key lifetime in ledgers.
+    pub fn get_idempotency_ttl(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get::<_, u32>(&DataKey::IdempotencyTtl)
+            .unwrap_or(DEFAULT_IDEMPOTENCY_TTL_LEDGERS)
+    }
+
+    // ========================================================================
+    // Expiry calendar
+    // ========================================================================
+
+    /// Count `duration_days` as business days (weekends skipped) for new
+    /// commitments instead of calendar days. Admin only.
+    pub fn set_business_day_expiry(e: Env, caller: Address, enabled: bool) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::BusinessDayExpiry, &enabled);
+        e.events().publish(
+            (Symbol::new(&e, "BizDayExpirySet"), caller),
+            (enabled, e.ledger().timestamp()),
+        );
+    }
+
+    /// Whether new commitments expire after business days (default: calendar days).
+    pub fn is_business_day_expiry(e: Env) -> bool {
+        e.storage()
+            .instance()
+            .get::<_, bool>(&DataKey::BusinessDayExpiry)
+            .unwrap_or(false)
+    }
+
     // ========================================================================
     // Fee collection (protocol revenue)
     // ========================================================================
@@ -1577,21 +3069,110 @@ impl CommitmentCoreContract {
         }
         e.storage().instance().set(&DataKey::CreationFeeBps, &fee_bps);
         e.events().publish(
-            (symbol_short!("FeeSet"), symbol_short!("creation"), caller),
-            (fee_bps, e.ledger().timestamp()),
+            (symbol_short!("FeeSet"), symbol_short!("creation"), caller),
+            (fee_bps, e.ledger().timestamp()),
+        );
+    }
+
+    /// Set the fee taken from the payout on normal settlement, in basis points (0-10000). Admin only.
+    pub fn set_settlement_fee_bps(e: Env, caller: Address, fee_bps: u32) {
+        require_admin(&e, &caller);
+        if fee_bps > BPS_MAX {
+            fail(&e, CommitmentError::InvalidFeeBps, "set_settlement_fee_bps");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::SettlementFeeBps, &fee_bps);
+        e.events().publish(
+            (symbol_short!("FeeSet"), symbol_short!("settle"), caller),
+            (fee_bps, e.ledger().timestamp()),
+        );
+    }
+
+    /// Set fee recipient (protocol treasury). Admin only.
+    pub fn set_fee_recipient(e: Env, caller: Address, recipient: Address) {
+        require_admin(&e, &caller);
+        e.storage().instance().set(&DataKey::FeeRecipient, &recipient);
+        e.events().publish(
+            (symbol_short!("FeeRecip"), caller),
+            (recipient, e.ledger().timestamp()),
+        );
+    }
+
+    /// Route `insurance_bps` of each early exit penalty to `insurance_fund`
+    /// instead of the treasury's collected fees. Admin only.
+    pub fn set_insurance_fund(e: Env, caller: Address, insurance_fund: Address, insurance_bps: u32) {
+        require_admin(&e, &caller);
+        if insurance_bps > BPS_MAX {
+            fail(&e, CommitmentError::InvalidFeeBps, "set_insurance_fund");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::InsuranceFund, &insurance_fund);
+        e.storage()
+            .instance()
+            .set(&DataKey::InsuranceBps, &insurance_bps);
+        e.events().publish(
+            (symbol_short!("InsurSet"), caller),
+            (insurance_fund, insurance_bps, e.ledger().timestamp()),
+        );
+    }
+
+    /// Set the attestation engine whose stored metrics supply attested fees
+    /// for `slash_for_underperformance`. Admin only.
+    pub fn set_attestation_engine(e: Env, caller: Address, attestation_engine: Address) {
+        require_admin(&e, &caller);
+        e.storage()
+            .instance()
+            .set(&DataKey::AttestationEngine, &attestation_engine);
+        e.events().publish(
+            (Symbol::new(&e, "AttestEngineSet"), caller),
+            (attestation_engine, e.ledger().timestamp()),
         );
     }
 
-    /// Set fee recipient (protocol treasury). Admin only.
-    pub fn set_fee_recipient(e: Env, caller: Address, recipient: Address) {
+    /// Get the attestation engine used for fee checks, if any.
+    pub fn get_attestation_engine(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::AttestationEngine)
+    }
+
+    /// Set the share of the payout slashed to the insurance fund when a
+    /// commitment misses its fee threshold, in basis points (0-10000). Admin only.
+    pub fn set_underperformance_slash_bps(e: Env, caller: Address, slash_bps: u32) {
         require_admin(&e, &caller);
-        e.storage().instance().set(&DataKey::FeeRecipient, &recipient);
+        if slash_bps > BPS_MAX {
+            fail(&e, CommitmentError::InvalidFeeBps, "set_underperformance_slash_bps");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::UnderperformanceSlashBps, &slash_bps);
         e.events().publish(
-            (symbol_short!("FeeRecip"), caller),
-            (recipient, e.ledger().timestamp()),
+            (symbol_short!("SlashSet"), caller),
+            (slash_bps, e.ledger().timestamp()),
         );
     }
 
+    /// Get the underperformance slash share in basis points (default 0).
+    pub fn get_underperformance_slash_bps(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get::<_, u32>(&DataKey::UnderperformanceSlashBps)
+            .unwrap_or(0)
+    }
+
+    /// Get the insurance fund address (optional).
+    pub fn get_insurance_fund(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::InsuranceFund)
+    }
+
+    /// Get the insurance share of penalties in basis points.
+    pub fn get_insurance_bps(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get::<_, u32>(&DataKey::InsuranceBps)
+            .unwrap_or(0)
+    }
+
     /// Withdraw collected fees to the configured fee recipient. Admin only.
     pub fn withdraw_fees(e: Env, caller: Address, asset_address: Address, amount: i128) {
         require_admin(&e, &caller);
@@ -1626,6 +3207,14 @@ impl CommitmentCoreContract {
             .unwrap_or(0)
     }
 
+    /// Get settlement fee in basis points.
+    pub fn get_settlement_fee_bps(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get::<_, u32>(&DataKey::SettlementFeeBps)
+            .unwrap_or(0)
+    }
+
     /// Get fee recipient address (optional).
     pub fn get_fee_recipient(e: Env) -> Option<Address> {
         e.storage().instance().get(&DataKey::FeeRecipient)
@@ -1639,6 +3228,36 @@ impl CommitmentCoreContract {
             .unwrap_or(0)
     }
 
+    /// Transfer the unaccounted balance of `asset_address` (rounding dust, stray
+    /// transfers) to `to`. Admin only. Returns the amount swept.
+    ///
+    /// What users are owed stays in the contract: the larger of the deposited
+    /// principal of open commitments and the asset's TVL, so a value markdown
+    /// never frees principal for sweeping. Collected fees stay too; only the
+    /// balance above both is swept.
+    pub fn sweep_dust(e: Env, caller: Address, asset_address: Address, to: Address) -> i128 {
+        require_admin(&e, &caller);
+        require_no_reentrancy(&e);
+
+        let contract_address = e.current_contract_address();
+        let token_client = token::Client::new(&e, &asset_address);
+        let owed = get_locked_principal(&e, &asset_address).max(
+            Self::get_total_value_locked_by_asset(e.clone(), asset_address.clone()),
+        );
+        let liabilities = owed + Self::get_collected_fees(e.clone(), asset_address.clone());
+        let surplus = token_client.balance(&contract_address) - liabilities;
+        if surplus <= 0 {
+            fail(&e, CommitmentError::NothingToSweep, "sweep_dust");
+        }
+
+        token_client.transfer(&contract_address, &to, &surplus);
+        e.events().publish(
+            (symbol_short!("DustSwept"), caller, to),
+            (asset_address, surplus, e.ledger().timestamp()),
+        );
+        surplus
+    }
+
     // ========================================================================
     // Emergency Functions (Issue #62)
     // ========================================================================
@@ -1685,7 +3304,12 @@ impl CommitmentCoreContract {
             read_commitment(&e, &commitment_id).unwrap_or_else(|| panic!("Commitment not found"));
 
         // Mark as settled
-        commitment.status = String::from_str(&e, "settled");
+        if commitment.status == String::from_str(&e, "active") {
+            adjust_locked_principal(&e, &commitment.asset_address, -commitment.amount);
+        }
+        let settled_status = String::from_str(&e, "settled");
+        record_status_change(&e, &commitment_id, commitment.status.clone(), settled_status.clone());
+        commitment.status = settled_status;
         let settlement_amount = commitment.current_value;
         commitment.current_value = 0;
         set_commitment(&e, &commitment);
@@ -1718,6 +3342,7 @@ impl CommitmentCoreContract {
         let mut args = Vec::new(&e);
         args.push_back(commitment.nft_token_id.into_val(&e));
         e.invoke_contract::<()>(&nft_contract, &Symbol::new(&e, "settle"), args);
+        append_settlement_log(&e, &commitment_id, settlement_amount);
 
         e.events().publish(
             (symbol_short!("EmgSettl"), commitment_id),
@@ -1752,6 +3377,9 @@ impl CommitmentCoreContract {
             .instance()
             .set(&DataKey::TotalValueLocked, &new_tvl);
 
+        if commitment.status != new_status {
+            record_status_change(&e, &commitment_id, commitment.status.clone(), new_status.clone());
+        }
         commitment.current_value = new_value;
         commitment.status = new_status;
         commitment.expires_at = new_expires_at;
@@ -1764,6 +3392,337 @@ impl CommitmentCoreContract {
         );
     }
 
+    /// Reassign a commitment to a new owner in emergency (admin only)
+    /// Recovery path for owners who lost key access. A co-owner share held by
+    /// the previous owner moves to `new_owner`. When `transfer_nft` is set,
+    /// the NFT is moved too via the NFT contract's `recover_transfer`, which
+    /// requires this contract to be configured as its core contract. Without
+    /// it the NFT stays with the old holder and `sync_owner_from_nft` is
+    /// blocked for the commitment.
+    pub fn emergency_reassign_owner(
+        e: Env,
+        caller: Address,
+        commitment_id: String,
+        new_owner: Address,
+        transfer_nft: bool,
+    ) {
+        require_admin(&e, &caller);
+        EmergencyControl::require_emergency(&e);
+
+        let mut commitment = read_commitment(&e, &commitment_id).unwrap_or_else(|| {
+            fail(
+                &e,
+                CommitmentError::CommitmentNotFound,
+                "emergency_reassign_owner",
+            )
+        });
+        let previous_owner = commitment.owner.clone();
+        if previous_owner == new_owner {
+            fail(
+                &e,
+                CommitmentError::InvalidStatus,
+                "emergency_reassign_owner",
+            );
+        }
+
+        commitment.owner = new_owner.clone();
+        set_commitment(&e, &commitment);
+        remove_owner_commitment(&e, &previous_owner, &commitment_id);
+        add_owner_commitment(&e, &new_owner, &commitment_id);
+        move_co_owner_share(&e, &commitment_id, &previous_owner, &new_owner);
+
+        if transfer_nft {
+            let nft_contract = e
+                .storage()
+                .instance()
+                .get::<_, Address>(&DataKey::NftContract)
+                .unwrap_or_else(|| {
+                    fail(
+                        &e,
+                        CommitmentError::NotInitialized,
+                        "emergency_reassign_owner",
+                    )
+                });
+            let mut args = Vec::new(&e);
+            args.push_back(commitment.nft_token_id.into_val(&e));
+            args.push_back(new_owner.clone().into_val(&e));
+            e.invoke_contract::<()>(&nft_contract, &Symbol::new(&e, "recover_transfer"), args);
+        }
+        e.storage()
+            .persistent()
+            .set(&DataKey::OwnerPinned(commitment_id.clone()), &!transfer_nft);
+
+        e.events().publish(
+            (symbol_short!("EmgOwner"), commitment_id),
+            (
+                previous_owner,
+                new_owner,
+                transfer_nft,
+                e.ledger().timestamp(),
+            ),
+        );
+    }
+
+    /// Rebuild `owner`'s commitment index from the commitment records.
+    /// Admin only, emergency mode only.
+    ///
+    /// Scans up to `limit` commitments in creation order from `start`; a call
+    /// with `start` 0 clears the index first. Returns the cursor to pass as the
+    /// next `start`, which equals `get_total_commitments` once the scan is done.
+    pub fn rebuild_owner_index(
+        e: Env,
+        caller: Address,
+        owner: Address,
+        start: u64,
+        limit: u32,
+    ) -> u64 {
+        require_admin(&e, &caller);
+        EmergencyControl::require_emergency(&e);
+
+        let key = DataKey::OwnerCommitments(owner.clone());
+        let mut index = if start == 0 {
+            Vec::new(&e)
+        } else {
+            get_owner_commitments(&e, &owner)
+        };
+        let next = Self::scan_commitments(&e, start, limit, "rebuild_owner_index", |commitment| {
+            if commitment.owner == owner && !index.contains(&commitment.commitment_id) {
+                index.push_back(commitment.commitment_id);
+            }
+        });
+        e.storage().instance().set(&key, &index);
+
+        e.events().publish(
+            (symbol_short!("IdxRebld"), symbol_short!("owner"), owner),
+            (start, next, index.len()),
+        );
+        next
+    }
+
+    /// Rebuild the index of all commitment ids from the commitment records.
+    /// Admin only, emergency mode only.
+    ///
+    /// Paged like `rebuild_owner_index`; lists every stored commitment in
+    /// creation order. Returns the cursor to pass as the next `start`.
+    pub fn rebuild_all_ids_index(e: Env, caller: Address, start: u64, limit: u32) -> u64 {
+        require_admin(&e, &caller);
+        EmergencyControl::require_emergency(&e);
+
+        let mut index = if start == 0 {
+            Vec::new(&e)
+        } else {
+            get_all_commitment_ids(&e)
+        };
+        let next = Self::scan_commitments(&e, start, limit, "rebuild_all_ids_index", |commitment| {
+            if !index.contains(&commitment.commitment_id) {
+                index.push_back(commitment.commitment_id);
+            }
+        });
+        e.storage()
+            .instance()
+            .set(&DataKey::AllCommitmentIds, &index);
+
+        e.events().publish(
+            (symbol_short!("IdxRebld"), symbol_short!("all_ids")),
+            (start, next, index.len()),
+        );
+        next
+    }
+
+    /// Rebuild the active commitment index from the commitment records.
+    /// Admin only, emergency mode only.
+    ///
+    /// Paged like `rebuild_owner_index`; lists commitments whose status is
+    /// "active". Returns the cursor to pass as the next `start`.
+    pub fn rebuild_active_index(e: Env, caller: Address, start: u64, limit: u32) -> u64 {
+        require_admin(&e, &caller);
+        EmergencyControl::require_emergency(&e);
+
+        let active_status = String::from_str(&e, "active");
+        let mut index = if start == 0 {
+            Vec::new(&e)
+        } else {
+            get_active_commitments(&e)
+        };
+        let next = Self::scan_commitments(&e, start, limit, "rebuild_active_index", |commitment| {
+            if commitment.status == active_status && !index.contains(&commitment.commitment_id) {
+                index.push_back(commitment.commitment_id);
+            }
+        });
+        e.storage()
+            .instance()
+            .set(&DataKey::ActiveCommitments, &index);
+
+        e.events().publish(
+            (symbol_short!("IdxRebld"), symbol_short!("active")),
+            (start, next, index.len()),
+        );
+        next
+    }
+
+    /// Visit the stored commitments with creation counters `start..start + limit`,
+    /// returning the counter to resume from.
+    fn scan_commitments(
+        e: &Env,
+        start: u64,
+        limit: u32,
+        context: &str,
+        mut visit: impl FnMut(Commitment),
+    ) -> u64 {
+        if limit == 0 || limit > MAX_INDEX_REBUILD_BATCH {
+            fail(e, CommitmentError::InvalidAmount, context);
+        }
+        let total = get_total_commitments(e);
+        let end = start.saturating_add(limit as u64).min(total);
+        for counter in start..end {
+            let commitment_id = Self::generate_commitment_id(e, counter);
+            if let Some(commitment) = read_commitment(e, &commitment_id) {
+                visit(commitment);
+            }
+        }
+        end.max(start)
+    }
+
+    // ========== Commitment types ==========
+
+    /// Get the accepted commitment types.
+    pub fn get_commitment_types(e: Env) -> Vec<String> {
+        get_commitment_types(&e)
+    }
+
+    /// Accept a new commitment type in `create_commitment`. Admin only.
+    pub fn add_commitment_type(e: Env, caller: Address, commitment_type: String) {
+        require_admin(&e, &caller);
+        if commitment_type.is_empty() {
+            fail(&e, CommitmentError::InvalidCommitmentType, "add_commitment_type");
+        }
+        let mut types = get_commitment_types(&e);
+        if !types.contains(&commitment_type) {
+            types.push_back(commitment_type.clone());
+            e.storage().instance().set(&DataKey::CommitmentTypes, &types);
+        }
+        e.events().publish(
+            (symbol_short!("TypeAdd"), caller),
+            (commitment_type, e.ledger().timestamp()),
+        );
+    }
+
+    /// Stop accepting a commitment type for new commitments. Admin only.
+    /// Existing commitments of that type are unaffected.
+    pub fn remove_commitment_type(e: Env, caller: Address, commitment_type: String) {
+        require_admin(&e, &caller);
+        let mut out = Vec::new(&e);
+        for t in get_commitment_types(&e).iter() {
+            if t != commitment_type {
+                out.push_back(t);
+            }
+        }
+        e.storage().instance().set(&DataKey::CommitmentTypes, &out);
+        e.events().publish(
+            (symbol_short!("TypeRem"), caller),
+            (commitment_type, e.ledger().timestamp()),
+        );
+    }
+
+    // ========== Commitment templates ==========
+
+    /// Register (or replace) a named `CommitmentRules` preset. Admin only.
+    /// The rules must pass the same validation as `create_commitment`.
+    pub fn register_template(e: Env, caller: Address, name: String, rules: CommitmentRules) {
+        require_admin(&e, &caller);
+        if let Err(err) = Self::validate_rules(&e, &rules) {
+            fail(&e, err, "register_template");
+        }
+        e.storage()
+            .instance()
+            .set(&DataKey::Template(name.clone()), &rules);
+        let mut names = Self::list_templates(e.clone());
+        if !names.contains(&name) {
+            names.push_back(name.clone());
+            e.storage().instance().set(&DataKey::TemplateNames, &names);
+        }
+        e.events().publish(
+            (symbol_short!("TmplReg"), caller),
+            (name, e.ledger().timestamp()),
+        );
+    }
+
+    /// Get the rules registered under a template name, if any.
+    pub fn get_template(e: Env, name: String) -> Option<CommitmentRules> {
+        e.storage()
+            .instance()
+            .get::<_, CommitmentRules>(&DataKey::Template(name))
+    }
+
+    /// List registered template names in registration order.
+    pub fn list_templates(e: Env) -> Vec<String> {
+        e.storage()
+            .instance()
+            .get::<_, Vec<String>>(&DataKey::TemplateNames)
+            .unwrap_or(Vec::new(&e))
+    }
+
+    /// Create a commitment using the rules of a registered template.
+    ///
+    /// Goes through `create_commitment` with no idempotency key or deadline.
+    /// Fails with `TemplateNotFound` for an unknown template.
+    pub fn create_commitment_from_template(
+        e: Env,
+        owner: Address,
+        amount: i128,
+        asset_address: Address,
+        template_name: String,
+    ) -> Result<String, CommitmentError> {
+        let rules = Self::get_template(e.clone(), template_name).ok_or_else(|| {
+            raise(&e, CommitmentError::TemplateNotFound, "create_commitment_from_template")
+        })?;
+        Self::create_commitment(e, owner, amount, asset_address, rules, None, 0)
+    }
+
+    /// Create a commitment funded by `owner` whose settlement and early-exit
+    /// proceeds are split across `co_owners` by `share_bps`.
+    ///
+    /// Shares must be positive and sum to 10000 (`InvalidShares` otherwise).
+    /// A single entry naming `owner` is the ordinary single-owner path. Goes
+    /// through `create_commitment` with no idempotency key or deadline.
+    pub fn create_co_owned_commitment(
+        e: Env,
+        owner: Address,
+        amount: i128,
+        asset_address: Address,
+        rules: CommitmentRules,
+        co_owners: Vec<CoOwner>,
+    ) -> Result<String, CommitmentError> {
+        let mut total_bps = 0u32;
+        for co_owner in co_owners.iter() {
+            if co_owner.share_bps == 0 {
+                return Err(raise(&e, CommitmentError::InvalidShares, "create_co_owned_commitment"));
+            }
+            total_bps = total_bps.saturating_add(co_owner.share_bps);
+        }
+        let single_owner = co_owners.len() == 1 && co_owners.get(0).unwrap().owner == owner;
+        if total_bps != BPS_MAX || (co_owners.len() == 1 && !single_owner) {
+            return Err(raise(&e, CommitmentError::InvalidShares, "create_co_owned_commitment"));
+        }
+
+        let commitment_id =
+            Self::create_commitment(e.clone(), owner, amount, asset_address, rules, None, 0)?;
+        if !single_owner {
+            e.storage()
+                .instance()
+                .set(&DataKey::CoOwners(commitment_id.clone()), &co_owners);
+        }
+        Ok(commitment_id)
+    }
+
+    /// Co-owners sharing a commitment's proceeds; empty for single-owner commitments.
+    pub fn get_co_owners(e: Env, commitment_id: String) -> Vec<CoOwner> {
+        e.storage()
+            .instance()
+            .get(&DataKey::CoOwners(commitment_id))
+            .unwrap_or(Vec::new(&e))
+    }
+
     // ========== Multi-asset support ==========
 
     /// Get the list of supported assets (whitelist). Empty = allow all assets.
@@ -1829,6 +3788,118 @@ impl CommitmentCoreContract {
             .get::<_, AssetMetadata>(&DataKey::AssetMetadata(asset))
     }
 
+    /// Get every whitelisted asset with its metadata (if set) and TVL, in
+    /// whitelist order. Returns an empty Vec when no whitelist is configured,
+    /// since every asset is then allowed.
+    ///
+    /// This is the `get_supported_assets_with_metadata` query from the asset
+    /// metadata spec. That name is 34 characters, over Soroban's 32-character
+    /// limit for contract functions, so it cannot be exported as written.
+    pub fn get_supported_assets_metadata(e: Env) -> Vec<(Address, Option<AssetMetadata>, i128)> {
+        let mut assets = Vec::new(&e);
+        for asset in Self::get_supported_assets(e.clone()).iter() {
+            let metadata = Self::get_asset_metadata(e.clone(), asset.clone());
+            let tvl = Self::get_total_value_locked_by_asset(e.clone(), asset.clone());
+            assets.push_back((asset, metadata, tvl));
+        }
+        assets
+    }
+
+    /// Set per-asset bounds on the amount passed to `create_commitment`. Admin only.
+    /// `None` removes that bound.
+    pub fn set_commitment_amount_limits(
+        e: Env,
+        caller: Address,
+        asset: Address,
+        min_amount: Option<i128>,
+        max_amount: Option<i128>,
+    ) {
+        require_admin(&e, &caller);
+        let invalid = matches!(min_amount, Some(min) if min <= 0)
+            || matches!(max_amount, Some(max) if max <= 0)
+            || matches!((min_amount, max_amount), (Some(min), Some(max)) if min > max);
+        if invalid {
+            fail(&e, CommitmentError::InvalidAmount, "set_commitment_amount_limits");
+        }
+
+        let min_key = DataKey::MinCommitmentAmount(asset.clone());
+        match min_amount {
+            Some(min) => e.storage().instance().set(&min_key, &min),
+            None => e.storage().instance().remove(&min_key),
+        }
+        let max_key = DataKey::MaxCommitmentAmount(asset.clone());
+        match max_amount {
+            Some(max) => e.storage().instance().set(&max_key, &max),
+            None => e.storage().instance().remove(&max_key),
+        }
+
+        e.events().publish(
+            (Symbol::new(&e, "AmountLimitsSet"), asset),
+            (min_amount, max_amount, e.ledger().timestamp()),
+        );
+    }
+
+    /// Get `(min, max)` commitment amount bounds for an asset; `None` means unbounded.
+    pub fn get_commitment_amount_limits(e: Env, asset: Address) -> (Option<i128>, Option<i128>) {
+        (
+            e.storage()
+                .instance()
+                .get::<_, i128>(&DataKey::MinCommitmentAmount(asset.clone())),
+            e.storage()
+                .instance()
+                .get::<_, i128>(&DataKey::MaxCommitmentAmount(asset)),
+        )
+    }
+
+    /// Set an absolute floor and cap on early exit penalties, applied after the
+    /// percentage penalty. Admin only. `None` removes that bound.
+    ///
+    /// Commitments with a zero `early_exit_penalty` are never charged, and the
+    /// penalty never exceeds the commitment's current value.
+    pub fn set_penalty_limits(
+        e: Env,
+        caller: Address,
+        min_penalty_amount: Option<i128>,
+        max_penalty_amount: Option<i128>,
+    ) {
+        require_admin(&e, &caller);
+        let invalid = matches!(min_penalty_amount, Some(min) if min < 0)
+            || matches!(max_penalty_amount, Some(max) if max < 0)
+            || matches!(
+                (min_penalty_amount, max_penalty_amount),
+                (Some(min), Some(max)) if min > max
+            );
+        if invalid {
+            fail(&e, CommitmentError::InvalidAmount, "set_penalty_limits");
+        }
+
+        match min_penalty_amount {
+            Some(min) => e.storage().instance().set(&DataKey::MinPenaltyAmount, &min),
+            None => e.storage().instance().remove(&DataKey::MinPenaltyAmount),
+        }
+        match max_penalty_amount {
+            Some(max) => e.storage().instance().set(&DataKey::MaxPenaltyAmount, &max),
+            None => e.storage().instance().remove(&DataKey::MaxPenaltyAmount),
+        }
+
+        e.events().publish(
+            (Symbol::new(&e, "PenaltyLimitsSet"), caller),
+            (min_penalty_amount, max_penalty_amount, e.ledger().timestamp()),
+        );
+    }
+
+    /// Get `(min, max)` early exit penalty bounds; `None` means unbounded.
+    pub fn get_penalty_limits(e: Env) -> (Option<i128>, Option<i128>) {
+        (
+            e.storage()
+                .instance()
+                .get::<_, i128>(&DataKey::MinPenaltyAmount),
+            e.storage()
+                .instance()
+                .get::<_, i128>(&DataKey::MaxPenaltyAmount),
+        )
+    }
+
     /// Get total value locked for a specific asset.
     pub fn get_total_value_locked_by_asset(e: Env, asset: Address) -> i128 {
         e.storage()
@@ -1837,6 +3908,45 @@ impl CommitmentCoreContract {
             .unwrap_or(0)
     }
 
+    /// Check that the stored TVL counters match the active commitments.
+    ///
+    /// Sums `current_value` over the active commitment index and compares it with
+    /// `TotalValueLocked` and, per asset, `TotalValueLockedByAsset`. Each mismatch
+    /// emits a `TvlMismatch` event with (computed, stored); returns false if any
+    /// counter is off.
+    pub fn verify_tvl_invariant(e: Env) -> bool {
+        let mut total: i128 = 0;
+        let mut by_asset: Map<Address, i128> = Map::new(&e);
+        for commitment_id in get_active_commitments(&e).iter() {
+            if let Some(commitment) = read_commitment(&e, &commitment_id) {
+                total += commitment.current_value;
+                let asset_total = by_asset.get(commitment.asset_address.clone()).unwrap_or(0);
+                by_asset.set(commitment.asset_address, asset_total + commitment.current_value);
+            }
+        }
+
+        let mut consistent = true;
+        let stored_total = Self::get_total_value_locked(e.clone());
+        if stored_total != total {
+            consistent = false;
+            e.events().publish(
+                (Symbol::new(&e, "TvlMismatch"),),
+                (total, stored_total),
+            );
+        }
+        for (asset, asset_total) in by_asset.iter() {
+            let stored = Self::get_total_value_locked_by_asset(e.clone(), asset.clone());
+            if stored != asset_total {
+                consistent = false;
+                e.events().publish(
+                    (Symbol::new(&e, "TvlMismatch"), asset),
+                    (asset_total, stored),
+                );
+            }
+        }
+        consistent
+    }
+
     /// Check if an asset is supported (whitelist empty = all supported).
     pub fn is_asset_supported(e: Env, asset: Address) -> bool {
         let supported = e
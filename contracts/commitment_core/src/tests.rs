@@ -31,6 +31,7 @@ fn create_test_commitment(
             early_exit_penalty: 10,
             min_fee_threshold: 1000,
             grace_period_days: 3,
+            max_loss_bps: 0,
         },
         amount,
         asset_address: Address::generate(e),
@@ -49,7 +50,9 @@ fn store_commitment(e: &Env, contract_id: &Address, commitment: &Commitment) {
 }
 
 fn create_test_env() -> Env {
-    Env::default()
+    let e = Env::default();
+    e.mock_all_auths();
+    e
 }
 
 fn setup_contract(e: &Env) -> Address {
@@ -58,46 +61,13 @@ fn setup_contract(e: &Env) -> Address {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     contract_id
 }
 
-fn create_test_commitment(e: &Env, contract_id: &Address) -> (String, Commitment) {
-    let commitment_id = String::from_str(e, "test_commitment_1");
-    let owner = Address::generate(e);
-    let asset_address = Address::generate(e);
-    
-    let rules = CommitmentRules {
-        duration_days: 365,
-        max_loss_percent: 20,
-        commitment_type: String::from_str(e, "balanced"),
-        early_exit_penalty: 10,
-        min_fee_threshold: 1000,
-    };
-    
-    let commitment = Commitment {
-        commitment_id: commitment_id.clone(),
-        owner: owner.clone(),
-        nft_token_id: 1,
-        rules: rules.clone(),
-        amount: 1000000, // 1000 tokens (assuming 1000 scaling)
-        asset_address: asset_address.clone(),
-        created_at: 1000,
-        expires_at: 1000 + (365 * 86400), // 365 days later
-        current_value: 1000000,
-        status: String::from_str(e, "active"),
-    };
-    
-    // Note: In a real test, we would need to actually store this commitment
-    // For now, this is a helper function structure
-    
-    (commitment_id, commitment)
-}
-
 #[test]
 fn test_initialize() {
-    let e = create_test_env();
     let e = Env::default();
     let contract_id = e.register_contract(None, CommitmentCoreContract);
 
@@ -106,23 +76,25 @@ fn test_initialize() {
 
     // Test successful initialization
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 }
 
 #[test]
 fn test_create_commitment_valid() {
     let e = Env::default();
+    let admin = Address::generate(&e);
+    let nft_contract = Address::generate(&e);
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     // Verify initialization succeeded (no panic)
 }
 
 #[test]
-#[should_panic(expected = "AlreadyInitialized")]
+#[should_panic(expected = "Contract already initialized")]
 fn test_initialize_twice() {
     let e = create_test_env();
     let admin = Address::generate(&e);
@@ -130,8 +102,8 @@ fn test_initialize_twice() {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
-    client.initialize(&admin, &nft_contract); // Should panic
+    client.initialize(&admin, &nft_contract, &None);
+    client.initialize(&admin, &nft_contract, &None); // Should panic
 }
 
 #[test]
@@ -142,10 +114,9 @@ fn test_add_authorized_allocator() {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     let allocator = Address::generate(&e);
-    admin.mock_auth(&e, &admin, &admin, &[]);
     client.add_authorized_allocator(&allocator);
     
     // Verify allocator is authorized
@@ -161,23 +132,46 @@ fn test_remove_authorized_allocator() {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     let allocator = Address::generate(&e);
     
     // Add allocator
-    admin.mock_auth(&e, &admin, &admin, &[]);
     client.add_authorized_allocator(&allocator);
     assert!(client.is_authorized_allocator(&allocator));
     
     // Remove allocator
-    admin.mock_auth(&e, &admin, &admin, &[]);
     client.remove_authorized_allocator(&allocator);
     assert!(!client.is_authorized_allocator(&allocator));
 }
 
 #[test]
-#[should_panic(expected = "Unauthorized")]
+fn test_get_authorized_allocators() {
+    let e = create_test_env();
+    let admin = Address::generate(&e);
+    let nft_contract = Address::generate(&e);
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &nft_contract, &None);
+    assert!(client.get_authorized_allocators().is_empty());
+
+    let first = Address::generate(&e);
+    let second = Address::generate(&e);
+    client.add_authorized_allocator(&first);
+    client.add_authorized_allocator(&second);
+    // Re-adding does not duplicate the entry
+    client.add_authorized_allocator(&first);
+    assert_eq!(
+        client.get_authorized_allocators(),
+        vec![&e, first.clone(), second.clone()]
+    );
+
+    client.remove_authorized_allocator(&first);
+    assert_eq!(client.get_authorized_allocators(), vec![&e, second]);
+}
+
+#[test]
 fn test_allocate_unauthorized_caller() {
     let e = create_test_env();
     let admin = Address::generate(&e);
@@ -185,18 +179,20 @@ fn test_allocate_unauthorized_caller() {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     let unauthorized_allocator = Address::generate(&e);
     let commitment_id = String::from_str(&e, "test_commitment");
     let target_pool = Address::generate(&e);
     
-    // Try to allocate with unauthorized caller - should panic
-    client.allocate(&unauthorized_allocator, &commitment_id, &target_pool, &1000);
+    // Try to allocate with unauthorized caller - should fail
+    assert_eq!(
+        client.try_allocate(&unauthorized_allocator, &commitment_id, &target_pool, &1000),
+        Err(Ok(CommitmentError::Unauthorized))
+    );
 }
 
 #[test]
-#[should_panic(expected = "InactiveCommitment")]
 fn test_allocate_inactive_commitment() {
     let e = create_test_env();
     let admin = Address::generate(&e);
@@ -204,21 +200,22 @@ fn test_allocate_inactive_commitment() {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     let allocator = Address::generate(&e);
-    admin.mock_auth(&e, &admin, &admin, &[]);
     client.add_authorized_allocator(&allocator);
     
-    // Try to allocate with non-existent commitment - should panic
+    // Try to allocate with non-existent commitment - should fail
     let commitment_id = String::from_str(&e, "nonexistent_commitment");
     let target_pool = Address::generate(&e);
     
-    client.allocate(&allocator, &commitment_id, &target_pool, &1000);
+    assert_eq!(
+        client.try_allocate(&allocator, &commitment_id, &target_pool, &1000),
+        Err(Ok(CommitmentError::CommitmentNotFound))
+    );
 }
 
 #[test]
-#[should_panic(expected = "InsufficientBalance")]
 fn test_allocate_insufficient_balance() {
     let e = create_test_env();
     let admin = Address::generate(&e);
@@ -226,25 +223,24 @@ fn test_allocate_insufficient_balance() {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     let allocator = Address::generate(&e);
-    admin.mock_auth(&e, &admin, &admin, &[]);
     client.add_authorized_allocator(&allocator);
     
-    // Note: This test requires a commitment with a known balance
-    // In a full implementation, we would create a commitment first
-    // and set its balance, then try to allocate more than available
+    let owner = Address::generate(&e);
+    let commitment = create_test_commitment(&e, "test_commitment", &owner, 1000, 1000, 10, 30, 0);
+    store_commitment(&e, &contract_id, &commitment);
     let commitment_id = String::from_str(&e, "test_commitment");
     let target_pool = Address::generate(&e);
     
-    // This will panic with InactiveCommitment first, but the test structure
-    // demonstrates the insufficient balance check would work once commitment exists
-    // client.allocate(&allocator, &commitment_id, &target_pool, &999999999);
+    assert_eq!(
+        client.try_allocate(&allocator, &commitment_id, &target_pool, &1001),
+        Err(Ok(CommitmentError::InsufficientBalance))
+    );
 }
 
 #[test]
-#[should_panic(expected = "InvalidAmount")]
 fn test_allocate_invalid_amount() {
     let e = create_test_env();
     let admin = Address::generate(&e);
@@ -252,19 +248,19 @@ fn test_allocate_invalid_amount() {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     let allocator = Address::generate(&e);
-    admin.mock_auth(&e, &admin, &admin, &[]);
     client.add_authorized_allocator(&allocator);
     
     let commitment_id = String::from_str(&e, "test_commitment");
     let target_pool = Address::generate(&e);
     
-    // Try to allocate with zero or negative amount - should panic
-    // Note: This would panic in transfer_asset function
-    // client.allocate(&allocator, &commitment_id, &target_pool, &0);
-    // Or: client.allocate(&allocator, &commitment_id, &target_pool, &-100);
+    // Try to allocate with zero amount - should fail
+    assert_eq!(
+        client.try_allocate(&allocator, &commitment_id, &target_pool, &0),
+        Err(Ok(CommitmentError::InvalidAmount))
+    );
 }
 
 #[test]
@@ -275,7 +271,7 @@ fn test_get_allocation_tracking() {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     let commitment_id = String::from_str(&e, "test_commitment");
     
@@ -293,14 +289,13 @@ fn test_deallocate() {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     let allocator = Address::generate(&e);
-    admin.mock_auth(&e, &admin, &admin, &[]);
     client.add_authorized_allocator(&allocator);
     
-    let commitment_id = String::from_str(&e, "test_commitment");
-    let target_pool = Address::generate(&e);
+    let _commitment_id = String::from_str(&e, "test_commitment");
+    let _target_pool = Address::generate(&e);
     
     // Note: This test would require a real commitment and successful allocation first
     // The deallocation function will panic with InactiveCommitment if commitment doesn't exist
@@ -316,7 +311,7 @@ fn test_deallocate_unauthorized() {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     let unauthorized_allocator = Address::generate(&e);
     let commitment_id = String::from_str(&e, "test_commitment");
@@ -335,11 +330,10 @@ fn test_allocation_flow_integration() {
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     
     // Setup authorized allocator
     let allocator = Address::generate(&e);
-    admin.mock_auth(&e, &admin, &admin, &[]);
     client.add_authorized_allocator(&allocator);
     
     // Note: Full integration test would require:
@@ -353,16 +347,6 @@ fn test_allocation_flow_integration() {
     // This test structure shows the flow, but actual implementation
     // would need proper commitment and asset contract setup
 
-    let admin = Address::generate(&e);
-    let nft_contract = Address::generate(&e);
-    let _owner = Address::generate(&e);
-    let _asset_address = Address::generate(&e);
-
-    // Initialize the contract
-    e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
-    });
-
     let _rules = CommitmentRules {
         duration_days: 30,
         max_loss_percent: 10,
@@ -370,6 +354,7 @@ fn test_allocation_flow_integration() {
         early_exit_penalty: 5,
         min_fee_threshold: 100,
         grace_period_days: 7,
+        max_loss_bps: 0,
     };
 
     let _amount = 1000i128;
@@ -377,12 +362,11 @@ fn test_allocation_flow_integration() {
     // Test commitment creation (this will panic if NFT contract is not properly set up)
     // For now, we'll test that the validation works by testing individual validation functions
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::validate_rules(&e, &_rules); // Should not panic
+        assert!(CommitmentCoreContract::validate_rules(&e, &_rules).is_ok());
     });
 }
 
 #[test]
-#[should_panic(expected = "Invalid duration")]
 fn test_validate_rules_invalid_duration() {
     let e = Env::default();
     let contract_id = e.register_contract(None, CommitmentCoreContract);
@@ -394,16 +378,17 @@ fn test_validate_rules_invalid_duration() {
         early_exit_penalty: 5,
         min_fee_threshold: 100,
         grace_period_days: 0,
+        max_loss_bps: 0,
     };
 
-    // Test invalid duration - should panic
-    e.as_contract(&contract_id, || {
-        CommitmentCoreContract::validate_rules(&e, &rules);
+    // Test invalid duration - should fail
+    let result = e.as_contract(&contract_id, || {
+        CommitmentCoreContract::validate_rules(&e, &rules)
     });
+    assert_eq!(result, Err(CommitmentError::InvalidDuration));
 }
 
 #[test]
-#[should_panic(expected = "Invalid percent")]
 fn test_validate_rules_invalid_max_loss() {
     let e = Env::default();
     let contract_id = e.register_contract(None, CommitmentCoreContract);
@@ -415,16 +400,17 @@ fn test_validate_rules_invalid_max_loss() {
         early_exit_penalty: 5,
         min_fee_threshold: 100,
         grace_period_days: 0,
+        max_loss_bps: 0,
     };
 
-    // Test invalid max loss percent - should panic
-    e.as_contract(&contract_id, || {
-        CommitmentCoreContract::validate_rules(&e, &rules);
+    // Test invalid max loss percent - should fail
+    let result = e.as_contract(&contract_id, || {
+        CommitmentCoreContract::validate_rules(&e, &rules)
     });
+    assert_eq!(result, Err(CommitmentError::InvalidMaxLossPercent));
 }
 
 #[test]
-#[should_panic(expected = "Invalid commitment type")]
 fn test_validate_rules_invalid_type() {
     let e = Env::default();
     let contract_id = e.register_contract(None, CommitmentCoreContract);
@@ -436,12 +422,14 @@ fn test_validate_rules_invalid_type() {
         early_exit_penalty: 5,
         min_fee_threshold: 100,
         grace_period_days: 0,
+        max_loss_bps: 0,
     };
 
-    // Test invalid commitment type - should panic
-    e.as_contract(&contract_id, || {
-        CommitmentCoreContract::validate_rules(&e, &rules);
+    // Test invalid commitment type - should fail
+    let result = e.as_contract(&contract_id, || {
+        CommitmentCoreContract::validate_rules(&e, &rules)
     });
+    assert_eq!(result, Err(CommitmentError::InvalidCommitmentType));
 }
 
 #[test]
@@ -454,7 +442,7 @@ fn test_get_owner_commitments() {
     let owner = Address::generate(&e);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 
     // Initially empty
@@ -473,7 +461,7 @@ fn test_get_total_commitments() {
     let nft_contract = Address::generate(&e);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 
     // Initially zero
@@ -492,7 +480,7 @@ fn test_get_admin() {
     let nft_contract = Address::generate(&e);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 
     let retrieved_admin = e.as_contract(&contract_id, || {
@@ -510,7 +498,7 @@ fn test_get_nft_contract() {
     let nft_contract = Address::generate(&e);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 
     let retrieved_nft_contract = e.as_contract(&contract_id, || {
@@ -589,6 +577,48 @@ fn test_check_violations_loss_limit_exceeded() {
     assert!(has_violations, "Should have loss limit violation");
 }
 
+#[test]
+fn test_check_violations_bps_mode_detects_sub_percent_loss() {
+    let e = Env::default();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let owner = Address::generate(&e);
+    let created_at = 1000u64;
+
+    // 1000 -> 995 is a 0.5% loss, which rounds to 0 in whole-percent precision
+    let percent_mode = create_test_commitment(&e, "percent_mode", &owner, 1000, 995, 0, 30, created_at);
+    let mut bps_mode = create_test_commitment(&e, "bps_mode", &owner, 1000, 995, 0, 30, created_at);
+    bps_mode.rules.max_loss_bps = 25; // 0.25%
+    store_commitment(&e, &contract_id, &percent_mode);
+    store_commitment(&e, &contract_id, &bps_mode);
+
+    e.ledger().with_mut(|l| {
+        l.timestamp = created_at + (5 * 86400);
+    });
+
+    let (percent_violated, bps_violated) = e.as_contract(&contract_id, || {
+        (
+            CommitmentCoreContract::check_violations(e.clone(), String::from_str(&e, "percent_mode")),
+            CommitmentCoreContract::check_violations(e.clone(), String::from_str(&e, "bps_mode")),
+        )
+    });
+
+    assert!(!percent_violated, "0.5% loss is invisible in percent mode");
+    assert!(bps_violated, "0.5% loss exceeds a 25 bps limit");
+}
+
+#[test]
+fn test_create_commitment_rejects_max_loss_bps_above_10000() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let mut rules = safe_rules(&e);
+    rules.max_loss_bps = 10_001;
+
+    assert_eq!(
+        client.try_create_commitment(&owner, &1000, &asset, &rules, &None, &0),
+        Err(Ok(CommitmentError::InvalidMaxLossPercent))
+    );
+}
+
 #[test]
 fn test_check_violations_duration_expired() {
     let e = Env::default();
@@ -888,11 +918,11 @@ fn test_create_commitment_event() {
     let e = Env::default();
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
-    let owner = Address::generate(&e);
+    let _owner = Address::generate(&e);
     let admin = Address::generate(&e);
     let nft_contract = Address::generate(&e);
 
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
 
     let _rules = CommitmentRules {
         duration_days: 30,
@@ -901,6 +931,7 @@ fn test_create_commitment_event() {
         early_exit_penalty: 5,
         min_fee_threshold: 100,
         grace_period_days: 0,
+        max_loss_bps: 0,
     };
 
     // Note: This might panic if mock token transfers are not set up, but we are testing events.
@@ -922,6 +953,7 @@ fn test_create_commitment_event() {
 #[test]
 fn test_update_value_event() {
     let e = Env::default();
+    e.mock_all_auths();
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
     let admin = Address::generate(&e);
@@ -930,7 +962,7 @@ fn test_update_value_event() {
     let commitment_id = String::from_str(&e, "test_id");
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
         let commitment = create_test_commitment(
             &e,
             "test_id",
@@ -948,7 +980,13 @@ fn test_update_value_event() {
             &1000i128,
         );
         // Call update_value in same context so it sees stored commitment
-        CommitmentCoreContract::update_value(e.clone(), commitment.commitment_id.clone(), 1100);
+        CommitmentCoreContract::update_value(
+            e.clone(),
+            admin.clone(),
+            commitment.commitment_id.clone(),
+            1100,
+        )
+        .unwrap();
     });
 
     let commitment = client.get_commitment(&commitment_id);
@@ -970,6 +1008,45 @@ fn test_update_value_event() {
     assert_eq!(data.0, 1100);
 }
 
+#[test]
+fn test_update_value_missing_commitment_returns_error() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    client.initialize(&admin, &Address::generate(&e), &None);
+
+    assert_eq!(
+        client.try_update_value(&admin, &String::from_str(&e, "missing"), &1100),
+        Err(Ok(CommitmentError::CommitmentNotFound))
+    );
+    assert_eq!(
+        client.try_update_value(&admin, &String::from_str(&e, "missing"), &-1),
+        Err(Ok(CommitmentError::InvalidAmount))
+    );
+}
+
+#[test]
+fn test_update_value_requires_admin_or_updater() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    let keeper = Address::generate(&e);
+    assert_eq!(
+        client.try_update_value(&keeper, &commitment_id, &1_100),
+        Err(Ok(CommitmentError::Unauthorized))
+    );
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 1_000);
+
+    client.add_authorized_updater(&keeper);
+    client.update_value(&keeper, &commitment_id, &1_100);
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 1_100);
+    client.update_value(&admin, &commitment_id, &1_200);
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 1_200);
+}
+
 #[test]
 #[should_panic(expected = "Rate limit exceeded")]
 fn test_update_value_rate_limit_enforced() {
@@ -983,9 +1060,9 @@ fn test_update_value_rate_limit_enforced() {
     let owner = Address::generate(&e);
     let commitment_id = String::from_str(&e, "rl_test");
 
-    // Initialize, configure rate limit (1 update per 60 seconds), store commitment, do first update in-context
+    // Initialize, configure rate limit (1 update per 60 seconds), store commitment
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
         CommitmentCoreContract::set_rate_limit(
             e.clone(),
             admin.clone(),
@@ -1009,29 +1086,28 @@ fn test_update_value_rate_limit_enforced() {
             &DataKey::TotalValueLockedByAsset(commitment.asset_address.clone()),
             &1000i128,
         );
-        // First update_value inside contract context (consumes the one allowed call)
-        CommitmentCoreContract::update_value(e.clone(), commitment.commitment_id.clone(), 100);
     });
 
-    // Second call via client should hit rate limit
-    client.update_value(&commitment_id, &200);
+    // First update consumes the one allowed call
+    client.update_value(&admin, &commitment_id, &100);
+
+    // Second call should hit rate limit
+    client.update_value(&admin, &commitment_id, &200);
 }
 
 #[test]
-#[should_panic(expected = "Commitment not found")]
 fn test_settle_event() {
     let e = Env::default();
     let contract_id = e.register_contract(None, CommitmentCoreContract);
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
 
     let commitment_id = String::from_str(&e, "test_id");
-    // This will panic because commitment doesn't exist
+    // This fails because commitment doesn't exist
     // The test verifies that the function properly validates preconditions
-    client.settle(&commitment_id);
+    assert_eq!(client.try_settle(&commitment_id), Err(Ok(CommitmentError::CommitmentNotFound)));
 }
 
 #[test]
-#[should_panic(expected = "Commitment not found")]
 fn test_early_exit_event() {
     let e = Env::default();
     let caller = Address::generate(&e);
@@ -1039,23 +1115,30 @@ fn test_early_exit_event() {
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
 
     let commitment_id = String::from_str(&e, "test_id");
-    // This will panic because commitment doesn't exist
+    // This fails because commitment doesn't exist
     // The test verifies that the function properly validates preconditions
-    client.early_exit(&commitment_id, &caller);
+    assert_eq!(
+        client.try_early_exit(&commitment_id, &caller),
+        Err(Ok(CommitmentError::CommitmentNotFound))
+    );
 }
 
 #[test]
-#[should_panic(expected = "Commitment not found")]
 fn test_allocate_event() {
-    let e = Env::default();
+    let e = create_test_env();
     let target_pool = Address::generate(&e);
-    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let contract_id = setup_contract(&e);
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let allocator = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
 
     let commitment_id = String::from_str(&e, "test_id");
-    // This will panic because commitment doesn't exist
+    // This fails because commitment doesn't exist
     // The test verifies that the function properly validates preconditions
-    client.allocate(&commitment_id, &target_pool, &500);
+    assert_eq!(
+        client.try_allocate(&allocator, &commitment_id, &target_pool, &500),
+        Err(Ok(CommitmentError::CommitmentNotFound))
+    );
 }
 
 /// Helper function to create a test commitment with custom penalty
@@ -1083,6 +1166,7 @@ fn create_test_commitment_with_penalty(
             early_exit_penalty,
             min_fee_threshold: 1000,
             grace_period_days: 3,
+            max_loss_bps: 0,
         },
         amount,
         asset_address: Address::generate(e),
@@ -1097,7 +1181,6 @@ fn create_test_commitment_with_penalty(
 // ============================================================================
 
 #[test]
-#[should_panic(expected = "Commitment not found")]
 fn test_early_exit_commitment_not_found() {
     let e = Env::default();
     e.mock_all_auths();
@@ -1108,21 +1191,21 @@ fn test_early_exit_commitment_not_found() {
     let nft_contract = Address::generate(&e);
     
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     
     // Try to exit a non-existent commitment
-    e.as_contract(&contract_id, || {
+    let result = e.as_contract(&contract_id, || {
         CommitmentCoreContract::early_exit(
             e.clone(),
             String::from_str(&e, "nonexistent_commitment"),
             owner.clone(),
-        );
+        )
     });
+    assert_eq!(result, Err(CommitmentError::CommitmentNotFound));
 }
 
 #[test]
-#[should_panic(expected = "Unauthorized: caller not allowed")]
 fn test_early_exit_unauthorized_caller() {
     let e = Env::default();
     e.mock_all_auths();
@@ -1135,7 +1218,7 @@ fn test_early_exit_unauthorized_caller() {
     let commitment_id = "test_commitment_unauthorized";
     
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     
     let commitment = create_test_commitment(
@@ -1152,17 +1235,17 @@ fn test_early_exit_unauthorized_caller() {
     store_commitment(&e, &contract_id, &commitment);
     
     // Try to exit with unauthorized caller
-    e.as_contract(&contract_id, || {
+    let result = e.as_contract(&contract_id, || {
         CommitmentCoreContract::early_exit(
             e.clone(),
             String::from_str(&e, commitment_id),
             unauthorized_caller.clone(),
-        );
+        )
     });
+    assert_eq!(result, Err(CommitmentError::Unauthorized));
 }
 
 #[test]
-#[should_panic(expected = "Commitment is not active")]
 fn test_early_exit_already_settled() {
     let e = Env::default();
     e.mock_all_auths();
@@ -1174,7 +1257,7 @@ fn test_early_exit_already_settled() {
     let commitment_id = "test_commitment_settled";
     
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     
     let mut commitment = create_test_commitment(
@@ -1193,17 +1276,17 @@ fn test_early_exit_already_settled() {
     store_commitment(&e, &contract_id, &commitment);
     
     // Try to exit already settled commitment
-    e.as_contract(&contract_id, || {
+    let result = e.as_contract(&contract_id, || {
         CommitmentCoreContract::early_exit(
             e.clone(),
             String::from_str(&e, commitment_id),
             owner.clone(),
-        );
+        )
     });
+    assert_eq!(result, Err(CommitmentError::NotActive));
 }
 
 #[test]
-#[should_panic(expected = "Commitment is not active")]
 fn test_early_exit_already_violated() {
     let e = Env::default();
     e.mock_all_auths();
@@ -1215,7 +1298,7 @@ fn test_early_exit_already_violated() {
     let commitment_id = "test_commitment_violated";
     
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     
     let mut commitment = create_test_commitment(
@@ -1234,17 +1317,17 @@ fn test_early_exit_already_violated() {
     store_commitment(&e, &contract_id, &commitment);
     
     // Try to exit violated commitment
-    e.as_contract(&contract_id, || {
+    let result = e.as_contract(&contract_id, || {
         CommitmentCoreContract::early_exit(
             e.clone(),
             String::from_str(&e, commitment_id),
             owner.clone(),
-        );
+        )
     });
+    assert_eq!(result, Err(CommitmentError::NotActive));
 }
 
 #[test]
-#[should_panic(expected = "Commitment is not active")]
 fn test_early_exit_already_exited() {
     let e = Env::default();
     e.mock_all_auths();
@@ -1256,7 +1339,7 @@ fn test_early_exit_already_exited() {
     let commitment_id = "test_commitment_already_exited";
     
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     
     let mut commitment = create_test_commitment(
@@ -1275,13 +1358,14 @@ fn test_early_exit_already_exited() {
     store_commitment(&e, &contract_id, &commitment);
     
     // Try to exit again
-    e.as_contract(&contract_id, || {
+    let result = e.as_contract(&contract_id, || {
         CommitmentCoreContract::early_exit(
             e.clone(),
             String::from_str(&e, commitment_id),
             owner.clone(),
-        );
+        )
     });
+    assert_eq!(result, Err(CommitmentError::NotActive));
 }
 
 // ============================================================================
@@ -1300,7 +1384,7 @@ fn test_early_exit_state_update() {
     let commitment_id = "test_commitment_state";
     
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     
     // Create commitment with 10% penalty
@@ -1401,7 +1485,7 @@ fn test_early_exit_event_emission() {
     let commitment_id = "test_commitment_event";
     
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     
     let commitment = create_test_commitment(
@@ -1559,7 +1643,7 @@ fn test_fee_get_creation_fee_bps_default() {
     let admin = Address::generate(&e);
     let nft_contract = Address::generate(&e);
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     let bps = e.as_contract(&contract_id, || CommitmentCoreContract::get_creation_fee_bps(e.clone()));
     assert_eq!(bps, 0);
@@ -1573,7 +1657,7 @@ fn test_fee_set_creation_fee_bps() {
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
     let admin = Address::generate(&e);
     let nft_contract = Address::generate(&e);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     assert_eq!(client.get_creation_fee_bps(), 0);
     client.set_creation_fee_bps(&admin, &100); // 1%
     assert_eq!(client.get_creation_fee_bps(), 100);
@@ -1590,7 +1674,7 @@ fn test_fee_set_and_get_fee_recipient() {
     let admin = Address::generate(&e);
     let nft_contract = Address::generate(&e);
     let treasury = Address::generate(&e);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     assert!(client.get_fee_recipient().is_none());
     client.set_fee_recipient(&admin, &treasury);
     assert_eq!(client.get_fee_recipient().unwrap(), treasury);
@@ -1604,7 +1688,7 @@ fn test_fee_get_collected_fees_default() {
     let nft_contract = Address::generate(&e);
     let asset = Address::generate(&e);
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
     assert_eq!(client.get_collected_fees(&asset), 0);
@@ -1619,7 +1703,7 @@ fn test_fee_set_creation_fee_bps_invalid() {
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
     let admin = Address::generate(&e);
     let nft_contract = Address::generate(&e);
-    client.initialize(&admin, &nft_contract);
+    client.initialize(&admin, &nft_contract, &None);
     client.set_creation_fee_bps(&admin, &10001);
 }
 
@@ -1634,7 +1718,7 @@ fn test_early_exit_status_transition() {
     let nft_contract = e.register_contract(None, CommitmentCoreContract);
     
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     
     let commitment_id = "test_status_transition";
@@ -1673,7 +1757,7 @@ fn test_settle_success_at_maturity() {
     let nft_contract = Address::generate(&e); // Mock NFT address
     
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     
     let created_at = 1000u64;
@@ -1707,7 +1791,6 @@ fn test_settle_success_at_maturity() {
 }
 
 #[test]
-#[should_panic(expected = "Commitment has not expired yet")]
 fn test_settle_fails_before_maturity() {
     let e = Env::default();
     e.mock_all_auths();
@@ -1718,7 +1801,7 @@ fn test_settle_fails_before_maturity() {
     let nft_contract = Address::generate(&e);
     
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
     
     let created_at = 1000u64;
@@ -1743,9 +1826,12 @@ fn test_settle_fails_before_maturity() {
         l.timestamp = expires_at - 1;
     });
     
-    e.as_contract(&contract_id, || {
-        CommitmentCoreContract::settle(e.clone(), String::from_str(&e, commitment_id));
+    let result = e.as_contract(&contract_id, || {
+        CommitmentCoreContract::settle(e.clone(), String::from_str(&e, commitment_id))
     });
+    assert_eq!(result, Err(CommitmentError::NotExpired));
+}
+
 // ============================================================================
 // Multi-asset support tests
 // ============================================================================
@@ -1758,7 +1844,7 @@ fn test_get_supported_assets_empty_by_default() {
     let nft_contract = Address::generate(&e);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 
     let supported = e.as_contract(&contract_id, || {
@@ -1777,7 +1863,7 @@ fn test_add_and_remove_supported_asset() {
     let asset = Address::generate(&e);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
@@ -1801,7 +1887,7 @@ fn test_is_asset_supported_empty_whitelist_allows_all() {
     let asset = Address::generate(&e);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
@@ -1820,7 +1906,7 @@ fn test_is_asset_supported_whitelist() {
     let asset_b = Address::generate(&e);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
@@ -1840,7 +1926,7 @@ fn test_asset_metadata_set_and_get() {
     let asset = Address::generate(&e);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
@@ -1852,6 +1938,39 @@ fn test_asset_metadata_set_and_get() {
     assert_eq!(meta.decimals, 6);
 }
 
+#[test]
+fn test_get_supported_assets_metadata() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+
+    // No whitelist: every asset is allowed and nothing is listed
+    assert_eq!(client.get_supported_assets_metadata().len(), 0);
+
+    let usdc = Address::generate(&e);
+    let bare = Address::generate(&e);
+    for a in [&asset, &usdc, &bare] {
+        client.add_supported_asset(&admin, a);
+    }
+    client.set_asset_metadata(&admin, &asset, &String::from_str(&e, "XLM"), &7);
+    client.set_asset_metadata(&admin, &usdc, &String::from_str(&e, "USDC"), &6);
+    client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    let assets = client.get_supported_assets_metadata();
+    assert_eq!(assets.len(), 3);
+
+    let (address, metadata, tvl) = assets.get(0).unwrap();
+    assert_eq!(address, asset);
+    assert_eq!(metadata.unwrap().symbol, String::from_str(&e, "XLM"));
+    assert_eq!(tvl, 1_000);
+
+    let (address, metadata, tvl) = assets.get(1).unwrap();
+    assert_eq!(address, usdc);
+    assert_eq!(metadata.unwrap().decimals, 6);
+    assert_eq!(tvl, 0);
+
+    assert_eq!(assets.get(2).unwrap(), (bare, None, 0));
+}
+
 #[test]
 fn test_get_total_value_locked_by_asset() {
     let e = Env::default();
@@ -1862,7 +1981,7 @@ fn test_get_total_value_locked_by_asset() {
     let asset = Address::generate(&e);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
     });
 
     let client = CommitmentCoreContractClient::new(&e, &contract_id);
@@ -1889,7 +2008,6 @@ fn test_get_total_value_locked_by_asset() {
 }
 
 #[test]
-#[should_panic(expected = "Asset is not in the supported whitelist")]
 fn test_create_commitment_requires_asset_supported_when_whitelist_set() {
     let e = Env::default();
     e.mock_all_auths();
@@ -1901,7 +2019,7 @@ fn test_create_commitment_requires_asset_supported_when_whitelist_set() {
     let disallowed_asset = Address::generate(&e);
 
     e.as_contract(&contract_id, || {
-        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone());
+        CommitmentCoreContract::initialize(e.clone(), admin.clone(), nft_contract.clone(), None);
         // Set whitelist to only allowed_asset
         let mut supported = Vec::new(&e);
         supported.push_back(allowed_asset.clone());
@@ -1915,8 +2033,2279 @@ fn test_create_commitment_requires_asset_supported_when_whitelist_set() {
         commitment_type: String::from_str(&e, "safe"),
         early_exit_penalty: 5,
         min_fee_threshold: 100,
+        grace_period_days: 0,
+        max_loss_bps: 0,
     };
 
-    // Creating with disallowed asset should panic
-    client.create_commitment(&owner, &1000, &disallowed_asset, &rules);
+    // Creating with disallowed asset should fail
+    assert_eq!(
+        client.try_create_commitment(&owner, &1000, &disallowed_asset, &rules, &None, &0),
+        Err(Ok(CommitmentError::AssetNotSupported))
+    );
+}
+
+// ============================================================================
+// Idempotency tests
+// ============================================================================
+
+#[contract]
+struct MockNftContract;
+
+#[contractimpl]
+impl MockNftContract {
+    #[allow(clippy::too_many_arguments)]
+    pub fn mint(
+        e: Env,
+        owner: Address,
+        _commitment_id: String,
+        _duration_days: u32,
+        _max_loss_percent: u32,
+        _commitment_type: String,
+        _initial_amount: i128,
+        _asset_address: Address,
+        _early_exit_penalty: u32,
+        _expires_at: Option<u64>,
+    ) -> u32 {
+        let minted: u32 = e.storage().instance().get(&symbol_short!("minted")).unwrap_or(0);
+        e.storage().instance().set(&symbol_short!("minted"), &(minted + 1));
+        e.storage().persistent().set(&minted, &owner);
+        minted
+    }
+
+    pub fn minted(e: Env) -> u32 {
+        e.storage().instance().get(&symbol_short!("minted")).unwrap_or(0)
+    }
+
+    pub fn owner_of(e: Env, token_id: u32) -> Address {
+        e.storage().persistent().get(&token_id).unwrap()
+    }
+
+    pub fn transfer(e: Env, from: Address, to: Address, token_id: u32) {
+        from.require_auth();
+        assert_eq!(Self::owner_of(e.clone(), token_id), from);
+        e.storage().persistent().set(&token_id, &to);
+    }
+
+    pub fn settle(_e: Env, _token_id: u32) {}
+}
+
+/// Core contract wired to a mock NFT and a funded token, ready for create_commitment.
+fn setup_funded_core(e: &Env) -> (CommitmentCoreContractClient<'_>, Address, Address, Address, Address) {
+    setup_funded_core_with_prefix(e, None)
+}
+
+fn setup_funded_core_with_prefix(
+    e: &Env,
+    id_prefix: Option<String>,
+) -> (CommitmentCoreContractClient<'_>, Address, Address, Address, Address) {
+    // The token transfer authorizes the owner below the core contract call
+    e.mock_all_auths_allowing_non_root_auth();
+    let admin = Address::generate(e);
+    let owner = Address::generate(e);
+    let nft_contract = e.register_contract(None, MockNftContract);
+    let asset = e.register_stellar_asset_contract_v2(admin.clone()).address();
+    token::StellarAssetClient::new(e, &asset).mint(&owner, &10_000);
+
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(e, &contract_id);
+    client.initialize(&admin, &nft_contract, &id_prefix);
+    (client, admin, owner, asset, nft_contract)
+}
+
+fn safe_rules(e: &Env) -> CommitmentRules {
+    CommitmentRules {
+        duration_days: 30,
+        max_loss_percent: 10,
+        commitment_type: String::from_str(e, "safe"),
+        early_exit_penalty: 5,
+        min_fee_threshold: 100,
+        grace_period_days: 0,
+        max_loss_bps: 0,
+    }
+}
+
+#[test]
+fn test_create_commitment_repeated_idempotency_key_returns_same_id() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, nft_contract) = setup_funded_core(&e);
+    let rules = safe_rules(&e);
+    let key = Some(String::from_str(&e, "order-42"));
+
+    let first = client.create_commitment(&owner, &1000, &asset, &rules, &key, &0);
+    let second = client.create_commitment(&owner, &1000, &asset, &rules, &key, &0);
+
+    assert_eq!(first, second);
+    assert_eq!(client.get_total_commitments(), 1);
+    assert_eq!(client.get_owner_commitments(&owner).len(), 1);
+    assert_eq!(client.get_total_value_locked(), 1000);
+    // Charged and minted exactly once
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 9_000);
+    assert_eq!(MockNftContractClient::new(&e, &nft_contract).minted(), 1);
+}
+
+#[test]
+fn test_create_commitment_distinct_idempotency_keys() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let rules = safe_rules(&e);
+
+    let first = client.create_commitment(
+        &owner,
+        &1000,
+        &asset,
+        &rules,
+        &Some(String::from_str(&e, "key-a")),
+        &0,
+    );
+    let second = client.create_commitment(
+        &owner,
+        &1000,
+        &asset,
+        &rules,
+        &Some(String::from_str(&e, "key-b")),
+        &0,
+    );
+
+    assert_ne!(first, second);
+    assert_eq!(client.get_total_commitments(), 2);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 8_000);
+}
+
+#[test]
+fn test_idempotency_key_scoped_per_owner() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, nft_contract) = setup_funded_core(&e);
+    let rules = safe_rules(&e);
+    let key = Some(String::from_str(&e, "order-42"));
+    let other = Address::generate(&e);
+    token::StellarAssetClient::new(&e, &asset).mint(&other, &10_000);
+
+    let first = client.create_commitment(&owner, &1000, &asset, &rules, &key, &0);
+    let second = client.create_commitment(&other, &1000, &asset, &rules, &key, &0);
+
+    assert_ne!(first, second);
+    assert_eq!(client.get_commitment(&second).owner, other);
+    assert_eq!(client.get_total_commitments(), 2);
+    assert_eq!(token::Client::new(&e, &asset).balance(&other), 9_000);
+    assert_eq!(MockNftContractClient::new(&e, &nft_contract).minted(), 2);
+}
+
+#[test]
+fn test_create_commitment_without_idempotency_key_not_deduplicated() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let rules = safe_rules(&e);
+
+    let first = client.create_commitment(&owner, &1000, &asset, &rules, &None, &0);
+    let second = client.create_commitment(&owner, &1000, &asset, &rules, &None, &0);
+
+    assert_ne!(first, second);
+    assert_eq!(client.get_total_commitments(), 2);
+}
+
+#[test]
+fn test_idempotency_key_expires_after_ttl() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let rules = safe_rules(&e);
+    let key = Some(String::from_str(&e, "order-42"));
+
+    client.set_idempotency_ttl(&admin, &100);
+    assert_eq!(client.get_idempotency_ttl(), 100);
+
+    let first = client.create_commitment(&owner, &1000, &asset, &rules, &key, &0);
+    e.ledger().with_mut(|l| {
+        l.sequence_number += 101;
+    });
+    let second = client.create_commitment(&owner, &1000, &asset, &rules, &key, &0);
+
+    assert_ne!(first, second);
+    assert_eq!(client.get_total_commitments(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Invalid duration")]
+fn test_set_idempotency_ttl_zero_rejected() {
+    let e = Env::default();
+    let (client, admin, _owner, _asset, _nft_contract) = setup_funded_core(&e);
+    client.set_idempotency_ttl(&admin, &0);
+}
+
+#[test]
+fn test_create_commitment_before_deadline() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    e.ledger().with_mut(|l| l.timestamp = 1_000);
+
+    client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &1_000);
+    assert_eq!(client.get_total_commitments(), 1);
+}
+
+#[test]
+fn test_create_commitment_after_deadline_rejected() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    e.ledger().with_mut(|l| l.timestamp = 1_001);
+
+    assert_eq!(
+        client.try_create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &1_000),
+        Err(Ok(CommitmentError::DeadlineExceeded))
+    );
+}
+
+// ============================================================================
+// Commitment id prefix tests
+// ============================================================================
+
+#[test]
+fn test_commitment_id_uses_default_prefix() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let rules = safe_rules(&e);
+
+    let first = client.create_commitment(&owner, &1000, &asset, &rules, &None, &0);
+    let second = client.create_commitment(&owner, &1000, &asset, &rules, &None, &0);
+
+    assert_eq!(first, String::from_str(&e, "c_0"));
+    assert_eq!(second, String::from_str(&e, "c_1"));
+}
+
+#[test]
+fn test_commitment_id_uses_custom_prefix() {
+    let e = Env::default();
+    let prefix = Some(String::from_str(&e, "mainnet_"));
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core_with_prefix(&e, prefix);
+    let rules = safe_rules(&e);
+
+    let first = client.create_commitment(&owner, &1000, &asset, &rules, &None, &0);
+    let second = client.create_commitment(&owner, &1000, &asset, &rules, &None, &0);
+
+    assert_eq!(first, String::from_str(&e, "mainnet_0"));
+    assert_eq!(second, String::from_str(&e, "mainnet_1"));
+    assert_eq!(client.get_commitment(&first).owner, owner);
+}
+
+#[test]
+fn test_commitment_id_max_prefix_fits_largest_counter() {
+    let e = Env::default();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let admin = Address::generate(&e);
+    let nft_contract = Address::generate(&e);
+
+    let id = e.as_contract(&contract_id, || {
+        CommitmentCoreContract::initialize(
+            e.clone(),
+            admin.clone(),
+            nft_contract.clone(),
+            Some(String::from_str(&e, "deployment12")),
+        );
+        CommitmentCoreContract::generate_commitment_id(&e, u64::MAX)
+    });
+
+    assert_eq!(id, String::from_str(&e, "deployment1218446744073709551615"));
+    assert_eq!(id.len(), 32);
+}
+
+#[test]
+#[should_panic(expected = "Invalid id prefix")]
+fn test_initialize_rejects_too_long_prefix() {
+    let e = create_test_env();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    let nft_contract = Address::generate(&e);
+
+    client.initialize(&admin, &nft_contract, &Some(String::from_str(&e, "deployment123")));
+}
+
+#[test]
+#[should_panic(expected = "Invalid id prefix")]
+fn test_initialize_rejects_empty_prefix() {
+    let e = create_test_env();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let admin = Address::generate(&e);
+    let nft_contract = Address::generate(&e);
+
+    client.initialize(&admin, &nft_contract, &Some(String::from_str(&e, "")));
+}
+
+// ============================================
+// Allocation hook tests
+// ============================================
+
+#[contract]
+struct MockAllocationContract;
+
+#[contractimpl]
+impl MockAllocationContract {
+    pub fn set_failing(e: Env, failing: bool) {
+        e.storage().instance().set(&symbol_short!("failing"), &failing);
+    }
+
+    pub fn on_commitment_closed(e: Env, commitment_id: String) {
+        if e.storage().instance().get(&symbol_short!("failing")).unwrap_or(false) {
+            panic!("allocation contract unavailable");
+        }
+        e.storage().instance().set(&symbol_short!("closed"), &commitment_id);
+    }
+
+    pub fn closed(e: Env) -> Option<String> {
+        e.storage().instance().get(&symbol_short!("closed"))
+    }
+}
+
+fn has_alloc_hook_failed_event(e: &Env, commitment_id: &String) -> bool {
+    let expected = vec![
+        e,
+        Symbol::new(e, "AllocHookFailed").into_val(e),
+        commitment_id.into_val(e),
+    ];
+    e.events().all().iter().any(|(_, topics, _)| topics == expected)
+}
+
+#[test]
+fn test_settle_notifies_allocation_contract() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let allocation_id = e.register_contract(None, MockAllocationContract);
+    let allocation = MockAllocationContractClient::new(&e, &allocation_id);
+
+    client.set_allocation_contract(&admin, &allocation_id);
+    assert_eq!(client.get_allocation_contract(), Some(allocation_id.clone()));
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    assert_eq!(allocation.closed(), Some(commitment_id.clone()));
+    assert_eq!(client.get_commitment(&commitment_id).status, String::from_str(&e, "settled"));
+}
+
+#[test]
+fn test_early_exit_notifies_allocation_contract() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let allocation_id = e.register_contract(None, MockAllocationContract);
+    let allocation = MockAllocationContractClient::new(&e, &allocation_id);
+    client.set_allocation_contract(&admin, &allocation_id);
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+    client.early_exit(&commitment_id, &owner);
+
+    assert_eq!(allocation.closed(), Some(commitment_id));
+}
+
+#[test]
+fn test_settle_succeeds_when_allocation_hook_fails() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let allocation_id = e.register_contract(None, MockAllocationContract);
+    let allocation = MockAllocationContractClient::new(&e, &allocation_id);
+    allocation.set_failing(&true);
+    client.set_allocation_contract(&admin, &allocation_id);
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    assert!(has_alloc_hook_failed_event(&e, &commitment_id));
+    assert_eq!(allocation.closed(), None);
+    assert_eq!(client.get_commitment(&commitment_id).status, String::from_str(&e, "settled"));
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 10_000);
+}
+
+#[test]
+fn test_settle_without_allocation_contract() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    assert_eq!(client.get_allocation_contract(), None);
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    assert!(!has_alloc_hook_failed_event(&e, &commitment_id));
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_set_allocation_contract_non_admin() {
+    let e = Env::default();
+    let (client, _admin, owner, _asset, _nft_contract) = setup_funded_core(&e);
+    let allocation_id = e.register_contract(None, MockAllocationContract);
+
+    client.set_allocation_contract(&owner, &allocation_id);
+}
+
+// ============================================
+// Business-day expiry tests
+// ============================================
+
+// 2024-01-05 12:00:00 UTC, a Friday
+const FRIDAY_NOON: u64 = 1_704_456_000;
+
+#[test]
+fn test_create_commitment_calendar_day_expiry_by_default() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    e.ledger().with_mut(|l| l.timestamp = FRIDAY_NOON);
+    assert!(!client.is_business_day_expiry());
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.expires_at, FRIDAY_NOON + 30 * 86400);
+}
+
+#[test]
+fn test_create_commitment_business_day_expiry() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    e.ledger().with_mut(|l| l.timestamp = FRIDAY_NOON);
+    client.set_business_day_expiry(&admin, &true);
+    assert!(client.is_business_day_expiry());
+
+    // 30 business days from a Friday is six calendar weeks later
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.expires_at, FRIDAY_NOON + 42 * 86400);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_set_business_day_expiry_non_admin() {
+    let e = Env::default();
+    let (client, _admin, owner, _asset, _nft_contract) = setup_funded_core(&e);
+
+    client.set_business_day_expiry(&owner, &true);
+}
+
+// ============================================
+// Batch value update tests
+// ============================================
+
+/// Three commitments of 1000 each; the middle one has exited early.
+fn setup_value_batch(e: &Env) -> (CommitmentCoreContractClient<'_>, Address, Vec<String>) {
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(e);
+    let mut ids = Vec::new(e);
+    for _ in 0..3 {
+        ids.push_back(client.create_commitment(&owner, &1000, &asset, &safe_rules(e), &None, &0));
+    }
+    client.early_exit(&ids.get(1).unwrap(), &owner);
+    (client, admin, ids)
+}
+
+fn value_updates(e: &Env, ids: &Vec<String>) -> Vec<UpdateValueParams> {
+    let mut updates = Vec::new(e);
+    for (commitment_id, new_value) in ids.iter().zip([900i128, 500, 1100]) {
+        updates.push_back(UpdateValueParams {
+            commitment_id,
+            new_value,
+        });
+    }
+    updates
+}
+
+#[test]
+fn test_batch_update_value_best_effort_skips_inactive() {
+    let e = Env::default();
+    let (client, admin, ids) = setup_value_batch(&e);
+    let updater = Address::generate(&e);
+    client.add_authorized_updater(&updater);
+    assert!(client.is_authorized_updater(&updater));
+
+    let result = client.batch_update_value(&updater, &value_updates(&e, &ids), &BatchMode::BestEffort);
+    assert!(!result.success);
+    assert_eq!(result.success_count, 2);
+    assert_eq!(result.errors.len(), 1);
+    let error = result.errors.get(0).unwrap();
+    assert_eq!(error.index, 1);
+    assert_eq!(error.error_code, CommitmentError::NotActive as u32);
+
+    assert_eq!(client.get_commitment(&ids.get(0).unwrap()).current_value, 900);
+    assert_eq!(client.get_commitment(&ids.get(2).unwrap()).current_value, 1100);
+    assert_eq!(client.get_total_value_locked(), 2000);
+
+    // The admin may also batch
+    let mut single = Vec::new(&e);
+    single.push_back(UpdateValueParams {
+        commitment_id: ids.get(0).unwrap(),
+        new_value: 1000,
+    });
+    assert!(client.batch_update_value(&admin, &single, &BatchMode::Atomic).success);
+    assert_eq!(client.get_total_value_locked(), 2100);
+}
+
+#[test]
+fn test_batch_update_value_atomic_rejects_whole_batch() {
+    let e = Env::default();
+    let (client, admin, ids) = setup_value_batch(&e);
+
+    let result = client.batch_update_value(&admin, &value_updates(&e, &ids), &BatchMode::Atomic);
+    assert!(!result.success);
+    assert_eq!(result.success_count, 0);
+    assert_eq!(result.errors.get(0).unwrap().index, 1);
+
+    assert_eq!(client.get_commitment(&ids.get(0).unwrap()).current_value, 1000);
+    assert_eq!(client.get_commitment(&ids.get(2).unwrap()).current_value, 1000);
+    assert_eq!(client.get_total_value_locked(), 2000);
+}
+
+#[test]
+fn test_batch_update_value_rejects_duplicate_id() {
+    let e = Env::default();
+    let (client, admin, ids) = setup_value_batch(&e);
+    let commitment_id = ids.get(0).unwrap();
+    let mut updates = Vec::new(&e);
+    for new_value in [500i128, 800] {
+        updates.push_back(UpdateValueParams {
+            commitment_id: commitment_id.clone(),
+            new_value,
+        });
+    }
+
+    let result = client.batch_update_value(&admin, &updates, &BatchMode::Atomic);
+    assert!(!result.success);
+    assert_eq!(result.errors.get(0).unwrap().index, 1);
+    assert_eq!(
+        result.errors.get(0).unwrap().error_code,
+        CommitmentError::DuplicateCommitmentId as u32
+    );
+    assert_eq!(client.get_total_value_locked(), 2000);
+
+    // Best effort applies the first entry only, moving TVL by its own delta
+    let result = client.batch_update_value(&admin, &updates, &BatchMode::BestEffort);
+    assert_eq!(result.success_count, 1);
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 500);
+    assert_eq!(client.get_total_value_locked(), 1500);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_batch_update_value_requires_authorized_updater() {
+    let e = Env::default();
+    let (client, _admin, ids) = setup_value_batch(&e);
+
+    client.batch_update_value(&Address::generate(&e), &value_updates(&e, &ids), &BatchMode::BestEffort);
+}
+
+// ============================================
+// Expiry query tests
+// ============================================
+
+#[test]
+fn test_get_commitments_expiring_before_filters_by_cutoff() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let start = e.ledger().timestamp();
+
+    let mut ids = Vec::new(&e);
+    for duration_days in [10u32, 45, 30, 60, 20] {
+        let mut rules = safe_rules(&e);
+        rules.duration_days = duration_days;
+        ids.push_back(client.create_commitment(&owner, &100, &asset, &rules, &None, &0));
+    }
+
+    // 30 days is inclusive; 45 and 60 fall after the cutoff
+    let cutoff = start + 30 * 86400;
+    let expiring = client.get_commitments_expiring_before(&cutoff, &0, &10);
+    assert_eq!(
+        expiring,
+        vec![&e, ids.get(0).unwrap(), ids.get(2).unwrap(), ids.get(4).unwrap()]
+    );
+
+    // Pages over the matches only
+    assert_eq!(
+        client.get_commitments_expiring_before(&cutoff, &1, &1),
+        vec![&e, ids.get(2).unwrap()]
+    );
+    assert_eq!(client.get_commitments_expiring_before(&cutoff, &3, &10).len(), 0);
+    assert_eq!(client.get_commitments_expiring_before(&cutoff, &0, &0).len(), 0);
+    assert_eq!(client.get_commitments_expiring_before(&start, &0, &10).len(), 0);
+}
+
+// ============================================
+// Settlement fee tests
+// ============================================
+
+#[test]
+fn test_settle_without_settlement_fee_pays_full_value() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    assert_eq!(client.get_settlement_fee_bps(), 0);
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 10_000);
+    assert_eq!(client.get_collected_fees(&asset), 0);
+}
+
+#[test]
+fn test_settle_with_settlement_fee_splits_payout() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let treasury = Address::generate(&e);
+    client.set_fee_recipient(&admin, &treasury);
+    client.set_settlement_fee_bps(&admin, &250); // 2.5%
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    let events = e.events().all();
+    let settled = events.last().unwrap();
+    let data: (i128, i128, u64) = settled.2.into_val(&e);
+    assert_eq!(data.0, 975);
+    assert_eq!(data.1, 25);
+
+    // Owner gets the remainder; the fee is collected for the treasury
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&owner), 9_000 + 975);
+    assert_eq!(client.get_collected_fees(&asset), 25);
+    assert_eq!(client.get_total_value_locked(), 0);
+
+    client.withdraw_fees(&admin, &asset, &25);
+    assert_eq!(token.balance(&treasury), 25);
+}
+
+#[test]
+fn test_settle_and_renew_rolls_into_new_commitment() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    client.set_settlement_fee_bps(&admin, &250); // 2.5%
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+    client.set_auto_renew(&owner, &commitment_id, &true);
+    assert!(client.is_auto_renew(&commitment_id));
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    let now = e.ledger().timestamp();
+    let new_id = client.settle_and_renew(&commitment_id);
+    assert_ne!(new_id, commitment_id);
+
+    let old = client.get_commitment(&commitment_id);
+    assert_eq!(old.status, String::from_str(&e, "settled"));
+    assert!(!client.is_auto_renew(&commitment_id));
+
+    let renewed = client.get_commitment(&new_id);
+    assert_eq!(renewed.owner, owner);
+    assert_eq!(renewed.rules, safe_rules(&e));
+    assert_eq!(renewed.amount, 975);
+    assert_eq!(renewed.status, String::from_str(&e, "active"));
+    assert_eq!(renewed.expires_at, now + 30 * 86400);
+    assert!(client.is_auto_renew(&new_id));
+
+    // Only the fee leaves TVL; the principal never leaves the contract
+    assert_eq!(client.get_total_value_locked(), 975);
+    assert_eq!(client.get_total_value_locked_by_asset(&asset), 975);
+    assert_eq!(client.get_collected_fees(&asset), 25);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 9_000);
+    assert_eq!(client.get_owner_commitments(&owner).len(), 2);
+}
+
+#[test]
+fn test_settle_and_renew_requires_opt_in() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    assert_eq!(
+        client.try_settle_and_renew(&commitment_id),
+        Err(Ok(CommitmentError::AutoRenewDisabled))
+    );
+
+    // Plain settlement is unaffected
+    client.settle(&commitment_id);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 10_000);
+}
+
+#[test]
+#[should_panic(expected = "Invalid fee")]
+fn test_set_settlement_fee_bps_above_max() {
+    let e = Env::default();
+    let (client, admin, _owner, _asset, _nft_contract) = setup_funded_core(&e);
+
+    client.set_settlement_fee_bps(&admin, &10_001);
+}
+
+// ============================================
+// Settlement preview tests
+// ============================================
+
+#[test]
+fn test_preview_settlement_matches_settle() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    client.set_settlement_fee_bps(&admin, &300);
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+    let (gross, fee, net) = client.preview_settlement(&commitment_id);
+    assert_eq!((gross, fee, net), (1000, 30, 970));
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    // Previewing is read-only
+    assert_eq!(client.preview_settlement(&commitment_id), (gross, fee, net));
+    assert_eq!(client.get_commitment(&commitment_id).status, String::from_str(&e, "active"));
+
+    client.settle(&commitment_id);
+
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 9_000 + net);
+    assert_eq!(client.get_collected_fees(&asset), fee);
+}
+
+#[test]
+fn test_preview_early_exit_matches_early_exit() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+    let (penalty, returned) = client.preview_early_exit(&commitment_id);
+    assert_eq!((penalty, returned), (50, 950));
+
+    client.early_exit(&commitment_id, &owner);
+
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 9_000 + returned);
+    assert_eq!(client.get_collected_fees(&asset), penalty);
+}
+
+#[test]
+#[should_panic(expected = "Commitment is not active")]
+fn test_preview_settlement_after_settle() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+
+    let commitment_id = client.create_commitment(&owner, &1000, &asset, &safe_rules(&e), &None, &0);
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    client.preview_settlement(&commitment_id);
+}
+
+// ============================================
+// Commitment amount limit tests
+// ============================================
+
+#[test]
+fn test_create_commitment_within_amount_limits() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    assert_eq!(client.get_commitment_amount_limits(&asset), (None, None));
+
+    client.set_commitment_amount_limits(&admin, &asset, &Some(500), &Some(2_000));
+    assert_eq!(client.get_commitment_amount_limits(&asset), (Some(500), Some(2_000)));
+
+    client.create_commitment(&owner, &500, &asset, &safe_rules(&e), &None, &0);
+    client.create_commitment(&owner, &2_000, &asset, &safe_rules(&e), &None, &0);
+    assert_eq!(client.get_total_value_locked(), 2_500);
+}
+
+#[test]
+fn test_create_commitment_below_minimum() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    client.set_commitment_amount_limits(&admin, &asset, &Some(500), &None);
+
+    assert_eq!(
+        client.try_create_commitment(&owner, &499, &asset, &safe_rules(&e), &None, &0),
+        Err(Ok(CommitmentError::AmountBelowMinimum))
+    );
+}
+
+#[test]
+fn test_create_commitment_above_maximum() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    client.set_commitment_amount_limits(&admin, &asset, &None, &Some(2_000));
+
+    assert_eq!(
+        client.try_create_commitment(&owner, &2_001, &asset, &safe_rules(&e), &None, &0),
+        Err(Ok(CommitmentError::AmountAboveMaximum))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid amount")]
+fn test_set_commitment_amount_limits_min_above_max() {
+    let e = Env::default();
+    let (client, admin, _owner, asset, _nft_contract) = setup_funded_core(&e);
+
+    client.set_commitment_amount_limits(&admin, &asset, &Some(3_000), &Some(2_000));
+}
+
+// ============================================
+// Allocation authorization tests
+// ============================================
+
+#[test]
+fn test_allocate_rejects_unauthorized_caller_on_funded_commitment() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let attacker = Address::generate(&e);
+    let target_pool = Address::generate(&e);
+
+    assert!(client
+        .try_allocate(&attacker, &commitment_id, &target_pool, &1_000)
+        .is_err());
+
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 1_000);
+    assert_eq!(token::Client::new(&e, &asset).balance(&target_pool), 0);
+    assert_eq!(client.get_allocation_tracking(&commitment_id).total_allocated, 0);
+    assert_eq!(client.get_total_value_locked(), 1_000);
+}
+
+#[test]
+fn test_allocate_by_authorized_allocator_updates_tracking() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let allocator = Address::generate(&e);
+    let target_pool = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
+
+    client.allocate(&allocator, &commitment_id, &target_pool, &400);
+
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 600);
+    assert_eq!(token::Client::new(&e, &asset).balance(&target_pool), 400);
+    let tracking = client.get_allocation_tracking(&commitment_id);
+    assert_eq!(tracking.total_allocated, 400);
+    assert_eq!(tracking.allocations.len(), 1);
+    assert_eq!(client.get_total_value_locked(), 600);
+}
+
+#[test]
+fn test_allocate_up_to_cap() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let allocator = Address::generate(&e);
+    let target_pool = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
+    client.set_max_allocation_bps(&admin, &8_000);
+    assert_eq!(client.get_max_allocation_bps(), 8_000);
+
+    // Cumulative 800 of 1_000 is exactly the 80% cap
+    client.allocate(&allocator, &commitment_id, &target_pool, &500);
+    client.allocate(&allocator, &commitment_id, &target_pool, &300);
+
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 200);
+    assert_eq!(client.get_allocation_tracking(&commitment_id).total_allocated, 800);
+}
+
+#[test]
+fn test_allocate_past_cap_fails() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let allocator = Address::generate(&e);
+    let target_pool = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
+    client.set_max_allocation_bps(&admin, &8_000);
+
+    client.allocate(&allocator, &commitment_id, &target_pool, &500);
+    assert_eq!(
+        client.try_allocate(&allocator, &commitment_id, &target_pool, &301),
+        Err(Ok(CommitmentError::AllocationCapExceeded))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Invalid allocation cap: basis points must be 1-10000")]
+fn test_set_max_allocation_bps_rejects_zero() {
+    let e = Env::default();
+    let (client, admin, _owner, _asset, _nft_contract) = setup_funded_core(&e);
+
+    client.set_max_allocation_bps(&admin, &0);
+}
+
+#[test]
+fn test_settle_with_outstanding_allocations_fails() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let allocator = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
+    client.allocate(&allocator, &commitment_id, &Address::generate(&e), &300);
+
+    e.ledger().with_mut(|l| l.timestamp = 31 * 86400);
+    assert_eq!(client.try_settle(&commitment_id), Err(Ok(CommitmentError::OutstandingAllocations)));
+}
+
+#[test]
+fn test_early_exit_with_outstanding_allocations_fails() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let allocator = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
+    client.allocate(&allocator, &commitment_id, &Address::generate(&e), &300);
+
+    assert_eq!(
+        client.try_early_exit(&commitment_id, &owner),
+        Err(Ok(CommitmentError::OutstandingAllocations))
+    );
+
+    // Once recalled, the owner can leave
+    client.deallocate_all(&allocator, &commitment_id);
+    client.early_exit(&commitment_id, &owner);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 9_000 + 950);
+}
+
+#[test]
+fn test_deallocate_all_then_settle() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let token_client = token::Client::new(&e, &asset);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let allocator = Address::generate(&e);
+    let pool_a = Address::generate(&e);
+    let pool_b = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
+
+    client.allocate(&allocator, &commitment_id, &pool_a, &300);
+    client.allocate(&allocator, &commitment_id, &pool_b, &200);
+    client.deallocate(&allocator, &commitment_id, &pool_a, &100);
+    let tracking = client.get_allocation_tracking(&commitment_id);
+    assert_eq!(tracking.total_allocated, 400);
+    // The partial recall is netted against pool_a's entry
+    assert_eq!(tracking.allocations.get(0).unwrap().amount, 200);
+
+    assert_eq!(client.deallocate_all(&allocator, &commitment_id), 400);
+
+    assert_eq!(token_client.balance(&pool_a), 0);
+    assert_eq!(token_client.balance(&pool_b), 0);
+    let tracking = client.get_allocation_tracking(&commitment_id);
+    assert_eq!(tracking.total_allocated, 0);
+    assert_eq!(tracking.allocations.len(), 0);
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 1_000);
+    assert_eq!(client.get_total_value_locked(), 1_000);
+    assert_eq!(client.get_total_value_locked_by_asset(&asset), 1_000);
+
+    e.ledger().with_mut(|l| l.timestamp = 31 * 86400);
+    client.settle(&commitment_id);
+
+    assert_eq!(client.get_commitment(&commitment_id).status, String::from_str(&e, "settled"));
+    assert_eq!(token_client.balance(&owner), 10_000);
+}
+
+#[test]
+fn test_deallocate_all_without_allocations_is_noop() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let allocator = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
+
+    assert_eq!(client.deallocate_all(&allocator, &commitment_id), 0);
+    assert_eq!(client.get_commitment(&commitment_id).current_value, 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: caller not allowed")]
+fn test_deallocate_all_unauthorized_caller() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    client.deallocate_all(&Address::generate(&e), &commitment_id);
+}
+
+// ============================================
+// Configurable commitment types
+// ============================================
+
+#[test]
+fn test_default_commitment_types() {
+    let e = Env::default();
+    let (client, _admin, _owner, _asset, _nft_contract) = setup_funded_core(&e);
+
+    let types = client.get_commitment_types();
+    assert_eq!(types.len(), 3);
+    assert!(types.contains(String::from_str(&e, "safe")));
+    assert!(types.contains(String::from_str(&e, "balanced")));
+    assert!(types.contains(String::from_str(&e, "aggressive")));
+}
+
+#[test]
+fn test_create_commitment_with_added_type() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let structured = String::from_str(&e, "structured");
+    client.add_commitment_type(&admin, &structured);
+    // Adding twice is a no-op
+    client.add_commitment_type(&admin, &structured);
+    assert_eq!(client.get_commitment_types().len(), 4);
+
+    let mut rules = safe_rules(&e);
+    rules.commitment_type = structured.clone();
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &rules, &None, &0);
+
+    assert_eq!(
+        client.get_commitment(&commitment_id).rules.commitment_type,
+        structured
+    );
+}
+
+#[test]
+fn test_create_commitment_with_removed_type_fails() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    client.remove_commitment_type(&admin, &String::from_str(&e, "safe"));
+    assert_eq!(client.get_commitment_types().len(), 2);
+
+    assert_eq!(
+        client.try_create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0),
+        Err(Ok(CommitmentError::InvalidCommitmentType))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: caller not allowed")]
+fn test_add_commitment_type_non_admin_fails() {
+    let e = Env::default();
+    let (client, _admin, owner, _asset, _nft_contract) = setup_funded_core(&e);
+
+    client.add_commitment_type(&owner, &String::from_str(&e, "structured"));
+}
+
+// ============================================
+// Settlement transfer failures
+// ============================================
+
+mod rejecting_token {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    /// Token stand-in whose transfers always fail
+    #[contract]
+    pub struct RejectingToken;
+
+    #[contractimpl]
+    impl RejectingToken {
+        pub fn transfer(_e: Env, _from: Address, _to: Address, _amount: i128) {
+            panic!("transfer rejected");
+        }
+    }
+}
+
+#[test]
+fn test_settle_reports_rejected_payout_transfer() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let contract_id = e.register_contract(None, CommitmentCoreContract);
+    let client = CommitmentCoreContractClient::new(&e, &contract_id);
+    let owner = Address::generate(&e);
+    let asset = e.register_contract(None, rejecting_token::RejectingToken);
+    let commitment_id = String::from_str(&e, "c_0");
+    client.initialize(&Address::generate(&e), &e.register_contract(None, MockNftContract), &None);
+
+    e.as_contract(&contract_id, || {
+        set_commitment(
+            &e,
+            &Commitment {
+                commitment_id: commitment_id.clone(),
+                owner: owner.clone(),
+                nft_token_id: 0,
+                rules: safe_rules(&e),
+                amount: 1_000,
+                asset_address: asset.clone(),
+                created_at: 0,
+                expires_at: 30 * 86400,
+                current_value: 1_000,
+                status: String::from_str(&e, "active"),
+            },
+        );
+    });
+
+    e.ledger().with_mut(|l| l.timestamp = 31 * 86400);
+    assert_eq!(client.try_settle(&commitment_id), Err(Ok(CommitmentError::TransferFailed)));
+}
+
+// ============================================
+// Fee-bearing deposit assets
+// ============================================
+
+mod fee_token {
+    use soroban_sdk::{contract, contractimpl, Address, Env};
+
+    /// Token stand-in that burns 1% of every transfer
+    #[contract]
+    pub struct FeeOnTransferToken;
+
+    #[contractimpl]
+    impl FeeOnTransferToken {
+        pub fn mint(e: Env, to: Address, amount: i128) {
+            let balance = Self::balance(e.clone(), to.clone());
+            e.storage().persistent().set(&to, &(balance + amount));
+        }
+
+        pub fn balance(e: Env, id: Address) -> i128 {
+            e.storage().persistent().get(&id).unwrap_or(0)
+        }
+
+        pub fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+            let from_balance = Self::balance(e.clone(), from.clone());
+            e.storage().persistent().set(&from, &(from_balance - amount));
+            let to_balance = Self::balance(e.clone(), to.clone());
+            e.storage().persistent().set(&to, &(to_balance + amount - amount / 100));
+        }
+    }
+}
+
+#[test]
+fn test_create_commitment_records_net_received_for_fee_token() {
+    let e = Env::default();
+    let (client, _admin, owner, _asset, _nft_contract) = setup_funded_core(&e);
+    let asset = e.register_contract(None, fee_token::FeeOnTransferToken);
+    let token_client = fee_token::FeeOnTransferTokenClient::new(&e, &asset);
+    token_client.mint(&owner, &10_000);
+
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    // 1% of the 1_000 sent never arrives
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.amount, 990);
+    assert_eq!(commitment.current_value, 990);
+    assert_eq!(client.get_total_value_locked(), 990);
+    assert_eq!(client.get_total_value_locked_by_asset(&asset), 990);
+    assert_eq!(token_client.balance(&client.address), 990);
+    assert_eq!(token_client.balance(&owner), 9_000);
+}
+
+#[test]
+fn test_amount_limits_apply_to_net_received() {
+    let e = Env::default();
+    let (client, admin, owner, _asset, _nft_contract) = setup_funded_core(&e);
+    let asset = e.register_contract(None, fee_token::FeeOnTransferToken);
+    fee_token::FeeOnTransferTokenClient::new(&e, &asset).mint(&owner, &10_000);
+    client.set_commitment_amount_limits(&admin, &asset, &Some(1_000), &None);
+
+    // 1_000 requested, but only 990 would be locked
+    assert_eq!(
+        client.try_create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0),
+        Err(Ok(CommitmentError::AmountBelowMinimum))
+    );
+
+    let commitment_id = client.create_commitment(&owner, &1_011, &asset, &safe_rules(&e), &None, &0);
+    assert_eq!(client.get_commitment(&commitment_id).amount, 1_001);
+}
+
+// ============================================
+// Insurance fund fee split
+// ============================================
+
+#[test]
+fn test_early_exit_penalty_split_with_insurance_fund() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let insurance_fund = Address::generate(&e);
+    client.set_insurance_fund(&admin, &insurance_fund, &2_000);
+    assert_eq!(client.get_insurance_fund(), Some(insurance_fund.clone()));
+    assert_eq!(client.get_insurance_bps(), 2_000);
+
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    client.early_exit(&commitment_id, &owner);
+
+    // 5% penalty of 50: 20% (10) to insurance, the rest to collected fees
+    let token_client = token::Client::new(&e, &asset);
+    assert_eq!(token_client.balance(&insurance_fund), 10);
+    assert_eq!(client.get_collected_fees(&asset), 40);
+    assert_eq!(token_client.balance(&owner), 9_950);
+}
+
+#[test]
+fn test_early_exit_penalty_without_insurance_fund_goes_to_treasury() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    client.early_exit(&commitment_id, &owner);
+
+    assert!(client.get_insurance_fund().is_none());
+    assert_eq!(client.get_collected_fees(&asset), 50);
+}
+
+#[test]
+#[should_panic(expected = "Invalid fee: basis points must be 0-10000")]
+fn test_set_insurance_fund_invalid_bps() {
+    let e = Env::default();
+    let (client, admin, _owner, _asset, _nft_contract) = setup_funded_core(&e);
+
+    client.set_insurance_fund(&admin, &Address::generate(&e), &10_001);
+}
+
+// ============================================
+// NFT ownership sync
+// ============================================
+
+#[test]
+fn test_sync_owner_from_nft_then_new_owner_early_exits() {
+    let e = Env::default();
+    let (client, _admin, seller, asset, nft_contract) = setup_funded_core(&e);
+    let nft_client = MockNftContractClient::new(&e, &nft_contract);
+    let buyer = Address::generate(&e);
+    let commitment_id = client.create_commitment(&seller, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let token_id = client.get_commitment(&commitment_id).nft_token_id;
+
+    // NFT sold on the marketplace; core still has the seller until synced
+    nft_client.transfer(&seller, &buyer, &token_id);
+    assert_eq!(client.get_commitment(&commitment_id).owner, seller);
+
+    assert_eq!(client.sync_owner_from_nft(&commitment_id), buyer);
+    assert_eq!(client.get_commitment(&commitment_id).owner, buyer);
+    assert_eq!(client.get_owner_commitments(&seller).len(), 0);
+    assert_eq!(client.get_owner_commitments(&buyer), vec![&e, commitment_id.clone()]);
+
+    client.early_exit(&commitment_id, &buyer);
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        String::from_str(&e, "early_exit")
+    );
+    // 5% penalty on 1_000
+    assert_eq!(token::Client::new(&e, &asset).balance(&buyer), 950);
+}
+
+#[test]
+fn test_sync_owner_moves_co_owner_share_to_buyer() {
+    let e = Env::default();
+    let (client, _admin, seller, asset, nft_contract) = setup_funded_core(&e);
+    let partner = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let commitment_id = client.create_co_owned_commitment(
+        &seller,
+        &1_000,
+        &asset,
+        &safe_rules(&e),
+        &sixty_forty(&e, &seller, &partner),
+    );
+    let token_id = client.get_commitment(&commitment_id).nft_token_id;
+
+    MockNftContractClient::new(&e, &nft_contract).transfer(&seller, &buyer, &token_id);
+    client.sync_owner_from_nft(&commitment_id);
+    assert_eq!(
+        client.get_co_owners(&commitment_id),
+        vec![
+            &e,
+            CoOwner { owner: partner.clone(), share_bps: 4_000 },
+            CoOwner { owner: buyer.clone(), share_bps: 6_000 },
+        ]
+    );
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&buyer), 600);
+    assert_eq!(token.balance(&partner), 400);
+    assert_eq!(token.balance(&seller), 9_000);
+}
+
+#[test]
+fn test_sync_owner_from_nft_unchanged_owner_is_noop() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    let events_before = e.events().all().len();
+    assert_eq!(client.sync_owner_from_nft(&commitment_id), owner);
+    assert_eq!(e.events().all().len(), events_before);
+    assert_eq!(client.get_owner_commitments(&owner).len(), 1);
+}
+
+#[test]
+fn test_sync_owner_from_nft_requires_active_commitment() {
+    let e = Env::default();
+    let (client, _admin, seller, asset, nft_contract) = setup_funded_core(&e);
+    let buyer = Address::generate(&e);
+    let commitment_id = client.create_commitment(&seller, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let token_id = client.get_commitment(&commitment_id).nft_token_id;
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+    MockNftContractClient::new(&e, &nft_contract).transfer(&seller, &buyer, &token_id);
+
+    assert!(client.try_sync_owner_from_nft(&commitment_id).is_err());
+    assert_eq!(client.get_commitment(&commitment_id).owner, seller);
+}
+
+#[test]
+fn test_sync_owner_blocked_after_reassign_without_nft() {
+    let e = Env::default();
+    let (client, admin, lost_owner, asset, _nft_contract) = setup_funded_core(&e);
+    let recovered = Address::generate(&e);
+    let commitment_id =
+        client.create_commitment(&lost_owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    client.set_emergency_mode(&admin, &true);
+    client.emergency_reassign_owner(&admin, &commitment_id, &recovered, &false);
+    client.set_emergency_mode(&admin, &false);
+
+    // The NFT still sits with the lost key; syncing must not hand it back
+    assert!(client.try_sync_owner_from_nft(&commitment_id).is_err());
+    assert_eq!(client.get_commitment(&commitment_id).owner, recovered);
+}
+
+// ============================================
+// Per-commitment allocation freeze
+// ============================================
+
+#[test]
+fn test_frozen_commitment_blocks_allocate() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let allocator = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
+
+    client.set_commitment_frozen(&owner, &commitment_id, &true);
+    assert!(client.is_commitment_frozen(&commitment_id));
+
+    assert_eq!(
+        client.try_allocate(&allocator, &commitment_id, &Address::generate(&e), &100),
+        Err(Ok(CommitmentError::CommitmentFrozen))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Commitment is frozen for allocations")]
+fn test_frozen_commitment_blocks_deallocate() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let allocator = Address::generate(&e);
+    let pool = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
+    client.allocate(&allocator, &commitment_id, &pool, &100);
+
+    client.set_commitment_frozen(&admin, &commitment_id, &true);
+    client.deallocate(&allocator, &commitment_id, &pool, &100);
+}
+
+#[test]
+fn test_frozen_commitment_allows_early_exit() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    client.set_commitment_frozen(&owner, &commitment_id, &true);
+
+    client.early_exit(&commitment_id, &owner);
+
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        String::from_str(&e, "early_exit")
+    );
+}
+
+#[test]
+fn test_unfreeze_restores_allocation() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let allocator = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
+
+    client.set_commitment_frozen(&owner, &commitment_id, &true);
+    client.set_commitment_frozen(&owner, &commitment_id, &false);
+    client.allocate(&allocator, &commitment_id, &Address::generate(&e), &100);
+
+    assert_eq!(client.get_allocation_tracking(&commitment_id).total_allocated, 100);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized: caller not allowed")]
+fn test_set_commitment_frozen_by_stranger_fails() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    client.set_commitment_frozen(&Address::generate(&e), &commitment_id, &true);
+}
+
+// ============================================
+// TVL invariant
+// ============================================
+
+#[test]
+fn test_verify_tvl_invariant_holds_after_allocation() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    client.create_commitment(&owner, &2_000, &asset, &safe_rules(&e), &None, &0);
+    let allocator = Address::generate(&e);
+    client.add_authorized_allocator(&allocator);
+
+    client.allocate(&allocator, &commitment_id, &Address::generate(&e), &400);
+
+    assert!(client.verify_tvl_invariant());
+}
+
+#[test]
+fn test_verify_tvl_invariant_detects_corrupted_asset_tvl() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    assert!(client.verify_tvl_invariant());
+
+    // The emergency update rewrites total TVL but leaves the per-asset counter at 1_000
+    client.set_emergency_mode(&admin, &true);
+    let commitment = client.get_commitment(&commitment_id);
+    client.emergency_update_commitment(
+        &admin,
+        &commitment_id,
+        &700,
+        &commitment.status,
+        &commitment.expires_at,
+    );
+    assert_eq!(client.get_total_value_locked(), 700);
+    assert_eq!(client.get_total_value_locked_by_asset(&asset), 1_000);
+
+    let events_before = e.events().all().len();
+    assert!(!client.verify_tvl_invariant());
+    assert_eq!(e.events().all().len(), events_before + 1);
+}
+
+// ============================================
+// get_commitment semantics
+// ============================================
+
+#[test]
+fn test_get_commitment_returns_stored_commitment() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.commitment_id, commitment_id);
+    assert_eq!(commitment.owner, owner);
+    assert_eq!(commitment.amount, 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Commitment not found")]
+fn test_get_commitment_unknown_id_panics() {
+    let e = Env::default();
+    let (client, _admin, _owner, _asset, _nft_contract) = setup_funded_core(&e);
+
+    client.get_commitment(&String::from_str(&e, "missing"));
+}
+
+#[test]
+fn test_try_get_commitment_unknown_id_is_err() {
+    let e = Env::default();
+    let (client, _admin, _owner, _asset, _nft_contract) = setup_funded_core(&e);
+
+    assert!(client
+        .try_get_commitment(&String::from_str(&e, "missing"))
+        .is_err());
+}
+
+// ============================================
+// sweep_dust
+// ============================================
+
+#[test]
+fn test_sweep_dust_takes_only_unaccounted_balance() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    client.set_creation_fee_bps(&admin, &100);
+    client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    // Stray transfer straight to the contract
+    token::StellarAssetClient::new(&e, &asset).mint(&client.address, &7);
+
+    let treasury = Address::generate(&e);
+    assert_eq!(client.sweep_dust(&admin, &asset, &treasury), 7);
+    assert_eq!(token::Client::new(&e, &asset).balance(&treasury), 7);
+    assert_eq!(token::Client::new(&e, &asset).balance(&client.address), 1_000);
+    assert_eq!(client.get_collected_fees(&asset), 10);
+    assert_eq!(client.get_total_value_locked_by_asset(&asset), 990);
+}
+
+#[test]
+fn test_sweep_dust_after_value_markdown_keeps_principal() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    client.update_value(&admin, &commitment_id, &500);
+    assert_eq!(client.get_total_value_locked_by_asset(&asset), 500);
+
+    // The markdown does not make the owner's deposit sweepable
+    assert!(client
+        .try_sweep_dust(&admin, &asset, &Address::generate(&e))
+        .is_err());
+
+    token::StellarAssetClient::new(&e, &asset).mint(&client.address, &7);
+    let treasury = Address::generate(&e);
+    assert_eq!(client.sweep_dust(&admin, &asset, &treasury), 7);
+    assert_eq!(token::Client::new(&e, &asset).balance(&client.address), 1_000);
+
+    // Once the commitment closes, nothing of it is left to protect
+    client.update_value(&admin, &commitment_id, &1_000);
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 10_000);
+    assert!(client
+        .try_sweep_dust(&admin, &asset, &treasury)
+        .is_err());
+}
+
+#[test]
+fn test_sweep_dust_without_surplus_fails() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    let result = client.try_sweep_dust(&admin, &asset, &Address::generate(&e));
+    assert!(result.is_err());
+}
+
+// ============================================
+// get_commitment_full
+// ============================================
+
+#[contract]
+struct MockAttestationContract;
+
+#[contractimpl]
+impl MockAttestationContract {
+    pub fn set_metrics(e: Env, metrics: HealthMetrics) {
+        e.storage().instance().set(&metrics.commitment_id.clone(), &metrics);
+    }
+
+    pub fn get_stored_health_metrics(e: Env, commitment_id: String) -> Option<HealthMetrics> {
+        e.storage().instance().get(&commitment_id)
+    }
+}
+
+#[contract]
+struct MockTransformationContract;
+
+#[contractimpl]
+impl MockTransformationContract {
+    pub fn get_commitment_tranche_sets(e: Env, _commitment_id: String) -> Vec<String> {
+        vec![&e, String::from_str(&e, "t1"), String::from_str(&e, "t2")]
+    }
+
+    pub fn get_commitment_collateral(e: Env, _commitment_id: String) -> Vec<String> {
+        vec![&e, String::from_str(&e, "c1")]
+    }
+
+    pub fn get_commitment_instruments(e: Env, _commitment_id: String) -> Vec<String> {
+        Vec::new(&e)
+    }
+
+    pub fn get_commitment_guarantees(e: Env, _commitment_id: String) -> Vec<String> {
+        vec![&e, String::from_str(&e, "g1")]
+    }
+}
+
+#[test]
+fn test_get_commitment_full_aggregates_satellites() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    let attestation_id = e.register_contract(None, MockAttestationContract);
+    let metrics = HealthMetrics {
+        commitment_id: commitment_id.clone(),
+        current_value: 1_000,
+        initial_value: 1_000,
+        drawdown_percent: 0,
+        fees_generated: 25,
+        volatility_exposure: 0,
+        last_attestation: 42,
+        compliance_score: 90,
+    };
+    MockAttestationContractClient::new(&e, &attestation_id).set_metrics(&metrics);
+    let transformation_id = e.register_contract(None, MockTransformationContract);
+
+    let (commitment, health, transformations) =
+        client.get_commitment_full(&commitment_id, &attestation_id, &transformation_id);
+    assert_eq!(commitment, client.get_commitment(&commitment_id));
+    assert_eq!(health, Some(metrics));
+    assert_eq!(
+        transformations,
+        Some(TransformationCounts {
+            tranche_sets: 2,
+            collateral: 1,
+            instruments: 0,
+            guarantees: 1,
+        })
+    );
+}
+
+#[test]
+fn test_get_commitment_full_degrades_without_satellites() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    // Neither address hosts the expected contract
+    let (commitment, health, transformations) =
+        client.get_commitment_full(&commitment_id, &asset, &Address::generate(&e));
+    assert_eq!(commitment.commitment_id, commitment_id);
+    assert_eq!(health, None);
+    assert_eq!(transformations, None);
+}
+
+// ============================================
+// Commitment templates
+// ============================================
+
+#[test]
+fn test_create_commitment_from_template() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let name = String::from_str(&e, "starter");
+    client.register_template(&admin, &name, &safe_rules(&e));
+
+    assert_eq!(client.get_template(&name), Some(safe_rules(&e)));
+    assert_eq!(client.list_templates(), vec![&e, name.clone()]);
+
+    let commitment_id = client.create_commitment_from_template(&owner, &1_000, &asset, &name);
+    let commitment = client.get_commitment(&commitment_id);
+    assert_eq!(commitment.rules, safe_rules(&e));
+    assert_eq!(commitment.amount, 1_000);
+}
+
+#[test]
+fn test_create_commitment_from_unknown_template_fails() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+
+    assert_eq!(
+        client.try_create_commitment_from_template(
+            &owner,
+            &1_000,
+            &asset,
+            &String::from_str(&e, "missing")
+        ),
+        Err(Ok(CommitmentError::TemplateNotFound))
+    );
+    assert_eq!(client.get_total_commitments(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Invalid commitment type")]
+fn test_register_template_validates_rules() {
+    let e = Env::default();
+    let (client, admin, _owner, _asset, _nft_contract) = setup_funded_core(&e);
+    let mut rules = safe_rules(&e);
+    rules.commitment_type = String::from_str(&e, "unknown");
+
+    client.register_template(&admin, &String::from_str(&e, "bad"), &rules);
+}
+
+// ============================================
+// Underperformance slashing tests
+// ============================================
+
+/// Funded core with an attestation engine mock reporting `fees` for the commitment,
+/// a 20% underperformance slash and an insurance fund. Returns (client, admin, owner,
+/// asset, insurance_fund, commitment_id) with the commitment already matured.
+fn setup_slashable_commitment(
+    e: &Env,
+    fees: i128,
+) -> (CommitmentCoreContractClient<'_>, Address, Address, Address, Address, String) {
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(e);
+    let insurance_fund = Address::generate(e);
+    client.set_insurance_fund(&admin, &insurance_fund, &0);
+    client.set_underperformance_slash_bps(&admin, &2000);
+
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(e), &None, &0);
+    let attestation_id = e.register_contract(None, MockAttestationContract);
+    MockAttestationContractClient::new(e, &attestation_id).set_metrics(&HealthMetrics {
+        commitment_id: commitment_id.clone(),
+        current_value: 1_000,
+        initial_value: 1_000,
+        drawdown_percent: 0,
+        fees_generated: fees,
+        volatility_exposure: 0,
+        last_attestation: 0,
+        compliance_score: 100,
+    });
+    client.set_attestation_engine(&admin, &attestation_id);
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    (client, admin, owner, asset, insurance_fund, commitment_id)
+}
+
+#[test]
+fn test_slash_for_underperformance_diverts_share_to_insurance() {
+    let e = Env::default();
+    let (client, admin, owner, asset, insurance_fund, commitment_id) =
+        setup_slashable_commitment(&e, 40);
+
+    assert_eq!(client.slash_for_underperformance(&admin, &commitment_id), 200);
+
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&insurance_fund), 200);
+    assert_eq!(token.balance(&owner), 9_000 + 800);
+    // The settlement log records what the owner received
+    assert_eq!(
+        client.get_settlements_paged(&0, &10),
+        vec![&e, (commitment_id.clone(), 800i128, e.ledger().timestamp())]
+    );
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        String::from_str(&e, "settled")
+    );
+    assert_eq!(client.get_total_value_locked(), 0);
+}
+
+#[test]
+fn test_slash_for_underperformance_rejected_when_threshold_met() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _insurance_fund, commitment_id) =
+        setup_slashable_commitment(&e, 100);
+
+    assert_eq!(
+        client.try_slash_for_underperformance(&admin, &commitment_id),
+        Err(Ok(CommitmentError::FeeThresholdMet))
+    );
+
+    // Ordinary settlement still pays out in full
+    client.settle(&commitment_id);
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 10_000);
+}
+
+// ============================================
+// Asset consistency on payout
+// ============================================
+
+#[test]
+fn test_settle_and_early_exit_abort_on_asset_mismatch() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    // Corrupt the stored asset so payouts would move a different token
+    let other_asset = e.register_stellar_asset_contract_v2(Address::generate(&e)).address();
+    let mut corrupted = client.get_commitment(&commitment_id);
+    corrupted.asset_address = other_asset;
+    e.as_contract(&client.address, || set_commitment(&e, &corrupted));
+
+    assert_eq!(
+        client.try_early_exit(&commitment_id, &owner),
+        Err(Ok(CommitmentError::AssetMismatch))
+    );
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    assert_eq!(
+        client.try_settle(&commitment_id),
+        Err(Ok(CommitmentError::AssetMismatch))
+    );
+
+    // Nothing moved and the commitment is still open
+    assert_eq!(token::Client::new(&e, &asset).balance(&owner), 9_000);
+    assert_eq!(
+        client.get_commitment(&commitment_id).status,
+        String::from_str(&e, "active")
+    );
+    assert_eq!(client.get_total_value_locked(), 1_000);
+}
+
+// ============================================
+// Commitment history
+// ============================================
+
+fn status_change(e: &Env, timestamp: u64, from: &str, to: &str) -> (u64, String, String) {
+    (timestamp, String::from_str(e, from), String::from_str(e, to))
+}
+
+#[test]
+fn test_commitment_history_records_status_transitions() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let created_at = e.ledger().timestamp();
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    // Recovery flags the commitment as violated, then restores it
+    e.ledger().with_mut(|l| l.timestamp += 100);
+    client.set_emergency_mode(&admin, &true);
+    let commitment = client.get_commitment(&commitment_id);
+    client.emergency_update_commitment(
+        &admin,
+        &commitment_id,
+        &1_000,
+        &String::from_str(&e, "violated"),
+        &commitment.expires_at,
+    );
+    client.emergency_update_commitment(
+        &admin,
+        &commitment_id,
+        &1_000,
+        &String::from_str(&e, "active"),
+        &commitment.expires_at,
+    );
+    // Value-only updates leave the history alone
+    client.emergency_update_commitment(
+        &admin,
+        &commitment_id,
+        &1_000,
+        &String::from_str(&e, "active"),
+        &commitment.expires_at,
+    );
+    client.set_emergency_mode(&admin, &false);
+
+    e.ledger().with_mut(|l| l.timestamp = commitment.expires_at);
+    client.settle(&commitment_id);
+
+    assert_eq!(
+        client.get_commitment_history(&commitment_id),
+        vec![
+            &e,
+            status_change(&e, created_at, "", "active"),
+            status_change(&e, created_at + 100, "active", "violated"),
+            status_change(&e, created_at + 100, "violated", "active"),
+            status_change(&e, commitment.expires_at, "active", "settled"),
+        ]
+    );
+}
+
+#[test]
+fn test_commitment_history_is_bounded() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let commitment = client.get_commitment(&commitment_id);
+
+    client.set_emergency_mode(&admin, &true);
+    for i in 0..MAX_HISTORY_ENTRIES {
+        let status = if i % 2 == 0 { "violated" } else { "active" };
+        client.emergency_update_commitment(
+            &admin,
+            &commitment_id,
+            &1_000,
+            &String::from_str(&e, status),
+            &commitment.expires_at,
+        );
+    }
+
+    // The creation entry was the first to be dropped
+    let history = client.get_commitment_history(&commitment_id);
+    assert_eq!(history.len(), MAX_HISTORY_ENTRIES);
+    let (_, first_from, first_to) = history.get(0).unwrap();
+    assert_eq!(first_from, String::from_str(&e, "active"));
+    assert_eq!(first_to, String::from_str(&e, "violated"));
+    assert!(client.get_commitment_history(&String::from_str(&e, "missing")).is_empty());
+}
+
+// ============================================
+// Co-owned commitments
+// ============================================
+
+fn sixty_forty(e: &Env, owner: &Address, partner: &Address) -> Vec<CoOwner> {
+    vec![
+        e,
+        CoOwner { owner: owner.clone(), share_bps: 6_000 },
+        CoOwner { owner: partner.clone(), share_bps: 4_000 },
+    ]
+}
+
+#[test]
+fn test_settle_splits_proceeds_between_co_owners() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let partner = Address::generate(&e);
+    let co_owners = sixty_forty(&e, &owner, &partner);
+
+    let commitment_id =
+        client.create_co_owned_commitment(&owner, &1_000, &asset, &safe_rules(&e), &co_owners);
+    assert_eq!(client.get_co_owners(&commitment_id), co_owners);
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&owner), 9_000 + 600);
+    assert_eq!(token.balance(&partner), 400);
+}
+
+#[test]
+fn test_early_exit_splits_proceeds_between_co_owners() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let partner = Address::generate(&e);
+    let commitment_id = client.create_co_owned_commitment(
+        &owner,
+        &1_000,
+        &asset,
+        &safe_rules(&e),
+        &sixty_forty(&e, &owner, &partner),
+    );
+
+    // 5% penalty leaves 950 to split
+    client.early_exit(&commitment_id, &owner);
+
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&owner), 9_000 + 570);
+    assert_eq!(token.balance(&partner), 380);
+}
+
+#[test]
+fn test_create_co_owned_commitment_validates_shares() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let partner = Address::generate(&e);
+
+    let short = vec![
+        &e,
+        CoOwner { owner: owner.clone(), share_bps: 6_000 },
+        CoOwner { owner: partner.clone(), share_bps: 3_000 },
+    ];
+    assert_eq!(
+        client.try_create_co_owned_commitment(&owner, &1_000, &asset, &safe_rules(&e), &short),
+        Err(Ok(CommitmentError::InvalidShares))
+    );
+    let zero_share = vec![
+        &e,
+        CoOwner { owner: owner.clone(), share_bps: 10_000 },
+        CoOwner { owner: partner.clone(), share_bps: 0 },
+    ];
+    assert_eq!(
+        client.try_create_co_owned_commitment(&owner, &1_000, &asset, &safe_rules(&e), &zero_share),
+        Err(Ok(CommitmentError::InvalidShares))
+    );
+
+    // A single full share for the funder is the ordinary single-owner path
+    let solo = vec![&e, CoOwner { owner: owner.clone(), share_bps: 10_000 }];
+    let commitment_id =
+        client.create_co_owned_commitment(&owner, &1_000, &asset, &safe_rules(&e), &solo);
+    assert!(client.get_co_owners(&commitment_id).is_empty());
+}
+
+// ============================================
+// Settlement recipient
+// ============================================
+
+#[test]
+fn test_settle_pays_settlement_recipient() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let custody = Address::generate(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    assert_eq!(client.get_settlement_recipient(&commitment_id), owner);
+
+    client.set_settlement_recipient(&owner, &commitment_id, &Some(custody.clone()));
+    assert_eq!(client.get_settlement_recipient(&commitment_id), custody);
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    // Only the payout moves; the commitment still belongs to the owner
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&custody), 1_000);
+    assert_eq!(token.balance(&owner), 9_000);
+    assert_eq!(client.get_commitment(&commitment_id).owner, owner);
+}
+
+#[test]
+fn test_settlement_recipient_owner_only_and_clearable() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let custody = Address::generate(&e);
+    let stranger = Address::generate(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    assert!(client
+        .try_set_settlement_recipient(&stranger, &commitment_id, &Some(stranger.clone()))
+        .is_err());
+
+    client.set_settlement_recipient(&owner, &commitment_id, &Some(custody.clone()));
+    client.set_settlement_recipient(&owner, &commitment_id, &None);
+    assert_eq!(client.get_settlement_recipient(&commitment_id), owner);
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&owner), 10_000);
+    assert_eq!(token.balance(&custody), 0);
+}
+
+#[test]
+fn test_settlement_recipient_receives_only_owner_share() {
+    let e = Env::default();
+    let (client, _admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let partner = Address::generate(&e);
+    let custody = Address::generate(&e);
+    let commitment_id = client.create_co_owned_commitment(
+        &owner,
+        &1_000,
+        &asset,
+        &safe_rules(&e),
+        &sixty_forty(&e, &owner, &partner),
+    );
+    client.set_settlement_recipient(&owner, &commitment_id, &Some(custody.clone()));
+
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    client.settle(&commitment_id);
+
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&custody), 600);
+    assert_eq!(token.balance(&partner), 400);
+    assert_eq!(token.balance(&owner), 9_000);
+}
+
+// ============================================
+// Early exit penalty floor and cap
+// ============================================
+
+#[test]
+fn test_early_exit_penalty_clamped_up_to_floor() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    // 5% of 10 rounds down to zero
+    let commitment_id = client.create_commitment(&owner, &10, &asset, &safe_rules(&e), &None, &0);
+    assert_eq!(client.preview_early_exit(&commitment_id), (0, 10));
+
+    client.set_penalty_limits(&admin, &Some(3), &None);
+    assert_eq!(client.get_penalty_limits(), (Some(3), None));
+    assert_eq!(client.preview_early_exit(&commitment_id), (3, 7));
+
+    client.early_exit(&commitment_id, &owner);
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&owner), 10_000 - 3);
+}
+
+#[test]
+fn test_early_exit_penalty_clamped_down_to_cap() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    assert_eq!(client.preview_early_exit(&commitment_id), (50, 950));
+
+    client.set_penalty_limits(&admin, &Some(10), &Some(20));
+    assert_eq!(client.preview_early_exit(&commitment_id), (20, 980));
+
+    client.early_exit(&commitment_id, &owner);
+    let token = token::Client::new(&e, &asset);
+    assert_eq!(token.balance(&owner), 10_000 - 20);
+
+    // Clearing the bounds restores the plain percentage
+    client.set_penalty_limits(&admin, &None, &None);
+    assert_eq!(client.get_penalty_limits(), (None, None));
+    assert!(client
+        .try_set_penalty_limits(&admin, &Some(30), &Some(20))
+        .is_err());
+}
+
+// ============================================
+// Index rebuild
+// ============================================
+
+#[test]
+fn test_rebuild_indices_after_corruption() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let mut ids = Vec::new(&e);
+    for _ in 0..3 {
+        ids.push_back(client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0));
+    }
+    client.early_exit(&ids.get(1).unwrap(), &owner);
+
+    let expected_owner = client.get_owner_commitments(&owner);
+    let expected_active = client.get_active_commitments();
+    assert_eq!(expected_active, vec![&e, ids.get(0).unwrap(), ids.get(2).unwrap()]);
+
+    // Drift both indices away from the commitment records
+    e.as_contract(&client.address, || {
+        e.storage().instance().set(
+            &DataKey::OwnerCommitments(owner.clone()),
+            &vec![&e, ids.get(2).unwrap(), String::from_str(&e, "bogus")],
+        );
+        e.storage()
+            .instance()
+            .set(&DataKey::ActiveCommitments, &Vec::<String>::new(&e));
+    });
+    assert_ne!(client.get_owner_commitments(&owner), expected_owner);
+
+    client.set_emergency_mode(&admin, &true);
+
+    // Two pages of two cover all three commitments
+    let cursor = client.rebuild_owner_index(&admin, &owner, &0, &2);
+    assert_eq!(cursor, 2);
+    assert_eq!(client.rebuild_owner_index(&admin, &owner, &cursor, &2), 3);
+    assert_eq!(client.get_owner_commitments(&owner), expected_owner);
+
+    let cursor = client.rebuild_active_index(&admin, &0, &2);
+    assert_eq!(client.rebuild_active_index(&admin, &cursor, &2), 3);
+    assert_eq!(client.get_active_commitments(), expected_active);
+}
+
+#[test]
+fn test_rebuild_all_ids_index_after_corruption() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let mut ids = Vec::new(&e);
+    for _ in 0..3 {
+        ids.push_back(client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0));
+    }
+    client.early_exit(&ids.get(1).unwrap(), &owner);
+    assert_eq!(client.get_all_commitment_ids(), ids);
+
+    // Drop one id and inject one that has no commitment record
+    e.as_contract(&client.address, || {
+        e.storage().instance().set(
+            &DataKey::AllCommitmentIds,
+            &vec![&e, ids.get(2).unwrap(), String::from_str(&e, "bogus")],
+        );
+    });
+    assert_ne!(client.get_all_commitment_ids(), ids);
+
+    client.set_emergency_mode(&admin, &true);
+    assert!(client.try_rebuild_all_ids_index(&owner, &0, &2).is_err());
+
+    let cursor = client.rebuild_all_ids_index(&admin, &0, &2);
+    assert_eq!(cursor, 2);
+    assert_eq!(client.rebuild_all_ids_index(&admin, &cursor, &2), 3);
+    assert_eq!(client.get_all_commitment_ids(), ids);
+}
+
+#[test]
+fn test_rebuild_index_requires_emergency_and_bounded_limit() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+
+    assert!(client.try_rebuild_owner_index(&admin, &owner, &0, &10).is_err());
+
+    client.set_emergency_mode(&admin, &true);
+    let stranger = Address::generate(&e);
+    assert!(client.try_rebuild_active_index(&stranger, &0, &10).is_err());
+    assert!(client.try_rebuild_active_index(&admin, &0, &0).is_err());
+    assert!(client
+        .try_rebuild_active_index(&admin, &0, &(MAX_INDEX_REBUILD_BATCH + 1))
+        .is_err());
+    assert_eq!(client.rebuild_active_index(&admin, &0, &10), 1);
+}
+
+// ============================================
+// Settlement log
+// ============================================
+
+#[test]
+fn test_settlement_log_records_and_pages() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    client.set_settlement_fee_bps(&admin, &250); // 2.5%
+
+    let mut ids = Vec::new(&e);
+    for amount in [1_000i128, 2_000, 3_000] {
+        ids.push_back(client.create_commitment(&owner, &amount, &asset, &safe_rules(&e), &None, &0));
+    }
+    e.ledger().with_mut(|l| l.timestamp += 31 * 86400);
+    let settled_at = e.ledger().timestamp();
+    client.settle(&ids.get(0).unwrap());
+    client.settle(&ids.get(1).unwrap());
+
+    // Emergency settlement pays the full value without a fee
+    client.set_emergency_mode(&admin, &true);
+    client.emergency_settle(&admin, &ids.get(2).unwrap());
+
+    assert_eq!(client.get_settlement_count(), 3);
+    assert_eq!(
+        client.get_settlements_paged(&0, &10),
+        vec![
+            &e,
+            (ids.get(0).unwrap(), 975i128, settled_at),
+            (ids.get(1).unwrap(), 1_950i128, settled_at),
+            (ids.get(2).unwrap(), 3_000i128, settled_at),
+        ]
+    );
+    assert_eq!(
+        client.get_settlements_paged(&1, &1),
+        vec![&e, (ids.get(1).unwrap(), 1_950i128, settled_at)]
+    );
+    assert_eq!(client.get_settlements_paged(&3, &10).len(), 0);
+    assert!(client.try_get_settlements_paged(&0, &0).is_err());
+    assert!(client
+        .try_get_settlements_paged(&0, &(MAX_SETTLEMENT_QUERY + 1))
+        .is_err());
+}
+
+#[test]
+fn test_settlement_log_spans_storage_pages() {
+    let e = Env::default();
+    let (client, ..) = setup_funded_core(&e);
+    let total = SETTLEMENT_LOG_PAGE_SIZE + 5;
+
+    e.as_contract(&client.address, || {
+        for i in 0..total {
+            append_settlement_log(&e, &String::from_str(&e, "c"), i as i128);
+        }
+        assert!(e
+            .storage()
+            .persistent()
+            .has(&DataKey::SettlementLogPage(1)));
+    });
+
+    // A query straddling the page boundary reads both pages in order
+    let start = (SETTLEMENT_LOG_PAGE_SIZE - 2) as u64;
+    let entries = client.get_settlements_paged(&start, &4);
+    assert_eq!(entries.len(), 4);
+    for (offset, (_, amount, _)) in entries.iter().enumerate() {
+        assert_eq!(amount, start as i128 + offset as i128);
+    }
+    assert_eq!(client.get_settlement_count(), total as u64);
+    assert_eq!(client.get_settlements_paged(&0, &MAX_SETTLEMENT_QUERY).len(), total);
+}
+
+// ============================================
+// Storage migration
+// ============================================
+
+fn rules_v1(rules: &CommitmentRules) -> CommitmentRulesV1 {
+    CommitmentRulesV1 {
+        duration_days: rules.duration_days,
+        max_loss_percent: rules.max_loss_percent,
+        commitment_type: rules.commitment_type.clone(),
+        early_exit_penalty: rules.early_exit_penalty,
+        min_fee_threshold: rules.min_fee_threshold,
+        grace_period_days: rules.grace_period_days,
+    }
+}
+
+#[test]
+fn test_migrate_v1_rewrites_commitments_and_templates() {
+    let e = Env::default();
+    let (client, admin, owner, asset, _nft_contract) = setup_funded_core(&e);
+    let commitment_id = client.create_commitment(&owner, &1_000, &asset, &safe_rules(&e), &None, &0);
+    let template = String::from_str(&e, "conservative");
+    client.register_template(&admin, &template, &safe_rules(&e));
+    let stored = client.get_commitment(&commitment_id);
+    assert_eq!(client.get_version(), CURRENT_VERSION);
+
+    // Rewrite storage in the version 1 layout, without max_loss_bps
+    e.as_contract(&client.address, || {
+        let legacy = CommitmentV1 {
+            commitment_id: stored.commitment_id.clone(),
+            owner: stored.owner.clone(),
+            nft_token_id: stored.nft_token_id,
+            rules: rules_v1(&stored.rules),
+            amount: stored.amount,
+            asset_address: stored.asset_address.clone(),
+            created_at: stored.created_at,
+            expires_at: stored.expires_at,
+            current_value: stored.current_value,
+            status: stored.status.clone(),
+        };
+        let storage = e.storage().instance();
+        storage.set(&DataKey::Commitment(commitment_id.clone()), &legacy);
+        storage.set(&DataKey::Template(template.clone()), &rules_v1(&safe_rules(&e)));
+        storage.set(&DataKey::Version, &1u32);
+    });
+
+    assert!(client.try_migrate(&admin, &0).is_err());
+    client.migrate(&admin, &1);
+    assert_eq!(client.get_version(), CURRENT_VERSION);
+    assert_eq!(client.get_commitment(&commitment_id), stored);
+    assert_eq!(client.get_template(&template), Some(safe_rules(&e)));
+
+    // Replays are rejected
+    assert!(client.try_migrate(&admin, &1).is_err());
 }
@@ -2,8 +2,9 @@
 
 use soroban_sdk::{
     contract, contractimpl, contracttype, contracterror, symbol_short,
-    Address, Env, Vec, Symbol, token
+    Address, Env, Map, Vec, Symbol, token
 };
+use shared_utils::{call, EmergencyControl};
 
 // ============================================================================
 // Error Types
@@ -56,6 +57,26 @@ pub enum MarketplaceError {
     ReentrancyDetected = 20,
     /// Transfer failed
     TransferFailed = 21,
+    /// Caller is not authorized
+    NotAuthorized = 22,
+    /// Invalid bid increment (flat must be >= 0, bps <= 10000)
+    InvalidBidIncrement = 23,
+    /// Auction has no buy-now price
+    BuyNowNotAvailable = 24,
+    /// No unclaimed auction winnings for this token
+    NothingToClaim = 25,
+    /// Invalid anti-snipe settings (window and extension must both be zero or both non-zero)
+    InvalidAntiSnipe = 26,
+    /// Token does not support fractional ownership
+    NotFractional = 27,
+    /// Fraction must be between 1 and 10000 basis points
+    InvalidFraction = 28,
+    /// Seller holds less of the token than the fraction being sold
+    InsufficientFraction = 29,
+    /// Address does not own the NFT
+    NotTokenOwner = 30,
+    /// Token is held fractionally and cannot be sold whole
+    TokenIsFractional = 31,
 }
 
 // ============================================================================
@@ -84,6 +105,45 @@ pub struct Offer {
     pub created_at: u64,
 }
 
+/// Offer for part of a fractional token
+///
+/// Stored apart from whole-token `Offer`s so their storage layout is unchanged.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FractionalOffer {
+    pub token_id: u32,
+    pub offerer: Address,
+    pub amount: i128,
+    pub payment_token: Address,
+    pub created_at: u64,
+    /// Share of the token still wanted, in basis points
+    pub fraction_bps: u32,
+}
+
+/// Minimum amount a new bid must exceed the current bid by
+///
+/// The required step is the larger of `flat` and `bps` of the current bid, and
+/// never less than 1.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidIncrement {
+    pub flat: i128,
+    pub bps: u32,
+}
+
+/// Anti-sniping settings, copied onto each auction when it starts
+///
+/// A bid placed within `window_seconds` of the end pushes `ends_at` out by
+/// `extension_seconds`, at most `max_extensions` times. Once the cap is reached
+/// the auction ends on schedule. A zero window disables extensions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AntiSnipe {
+    pub window_seconds: u64,
+    pub extension_seconds: u64,
+    pub max_extensions: u32,
+}
+
 /// Auction information
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -97,6 +157,10 @@ pub struct Auction {
     pub started_at: u64,
     pub ends_at: u64,
     pub ended: bool,
+    pub min_increment: BidIncrement,
+    pub buy_now_price: Option<i128>,
+    pub anti_snipe: AntiSnipe,
+    pub extension_count: u32,
 }
 
 /// Storage keys
@@ -120,10 +184,57 @@ pub enum DataKey {
     Auction(u32),
     /// Active auctions list
     ActiveAuctions,
-    /// Reentrancy guard
-    ReentrancyGuard,
+    /// Reentrancy guard held during a token's critical section (token_id -> bool).
+    /// Kept in temporary storage and removed on exit.
+    ReentrancyGuard(u32),
+    /// Reentrancy guard held while `sweep_ended_auctions` runs (temporary storage)
+    SweepGuard,
+    /// Default minimum bid increment for new auctions (BidIncrement)
+    MinBidIncrement,
+    /// Cumulative sale volume across all payment tokens
+    TotalVolume,
+    /// Cumulative marketplace fees collected
+    TotalFeesCollected,
+    /// Number of completed sales
+    TotalSales,
+    /// Cumulative sale volume per payment token (token -> i128)
+    TokenVolume(Address),
+    /// Winner still owed the NFT from a finished auction (token_id -> Address)
+    PendingClaim(u32),
+    /// Default anti-sniping settings for new auctions (AntiSnipe)
+    AntiSnipe,
+    /// Shortest auction `start_auction` accepts, in seconds (u64)
+    MinAuctionDuration,
+    /// Longest auction `start_auction` accepts, in seconds (u64)
+    MaxAuctionDuration,
+    /// Cached `decimals()` of a payment token (token -> u32)
+    TokenDecimals(Address),
+    /// Bid amount held in escrow for an auction (token_id -> i128)
+    Escrow(u32),
+    /// Total escrow held per payment token (token -> i128)
+    TotalEscrow(Address),
+    /// Fractional holdings of a token in basis points (token_id -> Map<Address, u32>).
+    /// Present only for tokens the admin has enabled for fractional trading; the
+    /// marketplace holds the NFT while it is present.
+    FractionalShares(u32),
+    /// Address exempt from the marketplace fee (Address -> bool)
+    FeeExempt(Address),
+    /// Whether an exempt buyer also waives the fee, not just an exempt seller (bool)
+    BuyerFeeExemption,
+    /// Fractional offers for a token (token_id -> Vec<FractionalOffer>)
+    FractionalOffers(u32),
 }
 
+/// Basis points representing a whole token
+const WHOLE_TOKEN_BPS: u32 = 10_000;
+
+/// Extension cap applied until the admin configures anti-sniping
+const DEFAULT_MAX_EXTENSIONS: u32 = 10;
+
+/// Auction duration bounds applied until the admin configures them (1 hour to 30 days)
+const DEFAULT_MIN_AUCTION_DURATION: u64 = 3_600;
+const DEFAULT_MAX_AUCTION_DURATION: u64 = 2_592_000;
+
 #[cfg(test)]
 mod tests;
 
@@ -197,6 +308,187 @@ impl CommitmentMarketplace {
         Ok(())
     }
 
+    /// Exempt an address from the marketplace fee, or remove the exemption (admin only)
+    ///
+    /// Sales by an exempt seller pay the full price to the seller. Exempt buyers only
+    /// waive the fee once enabled through `set_buyer_fee_exemption`.
+    pub fn set_fee_exempt(e: Env, address: Address, exempt: bool) -> Result<(), MarketplaceError> {
+        let admin: Address = Self::get_admin(e.clone())?;
+        admin.require_auth();
+
+        let key = DataKey::FeeExempt(address.clone());
+        if exempt {
+            e.storage().persistent().set(&key, &true);
+        } else {
+            e.storage().persistent().remove(&key);
+        }
+
+        e.events().publish(
+            (Symbol::new(&e, "FeeExemptSet"), address),
+            exempt,
+        );
+
+        Ok(())
+    }
+
+    /// Whether an address is exempt from the marketplace fee
+    pub fn is_fee_exempt(e: Env, address: Address) -> bool {
+        e.storage().persistent().has(&DataKey::FeeExempt(address))
+    }
+
+    /// Choose whether exempt buyers, as well as exempt sellers, waive the fee (admin only)
+    pub fn set_buyer_fee_exemption(e: Env, enabled: bool) -> Result<(), MarketplaceError> {
+        let admin: Address = Self::get_admin(e.clone())?;
+        admin.require_auth();
+
+        e.storage().instance().set(&DataKey::BuyerFeeExemption, &enabled);
+
+        e.events().publish(
+            (Symbol::new(&e, "BuyerExemptSet"),),
+            enabled,
+        );
+
+        Ok(())
+    }
+
+    /// Whether exempt buyers waive the fee (off until configured)
+    pub fn get_buyer_fee_exemption(e: Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::BuyerFeeExemption)
+            .unwrap_or(false)
+    }
+
+    /// Set the default minimum bid increment for auctions started afterwards (admin only)
+    pub fn set_min_bid_increment(e: Env, increment: BidIncrement) -> Result<(), MarketplaceError> {
+        let admin: Address = Self::get_admin(e.clone())?;
+        admin.require_auth();
+
+        if !Self::is_valid_increment(&increment) {
+            return Err(MarketplaceError::InvalidBidIncrement);
+        }
+
+        e.storage().instance().set(&DataKey::MinBidIncrement, &increment);
+
+        e.events().publish(
+            (Symbol::new(&e, "BidIncUpdated"),),
+            (increment.flat, increment.bps),
+        );
+
+        Ok(())
+    }
+
+    /// Get the default minimum bid increment (no increment beyond 1 until configured)
+    pub fn get_min_bid_increment(e: Env) -> BidIncrement {
+        e.storage()
+            .instance()
+            .get(&DataKey::MinBidIncrement)
+            .unwrap_or(BidIncrement { flat: 0, bps: 0 })
+    }
+
+    /// Set the anti-sniping settings for auctions started afterwards (admin only)
+    pub fn set_anti_snipe(e: Env, anti_snipe: AntiSnipe) -> Result<(), MarketplaceError> {
+        let admin: Address = Self::get_admin(e.clone())?;
+        admin.require_auth();
+
+        if (anti_snipe.window_seconds == 0) != (anti_snipe.extension_seconds == 0) {
+            return Err(MarketplaceError::InvalidAntiSnipe);
+        }
+
+        e.storage().instance().set(&DataKey::AntiSnipe, &anti_snipe);
+
+        e.events().publish(
+            (Symbol::new(&e, "AntiSnipeUpdated"),),
+            (
+                anti_snipe.window_seconds,
+                anti_snipe.extension_seconds,
+                anti_snipe.max_extensions,
+            ),
+        );
+
+        Ok(())
+    }
+
+    /// Get the anti-sniping settings (disabled, with a cap of 10 extensions, until configured)
+    pub fn get_anti_snipe(e: Env) -> AntiSnipe {
+        e.storage()
+            .instance()
+            .get(&DataKey::AntiSnipe)
+            .unwrap_or(AntiSnipe {
+                window_seconds: 0,
+                extension_seconds: 0,
+                max_extensions: DEFAULT_MAX_EXTENSIONS,
+            })
+    }
+
+    /// Set the bounds on `duration_seconds` accepted by `start_auction` (admin only)
+    ///
+    /// Both bounds are inclusive. `min_duration` must be non-zero and not exceed
+    /// `max_duration`. Live auctions are unaffected.
+    pub fn set_auction_duration_limits(
+        e: Env,
+        min_duration: u64,
+        max_duration: u64,
+    ) -> Result<(), MarketplaceError> {
+        let admin: Address = Self::get_admin(e.clone())?;
+        admin.require_auth();
+
+        if min_duration == 0 || min_duration > max_duration {
+            return Err(MarketplaceError::InvalidDuration);
+        }
+
+        e.storage()
+            .instance()
+            .set(&DataKey::MinAuctionDuration, &min_duration);
+        e.storage()
+            .instance()
+            .set(&DataKey::MaxAuctionDuration, &max_duration);
+
+        e.events().publish(
+            (Symbol::new(&e, "AuctionDurationUpdated"),),
+            (min_duration, max_duration),
+        );
+
+        Ok(())
+    }
+
+    /// Get the minimum auction duration in seconds (1 hour until configured)
+    pub fn get_min_auction_duration(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKey::MinAuctionDuration)
+            .unwrap_or(DEFAULT_MIN_AUCTION_DURATION)
+    }
+
+    /// Get the maximum auction duration in seconds (30 days until configured)
+    pub fn get_max_auction_duration(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKey::MaxAuctionDuration)
+            .unwrap_or(DEFAULT_MAX_AUCTION_DURATION)
+    }
+
+    /// Enable or disable emergency mode (admin only)
+    ///
+    /// While enabled, trading entry points are halted. Cancelling listings and
+    /// offers remains available so users can exit their positions.
+    pub fn set_emergency_mode(e: Env, caller: Address, enabled: bool) -> Result<(), MarketplaceError> {
+        let admin: Address = Self::get_admin(e.clone())?;
+        admin.require_auth();
+
+        if caller != admin {
+            return Err(MarketplaceError::NotAuthorized);
+        }
+
+        EmergencyControl::set_emergency_mode(&e, enabled);
+        Ok(())
+    }
+
+    /// Check whether the marketplace is in emergency mode
+    pub fn is_emergency_mode(e: Env) -> bool {
+        EmergencyControl::is_emergency_mode(&e)
+    }
+
     // ========================================================================
     // Listing Management
     // ========================================================================
@@ -220,34 +512,38 @@ impl CommitmentMarketplace {
     ) -> Result<(), MarketplaceError> {
         // Reentrancy protection
         let guard: bool = e.storage()
-            .instance()
-            .get(&DataKey::ReentrancyGuard)
-            .unwrap_or(false);
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
         if guard {
             return Err(MarketplaceError::ReentrancyDetected);
         }
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
 
         // CHECKS
         seller.require_auth();
 
         if price <= 0 {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
             return Err(MarketplaceError::InvalidPrice);
         }
 
         // Check if listing already exists
         if e.storage().persistent().has(&DataKey::Listing(token_id)) {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
             return Err(MarketplaceError::ListingExists);
         }
 
+        if Self::is_fractional(e.clone(), token_id) {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::TokenIsFractional);
+        }
+
         // Verify seller owns the NFT (external call - after checks)
         let _nft_contract: Address = e.storage()
             .instance()
             .get(&DataKey::NFTContract)
             .ok_or_else(|| {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 MarketplaceError::NotInitialized
             })?;
 
@@ -275,7 +571,7 @@ impl CommitmentMarketplace {
         e.storage().instance().set(&DataKey::ActiveListings, &active_listings);
 
         // Clear reentrancy guard
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
 
         // Emit event
         e.events().publish(
@@ -293,13 +589,12 @@ impl CommitmentMarketplace {
     pub fn cancel_listing(e: Env, seller: Address, token_id: u32) -> Result<(), MarketplaceError> {
         // Reentrancy protection
         let guard: bool = e.storage()
-            .instance()
-            .get(&DataKey::ReentrancyGuard)
-            .unwrap_or(false);
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
         if guard {
             return Err(MarketplaceError::ReentrancyDetected);
         }
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
 
         // CHECKS
         seller.require_auth();
@@ -308,12 +603,12 @@ impl CommitmentMarketplace {
             .persistent()
             .get(&DataKey::Listing(token_id))
             .ok_or_else(|| {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 MarketplaceError::ListingNotFound
             })?;
 
         if listing.seller != seller {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
             return Err(MarketplaceError::NotSeller);
         }
 
@@ -332,7 +627,7 @@ impl CommitmentMarketplace {
         e.storage().instance().set(&DataKey::ActiveListings, &active_listings);
 
         // Clear reentrancy guard
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
 
         // Emit event
         e.events().publish(
@@ -354,13 +649,13 @@ impl CommitmentMarketplace {
     pub fn buy_nft(e: Env, buyer: Address, token_id: u32) -> Result<(), MarketplaceError> {
         // Reentrancy protection
         let guard: bool = e.storage()
-            .instance()
-            .get(&DataKey::ReentrancyGuard)
-            .unwrap_or(false);
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
         if guard {
             return Err(MarketplaceError::ReentrancyDetected);
         }
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
+        EmergencyControl::require_not_emergency(&e);
 
         // CHECKS
         buyer.require_auth();
@@ -369,12 +664,12 @@ impl CommitmentMarketplace {
             .persistent()
             .get(&DataKey::Listing(token_id))
             .ok_or_else(|| {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 MarketplaceError::ListingNotFound
             })?;
 
         if listing.seller == buyer {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
             return Err(MarketplaceError::CannotBuyOwnListing);
         }
 
@@ -387,7 +682,7 @@ impl CommitmentMarketplace {
             .instance()
             .get(&DataKey::FeeRecipient)
             .ok_or_else(|| {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 MarketplaceError::NotInitialized
             })?;
 
@@ -395,12 +690,12 @@ impl CommitmentMarketplace {
             .instance()
             .get(&DataKey::NFTContract)
             .ok_or_else(|| {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 MarketplaceError::NotInitialized
             })?;
 
         // Calculate fee and seller proceeds
-        let marketplace_fee = (listing.price * fee_basis_points as i128) / 10000;
+        let marketplace_fee = Self::sale_fee(&e, &listing.seller, &buyer, listing.price, fee_basis_points);
         let seller_proceeds = listing.price - marketplace_fee;
 
         // EFFECTS
@@ -417,6 +712,8 @@ impl CommitmentMarketplace {
         }
         e.storage().instance().set(&DataKey::ActiveListings, &active_listings);
 
+        Self::record_sale(&e, &listing.payment_token, listing.price, marketplace_fee);
+
         // INTERACTIONS - External calls AFTER state changes
         // Transfer payment token from buyer to seller
         let payment_token_client = token::Client::new(&e, &listing.payment_token);
@@ -434,12 +731,17 @@ impl CommitmentMarketplace {
         // For this implementation, we assume the transfer happens correctly
 
         // Clear reentrancy guard
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
 
         // Emit event
         e.events().publish(
             (symbol_short!("NFTSold"), token_id),
-            (listing.seller, buyer, listing.price),
+            (
+                listing.seller,
+                buyer,
+                listing.price,
+                Self::get_payment_token_decimals(e.clone(), listing.payment_token),
+            ),
         );
 
         Ok(())
@@ -488,22 +790,27 @@ impl CommitmentMarketplace {
     ) -> Result<(), MarketplaceError> {
         // Reentrancy protection
         let guard: bool = e.storage()
-            .instance()
-            .get(&DataKey::ReentrancyGuard)
-            .unwrap_or(false);
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
         if guard {
             return Err(MarketplaceError::ReentrancyDetected);
         }
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
+        EmergencyControl::require_not_emergency(&e);
 
         // CHECKS
         offerer.require_auth();
 
         if amount <= 0 {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
             return Err(MarketplaceError::InvalidOfferAmount);
         }
 
+        if Self::is_fractional(e.clone(), token_id) {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::TokenIsFractional);
+        }
+
         // EFFECTS
         let offer = Offer {
             token_id,
@@ -521,7 +828,7 @@ impl CommitmentMarketplace {
         // Check if offerer already has an offer
         for existing_offer in offers.iter() {
             if existing_offer.offerer == offerer {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 return Err(MarketplaceError::OfferExists);
             }
         }
@@ -530,7 +837,7 @@ impl CommitmentMarketplace {
         e.storage().persistent().set(&DataKey::Offers(token_id), &offers);
 
         // Clear reentrancy guard
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
 
         // Emit event
         e.events().publish(
@@ -553,29 +860,35 @@ impl CommitmentMarketplace {
     ) -> Result<(), MarketplaceError> {
         // Reentrancy protection
         let guard: bool = e.storage()
-            .instance()
-            .get(&DataKey::ReentrancyGuard)
-            .unwrap_or(false);
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
         if guard {
             return Err(MarketplaceError::ReentrancyDetected);
         }
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
+        EmergencyControl::require_not_emergency(&e);
 
         // CHECKS
         seller.require_auth();
 
+        // Whole offers made before the token went fractional can no longer be filled
+        if Self::is_fractional(e.clone(), token_id) {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::TokenIsFractional);
+        }
+
         let offers: Vec<Offer> = e.storage()
             .persistent()
             .get(&DataKey::Offers(token_id))
             .ok_or_else(|| {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 MarketplaceError::OfferNotFound
             })?;
 
         // Find the offer
         let offer_index = offers.iter().position(|o| o.offerer == offerer)
             .ok_or_else(|| {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 MarketplaceError::OfferNotFound
             })?;
 
@@ -590,12 +903,12 @@ impl CommitmentMarketplace {
             .instance()
             .get(&DataKey::FeeRecipient)
             .ok_or_else(|| {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 MarketplaceError::NotInitialized
             })?;
 
         // Calculate fee and seller proceeds
-        let marketplace_fee = (offer.amount * fee_basis_points as i128) / 10000;
+        let marketplace_fee = Self::sale_fee(&e, &seller, &offerer, offer.amount, fee_basis_points);
         let seller_proceeds = offer.amount - marketplace_fee;
 
         // EFFECTS
@@ -616,6 +929,8 @@ impl CommitmentMarketplace {
             e.storage().instance().set(&DataKey::ActiveListings, &active_listings);
         }
 
+        Self::record_sale(&e, &offer.payment_token, offer.amount, marketplace_fee);
+
         // INTERACTIONS
         // Transfer payment
         let payment_token_client = token::Client::new(&e, &offer.payment_token);
@@ -629,12 +944,17 @@ impl CommitmentMarketplace {
         // Note: Use NFT contract client in production
 
         // Clear reentrancy guard
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
 
         // Emit event
         e.events().publish(
             (symbol_short!("OffAccpt"), token_id),
-            (seller, offerer, offer.amount),
+            (
+                seller,
+                offerer,
+                offer.amount,
+                Self::get_payment_token_decimals(e.clone(), offer.payment_token),
+            ),
         );
 
         Ok(())
@@ -677,130 +997,494 @@ impl CommitmentMarketplace {
     }
 
     // ========================================================================
-    // Auction System
+    // Fractional Offers
     // ========================================================================
 
-    /// Start an auction
+    /// Enable fractional trading for a token, recording `holder` as owning all of it
+    /// (admin and holder)
+    ///
+    /// `holder` must own the NFT (`NotTokenOwner`); it moves into marketplace custody
+    /// and fractional holdings are tracked by the marketplace in basis points. From
+    /// then on the token cannot be listed, offered on whole or auctioned
+    /// (`TokenIsFractional`). Fails with `InvalidFraction` if the token is already
+    /// enabled and `ListingExists` while a listing or unfinished auction is open.
+    pub fn enable_fractional(e: Env, token_id: u32, holder: Address) -> Result<(), MarketplaceError> {
+        let admin: Address = Self::get_admin(e.clone())?;
+        admin.require_auth();
+        holder.require_auth();
+
+        let key = DataKey::FractionalShares(token_id);
+        if e.storage().persistent().has(&key) {
+            return Err(MarketplaceError::InvalidFraction);
+        }
+
+        let open_auction = e
+            .storage()
+            .persistent()
+            .get::<_, Auction>(&DataKey::Auction(token_id))
+            .is_some_and(|auction| !auction.ended);
+        if open_auction || e.storage().persistent().has(&DataKey::Listing(token_id)) {
+            return Err(MarketplaceError::ListingExists);
+        }
+
+        if Self::nft_owner(&e, token_id)? != holder {
+            return Err(MarketplaceError::NotTokenOwner);
+        }
+
+        let mut shares: Map<Address, u32> = Map::new(&e);
+        shares.set(holder.clone(), WHOLE_TOKEN_BPS);
+        e.storage().persistent().set(&key, &shares);
+
+        // INTERACTIONS
+        let nft_contract: Address = e.storage()
+            .instance()
+            .get(&DataKey::NFTContract)
+            .ok_or(MarketplaceError::NotInitialized)?;
+        call::invoke3::<_, _, _, ()>(
+            &e,
+            &nft_contract,
+            "transfer",
+            holder.clone(),
+            e.current_contract_address(),
+            token_id,
+        );
+
+        e.events().publish(
+            (symbol_short!("FracOn"), token_id),
+            holder,
+        );
+
+        Ok(())
+    }
+
+    /// Whether a token has been enabled for fractional trading
+    pub fn is_fractional(e: Env, token_id: u32) -> bool {
+        e.storage().persistent().has(&DataKey::FractionalShares(token_id))
+    }
+
+    /// Share of a fractional token held by `holder`, in basis points
+    pub fn get_fraction(e: Env, token_id: u32, holder: Address) -> u32 {
+        e.storage()
+            .persistent()
+            .get::<_, Map<Address, u32>>(&DataKey::FractionalShares(token_id))
+            .and_then(|shares| shares.get(holder))
+            .unwrap_or(0)
+    }
+
+    /// Offer `amount` for `fraction_bps` of a fractional token
+    ///
+    /// The offer can be filled in parts through `fractional_accept`, with the price
+    /// scaling proportionally to the fraction filled.
     ///
     /// # Reentrancy Protection
     /// Protected with reentrancy guard
-    pub fn start_auction(
+    pub fn make_fractional_offer(
         e: Env,
-        seller: Address,
+        offerer: Address,
         token_id: u32,
-        starting_price: i128,
-        duration_seconds: u64,
+        fraction_bps: u32,
+        amount: i128,
         payment_token: Address,
     ) -> Result<(), MarketplaceError> {
         // Reentrancy protection
         let guard: bool = e.storage()
-            .instance()
-            .get(&DataKey::ReentrancyGuard)
-            .unwrap_or(false);
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
         if guard {
             return Err(MarketplaceError::ReentrancyDetected);
         }
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
+        EmergencyControl::require_not_emergency(&e);
 
         // CHECKS
-        seller.require_auth();
+        offerer.require_auth();
 
-        if starting_price <= 0 {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
-            return Err(MarketplaceError::InvalidPrice);
+        if !Self::is_fractional(e.clone(), token_id) {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::NotFractional);
         }
 
-        if duration_seconds == 0 {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
-            return Err(MarketplaceError::InvalidDuration);
+        if fraction_bps == 0 || fraction_bps > WHOLE_TOKEN_BPS {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::InvalidFraction);
         }
 
-        if e.storage().persistent().has(&DataKey::Auction(token_id)) {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
-            return Err(MarketplaceError::ListingExists);
+        if amount <= 0 {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::InvalidOfferAmount);
         }
 
-        // EFFECTS
-        let started_at = e.ledger().timestamp();
-        let ends_at = started_at + duration_seconds;
+        let mut offers: Vec<FractionalOffer> = e.storage()
+            .persistent()
+            .get(&DataKey::FractionalOffers(token_id))
+            .unwrap_or(Vec::new(&e));
 
-        let auction = Auction {
+        if offers.iter().any(|o| o.offerer == offerer) {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::OfferExists);
+        }
+
+        // EFFECTS
+        offers.push_back(FractionalOffer {
             token_id,
-            seller: seller.clone(),
-            starting_price,
-            current_bid: starting_price,
-            highest_bidder: None,
+            offerer: offerer.clone(),
+            amount,
             payment_token: payment_token.clone(),
-            started_at,
-            ends_at,
-            ended: false,
-        };
+            created_at: e.ledger().timestamp(),
+            fraction_bps,
+        });
+        e.storage().persistent().set(&DataKey::FractionalOffers(token_id), &offers);
 
-        e.storage().persistent().set(&DataKey::Auction(token_id), &auction);
+        // Clear reentrancy guard
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
 
-        let mut active_auctions: Vec<u32> = e.storage()
-            .instance()
-            .get(&DataKey::ActiveAuctions)
-            .unwrap_or(Vec::new(&e));
-        active_auctions.push_back(token_id);
-        e.storage().instance().set(&DataKey::ActiveAuctions, &active_auctions);
+        e.events().publish(
+            (symbol_short!("FracOffer"), token_id),
+            (offerer, fraction_bps, amount, payment_token),
+        );
 
-        // Clear reentrancy guard
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+        Ok(())
+    }
+
+    /// Cancel a fractional offer
+    pub fn cancel_fractional_offer(
+        e: Env,
+        offerer: Address,
+        token_id: u32,
+    ) -> Result<(), MarketplaceError> {
+        offerer.require_auth();
+
+        let mut offers: Vec<FractionalOffer> = e.storage()
+            .persistent()
+            .get(&DataKey::FractionalOffers(token_id))
+            .ok_or(MarketplaceError::OfferNotFound)?;
+
+        let offer_index = offers.iter().position(|o| o.offerer == offerer)
+            .ok_or(MarketplaceError::OfferNotFound)?;
+
+        offers.remove(offer_index as u32);
+
+        if offers.is_empty() {
+            e.storage().persistent().remove(&DataKey::FractionalOffers(token_id));
+        } else {
+            e.storage().persistent().set(&DataKey::FractionalOffers(token_id), &offers);
+        }
 
-        // Emit event
         e.events().publish(
-            (symbol_short!("AucStart"), token_id),
-            (seller, starting_price, ends_at),
+            (symbol_short!("FracCanc"), token_id),
+            offerer,
         );
 
         Ok(())
     }
 
-    /// Place a bid
+    /// Get all fractional offers for a token
+    pub fn get_fractional_offers(e: Env, token_id: u32) -> Vec<FractionalOffer> {
+        e.storage()
+            .persistent()
+            .get(&DataKey::FractionalOffers(token_id))
+            .unwrap_or(Vec::new(&e))
+    }
+
+    /// Sell `fill_bps` of a fractional token to an offerer
+    ///
+    /// `fill_bps` may be less than the offer's fraction; the offer keeps the unfilled
+    /// remainder at the same price per basis point. The offerer pays the proportional
+    /// share of the offer amount, minus the marketplace fee, and the fraction moves
+    /// from the seller to the offerer.
     ///
     /// # Reentrancy Protection
-    /// Critical - handles token transfers for bid refunds. Protected with reentrancy guard.
-    pub fn place_bid(
+    /// Critical - handles token transfers. Protected with reentrancy guard.
+    pub fn fractional_accept(
         e: Env,
-        bidder: Address,
+        seller: Address,
         token_id: u32,
-        bid_amount: i128,
+        offerer: Address,
+        fill_bps: u32,
     ) -> Result<(), MarketplaceError> {
         // Reentrancy protection
         let guard: bool = e.storage()
-            .instance()
-            .get(&DataKey::ReentrancyGuard)
-            .unwrap_or(false);
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
         if guard {
             return Err(MarketplaceError::ReentrancyDetected);
         }
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
+        EmergencyControl::require_not_emergency(&e);
 
         // CHECKS
-        bidder.require_auth();
+        seller.require_auth();
 
-        let mut auction: Auction = e.storage()
+        let mut shares: Map<Address, u32> = e.storage()
+            .persistent()
+            .get(&DataKey::FractionalShares(token_id))
+            .ok_or_else(|| {
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+                MarketplaceError::NotFractional
+            })?;
+
+        let mut offers: Vec<FractionalOffer> = e.storage()
+            .persistent()
+            .get(&DataKey::FractionalOffers(token_id))
+            .ok_or_else(|| {
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+                MarketplaceError::OfferNotFound
+            })?;
+
+        let offer_index = offers.iter().position(|o| o.offerer == offerer)
+            .ok_or_else(|| {
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+                MarketplaceError::OfferNotFound
+            })? as u32;
+
+        let offer = offers.get(offer_index).unwrap();
+
+        if fill_bps == 0 || fill_bps > offer.fraction_bps {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::InvalidFraction);
+        }
+
+        let seller_bps = shares.get(seller.clone()).unwrap_or(0);
+        if seller_bps < fill_bps {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::InsufficientFraction);
+        }
+
+        // The fractions are only backed while the marketplace still holds the NFT
+        let custodian = Self::nft_owner(&e, token_id).inspect_err(|_| {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+        })?;
+        if custodian != e.current_contract_address() {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::NotTokenOwner);
+        }
+
+        let fee_basis_points: u32 = e.storage()
+            .instance()
+            .get(&DataKey::MarketplaceFee)
+            .unwrap_or(0);
+
+        let fee_recipient: Address = e.storage()
+            .instance()
+            .get(&DataKey::FeeRecipient)
+            .ok_or_else(|| {
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+                MarketplaceError::NotInitialized
+            })?;
+
+        // A full fill takes the whole amount so rounding never leaves dust on the offer
+        let price = if fill_bps == offer.fraction_bps {
+            offer.amount
+        } else {
+            (offer.amount * fill_bps as i128) / offer.fraction_bps as i128
+        };
+        let marketplace_fee = Self::sale_fee(&e, &seller, &offerer, price, fee_basis_points);
+        let seller_proceeds = price - marketplace_fee;
+
+        // EFFECTS
+        if seller_bps == fill_bps {
+            shares.remove(seller.clone());
+        } else {
+            shares.set(seller.clone(), seller_bps - fill_bps);
+        }
+        let buyer_bps = shares.get(offerer.clone()).unwrap_or(0);
+        shares.set(offerer.clone(), buyer_bps + fill_bps);
+        e.storage().persistent().set(&DataKey::FractionalShares(token_id), &shares);
+
+        if fill_bps == offer.fraction_bps {
+            offers.remove(offer_index);
+        } else {
+            let mut remaining = offer.clone();
+            remaining.fraction_bps -= fill_bps;
+            remaining.amount -= price;
+            offers.set(offer_index, remaining);
+        }
+        if offers.is_empty() {
+            e.storage().persistent().remove(&DataKey::FractionalOffers(token_id));
+        } else {
+            e.storage().persistent().set(&DataKey::FractionalOffers(token_id), &offers);
+        }
+
+        Self::record_sale(&e, &offer.payment_token, price, marketplace_fee);
+
+        // INTERACTIONS
+        let payment_token_client = token::Client::new(&e, &offer.payment_token);
+        payment_token_client.transfer(&offerer, &seller, &seller_proceeds);
+
+        if marketplace_fee > 0 {
+            payment_token_client.transfer(&offerer, &fee_recipient, &marketplace_fee);
+        }
+
+        // Clear reentrancy guard
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+
+        e.events().publish(
+            (symbol_short!("FracAccpt"), token_id),
+            (seller, offerer, fill_bps, price),
+        );
+
+        Ok(())
+    }
+
+    // ========================================================================
+    // Auction System
+    // ========================================================================
+
+    /// Start an auction
+    ///
+    /// `min_increment` overrides the marketplace default bid increment for this auction.
+    /// `buy_now_price`, if set, must exceed `starting_price` and lets a buyer end the
+    /// auction early through `buy_now`. `duration_seconds` must lie within the
+    /// configured auction duration limits.
+    ///
+    /// # Reentrancy Protection
+    /// Protected with reentrancy guard
+    #[allow(clippy::too_many_arguments)]
+    pub fn start_auction(
+        e: Env,
+        seller: Address,
+        token_id: u32,
+        starting_price: i128,
+        duration_seconds: u64,
+        payment_token: Address,
+        min_increment: Option<BidIncrement>,
+        buy_now_price: Option<i128>,
+    ) -> Result<(), MarketplaceError> {
+        // Reentrancy protection
+        let guard: bool = e.storage()
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
+        if guard {
+            return Err(MarketplaceError::ReentrancyDetected);
+        }
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
+        EmergencyControl::require_not_emergency(&e);
+
+        // CHECKS
+        seller.require_auth();
+
+        if starting_price <= 0 {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::InvalidPrice);
+        }
+
+        if duration_seconds < Self::get_min_auction_duration(e.clone())
+            || duration_seconds > Self::get_max_auction_duration(e.clone())
+        {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::InvalidDuration);
+        }
+
+        let min_increment = min_increment.unwrap_or_else(|| Self::get_min_bid_increment(e.clone()));
+        if !Self::is_valid_increment(&min_increment) {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::InvalidBidIncrement);
+        }
+
+        if let Some(price) = buy_now_price {
+            if price <= starting_price {
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+                return Err(MarketplaceError::InvalidPrice);
+            }
+        }
+
+        if e.storage().persistent().has(&DataKey::Auction(token_id)) {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::ListingExists);
+        }
+
+        if Self::is_fractional(e.clone(), token_id) {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::TokenIsFractional);
+        }
+
+        // EFFECTS
+        let started_at = e.ledger().timestamp();
+        let ends_at = started_at + duration_seconds;
+
+        let auction = Auction {
+            token_id,
+            seller: seller.clone(),
+            starting_price,
+            current_bid: starting_price,
+            highest_bidder: None,
+            payment_token: payment_token.clone(),
+            started_at,
+            ends_at,
+            ended: false,
+            min_increment,
+            buy_now_price,
+            anti_snipe: Self::get_anti_snipe(e.clone()),
+            extension_count: 0,
+        };
+
+        e.storage().persistent().set(&DataKey::Auction(token_id), &auction);
+
+        let mut active_auctions: Vec<u32> = e.storage()
+            .instance()
+            .get(&DataKey::ActiveAuctions)
+            .unwrap_or(Vec::new(&e));
+        active_auctions.push_back(token_id);
+        e.storage().instance().set(&DataKey::ActiveAuctions, &active_auctions);
+
+        // Clear reentrancy guard
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+
+        // Emit event
+        e.events().publish(
+            (symbol_short!("AucStart"), token_id),
+            (seller, starting_price, ends_at),
+        );
+
+        Ok(())
+    }
+
+    /// Place a bid
+    ///
+    /// # Reentrancy Protection
+    /// Critical - handles token transfers for bid refunds. Protected with reentrancy guard.
+    pub fn place_bid(
+        e: Env,
+        bidder: Address,
+        token_id: u32,
+        bid_amount: i128,
+    ) -> Result<(), MarketplaceError> {
+        // Reentrancy protection
+        let guard: bool = e.storage()
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
+        if guard {
+            return Err(MarketplaceError::ReentrancyDetected);
+        }
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
+        EmergencyControl::require_not_emergency(&e);
+
+        // CHECKS
+        bidder.require_auth();
+
+        let mut auction: Auction = e.storage()
             .persistent()
             .get(&DataKey::Auction(token_id))
             .ok_or_else(|| {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 MarketplaceError::AuctionNotFound
             })?;
 
+        // Auctions closed early via buy_now are ended before ends_at
         let current_time = e.ledger().timestamp();
-        if current_time >= auction.ends_at {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+        if auction.ended || current_time >= auction.ends_at {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
             return Err(MarketplaceError::AuctionEnded);
         }
 
-        if bid_amount <= auction.current_bid {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+        let required_increment = Self::required_increment(&auction);
+        if bid_amount < auction.current_bid.saturating_add(required_increment) {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
             return Err(MarketplaceError::BidTooLow);
         }
 
         if auction.seller == bidder {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
             return Err(MarketplaceError::CannotBuyOwnListing);
         }
 
@@ -811,8 +1495,20 @@ impl CommitmentMarketplace {
         auction.current_bid = bid_amount;
         auction.highest_bidder = Some(bidder.clone());
 
+        // Late bids push the end out until the extension cap is reached
+        let extended = auction.anti_snipe.window_seconds > 0
+            && auction.ends_at - current_time <= auction.anti_snipe.window_seconds
+            && auction.extension_count < auction.anti_snipe.max_extensions;
+        if extended {
+            auction.ends_at = auction.ends_at.saturating_add(auction.anti_snipe.extension_seconds);
+            auction.extension_count += 1;
+        }
+
         e.storage().persistent().set(&DataKey::Auction(token_id), &auction);
 
+        let refunded = if previous_bidder.is_some() { previous_bid } else { 0 };
+        Self::adjust_escrow(&e, token_id, &auction.payment_token, bid_amount - refunded);
+
         // INTERACTIONS
         let payment_token_client = token::Client::new(&e, &auction.payment_token);
 
@@ -825,49 +1521,58 @@ impl CommitmentMarketplace {
         }
 
         // Clear reentrancy guard
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
 
         // Emit event
         e.events().publish(
             (symbol_short!("BidPlaced"), token_id),
             (bidder, bid_amount),
         );
+        if extended {
+            e.events().publish(
+                (symbol_short!("AucExtend"), token_id),
+                (auction.ends_at, auction.extension_count),
+            );
+        }
 
         Ok(())
     }
 
     /// End an auction
     ///
+    /// Pays out the escrowed winning bid and records the winner, who takes the
+    /// NFT in a separate `claim_auction_winnings` call.
+    ///
     /// # Reentrancy Protection
     /// Critical - handles final settlement. Protected with reentrancy guard.
     pub fn end_auction(e: Env, token_id: u32) -> Result<(), MarketplaceError> {
         // Reentrancy protection
         let guard: bool = e.storage()
-            .instance()
-            .get(&DataKey::ReentrancyGuard)
-            .unwrap_or(false);
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
         if guard {
             return Err(MarketplaceError::ReentrancyDetected);
         }
-        e.storage().instance().set(&DataKey::ReentrancyGuard, &true);
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
+        EmergencyControl::require_not_emergency(&e);
 
         // CHECKS
-        let mut auction: Auction = e.storage()
+        let auction: Auction = e.storage()
             .persistent()
             .get(&DataKey::Auction(token_id))
             .ok_or_else(|| {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 MarketplaceError::AuctionNotFound
             })?;
 
         let current_time = e.ledger().timestamp();
         if current_time < auction.ends_at {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
             return Err(MarketplaceError::AuctionNotEnded);
         }
 
         if auction.ended {
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
             return Err(MarketplaceError::AuctionEnded);
         }
 
@@ -880,64 +1585,370 @@ impl CommitmentMarketplace {
             .instance()
             .get(&DataKey::FeeRecipient)
             .ok_or_else(|| {
-                e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
                 MarketplaceError::NotInitialized
             })?;
 
+        Self::settle_auction(&e, auction, fee_basis_points, &fee_recipient);
+
+        // Clear reentrancy guard
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+
+        Ok(())
+    }
+
+    /// Buy an auctioned NFT outright at its buy-now price
+    ///
+    /// Ends a live auction immediately: the current highest bidder (if any) is
+    /// refunded from escrow and the buyer pays the seller and marketplace fee.
+    /// The buyer then takes the NFT through `claim_auction_winnings`.
+    ///
+    /// # Reentrancy Protection
+    /// Critical - handles token transfers and refunds. Protected with reentrancy guard.
+    pub fn buy_now(e: Env, buyer: Address, token_id: u32) -> Result<(), MarketplaceError> {
+        // Reentrancy protection
+        let guard: bool = e.storage()
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
+        if guard {
+            return Err(MarketplaceError::ReentrancyDetected);
+        }
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
+        EmergencyControl::require_not_emergency(&e);
+
+        // CHECKS
+        buyer.require_auth();
+
+        let mut auction: Auction = e.storage()
+            .persistent()
+            .get(&DataKey::Auction(token_id))
+            .ok_or_else(|| {
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+                MarketplaceError::AuctionNotFound
+            })?;
+
+        if auction.ended || e.ledger().timestamp() >= auction.ends_at {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::AuctionEnded);
+        }
+
+        let price = auction.buy_now_price.ok_or_else(|| {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            MarketplaceError::BuyNowNotAvailable
+        })?;
+
+        if auction.seller == buyer {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::CannotBuyOwnListing);
+        }
+
+        let fee_basis_points: u32 = e.storage()
+            .instance()
+            .get(&DataKey::MarketplaceFee)
+            .unwrap_or(0);
+
+        let fee_recipient: Address = e.storage()
+            .instance()
+            .get(&DataKey::FeeRecipient)
+            .ok_or_else(|| {
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+                MarketplaceError::NotInitialized
+            })?;
+
+        let marketplace_fee = Self::sale_fee(&e, &auction.seller, &buyer, price, fee_basis_points);
+        let seller_proceeds = price - marketplace_fee;
+
         // EFFECTS
+        let previous_bidder = auction.highest_bidder.clone();
+        let previous_bid = auction.current_bid;
+
         auction.ended = true;
+        auction.current_bid = price;
+        auction.highest_bidder = Some(buyer.clone());
         e.storage().persistent().set(&DataKey::Auction(token_id), &auction);
 
-        // Remove from active auctions
-        let mut active_auctions: Vec<u32> = e.storage()
+        Self::remove_active_auction(&e, token_id);
+        e.storage().persistent().set(&DataKey::PendingClaim(token_id), &buyer);
+
+        Self::record_sale(&e, &auction.payment_token, price, marketplace_fee);
+        if previous_bidder.is_some() {
+            Self::adjust_escrow(&e, token_id, &auction.payment_token, -previous_bid);
+        }
+
+        // INTERACTIONS
+        let payment_token_client = token::Client::new(&e, &auction.payment_token);
+
+        // Refund the outbid escrow before taking the buyer's payment
+        if let Some(prev_bidder) = previous_bidder {
+            payment_token_client.transfer(&e.current_contract_address(), &prev_bidder, &previous_bid);
+        }
+
+        payment_token_client.transfer(&buyer, &auction.seller, &seller_proceeds);
+
+        if marketplace_fee > 0 {
+            payment_token_client.transfer(&buyer, &fee_recipient, &marketplace_fee);
+        }
+
+        // Clear reentrancy guard
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+
+        e.events().publish(
+            (symbol_short!("AucBuyNow"), token_id),
+            (
+                auction.seller,
+                buyer,
+                price,
+                Self::get_payment_token_decimals(e.clone(), auction.payment_token),
+            ),
+        );
+
+        Ok(())
+    }
+
+    /// Settle every ended auction still in the active list, up to `limit`
+    ///
+    /// Keeper entry point: anyone may call it. Each auction is settled exactly as
+    /// `end_auction` would (escrowed bid to seller minus marketplace fee). Returns
+    /// the number of auctions settled.
+    ///
+    /// # Reentrancy Protection
+    /// Critical - handles final settlement. Protected with reentrancy guard.
+    pub fn sweep_ended_auctions(e: Env, limit: u32) -> Result<u32, MarketplaceError> {
+        // Reentrancy protection
+        let guard: bool = e.storage()
+            .temporary()
+            .has(&DataKey::SweepGuard);
+        if guard {
+            return Err(MarketplaceError::ReentrancyDetected);
+        }
+        e.storage().temporary().set(&DataKey::SweepGuard, &true);
+        EmergencyControl::require_not_emergency(&e);
+
+        let fee_basis_points: u32 = e.storage()
+            .instance()
+            .get(&DataKey::MarketplaceFee)
+            .unwrap_or(0);
+
+        let fee_recipient: Address = e.storage()
+            .instance()
+            .get(&DataKey::FeeRecipient)
+            .ok_or_else(|| {
+                e.storage().temporary().remove(&DataKey::SweepGuard);
+                MarketplaceError::NotInitialized
+            })?;
+
+        // Iterate a snapshot; settle_auction rewrites the stored list
+        let active_auctions: Vec<u32> = e.storage()
             .instance()
             .get(&DataKey::ActiveAuctions)
             .unwrap_or(Vec::new(&e));
-        if let Some(index) = active_auctions.iter().position(|id| id == token_id) {
-            active_auctions.remove(index as u32);
+        let current_time = e.ledger().timestamp();
+        let mut settled: u32 = 0;
+
+        for token_id in active_auctions.iter() {
+            if settled >= limit {
+                break;
+            }
+            if let Some(auction) = e.storage().persistent().get::<_, Auction>(&DataKey::Auction(token_id)) {
+                // Leave tokens mid-operation for a later sweep
+                if e.storage().temporary().has(&DataKey::ReentrancyGuard(token_id)) {
+                    continue;
+                }
+                if !auction.ended && current_time >= auction.ends_at {
+                    Self::settle_auction(&e, auction, fee_basis_points, &fee_recipient);
+                    settled += 1;
+                }
+            }
+        }
+
+        // Clear reentrancy guard
+        e.storage().temporary().remove(&DataKey::SweepGuard);
+
+        e.events().publish(
+            (symbol_short!("AucSweep"),),
+            (settled, current_time),
+        );
+
+        Ok(settled)
+    }
+
+    /// Take delivery of the NFT from a finished auction
+    ///
+    /// Second step after `end_auction`, `sweep_ended_auctions` or `buy_now`:
+    /// payment is already settled, so only the recorded winner may claim, once.
+    ///
+    /// # Reentrancy Protection
+    /// Protected with reentrancy guard
+    pub fn claim_auction_winnings(e: Env, winner: Address, token_id: u32) -> Result<(), MarketplaceError> {
+        // Reentrancy protection
+        let guard: bool = e.storage()
+            .temporary()
+            .has(&DataKey::ReentrancyGuard(token_id));
+        if guard {
+            return Err(MarketplaceError::ReentrancyDetected);
+        }
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(token_id), &true);
+        EmergencyControl::require_not_emergency(&e);
+
+        // CHECKS
+        winner.require_auth();
+
+        let recorded: Address = e.storage()
+            .persistent()
+            .get(&DataKey::PendingClaim(token_id))
+            .ok_or_else(|| {
+                e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+                MarketplaceError::NothingToClaim
+            })?;
+
+        if recorded != winner {
+            e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+            return Err(MarketplaceError::NotAuthorized);
+        }
+
+        // EFFECTS
+        e.storage().persistent().remove(&DataKey::PendingClaim(token_id));
+
+        // INTERACTIONS
+        // Transfer NFT to winner
+        // Note: Use NFT contract client in production
+
+        // Clear reentrancy guard
+        e.storage().temporary().remove(&DataKey::ReentrancyGuard(token_id));
+
+        e.events().publish(
+            (symbol_short!("AucClaim"), token_id),
+            winner,
+        );
+
+        Ok(())
+    }
+
+    /// Get the winner still owed the NFT from a finished auction, if any
+    pub fn get_pending_claim(e: Env, token_id: u32) -> Option<Address> {
+        e.storage().persistent().get(&DataKey::PendingClaim(token_id))
+    }
+
+    fn is_valid_increment(increment: &BidIncrement) -> bool {
+        increment.flat >= 0 && increment.bps <= 10000
+    }
+
+    /// Smallest amount a bid must add to the auction's current bid
+    fn required_increment(auction: &Auction) -> i128 {
+        let pct = (auction.current_bid * auction.min_increment.bps as i128) / 10000;
+        auction.min_increment.flat.max(pct).max(1)
+    }
+
+    /// Mark an auction ended, drop it from the active list and pay out the winning bid
+    fn settle_auction(e: &Env, mut auction: Auction, fee_basis_points: u32, fee_recipient: &Address) {
+        let token_id = auction.token_id;
+
+        // EFFECTS
+        auction.ended = true;
+        e.storage().persistent().set(&DataKey::Auction(token_id), &auction);
+
+        Self::remove_active_auction(e, token_id);
+
+        if let Some(winner) = &auction.highest_bidder {
+            e.storage().persistent().set(&DataKey::PendingClaim(token_id), winner);
         }
-        e.storage().instance().set(&DataKey::ActiveAuctions, &active_auctions);
 
         // INTERACTIONS
         if let Some(winner) = auction.highest_bidder {
             // Calculate fees
-            let marketplace_fee = (auction.current_bid * fee_basis_points as i128) / 10000;
+            let marketplace_fee =
+                Self::sale_fee(e, &auction.seller, &winner, auction.current_bid, fee_basis_points);
             let seller_proceeds = auction.current_bid - marketplace_fee;
 
-            let payment_token_client = token::Client::new(&e, &auction.payment_token);
+            Self::record_sale(e, &auction.payment_token, auction.current_bid, marketplace_fee);
+            Self::adjust_escrow(e, token_id, &auction.payment_token, -auction.current_bid);
+
+            let payment_token_client = token::Client::new(e, &auction.payment_token);
 
             // Transfer payment from escrow to seller
             payment_token_client.transfer(&e.current_contract_address(), &auction.seller, &seller_proceeds);
 
             // Transfer fee
             if marketplace_fee > 0 {
-                payment_token_client.transfer(&e.current_contract_address(), &fee_recipient, &marketplace_fee);
+                payment_token_client.transfer(&e.current_contract_address(), fee_recipient, &marketplace_fee);
             }
 
-            // Transfer NFT to winner
-            // Note: Use NFT contract client in production
-
-            // Clear reentrancy guard
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
-
-            // Emit event
             e.events().publish(
                 (symbol_short!("AucEnd"), token_id),
-                (winner, auction.current_bid),
+                (
+                    winner,
+                    auction.current_bid,
+                    Self::get_payment_token_decimals(e.clone(), auction.payment_token.clone()),
+                ),
             );
         } else {
             // No bids - return NFT to seller
-
-            // Clear reentrancy guard
-            e.storage().instance().set(&DataKey::ReentrancyGuard, &false);
-
             e.events().publish(
                 (symbol_short!("AucNoBid"), token_id),
                 auction.seller,
             );
         }
+    }
 
-        Ok(())
+    /// Marketplace fee on a sale, waived if the seller is exempt, or the buyer when
+    /// buyer exemption is enabled
+    fn sale_fee(e: &Env, seller: &Address, buyer: &Address, price: i128, fee_basis_points: u32) -> i128 {
+        let storage = e.storage().persistent();
+        let exempt = storage.has(&DataKey::FeeExempt(seller.clone()))
+            || (e.storage().instance().get(&DataKey::BuyerFeeExemption).unwrap_or(false)
+                && storage.has(&DataKey::FeeExempt(buyer.clone())));
+        if exempt {
+            0
+        } else {
+            (price * fee_basis_points as i128) / 10000
+        }
+    }
+
+    /// Current holder of `token_id` according to the NFT contract
+    fn nft_owner(e: &Env, token_id: u32) -> Result<Address, MarketplaceError> {
+        let nft_contract: Address = e.storage()
+            .instance()
+            .get(&DataKey::NFTContract)
+            .ok_or(MarketplaceError::NotInitialized)?;
+        call::try_invoke1(e, &nft_contract, "owner_of", token_id)
+            .map_err(|_| MarketplaceError::NFTContractError)
+    }
+
+    /// Add a completed sale to the cumulative marketplace stats
+    fn record_sale(e: &Env, payment_token: &Address, price: i128, fee: i128) {
+        let storage = e.storage().instance();
+        let volume: i128 = storage.get(&DataKey::TotalVolume).unwrap_or(0);
+        storage.set(&DataKey::TotalVolume, &(volume + price));
+        let fees: i128 = storage.get(&DataKey::TotalFeesCollected).unwrap_or(0);
+        storage.set(&DataKey::TotalFeesCollected, &(fees + fee));
+        let sales: u64 = storage.get(&DataKey::TotalSales).unwrap_or(0);
+        storage.set(&DataKey::TotalSales, &(sales + 1));
+
+        let token_key = DataKey::TokenVolume(payment_token.clone());
+        let token_volume: i128 = e.storage().persistent().get(&token_key).unwrap_or(0);
+        e.storage().persistent().set(&token_key, &(token_volume + price));
+    }
+
+    /// Move the per-auction and per-payment-token escrow counters by `delta`
+    fn adjust_escrow(e: &Env, token_id: u32, payment_token: &Address, delta: i128) {
+        let storage = e.storage().persistent();
+        let escrowed: i128 = storage.get(&DataKey::Escrow(token_id)).unwrap_or(0);
+        storage.set(&DataKey::Escrow(token_id), &(escrowed + delta));
+
+        let total_key = DataKey::TotalEscrow(payment_token.clone());
+        let total: i128 = storage.get(&total_key).unwrap_or(0);
+        storage.set(&total_key, &(total + delta));
+    }
+
+    fn remove_active_auction(e: &Env, token_id: u32) {
+        let mut active_auctions: Vec<u32> = e.storage()
+            .instance()
+            .get(&DataKey::ActiveAuctions)
+            .unwrap_or(Vec::new(e));
+        if let Some(index) = active_auctions.iter().position(|id| id == token_id) {
+            active_auctions.remove(index as u32);
+        }
+        e.storage().instance().set(&DataKey::ActiveAuctions, &active_auctions);
     }
 
     /// Get auction details
@@ -965,6 +1976,60 @@ impl CommitmentMarketplace {
 
         auctions
     }
+
+    // ========================================================================
+    // Stats
+    // ========================================================================
+
+    /// Get cumulative marketplace stats as (total volume, total fees, total sales)
+    ///
+    /// Volume sums sale prices across all payment tokens; use `get_token_volume`
+    /// for a per-token breakdown.
+    pub fn get_marketplace_stats(e: Env) -> (i128, i128, u64) {
+        let storage = e.storage().instance();
+        (
+            storage.get(&DataKey::TotalVolume).unwrap_or(0),
+            storage.get(&DataKey::TotalFeesCollected).unwrap_or(0),
+            storage.get(&DataKey::TotalSales).unwrap_or(0),
+        )
+    }
+
+    /// Get cumulative sale volume settled in `payment_token`
+    pub fn get_token_volume(e: Env, payment_token: Address) -> i128 {
+        e.storage()
+            .persistent()
+            .get(&DataKey::TokenVolume(payment_token))
+            .unwrap_or(0)
+    }
+
+    /// Get the bid amount currently held in escrow for an auction
+    pub fn get_escrowed_for_token(e: Env, token_id: u32) -> i128 {
+        e.storage()
+            .persistent()
+            .get(&DataKey::Escrow(token_id))
+            .unwrap_or(0)
+    }
+
+    /// Get the total amount held in escrow across auctions in `payment_token`
+    pub fn get_total_escrow(e: Env, payment_token: Address) -> i128 {
+        e.storage()
+            .persistent()
+            .get(&DataKey::TotalEscrow(payment_token))
+            .unwrap_or(0)
+    }
+
+    /// Get a payment token's decimals, so sale event amounts are self-describing
+    ///
+    /// Read from the token's `decimals()` on first use and cached afterwards.
+    pub fn get_payment_token_decimals(e: Env, payment_token: Address) -> u32 {
+        let key = DataKey::TokenDecimals(payment_token.clone());
+        if let Some(decimals) = e.storage().persistent().get(&key) {
+            return decimals;
+        }
+        let decimals = token::Client::new(&e, &payment_token).decimals();
+        e.storage().persistent().set(&key, &decimals);
+        decimals
+    }
 }
 
 #[cfg(all(test, feature = "benchmark"))]
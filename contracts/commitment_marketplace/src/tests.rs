@@ -4,7 +4,7 @@ extern crate std;
 
 use crate::*;
 use soroban_sdk::{
-    symbol_short,
+    contract, contractimpl, symbol_short,
     testutils::{Address as _, Events, Ledger},
     Address, Env, vec, IntoVal,
 };
@@ -18,8 +18,7 @@ fn setup_marketplace(e: &Env) -> (Address, Address, CommitmentMarketplaceClient<
     let nft_contract = Address::generate(e);
     let fee_recipient = Address::generate(e);
 
-    // Use register instead of register_contract
-    let marketplace_id = e.register(CommitmentMarketplace, ());
+    let marketplace_id = e.register_contract(None, CommitmentMarketplace);
     let client = CommitmentMarketplaceClient::new(e, &marketplace_id);
 
     client.initialize(&admin, &nft_contract, &250, &fee_recipient); // 2.5% fee
@@ -46,7 +45,7 @@ fn test_initialize_marketplace() {
     let nft_contract = Address::generate(&e);
     let fee_recipient = Address::generate(&e);
 
-    let marketplace_id = e.register(CommitmentMarketplace, ());
+    let marketplace_id = e.register_contract(None, CommitmentMarketplace);
     let client = CommitmentMarketplaceClient::new(&e, &marketplace_id);
 
     client.initialize(&admin, &nft_contract, &250, &fee_recipient);
@@ -354,7 +353,7 @@ fn test_start_auction_zero_price_fails() {
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
-    client.start_auction(&seller, &1, &0, &86400, &payment_token);
+    client.start_auction(&seller, &1, &0, &86400, &payment_token, &None, &None);
 }
 
 #[test]
@@ -368,7 +367,7 @@ fn test_start_auction_zero_duration_fails() {
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
-    client.start_auction(&seller, &1, &1000, &0, &payment_token);
+    client.start_auction(&seller, &1, &1000, &0, &payment_token, &None, &None);
 }
 
 #[test]
@@ -385,7 +384,7 @@ fn test_place_bid() {
     let starting_price = 1000_0000000i128;
     let _bid_amount = 1200_0000000i128;
 
-    client.start_auction(&seller, &token_id, &starting_price, &86400, &payment_token);
+    client.start_auction(&seller, &token_id, &starting_price, &86400, &payment_token, &None, &None);
 
     // Note: In real test, setup token contract and balances
     // client.place_bid(&bidder, &token_id, &bid_amount);
@@ -407,7 +406,7 @@ fn test_place_bid_too_low_fails() {
     let payment_token = setup_test_token(&e);
     let token_id = 1u32;
 
-    client.start_auction(&seller, &token_id, &1000, &86400, &payment_token);
+    client.start_auction(&seller, &token_id, &1000, &86400, &payment_token, &None, &None);
     client.place_bid(&bidder, &token_id, &500); // Lower than starting price
 }
 
@@ -425,7 +424,7 @@ fn test_place_bid_after_auction_ends_fails() {
     let token_id = 1u32;
     let duration = 86400u64; // 1 day
 
-    client.start_auction(&seller, &token_id, &1000, &duration, &payment_token);
+    client.start_auction(&seller, &token_id, &1000, &duration, &payment_token, &None, &None);
 
     // Fast forward time past auction end
     e.ledger().with_mut(|li| {
@@ -446,7 +445,7 @@ fn test_end_auction_before_time_fails() {
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
-    client.start_auction(&seller, &1, &1000, &86400, &payment_token);
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &None);
     client.end_auction(&1); // Try to end immediately
 }
 
@@ -461,7 +460,7 @@ fn test_end_auction_twice_fails() {
     let seller = Address::generate(&e);
     let payment_token = setup_test_token(&e);
 
-    client.start_auction(&seller, &1, &1000, &86400, &payment_token);
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &None);
 
     e.ledger().with_mut(|li| {
         li.timestamp = 86400 + 1;
@@ -482,14 +481,694 @@ fn test_get_all_auctions() {
     let payment_token = setup_test_token(&e);
 
     // Start 3 auctions
-    client.start_auction(&seller, &1, &1000, &86400, &payment_token);
-    client.start_auction(&seller, &2, &2000, &86400, &payment_token);
-    client.start_auction(&seller, &3, &3000, &86400, &payment_token);
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &None);
+    client.start_auction(&seller, &2, &2000, &86400, &payment_token, &None, &None);
+    client.start_auction(&seller, &3, &3000, &86400, &payment_token, &None, &None);
 
     let auctions = client.get_all_auctions();
     assert_eq!(auctions.len(), 3);
 }
 
+fn setup_funded_token(e: &Env, holder: &Address) -> Address {
+    let token = e
+        .register_stellar_asset_contract_v2(Address::generate(e))
+        .address();
+    token::StellarAssetClient::new(e, &token).mint(holder, &1_000_000);
+    token
+}
+
+#[test]
+fn test_bid_at_flat_increment_succeeds() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+    client.set_min_bid_increment(&BidIncrement { flat: 100, bps: 0 });
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &bidder);
+
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &None);
+    assert_eq!(
+        client.try_place_bid(&bidder, &1, &1099),
+        Err(Ok(MarketplaceError::BidTooLow))
+    );
+
+    client.place_bid(&bidder, &1, &1100);
+    assert_eq!(client.get_auction(&1).current_bid, 1100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")] // BidTooLow
+fn test_bid_below_bps_increment_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+    // 5% of the current bid, which outweighs the flat step here
+    client.set_min_bid_increment(&BidIncrement { flat: 10, bps: 500 });
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &bidder);
+
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &None);
+    client.place_bid(&bidder, &1, &1049);
+}
+
+#[test]
+fn test_auction_increment_override() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+    client.set_min_bid_increment(&BidIncrement { flat: 500, bps: 0 });
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &bidder);
+
+    let increment = BidIncrement { flat: 10, bps: 0 };
+    client.start_auction(
+        &seller,
+        &1,
+        &1000,
+        &86400,
+        &payment_token,
+        &Some(increment.clone()),
+        &None,
+    );
+    assert_eq!(client.get_auction(&1).min_increment, increment);
+
+    client.place_bid(&bidder, &1, &1010);
+    assert_eq!(client.get_auction(&1).current_bid, 1010);
+}
+
+#[test]
+fn test_set_min_bid_increment_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+    assert_eq!(client.get_min_bid_increment(), BidIncrement { flat: 0, bps: 0 });
+
+    assert_eq!(
+        client.try_set_min_bid_increment(&BidIncrement { flat: -1, bps: 0 }),
+        Err(Ok(MarketplaceError::InvalidBidIncrement))
+    );
+    assert_eq!(
+        client.try_set_min_bid_increment(&BidIncrement { flat: 0, bps: 10001 }),
+        Err(Ok(MarketplaceError::InvalidBidIncrement))
+    );
+}
+
+#[test]
+fn test_anti_snipe_extensions_stop_at_cap() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+    client.set_anti_snipe(&AntiSnipe {
+        window_seconds: 300,
+        extension_seconds: 300,
+        max_extensions: 2,
+    });
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &bidder);
+
+    client.start_auction(&seller, &1, &1000, &3600, &payment_token, &None, &None);
+
+    // Outside the window: no extension
+    e.ledger().with_mut(|li| li.timestamp = 3100);
+    client.place_bid(&bidder, &1, &1100);
+    assert_eq!(client.get_auction(&1).ends_at, 3600);
+
+    e.ledger().with_mut(|li| li.timestamp = 3500);
+    client.place_bid(&bidder, &1, &1200);
+    let auction = client.get_auction(&1);
+    assert_eq!((auction.ends_at, auction.extension_count), (3900, 1));
+
+    e.ledger().with_mut(|li| li.timestamp = 3800);
+    client.place_bid(&bidder, &1, &1300);
+    let auction = client.get_auction(&1);
+    assert_eq!((auction.ends_at, auction.extension_count), (4200, 2));
+
+    // Cap reached: the auction ends on schedule
+    e.ledger().with_mut(|li| li.timestamp = 4199);
+    client.place_bid(&bidder, &1, &1400);
+    let auction = client.get_auction(&1);
+    assert_eq!((auction.ends_at, auction.extension_count), (4200, 2));
+
+    e.ledger().with_mut(|li| li.timestamp = 4200);
+    client.end_auction(&1);
+    assert_eq!(client.get_pending_claim(&1), Some(bidder));
+}
+
+#[test]
+fn test_set_anti_snipe_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+    assert_eq!(
+        client.get_anti_snipe(),
+        AntiSnipe { window_seconds: 0, extension_seconds: 0, max_extensions: 10 }
+    );
+
+    assert_eq!(
+        client.try_set_anti_snipe(&AntiSnipe {
+            window_seconds: 300,
+            extension_seconds: 0,
+            max_extensions: 5,
+        }),
+        Err(Ok(MarketplaceError::InvalidAntiSnipe))
+    );
+}
+
+#[test]
+fn test_start_auction_duration_bounds() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+    assert_eq!(client.get_min_auction_duration(), 3600);
+    assert_eq!(client.get_max_auction_duration(), 2_592_000);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    assert_eq!(
+        client.try_start_auction(&seller, &1, &1000, &3599, &payment_token, &None, &None),
+        Err(Ok(MarketplaceError::InvalidDuration))
+    );
+    assert_eq!(
+        client.try_start_auction(&seller, &1, &1000, &2_592_001, &payment_token, &None, &None),
+        Err(Ok(MarketplaceError::InvalidDuration))
+    );
+    client.start_auction(&seller, &1, &1000, &3600, &payment_token, &None, &None);
+    client.start_auction(&seller, &2, &1000, &2_592_000, &payment_token, &None, &None);
+}
+
+#[test]
+fn test_set_auction_duration_limits() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+    assert_eq!(
+        client.try_set_auction_duration_limits(&0, &100),
+        Err(Ok(MarketplaceError::InvalidDuration))
+    );
+    assert_eq!(
+        client.try_set_auction_duration_limits(&200, &100),
+        Err(Ok(MarketplaceError::InvalidDuration))
+    );
+
+    client.set_auction_duration_limits(&60, &600);
+    assert_eq!(client.get_min_auction_duration(), 60);
+    assert_eq!(client.get_max_auction_duration(), 600);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+    client.start_auction(&seller, &1, &1000, &60, &payment_token, &None, &None);
+    client.start_auction(&seller, &2, &1000, &600, &payment_token, &None, &None);
+    assert_eq!(
+        client.try_start_auction(&seller, &3, &1000, &601, &payment_token, &None, &None),
+        Err(Ok(MarketplaceError::InvalidDuration))
+    );
+}
+
+#[test]
+fn test_buy_now_ends_live_auction_and_refunds_bidder() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, fee_recipient, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &bidder);
+    token::StellarAssetClient::new(&e, &payment_token).mint(&buyer, &1_000_000);
+    let token_client = token::Client::new(&e, &payment_token);
+
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &Some(2000));
+    client.place_bid(&bidder, &1, &1500);
+    assert_eq!(token_client.balance(&bidder), 1_000_000 - 1500);
+
+    client.buy_now(&buyer, &1);
+
+    let auction = client.get_auction(&1);
+    assert!(auction.ended);
+    assert_eq!(auction.current_bid, 2000);
+    assert_eq!(auction.highest_bidder, Some(buyer.clone()));
+    assert_eq!(client.get_all_auctions().len(), 0);
+
+    // Prior bidder refunded in full; 2.5% fee taken from the buy-now price
+    assert_eq!(token_client.balance(&bidder), 1_000_000);
+    assert_eq!(token_client.balance(&buyer), 1_000_000 - 2000);
+    assert_eq!(token_client.balance(&seller), 1950);
+    assert_eq!(token_client.balance(&fee_recipient), 50);
+    assert_eq!(token_client.balance(&client.address), 0);
+
+    assert_eq!(
+        client.try_place_bid(&bidder, &1, &2500),
+        Err(Ok(MarketplaceError::AuctionEnded))
+    );
+}
+
+#[test]
+fn test_buy_now_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &buyer);
+
+    assert_eq!(
+        client.try_start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &Some(1000)),
+        Err(Ok(MarketplaceError::InvalidPrice))
+    );
+
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &None);
+    assert_eq!(
+        client.try_buy_now(&buyer, &1),
+        Err(Ok(MarketplaceError::BuyNowNotAvailable))
+    );
+
+    client.start_auction(&seller, &2, &1000, &3600, &payment_token, &None, &Some(1500));
+    e.ledger().with_mut(|li| li.timestamp += 3600);
+    assert_eq!(
+        client.try_buy_now(&buyer, &2),
+        Err(Ok(MarketplaceError::AuctionEnded))
+    );
+}
+
+#[test]
+fn test_sweep_ended_auctions_settles_only_expired() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, fee_recipient, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let payment_token = e
+        .register_stellar_asset_contract_v2(Address::generate(&e))
+        .address();
+    token::StellarAssetClient::new(&e, &payment_token).mint(&bidder, &10_000);
+
+    // Three short auctions and one that is still live after the sweep
+    client.start_auction(&seller, &1, &1000, &3600, &payment_token, &None, &None);
+    client.start_auction(&seller, &2, &1000, &7200, &payment_token, &None, &None);
+    client.start_auction(&seller, &3, &1000, &10800, &payment_token, &None, &None);
+    client.start_auction(&seller, &4, &1000, &86400, &payment_token, &None, &None);
+    client.place_bid(&bidder, &2, &2000);
+
+    e.ledger().with_mut(|li| {
+        li.timestamp = 20_000;
+    });
+
+    assert_eq!(client.sweep_ended_auctions(&10), 3);
+
+    for token_id in 1..=3u32 {
+        assert!(client.get_auction(&token_id).ended);
+    }
+    assert!(!client.get_auction(&4).ended);
+    let active = client.get_all_auctions();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active.get(0).unwrap().token_id, 4);
+
+    // Winning bid settled with the 2.5% marketplace fee
+    let payment = token::Client::new(&e, &payment_token);
+    assert_eq!(payment.balance(&seller), 1950);
+    assert_eq!(payment.balance(&fee_recipient), 50);
+
+    let last_event = e.events().all().last().unwrap();
+    assert_eq!(
+        last_event.1,
+        vec![&e, symbol_short!("AucSweep").into_val(&e)]
+    );
+
+    // Nothing left to sweep
+    assert_eq!(client.sweep_ended_auctions(&10), 0);
+}
+
+#[test]
+fn test_sweep_ended_auctions_respects_limit() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.start_auction(&seller, &1, &1000, &3600, &payment_token, &None, &None);
+    client.start_auction(&seller, &2, &1000, &3600, &payment_token, &None, &None);
+    client.start_auction(&seller, &3, &1000, &3600, &payment_token, &None, &None);
+
+    e.ledger().with_mut(|li| {
+        li.timestamp = 20_000;
+    });
+
+    assert_eq!(client.sweep_ended_auctions(&2), 2);
+    assert_eq!(client.get_all_auctions().len(), 1);
+    assert_eq!(client.sweep_ended_auctions(&2), 1);
+    assert_eq!(client.get_all_auctions().len(), 0);
+}
+
+#[test]
+fn test_end_auction_then_winner_claims() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &bidder);
+
+    client.start_auction(&seller, &1, &1000, &3600, &payment_token, &None, &None);
+    client.place_bid(&bidder, &1, &1500);
+    assert_eq!(client.get_pending_claim(&1), None);
+
+    e.ledger().with_mut(|li| li.timestamp += 3600);
+    client.end_auction(&1);
+
+    // Payment is settled at end_auction; the NFT waits for the winner
+    assert_eq!(token::Client::new(&e, &payment_token).balance(&seller), 1463);
+    assert_eq!(client.get_pending_claim(&1), Some(bidder.clone()));
+
+    client.claim_auction_winnings(&bidder, &1);
+    assert_eq!(client.get_pending_claim(&1), None);
+
+    let last_event = e.events().all().last().unwrap();
+    assert_eq!(
+        last_event.1,
+        vec![&e, symbol_short!("AucClaim").into_val(&e), 1u32.into_val(&e)]
+    );
+
+    assert_eq!(
+        client.try_claim_auction_winnings(&bidder, &1),
+        Err(Ok(MarketplaceError::NothingToClaim))
+    );
+}
+
+#[test]
+fn test_claim_auction_winnings_validation() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &bidder);
+
+    client.start_auction(&seller, &1, &1000, &3600, &payment_token, &None, &None);
+    client.place_bid(&bidder, &1, &1500);
+
+    // Nothing to claim while the auction is live
+    assert_eq!(
+        client.try_claim_auction_winnings(&bidder, &1),
+        Err(Ok(MarketplaceError::NothingToClaim))
+    );
+
+    e.ledger().with_mut(|li| li.timestamp += 3600);
+    client.end_auction(&1);
+
+    assert_eq!(
+        client.try_claim_auction_winnings(&seller, &1),
+        Err(Ok(MarketplaceError::NotAuthorized))
+    );
+    assert_eq!(client.get_pending_claim(&1), Some(bidder));
+}
+
+#[test]
+fn test_auction_without_bids_has_no_claim() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.start_auction(&seller, &1, &1000, &3600, &payment_token, &None, &None);
+    e.ledger().with_mut(|li| li.timestamp += 3600);
+    client.end_auction(&1);
+
+    assert_eq!(client.get_pending_claim(&1), None);
+    assert_eq!(
+        client.try_claim_auction_winnings(&seller, &1),
+        Err(Ok(MarketplaceError::NothingToClaim))
+    );
+}
+
+#[test]
+fn test_buy_now_buyer_claims() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &buyer);
+
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &Some(2000));
+    client.buy_now(&buyer, &1);
+    assert_eq!(client.get_pending_claim(&1), Some(buyer.clone()));
+
+    client.claim_auction_winnings(&buyer, &1);
+    assert_eq!(client.get_pending_claim(&1), None);
+}
+
+// ============================================================================
+// Emergency Mode Tests
+// ============================================================================
+
+#[test]
+fn test_set_emergency_mode() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, _, client) = setup_marketplace(&e);
+
+    assert!(!client.is_emergency_mode());
+    client.set_emergency_mode(&admin, &true);
+    assert!(client.is_emergency_mode());
+    client.set_emergency_mode(&admin, &false);
+    assert!(!client.is_emergency_mode());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #22)")] // NotAuthorized
+fn test_set_emergency_mode_non_admin_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let attacker = Address::generate(&e);
+    client.set_emergency_mode(&attacker, &true);
+}
+
+#[test]
+#[should_panic(expected = "Action not allowed in emergency mode")]
+fn test_buy_nft_blocked_in_emergency() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.list_nft(&seller, &1, &1000, &payment_token);
+    client.set_emergency_mode(&admin, &true);
+    client.buy_nft(&buyer, &1);
+}
+
+#[test]
+#[should_panic(expected = "Action not allowed in emergency mode")]
+fn test_make_offer_blocked_in_emergency() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, _, client) = setup_marketplace(&e);
+
+    let offerer = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.set_emergency_mode(&admin, &true);
+    client.make_offer(&offerer, &1, &500, &payment_token);
+}
+
+#[test]
+#[should_panic(expected = "Action not allowed in emergency mode")]
+fn test_accept_offer_blocked_in_emergency() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let offerer = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.make_offer(&offerer, &1, &500, &payment_token);
+    client.set_emergency_mode(&admin, &true);
+    client.accept_offer(&seller, &1, &offerer);
+}
+
+#[test]
+#[should_panic(expected = "Action not allowed in emergency mode")]
+fn test_start_auction_blocked_in_emergency() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.set_emergency_mode(&admin, &true);
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "Action not allowed in emergency mode")]
+fn test_place_bid_blocked_in_emergency() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &None);
+    client.set_emergency_mode(&admin, &true);
+    client.place_bid(&bidder, &1, &1500);
+}
+
+#[test]
+#[should_panic(expected = "Action not allowed in emergency mode")]
+fn test_end_auction_blocked_in_emergency() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.start_auction(&seller, &1, &1000, &86400, &payment_token, &None, &None);
+    e.ledger().with_mut(|li| {
+        li.timestamp = 86400 + 1;
+    });
+    client.set_emergency_mode(&admin, &true);
+    client.end_auction(&1);
+}
+
+#[test]
+fn test_cancels_allowed_in_emergency() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let offerer = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.list_nft(&seller, &1, &1000, &payment_token);
+    client.make_offer(&offerer, &2, &500, &payment_token);
+
+    client.set_emergency_mode(&admin, &true);
+
+    client.cancel_listing(&seller, &1);
+    client.cancel_offer(&offerer, &2);
+
+    assert_eq!(client.get_all_listings().len(), 0);
+    assert_eq!(client.get_offers(&2).len(), 0);
+}
+
+// ============================================================================
+// Stats Tests
+// ============================================================================
+
+#[test]
+fn test_marketplace_stats_start_at_zero() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (_, _, client) = setup_marketplace(&e);
+
+    assert_eq!(client.get_marketplace_stats(), (0, 0, 0));
+    assert_eq!(client.get_token_volume(&Address::generate(&e)), 0);
+}
+
+#[test]
+fn test_marketplace_stats_track_sales() {
+    let e = Env::default();
+    // accept_offer pulls payment from the offerer inside the seller's call
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &buyer);
+    let other_token = setup_funded_token(&e, &buyer);
+
+    // Fixed-price sale: 2.5% of 1000
+    client.list_nft(&seller, &1, &1000, &payment_token);
+    client.buy_nft(&buyer, &1);
+    assert_eq!(client.get_marketplace_stats(), (1000, 25, 1));
+
+    // Accepted offer in a second payment token
+    client.make_offer(&buyer, &2, &2000, &other_token);
+    client.accept_offer(&seller, &2, &buyer);
+    assert_eq!(client.get_marketplace_stats(), (3000, 75, 2));
+
+    // Settled auction
+    client.start_auction(&seller, &3, &1000, &3600, &payment_token, &None, &None);
+    client.place_bid(&buyer, &3, &1600);
+    e.ledger().with_mut(|li| li.timestamp += 3600);
+    client.end_auction(&3);
+    assert_eq!(client.get_marketplace_stats(), (4600, 115, 3));
+
+    assert_eq!(client.get_token_volume(&payment_token), 2600);
+    assert_eq!(client.get_token_volume(&other_token), 2000);
+}
+
+#[test]
+fn test_marketplace_stats_skip_auction_without_bids() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.start_auction(&seller, &1, &1000, &3600, &payment_token, &None, &None);
+    e.ledger().with_mut(|li| li.timestamp += 3600);
+    client.end_auction(&1);
+
+    assert_eq!(client.get_marketplace_stats(), (0, 0, 0));
+}
+
 // ============================================================================
 // Edge Cases and Integration Tests
 // ============================================================================
@@ -512,7 +1191,7 @@ fn test_list_then_start_auction_same_token() {
     client.cancel_listing(&seller, &token_id);
 
     // Now start auction (should work)
-    client.start_auction(&seller, &token_id, &1000, &86400, &payment_token);
+    client.start_auction(&seller, &token_id, &1000, &86400, &payment_token, &None, &None);
 
     let auction = client.get_auction(&token_id);
     assert_eq!(auction.token_id, token_id);
@@ -556,4 +1235,426 @@ fn test_gas_listing_operations() {
 
     // In production, you'd log or assert gas usage
     assert_eq!(client.get_all_listings().len(), 10);
-}
\ No newline at end of file
+}
+#[test]
+fn test_sale_event_includes_payment_token_decimals() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &buyer);
+
+    client.list_nft(&seller, &1, &1000, &payment_token);
+    client.buy_nft(&buyer, &1);
+
+    let last_event = e.events().all().last().unwrap();
+    assert_eq!(
+        last_event.1,
+        vec![&e, symbol_short!("NFTSold").into_val(&e), 1u32.into_val(&e)]
+    );
+    let data: (Address, Address, i128, u32) = last_event.2.into_val(&e);
+    assert_eq!(data, (seller, buyer, 1000, 7));
+
+    // Stellar asset contracts report 7 decimals; the value is cached after the first sale
+    assert_eq!(client.get_payment_token_decimals(&payment_token), 7);
+}
+
+#[test]
+fn test_escrow_view_tracks_bids_and_refunds() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let bidder1 = Address::generate(&e);
+    let bidder2 = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &bidder1);
+    token::StellarAssetClient::new(&e, &payment_token).mint(&bidder2, &1_000_000);
+
+    client.start_auction(&seller, &1, &1000, &3600, &payment_token, &None, &None);
+    client.start_auction(&seller, &2, &1000, &3600, &payment_token, &None, &None);
+    assert_eq!(client.get_total_escrow(&payment_token), 0);
+
+    // Outbidding refunds the previous bid, so only the highest bid stays escrowed
+    client.place_bid(&bidder1, &1, &1100);
+    client.place_bid(&bidder2, &1, &1300);
+    client.place_bid(&bidder1, &2, &1200);
+    assert_eq!(client.get_escrowed_for_token(&1), 1300);
+    assert_eq!(client.get_escrowed_for_token(&2), 1200);
+    assert_eq!(client.get_total_escrow(&payment_token), 2500);
+
+    // The views match the tokens the contract actually holds
+    let payment = token::Client::new(&e, &payment_token);
+    assert_eq!(payment.balance(&client.address), 2500);
+
+    // Settlement releases the winning bid
+    e.ledger().with_mut(|li| li.timestamp += 3600);
+    client.end_auction(&1);
+    assert_eq!(client.get_escrowed_for_token(&1), 0);
+    assert_eq!(client.get_total_escrow(&payment_token), 1200);
+
+    client.end_auction(&2);
+    assert_eq!(client.get_total_escrow(&payment_token), 0);
+    assert_eq!(payment.balance(&client.address), 0);
+}
+
+#[test]
+fn test_buy_now_releases_escrowed_bid() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &bidder);
+    token::StellarAssetClient::new(&e, &payment_token).mint(&buyer, &1_000_000);
+
+    client.start_auction(&seller, &1, &1000, &3600, &payment_token, &None, &Some(5000));
+    client.place_bid(&bidder, &1, &1500);
+    assert_eq!(client.get_escrowed_for_token(&1), 1500);
+
+    // The buyer pays the seller directly; the refunded bid leaves escrow
+    client.buy_now(&buyer, &1);
+    assert_eq!(client.get_escrowed_for_token(&1), 0);
+    assert_eq!(client.get_total_escrow(&payment_token), 0);
+}
+
+// ============================================================================
+// Fractional Offer Tests
+// ============================================================================
+
+/// Minimal NFT contract tracking token owners for custody checks
+#[contract]
+pub struct MockNft;
+
+#[contractimpl]
+impl MockNft {
+    pub fn mint(e: Env, to: Address, token_id: u32) {
+        e.storage().persistent().set(&token_id, &to);
+    }
+
+    pub fn owner_of(e: Env, token_id: u32) -> Address {
+        e.storage().persistent().get(&token_id).unwrap()
+    }
+
+    pub fn transfer(e: Env, from: Address, to: Address, token_id: u32) {
+        from.require_auth();
+        assert_eq!(Self::owner_of(e.clone(), token_id), from);
+        e.storage().persistent().set(&token_id, &to);
+    }
+}
+
+/// Marketplace backed by a `MockNft` in which `holder` owns token 1
+fn setup_fractional_marketplace<'a>(
+    e: &Env,
+    holder: &Address,
+) -> (Address, CommitmentMarketplaceClient<'a>, MockNftClient<'a>) {
+    let nft = MockNftClient::new(e, &e.register_contract(None, MockNft));
+    nft.mint(holder, &1);
+
+    let fee_recipient = Address::generate(e);
+    let client = CommitmentMarketplaceClient::new(e, &e.register_contract(None, CommitmentMarketplace));
+    client.initialize(&Address::generate(e), &nft.address, &250, &fee_recipient);
+
+    (fee_recipient, client, nft)
+}
+
+#[test]
+fn test_fractional_accept_half_offer() {
+    let e = Env::default();
+    // fractional_accept pulls payment from the offerer inside the seller's call
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let seller = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let (fee_recipient, client, nft) = setup_fractional_marketplace(&e, &seller);
+    let payment_token = setup_funded_token(&e, &buyer);
+
+    client.enable_fractional(&1, &seller);
+    assert_eq!(nft.owner_of(&1), client.address);
+    client.make_fractional_offer(&buyer, &1, &5000, &2000, &payment_token);
+
+    // Fill half of the 50% offer, then the rest
+    client.fractional_accept(&seller, &1, &buyer, &2500);
+    assert_eq!(client.get_fraction(&1, &seller), 7500);
+    assert_eq!(client.get_fraction(&1, &buyer), 2500);
+
+    let remaining = client.get_fractional_offers(&1).get(0).unwrap();
+    assert_eq!(remaining.fraction_bps, 2500);
+    assert_eq!(remaining.amount, 1000);
+
+    client.fractional_accept(&seller, &1, &buyer, &2500);
+    assert_eq!(client.get_fraction(&1, &seller), 5000);
+    assert_eq!(client.get_fraction(&1, &buyer), 5000);
+    assert_eq!(client.get_fractional_offers(&1).len(), 0);
+
+    // 2000 paid in total, 2.5% to the fee recipient
+    let payment = token::Client::new(&e, &payment_token);
+    assert_eq!(payment.balance(&seller), 1950);
+    assert_eq!(payment.balance(&fee_recipient), 50);
+    assert_eq!(client.get_marketplace_stats(), (2000, 50, 2));
+}
+
+#[test]
+fn test_fractional_offer_rejected_for_whole_token() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let offerer = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    let result = client.try_make_fractional_offer(&offerer, &1, &5000, &1000, &payment_token);
+    assert_eq!(result, Err(Ok(MarketplaceError::NotFractional)));
+
+    client.make_offer(&offerer, &1, &1000, &payment_token);
+    let result = client.try_fractional_accept(&seller, &1, &offerer, &5000);
+    assert_eq!(result, Err(Ok(MarketplaceError::NotFractional)));
+}
+
+#[test]
+fn test_fractional_accept_requires_seller_holding() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let holder = Address::generate(&e);
+    let (_, client, nft) = setup_fractional_marketplace(&e, &holder);
+    let other = Address::generate(&e);
+    let offerer = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+
+    client.enable_fractional(&1, &holder);
+    client.make_fractional_offer(&offerer, &1, &5000, &1000, &payment_token);
+
+    let result = client.try_fractional_accept(&other, &1, &offerer, &5000);
+    assert_eq!(result, Err(Ok(MarketplaceError::InsufficientFraction)));
+
+    let result = client.try_fractional_accept(&holder, &1, &offerer, &6000);
+    assert_eq!(result, Err(Ok(MarketplaceError::InvalidFraction)));
+
+    // Fractional offers cannot be taken whole through accept_offer
+    let result = client.try_accept_offer(&holder, &1, &offerer);
+    assert_eq!(result, Err(Ok(MarketplaceError::TokenIsFractional)));
+
+    // Fractions stop trading once the NFT leaves marketplace custody
+    nft.transfer(&client.address, &other, &1);
+    let result = client.try_fractional_accept(&holder, &1, &offerer, &5000);
+    assert_eq!(result, Err(Ok(MarketplaceError::NotTokenOwner)));
+
+    // Whole-token offers keep their own list
+    assert_eq!(client.get_offers(&1).len(), 0);
+    client.cancel_fractional_offer(&offerer, &1);
+    assert_eq!(client.get_fractional_offers(&1).len(), 0);
+}
+
+#[test]
+fn test_enable_fractional_requires_nft_owner() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let owner = Address::generate(&e);
+    let (_, client, nft) = setup_fractional_marketplace(&e, &owner);
+
+    let result = client.try_enable_fractional(&1, &Address::generate(&e));
+    assert_eq!(result, Err(Ok(MarketplaceError::NotTokenOwner)));
+    assert!(!client.is_fractional(&1));
+    assert_eq!(nft.owner_of(&1), owner);
+}
+
+#[test]
+fn test_fractional_token_cannot_be_sold_whole() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let holder = Address::generate(&e);
+    let offerer = Address::generate(&e);
+    let (_, client, _) = setup_fractional_marketplace(&e, &holder);
+    let payment_token = setup_funded_token(&e, &offerer);
+
+    // A whole offer made before fractionalization can no longer be filled
+    client.make_offer(&offerer, &1, &1000, &payment_token);
+    client.enable_fractional(&1, &holder);
+    assert_eq!(
+        client.try_accept_offer(&holder, &1, &offerer),
+        Err(Ok(MarketplaceError::TokenIsFractional))
+    );
+
+    assert_eq!(
+        client.try_list_nft(&holder, &1, &1000, &payment_token),
+        Err(Ok(MarketplaceError::TokenIsFractional))
+    );
+    assert_eq!(
+        client.try_make_offer(&Address::generate(&e), &1, &1000, &payment_token),
+        Err(Ok(MarketplaceError::TokenIsFractional))
+    );
+    assert_eq!(
+        client.try_start_auction(&holder, &1, &1000, &3600, &payment_token, &None, &None),
+        Err(Ok(MarketplaceError::TokenIsFractional))
+    );
+}
+
+#[test]
+fn test_enable_fractional_rejected_while_listed() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let holder = Address::generate(&e);
+    let (_, client, nft) = setup_fractional_marketplace(&e, &holder);
+    let payment_token = setup_test_token(&e);
+
+    client.list_nft(&holder, &1, &1000, &payment_token);
+    assert_eq!(
+        client.try_enable_fractional(&1, &holder),
+        Err(Ok(MarketplaceError::ListingExists))
+    );
+    assert_eq!(nft.owner_of(&1), holder);
+}
+
+// ============================================================================
+// Fee Exemption Tests
+// ============================================================================
+
+#[test]
+fn test_exempt_seller_pays_no_fee() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, fee_recipient, client) = setup_marketplace(&e);
+
+    let partner = Address::generate(&e);
+    let seller = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &buyer);
+    let payment = token::Client::new(&e, &payment_token);
+
+    client.set_fee_exempt(&partner, &true);
+    assert!(client.is_fee_exempt(&partner));
+    assert!(!client.is_fee_exempt(&seller));
+
+    client.list_nft(&partner, &1, &1000, &payment_token);
+    client.buy_nft(&buyer, &1);
+    assert_eq!(payment.balance(&partner), 1000);
+    assert_eq!(payment.balance(&fee_recipient), 0);
+
+    client.list_nft(&seller, &2, &1000, &payment_token);
+    client.buy_nft(&buyer, &2);
+    assert_eq!(payment.balance(&seller), 975);
+    assert_eq!(payment.balance(&fee_recipient), 25);
+
+    // Auction settlement honours the exemption too
+    client.start_auction(&partner, &3, &1000, &3600, &payment_token, &None, &None);
+    client.place_bid(&buyer, &3, &1100);
+    e.ledger().with_mut(|li| li.timestamp += 3600);
+    client.end_auction(&3);
+    assert_eq!(payment.balance(&partner), 2100);
+    assert_eq!(client.get_marketplace_stats(), (3100, 25, 3));
+
+    // Removing the exemption restores the fee
+    client.set_fee_exempt(&partner, &false);
+    assert!(!client.is_fee_exempt(&partner));
+    client.list_nft(&partner, &4, &1000, &payment_token);
+    client.buy_nft(&buyer, &4);
+    assert_eq!(payment.balance(&fee_recipient), 50);
+}
+
+#[test]
+fn test_exempt_buyer_requires_buyer_exemption_enabled() {
+    let e = Env::default();
+    // accept_offer pulls payment from the offerer inside the seller's call
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let (_, fee_recipient, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &buyer);
+    let payment = token::Client::new(&e, &payment_token);
+
+    client.set_fee_exempt(&buyer, &true);
+    assert!(!client.get_buyer_fee_exemption());
+
+    client.make_offer(&buyer, &1, &1000, &payment_token);
+    client.accept_offer(&seller, &1, &buyer);
+    assert_eq!(payment.balance(&fee_recipient), 25);
+
+    client.set_buyer_fee_exemption(&true);
+    client.make_offer(&buyer, &2, &1000, &payment_token);
+    client.accept_offer(&seller, &2, &buyer);
+    assert_eq!(payment.balance(&fee_recipient), 25);
+    assert_eq!(payment.balance(&seller), 1975);
+}
+
+// ============================================================================
+// Reentrancy Guard Scope Tests
+// ============================================================================
+
+/// Calls several marketplace operations from a single contract invocation
+#[contract]
+pub struct Composer;
+
+#[contractimpl]
+impl Composer {
+    pub fn list_and_bid(
+        e: Env,
+        marketplace: Address,
+        seller: Address,
+        bidder: Address,
+        payment_token: Address,
+    ) {
+        let client = CommitmentMarketplaceClient::new(&e, &marketplace);
+        client.list_nft(&seller, &1, &1000, &payment_token);
+        client.place_bid(&bidder, &2, &1500);
+    }
+}
+
+#[test]
+fn test_unrelated_ops_compose_in_one_call() {
+    let e = Env::default();
+    e.mock_all_auths_allowing_non_root_auth();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let bidder = Address::generate(&e);
+    let payment_token = setup_funded_token(&e, &bidder);
+    client.start_auction(&seller, &2, &1000, &3600, &payment_token, &None, &None);
+
+    let composer = e.register_contract(None, Composer);
+    ComposerClient::new(&e, &composer)
+        .list_and_bid(&client.address, &seller, &bidder, &payment_token);
+
+    assert_eq!(client.get_listing(&1).seller, seller);
+    assert_eq!(client.get_auction(&2).current_bid, 1500);
+}
+
+#[test]
+fn test_reentrancy_guard_is_scoped_to_token() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (_, _, client) = setup_marketplace(&e);
+
+    let seller = Address::generate(&e);
+    let payment_token = setup_test_token(&e);
+    client.list_nft(&seller, &1, &1000, &payment_token);
+
+    // Token 1 is mid-operation
+    e.as_contract(&client.address, || {
+        e.storage().temporary().set(&DataKey::ReentrancyGuard(1), &true);
+    });
+
+    assert_eq!(
+        client.try_cancel_listing(&seller, &1),
+        Err(Ok(MarketplaceError::ReentrancyDetected))
+    );
+    client.list_nft(&seller, &2, &1000, &payment_token);
+    assert_eq!(client.get_all_listings().len(), 2);
+}
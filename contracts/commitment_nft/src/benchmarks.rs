@@ -88,6 +88,7 @@ fn benchmark_mint() {
             1000_0000000,
             Address::generate(&e),
             10,
+            None,
         )
         .unwrap();
         let end = e.ledger().sequence();
@@ -114,6 +115,7 @@ fn benchmark_get_metadata() {
             1000_0000000,
             Address::generate(&e),
             10,
+            None,
         )
         .unwrap()
     });
@@ -147,6 +149,7 @@ fn benchmark_owner_of() {
             1000_0000000,
             Address::generate(&e),
             10,
+            None,
         )
         .unwrap()
     });
@@ -180,6 +183,7 @@ fn benchmark_balance_of() {
             1000_0000000,
             Address::generate(&e),
             10,
+            None,
         )
         .unwrap();
     });
@@ -217,6 +221,7 @@ fn benchmark_batch_mint() {
                 1000_0000000,
                 Address::generate(&e),
                 10,
+                None,
             )
             .unwrap();
         }
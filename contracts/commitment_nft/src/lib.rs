@@ -6,6 +6,15 @@ use soroban_sdk::{
 
 pub const CURRENT_VERSION: u32 = 1;
 
+/// Maximum number of items returned by a paged query
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+/// Maximum length in bytes of a base or token URI
+pub const MAX_URI_LENGTH: u32 = 256;
+
+/// Default duration multiplier used by `mint` to turn days into seconds
+pub const DEFAULT_SECONDS_PER_DAY: u64 = 86_400;
+
 // ============================================================================
 // Error Types
 // ============================================================================
@@ -49,6 +58,8 @@ pub enum ContractError {
     InvalidVersion = 16,
     /// Migration already applied
     AlreadyMigrated = 17,
+    /// Invalid URI (empty or too long)
+    InvalidUri = 18,
 }
 
 // ============================================================================
@@ -114,6 +125,12 @@ pub enum DataKey {
     ReentrancyGuard,
     /// Contract version
     Version,
+    /// Explicit token URI (token_id -> String)
+    TokenUri(u32),
+    /// Base URI used when no explicit token URI is set
+    BaseUri,
+    /// Seconds per duration day used to compute expires_at at mint
+    SecondsPerDay,
 }
 
 // Events
@@ -262,6 +279,38 @@ impl CommitmentNFTContract {
         Ok(())
     }
 
+    /// Set the number of seconds in one duration day used by `mint` (admin only)
+    ///
+    /// Only affects NFTs minted afterwards; existing expiries are unchanged.
+    pub fn set_seconds_per_day(
+        e: Env,
+        caller: Address,
+        seconds_per_day: u64,
+    ) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+
+        if seconds_per_day == 0 {
+            return Err(ContractError::InvalidDuration);
+        }
+
+        e.storage()
+            .instance()
+            .set(&DataKey::SecondsPerDay, &seconds_per_day);
+
+        e.events()
+            .publish((Symbol::new(&e, "SecondsPerDaySet"),), seconds_per_day);
+
+        Ok(())
+    }
+
+    /// Get the seconds-per-day multiplier (defaults to 86400)
+    pub fn get_seconds_per_day(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get(&DataKey::SecondsPerDay)
+            .unwrap_or(DEFAULT_SECONDS_PER_DAY)
+    }
+
     // ========================================================================
     // NFT Minting
     // ========================================================================
@@ -277,6 +326,8 @@ impl CommitmentNFTContract {
     /// * `commitment_type` - Type of commitment ("safe", "balanced", "aggressive")
     /// * `initial_amount` - Initial amount committed
     /// * `asset_address` - Address of the asset contract
+    /// * `expires_at` - Expiry computed by the caller (e.g. commitment_core). When `None`,
+    ///   it is derived from `duration_days` and the configured seconds-per-day.
     ///
     /// # Returns
     /// The token_id of the newly minted NFT
@@ -294,6 +345,7 @@ impl CommitmentNFTContract {
         initial_amount: i128,
         asset_address: Address,
         early_exit_penalty: u32,
+        expires_at: Option<u64>,
     ) -> Result<u32, ContractError> {
         // Reentrancy protection
         let guard: bool = e
@@ -342,6 +394,25 @@ impl CommitmentNFTContract {
             return Err(ContractError::InvalidAmount);
         }
 
+        // Calculate timestamps
+        let created_at = e.ledger().timestamp();
+        let expires_at = match expires_at {
+            Some(expires_at) if expires_at > created_at => Some(expires_at),
+            Some(_) => None,
+            None => {
+                let seconds_per_day = Self::get_seconds_per_day(e.clone());
+                (duration_days as u64)
+                    .checked_mul(seconds_per_day)
+                    .and_then(|duration| created_at.checked_add(duration))
+            }
+        }
+        .ok_or_else(|| {
+            e.storage()
+                .instance()
+                .set(&DataKey::ReentrancyGuard, &false);
+            ContractError::InvalidDuration
+        })?;
+
         // EFFECTS: Update state
         // Generate unique token_id
         let token_id: u32 = e
@@ -354,11 +425,6 @@ impl CommitmentNFTContract {
             .instance()
             .set(&DataKey::TokenCounter, &next_token_id);
 
-        // Calculate timestamps
-        let created_at = e.ledger().timestamp();
-        let seconds_per_day: u64 = 86400;
-        let expires_at = created_at + (duration_days as u64 * seconds_per_day);
-
         // Create CommitmentMetadata
         let metadata = CommitmentMetadata {
             commitment_id: commitment_id.clone(),
@@ -479,7 +545,7 @@ impl CommitmentNFTContract {
         from.require_auth();
 
         // Get the NFT
-        let mut nft: CommitmentNFT = e
+        let nft: CommitmentNFT = e
             .storage()
             .persistent()
             .get(&DataKey::NFT(token_id))
@@ -507,68 +573,35 @@ impl CommitmentNFTContract {
         // }
 
         // EFFECTS: Update state
-        // Update owner
-        nft.owner = to.clone();
-        e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+        move_token(&e, nft, &from, &to, token_id);
 
-        // OPTIMIZATION: Batch read balances before updating
-        let (from_balance, to_balance) = {
-            let from_bal = e
-                .storage()
-                .persistent()
-                .get(&DataKey::OwnerBalance(from.clone()))
-                .unwrap_or(0u32);
-            let to_bal = e
-                .storage()
-                .persistent()
-                .get(&DataKey::OwnerBalance(to.clone()))
-                .unwrap_or(0u32);
-            (from_bal, to_bal)
-        };
-
-        // Update balance counts
-        if from_balance > 0 {
-            e.storage()
-                .persistent()
-                .set(&DataKey::OwnerBalance(from.clone()), &(from_balance - 1));
-        }
+        // Clear reentrancy guard
         e.storage()
-            .persistent()
-            .set(&DataKey::OwnerBalance(to.clone()), &(to_balance + 1));
+            .instance()
+            .set(&DataKey::ReentrancyGuard, &false);
 
-        // Update owner tokens lists
-        let mut from_tokens: Vec<u32> = e
-            .storage()
-            .persistent()
-            .get(&DataKey::OwnerTokens(from.clone()))
-            .unwrap_or(Vec::new(&e));
-        if let Some(index) = from_tokens.iter().position(|id| id == token_id) {
-            from_tokens.remove(index as u32);
-        }
-        e.storage()
-            .persistent()
-            .set(&DataKey::OwnerTokens(from.clone()), &from_tokens);
+        Ok(())
+    }
 
-        let mut to_tokens: Vec<u32> = e
+    /// Move an NFT to a new owner on behalf of commitment_core
+    ///
+    /// Used for admin-driven ownership recovery, where the current owner can no
+    /// longer sign. Only the configured core contract may call this.
+    pub fn recover_transfer(e: Env, token_id: u32, to: Address) -> Result<(), ContractError> {
+        let core_contract: Address = e
             .storage()
-            .persistent()
-            .get(&DataKey::OwnerTokens(to.clone()))
-            .unwrap_or(Vec::new(&e));
-        to_tokens.push_back(token_id);
-        e.storage()
-            .persistent()
-            .set(&DataKey::OwnerTokens(to.clone()), &to_tokens);
-
-        // Clear reentrancy guard
-        e.storage()
             .instance()
-            .set(&DataKey::ReentrancyGuard, &false);
+            .get(&DataKey::CoreContract)
+            .ok_or(ContractError::NotAuthorized)?;
+        core_contract.require_auth();
 
-        // Emit transfer event
-        e.events().publish(
-            (symbol_short!("Transfer"), from, to),
-            (token_id, e.ledger().timestamp()),
-        );
+        let nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+        let from = nft.owner.clone();
+        move_token(&e, nft, &from, &to, token_id);
 
         Ok(())
     }
@@ -646,6 +679,129 @@ impl CommitmentNFTContract {
         owned_nfts
     }
 
+    /// Get a page of all NFTs metadata
+    ///
+    /// Returns at most `limit` NFTs (capped at `MAX_PAGE_SIZE`) starting at index `start`
+    /// of the global token list. Returns an empty vector once `start` is past the end.
+    pub fn get_all_metadata_paged(e: Env, start: u32, limit: u32) -> Vec<CommitmentNFT> {
+        let token_ids: Vec<u32> = e
+            .storage()
+            .instance()
+            .get(&DataKey::TokenIds)
+            .unwrap_or(Vec::new(&e));
+
+        resolve_nft_page(&e, &token_ids, start, limit)
+    }
+
+    /// Get a page of the NFTs owned by a specific address
+    ///
+    /// Returns at most `limit` NFTs (capped at `MAX_PAGE_SIZE`) starting at index `start`
+    /// of the owner's token list. Returns an empty vector once `start` is past the end.
+    pub fn get_nfts_by_owner_paged(
+        e: Env,
+        owner: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<CommitmentNFT> {
+        let token_ids: Vec<u32> = e
+            .storage()
+            .persistent()
+            .get(&DataKey::OwnerTokens(owner))
+            .unwrap_or(Vec::new(&e));
+
+        resolve_nft_page(&e, &token_ids, start, limit)
+    }
+
+    // ========================================================================
+    // Token URI
+    // ========================================================================
+
+    /// Set an explicit URI for a token (admin or token owner)
+    pub fn set_token_uri(
+        e: Env,
+        caller: Address,
+        token_id: u32,
+        uri: String,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        let nft: CommitmentNFT = e
+            .storage()
+            .persistent()
+            .get(&DataKey::NFT(token_id))
+            .ok_or(ContractError::TokenNotFound)?;
+
+        let admin: Address = e
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(ContractError::NotInitialized)?;
+        if caller != admin && caller != nft.owner {
+            return Err(ContractError::NotAuthorized);
+        }
+
+        if uri.is_empty() || uri.len() > MAX_URI_LENGTH {
+            return Err(ContractError::InvalidUri);
+        }
+
+        e.storage()
+            .persistent()
+            .set(&DataKey::TokenUri(token_id), &uri);
+
+        e.events()
+            .publish((Symbol::new(&e, "TokenUriSet"), token_id), uri);
+
+        Ok(())
+    }
+
+    /// Set the base URI used for tokens without an explicit URI (admin only)
+    pub fn set_base_uri(e: Env, caller: Address, base_uri: String) -> Result<(), ContractError> {
+        require_admin(&e, &caller)?;
+
+        // Leave room for the token_id suffix (u32 has at most 10 digits)
+        if base_uri.len() > MAX_URI_LENGTH - 10 {
+            return Err(ContractError::InvalidUri);
+        }
+
+        e.storage().instance().set(&DataKey::BaseUri, &base_uri);
+
+        e.events()
+            .publish((Symbol::new(&e, "BaseUriSet"),), base_uri);
+
+        Ok(())
+    }
+
+    /// Get the base URI (empty if not set)
+    pub fn get_base_uri(e: Env) -> String {
+        e.storage()
+            .instance()
+            .get(&DataKey::BaseUri)
+            .unwrap_or(String::from_str(&e, ""))
+    }
+
+    /// Get the URI for a token
+    ///
+    /// Returns the explicit token URI if set, otherwise `base_uri + token_id`.
+    /// Returns an empty string when neither is configured.
+    pub fn token_uri(e: Env, token_id: u32) -> Result<String, ContractError> {
+        if !e.storage().persistent().has(&DataKey::NFT(token_id)) {
+            return Err(ContractError::TokenNotFound);
+        }
+
+        if let Some(uri) = e
+            .storage()
+            .persistent()
+            .get::<DataKey, String>(&DataKey::TokenUri(token_id))
+        {
+            return Ok(uri);
+        }
+
+        match e.storage().instance().get::<DataKey, String>(&DataKey::BaseUri) {
+            Some(base_uri) if !base_uri.is_empty() => Ok(build_token_uri(&e, &base_uri, token_id)),
+            _ => Ok(String::from_str(&e, "")),
+        }
+    }
+
     // ========================================================================
     // Settlement (Issue #5 - Main Implementation)
     // ========================================================================
@@ -757,6 +913,66 @@ fn read_version(e: &Env) -> u32 {
         .unwrap_or(0)
 }
 
+/// Reassign an NFT to `to`, updating balances and owner token lists
+fn move_token(e: &Env, mut nft: CommitmentNFT, from: &Address, to: &Address, token_id: u32) {
+    // Update owner
+    nft.owner = to.clone();
+    e.storage().persistent().set(&DataKey::NFT(token_id), &nft);
+
+    // OPTIMIZATION: Batch read balances before updating
+    let (from_balance, to_balance) = {
+        let from_bal = e
+            .storage()
+            .persistent()
+            .get(&DataKey::OwnerBalance(from.clone()))
+            .unwrap_or(0u32);
+        let to_bal = e
+            .storage()
+            .persistent()
+            .get(&DataKey::OwnerBalance(to.clone()))
+            .unwrap_or(0u32);
+        (from_bal, to_bal)
+    };
+
+    // Update balance counts
+    if from_balance > 0 {
+        e.storage()
+            .persistent()
+            .set(&DataKey::OwnerBalance(from.clone()), &(from_balance - 1));
+    }
+    e.storage()
+        .persistent()
+        .set(&DataKey::OwnerBalance(to.clone()), &(to_balance + 1));
+
+    // Update owner tokens lists
+    let mut from_tokens: Vec<u32> = e
+        .storage()
+        .persistent()
+        .get(&DataKey::OwnerTokens(from.clone()))
+        .unwrap_or(Vec::new(e));
+    if let Some(index) = from_tokens.iter().position(|id| id == token_id) {
+        from_tokens.remove(index as u32);
+    }
+    e.storage()
+        .persistent()
+        .set(&DataKey::OwnerTokens(from.clone()), &from_tokens);
+
+    let mut to_tokens: Vec<u32> = e
+        .storage()
+        .persistent()
+        .get(&DataKey::OwnerTokens(to.clone()))
+        .unwrap_or(Vec::new(e));
+    to_tokens.push_back(token_id);
+    e.storage()
+        .persistent()
+        .set(&DataKey::OwnerTokens(to.clone()), &to_tokens);
+
+    e.events().publish(
+        (symbol_short!("Transfer"), from.clone(), to.clone()),
+        (token_id, e.ledger().timestamp()),
+    );
+}
+
 fn require_admin(e: &Env, caller: &Address) -> Result<(), ContractError> {
     caller.require_auth();
     let admin: Address = e
@@ -770,6 +986,56 @@ fn require_admin(e: &Env, caller: &Address) -> Result<(), ContractError> {
     Ok(())
 }
 
+/// Resolve a bounded slice of `token_ids` to their NFT structs
+fn resolve_nft_page(e: &Env, token_ids: &Vec<u32>, start: u32, limit: u32) -> Vec<CommitmentNFT> {
+    let mut nfts: Vec<CommitmentNFT> = Vec::new(e);
+    let len = token_ids.len();
+    if start >= len {
+        return nfts;
+    }
+
+    let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+    for i in start..end {
+        let token_id = token_ids.get(i).unwrap();
+        if let Some(nft) = e
+            .storage()
+            .persistent()
+            .get::<DataKey, CommitmentNFT>(&DataKey::NFT(token_id))
+        {
+            nfts.push_back(nft);
+        }
+    }
+
+    nfts
+}
+
+/// Concatenate `base_uri` with the decimal representation of `token_id`
+fn build_token_uri(e: &Env, base_uri: &String, token_id: u32) -> String {
+    let mut buf = [0u8; MAX_URI_LENGTH as usize];
+    let base_len = base_uri.len() as usize;
+    base_uri.copy_into_slice(&mut buf[..base_len]);
+
+    let mut digits = [0u8; 10];
+    let mut digit_count = 0;
+    let mut n = token_id;
+    loop {
+        digits[digit_count] = (n % 10) as u8 + b'0';
+        n /= 10;
+        digit_count += 1;
+        if n == 0 {
+            break;
+        }
+    }
+
+    let mut i = base_len;
+    for j in 0..digit_count {
+        buf[i] = digits[digit_count - 1 - j];
+        i += 1;
+    }
+
+    String::from_bytes(e, &buf[..i])
+}
+
 fn require_valid_wasm_hash(e: &Env, wasm_hash: &BytesN<32>) -> Result<(), ContractError> {
     let zero = BytesN::from_array(e, &[0; 32]);
     if *wasm_hash == zero {
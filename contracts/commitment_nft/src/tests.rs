@@ -99,6 +99,7 @@ fn test_mint() {
         &amount,
         &asset,
         &penalty,
+        &None,
     );
 
     assert_eq!(token_id, 0);
@@ -142,6 +143,7 @@ fn test_mint_multiple() {
         &1000,
         &asset_address,
         &5,
+        &None,
     );
     assert_eq!(token_id_0, 0);
 
@@ -154,6 +156,7 @@ fn test_mint_multiple() {
         &1000,
         &asset_address,
         &5,
+        &None,
     );
     assert_eq!(token_id_1, 1);
 
@@ -166,6 +169,7 @@ fn test_mint_multiple() {
         &1000,
         &asset_address,
         &5,
+        &None,
     );
     assert_eq!(token_id_2, 2);
 
@@ -193,6 +197,7 @@ fn test_mint_without_initialize_fails() {
         &amount,
         &asset,
         &penalty,
+        &None,
     );
 }
 
@@ -224,6 +229,7 @@ fn test_get_metadata() {
         &amount,
         &asset_address,
         &10,
+        &None,
     );
 
     let nft = client.get_metadata(&token_id);
@@ -275,6 +281,7 @@ fn test_owner_of() {
         &amount,
         &asset,
         &penalty,
+        &None,
     );
 
     let retrieved_owner = client.owner_of(&token_id);
@@ -317,6 +324,7 @@ fn test_is_active() {
         &amount,
         &asset,
         &penalty,
+        &None,
     );
 
     // Newly minted NFT should be active
@@ -368,6 +376,7 @@ fn test_total_supply_after_minting() {
             &1000,
             &asset_address,
             &5,
+            &None,
         );
     }
 
@@ -411,6 +420,7 @@ fn test_balance_of_after_minting() {
             &1000,
             &asset_address,
             &5,
+            &None,
         );
     }
 
@@ -425,6 +435,7 @@ fn test_balance_of_after_minting() {
             &1000,
             &asset_address,
             &5,
+            &None,
         );
     }
 
@@ -467,6 +478,7 @@ fn test_get_all_metadata() {
             &1000,
             &asset_address,
             &5,
+            &None,
         );
     }
 
@@ -516,6 +528,7 @@ fn test_get_nfts_by_owner() {
             &1000,
             &asset_address,
             &5,
+            &None,
         );
     }
 
@@ -530,6 +543,7 @@ fn test_get_nfts_by_owner() {
             &1000,
             &asset_address,
             &5,
+            &None,
         );
     }
 
@@ -586,6 +600,7 @@ fn test_transfer() {
         &amount,
         &asset,
         &penalty,
+        &None,
     );
 
     // Verify initial state
@@ -645,6 +660,7 @@ fn test_transfer_not_owner() {
         &amount,
         &asset,
         &penalty,
+        &None,
     );
 
     // Try to transfer from non-owner (should fail)
@@ -666,6 +682,65 @@ fn test_transfer_nonexistent_token() {
     client.transfer(&owner, &recipient, &999);
 }
 
+#[test]
+fn test_recover_transfer_by_core_contract() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let new_owner = Address::generate(&e);
+    let core_contract = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    client.initialize(&admin);
+    client.set_core_contract(&core_contract);
+    let token_id = client.mint(
+        &owner,
+        &String::from_str(&e, "commitment_001"),
+        &30,
+        &10,
+        &String::from_str(&e, "safe"),
+        &1000,
+        &asset_address,
+        &5,
+        &None,
+    );
+
+    client.recover_transfer(&token_id, &new_owner);
+
+    assert_eq!(client.owner_of(&token_id), new_owner);
+    assert_eq!(client.balance_of(&owner), 0);
+    assert_eq!(client.balance_of(&new_owner), 1);
+    assert_eq!(client.get_nfts_by_owner(&new_owner).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")] // NotAuthorized
+fn test_recover_transfer_without_core_contract_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    client.initialize(&admin);
+    let token_id = client.mint(
+        &owner,
+        &String::from_str(&e, "commitment_001"),
+        &30,
+        &10,
+        &String::from_str(&e, "safe"),
+        &1000,
+        &asset_address,
+        &5,
+        &None,
+    );
+
+    client.recover_transfer(&token_id, &Address::generate(&e));
+}
+
 // ============================================
 // Settle Tests
 // ============================================
@@ -689,6 +764,7 @@ fn test_settle() {
         &1000,
         &asset_address,
         &5,
+        &None,
     );
 
     // NFT should be active initially
@@ -744,6 +820,7 @@ fn test_settle_not_expired() {
         &1000,
         &asset_address,
         &5,
+        &None,
     );
 
     // Try to settle before expiration (should fail)
@@ -769,6 +846,7 @@ fn test_settle_already_settled() {
         &1000,
         &asset_address,
         &5,
+        &None,
     );
 
     // Fast forward time
@@ -802,6 +880,7 @@ fn test_is_expired() {
         &1000,
         &asset_address,
         &5,
+        &None,
     );
 
     // Should not be expired initially
@@ -855,6 +934,7 @@ fn test_token_exists() {
         &amount,
         &asset,
         &penalty,
+        &None,
     );
 
     // Token should now exist
@@ -915,6 +995,7 @@ fn test_metadata_timestamps() {
         &1000,
         &asset_address,
         &5,
+        &None,
     );
 
     let metadata = client.get_metadata(&token_id);
@@ -947,6 +1028,7 @@ fn test_balance_updates_after_transfer() {
         &1000,
         &asset_address,
         &5,
+        &None,
     );
     client.mint(
         &owner1,
@@ -957,6 +1039,7 @@ fn test_balance_updates_after_transfer() {
         &1000,
         &asset_address,
         &5,
+        &None,
     );
     client.mint(
         &owner1,
@@ -967,6 +1050,7 @@ fn test_balance_updates_after_transfer() {
         &1000,
         &asset_address,
         &5,
+        &None,
     );
 
     assert_eq!(client.balance_of(&owner1), 3);
@@ -991,3 +1075,378 @@ fn test_balance_updates_after_transfer() {
     assert_eq!(owner1_nfts.len(), 1);
     assert_eq!(owner2_nfts.len(), 2);
 }
+
+// ============================================================================
+// Seconds-per-day Tests
+// ============================================================================
+
+#[test]
+fn test_seconds_per_day_defaults_to_one_day() {
+    let e = Env::default();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+
+    assert_eq!(client.get_seconds_per_day(), DEFAULT_SECONDS_PER_DAY);
+}
+
+#[test]
+fn test_mint_uses_custom_seconds_per_day() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    client.initialize(&admin);
+    // One "day" is a minute, so a 30-day commitment lasts half an hour
+    client.set_seconds_per_day(&admin, &60);
+    assert_eq!(client.get_seconds_per_day(), 60);
+
+    e.ledger().with_mut(|li| {
+        li.timestamp = 1_000;
+    });
+    let token_id = client.mint(
+        &owner,
+        &String::from_str(&e, "short_commitment"),
+        &30,
+        &10,
+        &String::from_str(&e, "safe"),
+        &1000,
+        &asset_address,
+        &5,
+        &None,
+    );
+
+    let metadata = client.get_metadata(&token_id).metadata;
+    assert_eq!(metadata.created_at, 1_000);
+    assert_eq!(metadata.expires_at, 1_000 + 30 * 60);
+
+    e.ledger().with_mut(|li| {
+        li.timestamp = 1_000 + 30 * 60;
+    });
+    assert!(client.is_expired(&token_id));
+    client.settle(&token_id);
+    assert!(!client.is_active(&token_id));
+}
+
+#[test]
+fn test_mint_with_explicit_expires_at() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    client.initialize(&admin);
+    e.ledger().with_mut(|li| {
+        li.timestamp = 1_000;
+    });
+
+    // The caller's expiry wins over duration_days * seconds_per_day
+    let token_id = client.mint(
+        &owner,
+        &String::from_str(&e, "core_commitment"),
+        &30,
+        &10,
+        &String::from_str(&e, "safe"),
+        &1000,
+        &asset_address,
+        &5,
+        &Some(5_000),
+    );
+
+    assert_eq!(client.get_metadata(&token_id).metadata.expires_at, 5_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")] // InvalidDuration
+fn test_mint_with_past_expires_at_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    client.initialize(&admin);
+    e.ledger().with_mut(|li| {
+        li.timestamp = 1_000;
+    });
+
+    client.mint(
+        &owner,
+        &String::from_str(&e, "core_commitment"),
+        &30,
+        &10,
+        &String::from_str(&e, "safe"),
+        &1000,
+        &asset_address,
+        &5,
+        &Some(1_000),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")] // InvalidDuration
+fn test_set_seconds_per_day_zero_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+
+    client.set_seconds_per_day(&admin, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")] // NotAuthorized
+fn test_set_seconds_per_day_non_admin_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    client.initialize(&admin);
+
+    client.set_seconds_per_day(&Address::generate(&e), &60);
+}
+
+// ============================================================================
+// Token URI Tests
+// ============================================================================
+
+#[test]
+fn test_token_uri_explicit() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    client.initialize(&admin);
+
+    let (commitment_id, duration, max_loss, commitment_type, amount, asset, penalty) =
+        create_test_metadata(&e, &asset_address);
+    let token_id = client.mint(
+        &owner,
+        &commitment_id,
+        &duration,
+        &max_loss,
+        &commitment_type,
+        &amount,
+        &asset,
+        &penalty,
+        &None,
+    );
+
+    client.set_base_uri(&admin, &String::from_str(&e, "ipfs://base/"));
+
+    let uri = String::from_str(&e, "ipfs://explicit.json");
+    client.set_token_uri(&owner, &token_id, &uri);
+
+    assert_eq!(client.token_uri(&token_id), uri);
+}
+
+#[test]
+fn test_token_uri_base_fallback() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    client.initialize(&admin);
+
+    // No URI configured yet
+    assert_eq!(client.get_base_uri(), String::from_str(&e, ""));
+
+    for i in 0..12 {
+        client.mint(
+            &owner,
+            &String::from_str(&e, "commitment"),
+            &30,
+            &10,
+            &String::from_str(&e, "safe"),
+            &1000,
+            &asset_address,
+            &i,
+            &None,
+        );
+    }
+    assert_eq!(client.token_uri(&0), String::from_str(&e, ""));
+
+    let base_uri = String::from_str(&e, "https://commitlabs.io/nft/");
+    client.set_base_uri(&admin, &base_uri);
+    assert_eq!(client.get_base_uri(), base_uri);
+
+    assert_eq!(
+        client.token_uri(&0),
+        String::from_str(&e, "https://commitlabs.io/nft/0")
+    );
+    assert_eq!(
+        client.token_uri(&11),
+        String::from_str(&e, "https://commitlabs.io/nft/11")
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")] // TokenNotFound
+fn test_token_uri_nonexistent_token() {
+    let e = Env::default();
+    let (admin, client) = setup_contract(&e);
+
+    client.initialize(&admin);
+    client.token_uri(&999);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")] // InvalidUri
+fn test_set_token_uri_empty_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    client.initialize(&admin);
+
+    let (commitment_id, duration, max_loss, commitment_type, amount, asset, penalty) =
+        create_test_metadata(&e, &asset_address);
+    let token_id = client.mint(
+        &owner,
+        &commitment_id,
+        &duration,
+        &max_loss,
+        &commitment_type,
+        &amount,
+        &asset,
+        &penalty,
+        &None,
+    );
+
+    client.set_token_uri(&admin, &token_id, &String::from_str(&e, ""));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")] // NotAuthorized
+fn test_set_token_uri_unauthorized_fails() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let stranger = Address::generate(&e);
+    let asset_address = Address::generate(&e);
+
+    client.initialize(&admin);
+
+    let (commitment_id, duration, max_loss, commitment_type, amount, asset, penalty) =
+        create_test_metadata(&e, &asset_address);
+    let token_id = client.mint(
+        &owner,
+        &commitment_id,
+        &duration,
+        &max_loss,
+        &commitment_type,
+        &amount,
+        &asset,
+        &penalty,
+        &None,
+    );
+
+    client.set_token_uri(&stranger, &token_id, &String::from_str(&e, "ipfs://x"));
+}
+
+// ============================================================================
+// Pagination Tests
+// ============================================================================
+
+fn mint_many(e: &Env, client: &CommitmentNFTContractClient<'_>, owner: &Address, count: u32) {
+    let asset_address = Address::generate(e);
+    for _ in 0..count {
+        client.mint(
+            owner,
+            &String::from_str(e, "commitment"),
+            &30,
+            &10,
+            &String::from_str(e, "safe"),
+            &1000,
+            &asset_address,
+            &5,
+            &None,
+        );
+    }
+}
+
+#[test]
+fn test_get_all_metadata_paged() {
+    let e = Env::default();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+
+    client.initialize(&admin);
+    mint_many(&e, &client, &owner, 7);
+
+    let page = client.get_all_metadata_paged(&0, &3);
+    assert_eq!(page.len(), 3);
+    assert_eq!(page.get(0).unwrap().token_id, 0);
+    assert_eq!(page.get(2).unwrap().token_id, 2);
+
+    let page = client.get_all_metadata_paged(&3, &3);
+    assert_eq!(page.len(), 3);
+    assert_eq!(page.get(0).unwrap().token_id, 3);
+
+    // Last partial page
+    let page = client.get_all_metadata_paged(&6, &3);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().token_id, 6);
+
+    // Past the end
+    assert_eq!(client.get_all_metadata_paged(&7, &3).len(), 0);
+    assert_eq!(client.get_all_metadata_paged(&100, &3).len(), 0);
+}
+
+#[test]
+fn test_get_nfts_by_owner_paged() {
+    let e = Env::default();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+    let other = Address::generate(&e);
+
+    client.initialize(&admin);
+    mint_many(&e, &client, &other, 2);
+    mint_many(&e, &client, &owner, 5);
+
+    let page = client.get_nfts_by_owner_paged(&owner, &0, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().token_id, 2);
+    assert_eq!(page.get(1).unwrap().token_id, 3);
+
+    let page = client.get_nfts_by_owner_paged(&owner, &4, &2);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().token_id, 6);
+
+    assert_eq!(client.get_nfts_by_owner_paged(&owner, &5, &2).len(), 0);
+    assert_eq!(
+        client
+            .get_nfts_by_owner_paged(&Address::generate(&e), &0, &2)
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn test_paged_queries_cap_limit() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    let (admin, client) = setup_contract(&e);
+    let owner = Address::generate(&e);
+
+    client.initialize(&admin);
+    mint_many(&e, &client, &owner, MAX_PAGE_SIZE + 5);
+
+    assert_eq!(
+        client.get_all_metadata_paged(&0, &u32::MAX).len(),
+        MAX_PAGE_SIZE
+    );
+    assert_eq!(
+        client.get_nfts_by_owner_paged(&owner, &0, &u32::MAX).len(),
+        MAX_PAGE_SIZE
+    );
+    assert_eq!(client.get_all_metadata_paged(&MAX_PAGE_SIZE, &u32::MAX).len(), 5);
+}
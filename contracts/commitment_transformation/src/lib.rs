@@ -6,9 +6,13 @@
 #![no_std]
 
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, String, Vec,
+    contract, contracterror, contractimpl, contracttype, symbol_short, token, Address, Env, IntoVal,
+    Map, String, Symbol, Vec,
 };
-use shared_utils::{Validation, emit_error_event};
+use shared_utils::{split_fee, Validation, emit_error_event};
+
+/// Tranche count limit applied until the admin configures one.
+pub const DEFAULT_MAX_TRANCHES: u32 = 10;
 
 // ============================================================================
 // Errors (aligned with shared_utils::error_codes)
@@ -30,6 +34,11 @@ pub enum TransformationError {
     ReentrancyDetected = 10,
     FeeRecipientNotSet = 11,
     InsufficientFees = 12,
+    InstrumentExpired = 13,
+    InstrumentNotExpired = 14,
+    InvalidGuarantee = 15,
+    CommitmentNotSettled = 16,
+    NothingToSweep = 17,
 }
 
 impl TransformationError {
@@ -47,6 +56,11 @@ impl TransformationError {
             TransformationError::ReentrancyDetected => "Reentrancy detected",
             TransformationError::FeeRecipientNotSet => "Fee recipient not set",
             TransformationError::InsufficientFees => "Insufficient collected fees to withdraw",
+            TransformationError::InstrumentExpired => "Instrument has expired",
+            TransformationError::InstrumentNotExpired => "Instrument has not expired",
+            TransformationError::InvalidGuarantee => "Guarantee type and terms hash are required",
+            TransformationError::CommitmentNotSettled => "Commitment has not settled",
+            TransformationError::NothingToSweep => "No unaccounted balance to sweep",
         }
     }
 }
@@ -92,6 +106,7 @@ pub struct CollateralizedAsset {
     pub collateral_amount: i128,
     pub asset_address: Address,
     pub created_at: u64,
+    pub released: bool,
 }
 
 #[contracttype]
@@ -103,6 +118,8 @@ pub struct SecondaryInstrument {
     pub instrument_type: String, // "receivable", "option", "warrant"
     pub amount: i128,
     pub created_at: u64,
+    pub expires_at: u64, // 0 = never expires
+    pub status: String,  // "active", "exercised", "expired"
 }
 
 #[contracttype]
@@ -115,6 +132,34 @@ pub struct ProtocolGuarantee {
     pub created_at: u64,
 }
 
+// Commitment types from commitment_core (defined locally for cross-contract calls)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommitmentRules {
+    pub duration_days: u32,
+    pub max_loss_percent: u32,
+    pub commitment_type: String,
+    pub early_exit_penalty: u32,
+    pub min_fee_threshold: i128,
+    pub grace_period_days: u32,
+    pub max_loss_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Commitment {
+    pub commitment_id: String,
+    pub owner: Address,
+    pub nft_token_id: u32,
+    pub rules: CommitmentRules,
+    pub amount: i128,
+    pub asset_address: Address,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub current_value: i128,
+    pub status: String, // "active", "settled", "violated", "early_exit"
+}
+
 #[contracttype]
 #[derive(Clone)]
 pub enum DataKey {
@@ -136,6 +181,18 @@ pub enum DataKey {
     FeeRecipient,
     /// Collected transformation fees per asset (asset -> i128)
     CollectedFees(Address),
+    /// Sequence number of the last commitment event (u64)
+    EventSequence,
+    /// Maximum number of tranches per tranche set (u32)
+    MaxTranches,
+    /// Insurance fund receiving a share of transformation fees (Address)
+    InsuranceFund,
+    /// Share of transformation fees routed to the insurance fund, in bps (u32)
+    InsuranceBps,
+    /// Transformation fee overrides by commitment type (Map<String, u32>)
+    TypeFeeBps,
+    /// Unreleased collateral held in escrow per asset (asset -> i128)
+    EscrowedCollateral(Address),
 }
 
 // ============================================================================
@@ -187,6 +244,53 @@ fn set_reentrancy_guard(e: &Env, value: bool) {
     e.storage().instance().set(&DataKey::ReentrancyGuard, &value);
 }
 
+fn add_escrowed_collateral(e: &Env, asset_address: &Address, delta: i128) {
+    let key = DataKey::EscrowedCollateral(asset_address.clone());
+    let current: i128 = e.storage().instance().get::<_, i128>(&key).unwrap_or(0);
+    e.storage().instance().set(&key, &(current + delta));
+}
+
+fn get_type_fee_overrides(e: &Env) -> Map<String, u32> {
+    e.storage()
+        .instance()
+        .get::<_, Map<String, u32>>(&DataKey::TypeFeeBps)
+        .unwrap_or(Map::new(e))
+}
+
+/// Transformation fee for a commitment. Core is only consulted for the commitment
+/// type when at least one type override is configured.
+fn fee_bps_for_commitment(e: &Env, commitment_id: &String) -> u32 {
+    if get_type_fee_overrides(e).is_empty() {
+        return CommitmentTransformationContract::get_transformation_fee_bps(e.clone());
+    }
+    let commitment = core_get_commitment(e, commitment_id);
+    CommitmentTransformationContract::get_fee_for_type(e.clone(), commitment.rules.commitment_type)
+}
+
+/// Fetch a commitment from the core contract (panics if the core does not know it).
+fn core_get_commitment(e: &Env, commitment_id: &String) -> Commitment {
+    let core = e
+        .storage()
+        .instance()
+        .get::<_, Address>(&DataKey::CoreContract)
+        .unwrap_or_else(|| fail(e, TransformationError::NotInitialized, "core_get_commitment"));
+    let mut args = Vec::new(e);
+    args.push_back(commitment_id.clone().into_val(e));
+    e.invoke_contract(&core, &Symbol::new(e, "get_commitment"), args)
+}
+
+/// Advance the event sequence and return the value for the next event.
+fn next_event_sequence(e: &Env) -> u64 {
+    let next = e
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKey::EventSequence)
+        .unwrap_or(0)
+        + 1;
+    e.storage().instance().set(&DataKey::EventSequence, &next);
+    next
+}
+
 // ============================================================================
 // Contract
 // ============================================================================
@@ -220,6 +324,36 @@ impl CommitmentTransformationContract {
         );
     }
 
+    /// Override the transformation fee for one commitment type; `None` clears the
+    /// override so the flat fee applies again. Admin only.
+    pub fn set_fee_for_type(e: Env, caller: Address, commitment_type: String, fee_bps: Option<u32>) {
+        require_admin(&e, &caller);
+        let mut overrides = get_type_fee_overrides(&e);
+        match fee_bps {
+            Some(bps) => {
+                if bps > 10000 {
+                    fail(&e, TransformationError::InvalidFeeBps, "set_fee_for_type");
+                }
+                overrides.set(commitment_type.clone(), bps);
+            }
+            None => {
+                overrides.remove(commitment_type.clone());
+            }
+        }
+        e.storage().instance().set(&DataKey::TypeFeeBps, &overrides);
+        e.events().publish(
+            (symbol_short!("TypeFee"), caller),
+            (commitment_type, fee_bps, e.ledger().timestamp()),
+        );
+    }
+
+    /// Transformation fee for a commitment type: its override, else the flat fee.
+    pub fn get_fee_for_type(e: Env, commitment_type: String) -> u32 {
+        get_type_fee_overrides(&e)
+            .get(commitment_type)
+            .unwrap_or_else(|| Self::get_transformation_fee_bps(e.clone()))
+    }
+
     /// Set or clear authorized transformer contract. Admin only.
     pub fn set_authorized_transformer(e: Env, caller: Address, transformer: Address, allowed: bool) {
         require_admin(&e, &caller);
@@ -232,8 +366,31 @@ impl CommitmentTransformationContract {
         );
     }
 
+    /// Set the maximum number of tranches per tranche set. Admin only; must be at least 1.
+    pub fn set_max_tranches(e: Env, caller: Address, max_tranches: u32) {
+        require_admin(&e, &caller);
+        if max_tranches == 0 {
+            fail(&e, TransformationError::InvalidAmount, "set_max_tranches");
+        }
+        e.storage().instance().set(&DataKey::MaxTranches, &max_tranches);
+        e.events().publish(
+            (symbol_short!("MaxTrSet"), caller),
+            (max_tranches, e.ledger().timestamp()),
+        );
+    }
+
+    /// Maximum number of tranches per tranche set (defaults to DEFAULT_MAX_TRANCHES).
+    pub fn get_max_tranches(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get::<_, u32>(&DataKey::MaxTranches)
+            .unwrap_or(DEFAULT_MAX_TRANCHES)
+    }
+
     /// Split a commitment into risk tranches. Caller must be commitment owner or authorized.
-    /// When transformation_fee_bps > 0, caller must send fee_amount of fee_asset to the contract.
+    /// When the fee is > 0, caller must send fee_amount of fee_asset to the contract.
+    /// The fee is the override for the commitment's type (read from core) if one is set,
+    /// else transformation_fee_bps.
     /// tranche_share_bps: e.g. [6000, 3000, 1000] for 60% senior, 30% mezzanine, 10% equity.
     pub fn create_tranches(
         e: Env,
@@ -249,94 +406,27 @@ impl CommitmentTransformationContract {
         set_reentrancy_guard(&e, true);
 
         Validation::require_positive(total_value);
-        if tranche_share_bps.len() != risk_levels.len() || tranche_share_bps.len() == 0 {
+        if !tranche_shares_valid(&e, &tranche_share_bps, &risk_levels) {
             set_reentrancy_guard(&e, false);
             fail(&e, TransformationError::InvalidTrancheRatios, "create_tranches");
         }
-        let mut sum_bps: u32 = 0;
-        for bps in tranche_share_bps.iter() {
-            sum_bps = sum_bps.saturating_add(bps);
-        }
-        if sum_bps != 10000 {
-            set_reentrancy_guard(&e, false);
-            fail(&e, TransformationError::InvalidTrancheRatios, "create_tranches");
-        }
-
-        let fee_bps: u32 = e
-            .storage()
-            .instance()
-            .get::<_, u32>(&DataKey::TransformationFeeBps)
-            .unwrap_or(0);
-        let fee_amount = (total_value * fee_bps as i128) / 10000i128;
-
-        // Collect transformation fee from caller when fee_bps > 0
-        if fee_amount > 0 {
-            let contract_address = e.current_contract_address();
-            let token_client = token::Client::new(&e, &fee_asset);
-            token_client.transfer(&caller, &contract_address, &fee_amount);
-            let key = DataKey::CollectedFees(fee_asset.clone());
-            let current: i128 = e.storage().instance().get::<_, i128>(&key).unwrap_or(0);
-            e.storage().instance().set(&key, &(current + fee_amount));
-        }
 
-        let counter: u64 = e
-            .storage()
-            .instance()
-            .get::<_, u64>(&DataKey::TrancheSetCounter)
-            .unwrap_or(0);
-        let transformation_id = format_tranformation_id(&e, "tr", counter);
-        e.storage()
-            .instance()
-            .set(&DataKey::TrancheSetCounter, &(counter + 1));
-
-        let mut tranches = Vec::new(&e);
-        let net_value = total_value - fee_amount;
-        for (i, (bps, risk)) in tranche_share_bps.iter().zip(risk_levels.iter()).enumerate() {
-            let bps_u32: u32 = bps;
-            let amount = (net_value * bps_u32 as i128) / 10000i128;
-            let tranche_id = format_tranformation_id(&e, "t", counter * 10 + i as u64);
-            tranches.push_back(RiskTranche {
-                tranche_id: tranche_id.clone(),
-                commitment_id: commitment_id.clone(),
-                risk_level: risk.clone(),
-                amount,
-                share_bps: bps_u32,
-                created_at: e.ledger().timestamp(),
-            });
-        }
-
-        let set = TrancheSet {
-            transformation_id: transformation_id.clone(),
-            commitment_id: commitment_id.clone(),
-            owner: caller.clone(),
+        let transformation_id = store_tranche_set(
+            &e,
+            &caller,
+            &commitment_id,
             total_value,
-            tranches: tranches.clone(),
-            fee_paid: fee_amount,
-            created_at: e.ledger().timestamp(),
-        };
-        e.storage()
-            .instance()
-            .set(&DataKey::TrancheSet(transformation_id.clone()), &set);
-
-        let mut sets = e
-            .storage()
-            .instance()
-            .get::<_, Vec<String>>(&DataKey::CommitmentTrancheSets(commitment_id.clone()))
-            .unwrap_or(Vec::new(&e));
-        sets.push_back(transformation_id.clone());
-        e.storage()
-            .instance()
-            .set(&DataKey::CommitmentTrancheSets(commitment_id.clone()), &sets);
+            &tranche_share_bps,
+            &risk_levels,
+            &fee_asset,
+        );
 
         set_reentrancy_guard(&e, false);
-        e.events().publish(
-            (symbol_short!("TrCreated"), transformation_id.clone(), caller),
-            (total_value, fee_amount, e.ledger().timestamp()),
-        );
         transformation_id
     }
 
     /// Create a collateralized asset backed by a commitment.
+    /// The collateral is escrowed in this contract until release_collateral.
     pub fn collateralize(
         e: Env,
         caller: Address,
@@ -350,6 +440,14 @@ impl CommitmentTransformationContract {
 
         Validation::require_positive(collateral_amount);
 
+        let contract_address = e.current_contract_address();
+        token::Client::new(&e, &asset_address).transfer(
+            &caller,
+            &contract_address,
+            &collateral_amount,
+        );
+        add_escrowed_collateral(&e, &asset_address, collateral_amount);
+
         let counter: u64 = e
             .storage()
             .instance()
@@ -367,6 +465,7 @@ impl CommitmentTransformationContract {
             collateral_amount,
             asset_address: asset_address.clone(),
             created_at: e.ledger().timestamp(),
+            released: false,
         };
         e.storage()
             .instance()
@@ -384,19 +483,76 @@ impl CommitmentTransformationContract {
 
         set_reentrancy_guard(&e, false);
         e.events().publish(
-            (symbol_short!("Collater"), asset_id.clone(), caller),
-            (commitment_id, collateral_amount, asset_address, e.ledger().timestamp()),
+            (
+                symbol_short!("Collater"),
+                commitment_id,
+                asset_id.clone(),
+                next_event_sequence(&e),
+            ),
+            (caller, collateral_amount, asset_address, e.ledger().timestamp()),
         );
         asset_id
     }
 
+    /// Return escrowed collateral to the asset owner once the commitment has settled.
+    /// Caller must be the asset owner or admin; active, violated and early-exited
+    /// commitments are rejected.
+    pub fn release_collateral(e: Env, caller: Address, asset_id: String) {
+        caller.require_auth();
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        let key = DataKey::CollateralizedAsset(asset_id.clone());
+        let mut collateral = e
+            .storage()
+            .instance()
+            .get::<_, CollateralizedAsset>(&key)
+            .unwrap_or_else(|| fail(&e, TransformationError::TransformationNotFound, "release_collateral"));
+        let admin = e.storage().instance().get::<_, Address>(&DataKey::Admin);
+        if caller != collateral.owner && Some(caller.clone()) != admin {
+            fail(&e, TransformationError::Unauthorized, "release_collateral");
+        }
+        if collateral.released {
+            fail(&e, TransformationError::InvalidState, "release_collateral");
+        }
+        let commitment = core_get_commitment(&e, &collateral.commitment_id);
+        if commitment.status != String::from_str(&e, "settled") {
+            fail(&e, TransformationError::CommitmentNotSettled, "release_collateral");
+        }
+
+        // Effects before the token transfer
+        collateral.released = true;
+        e.storage().instance().set(&key, &collateral);
+        add_escrowed_collateral(&e, &collateral.asset_address, -collateral.collateral_amount);
+
+        let contract_address = e.current_contract_address();
+        token::Client::new(&e, &collateral.asset_address).transfer(
+            &contract_address,
+            &collateral.owner,
+            &collateral.collateral_amount,
+        );
+
+        set_reentrancy_guard(&e, false);
+        e.events().publish(
+            (
+                symbol_short!("ColRel"),
+                collateral.commitment_id,
+                asset_id,
+                next_event_sequence(&e),
+            ),
+            (collateral.owner, collateral.collateral_amount, e.ledger().timestamp()),
+        );
+    }
+
     /// Create a secondary market instrument (receivable, option, warrant).
+    /// duration_secs sets expires_at relative to now; 0 creates a non-expiring instrument.
     pub fn create_secondary_instrument(
         e: Env,
         caller: Address,
         commitment_id: String,
         instrument_type: String,
         amount: i128,
+        duration_secs: u64,
     ) -> String {
         require_authorized(&e, &caller);
         require_no_reentrancy(&e);
@@ -414,13 +570,21 @@ impl CommitmentTransformationContract {
             .instance()
             .set(&DataKey::TrancheSetCounter, &(counter + 1));
 
+        let now = e.ledger().timestamp();
+        let expires_at = if duration_secs == 0 {
+            0
+        } else {
+            now.saturating_add(duration_secs)
+        };
         let instrument = SecondaryInstrument {
             instrument_id: instrument_id.clone(),
             commitment_id: commitment_id.clone(),
             owner: caller.clone(),
             instrument_type: instrument_type.clone(),
             amount,
-            created_at: e.ledger().timestamp(),
+            created_at: now,
+            expires_at,
+            status: String::from_str(&e, "active"),
         };
         e.storage()
             .instance()
@@ -438,12 +602,79 @@ impl CommitmentTransformationContract {
 
         set_reentrancy_guard(&e, false);
         e.events().publish(
-            (symbol_short!("SecCreat"), instrument_id.clone(), caller),
-            (commitment_id, instrument_type, amount, e.ledger().timestamp()),
+            (
+                symbol_short!("SecCreat"),
+                commitment_id,
+                instrument_id.clone(),
+                next_event_sequence(&e),
+            ),
+            (caller, instrument_type, amount, e.ledger().timestamp()),
         );
         instrument_id
     }
 
+    /// Exercise an active instrument. Instrument owner only; rejected at or after expires_at.
+    pub fn exercise_instrument(e: Env, caller: Address, instrument_id: String) {
+        caller.require_auth();
+        let key = DataKey::SecondaryInstrument(instrument_id.clone());
+        let mut instrument = e
+            .storage()
+            .instance()
+            .get::<_, SecondaryInstrument>(&key)
+            .unwrap_or_else(|| fail(&e, TransformationError::TransformationNotFound, "exercise_instrument"));
+        if caller != instrument.owner {
+            fail(&e, TransformationError::Unauthorized, "exercise_instrument");
+        }
+        if instrument.status != String::from_str(&e, "active") {
+            fail(&e, TransformationError::InvalidState, "exercise_instrument");
+        }
+        if instrument.expires_at != 0 && e.ledger().timestamp() >= instrument.expires_at {
+            fail(&e, TransformationError::InstrumentExpired, "exercise_instrument");
+        }
+
+        instrument.status = String::from_str(&e, "exercised");
+        e.storage().instance().set(&key, &instrument);
+        e.events().publish(
+            (
+                symbol_short!("SecExerc"),
+                instrument.commitment_id,
+                instrument_id,
+                next_event_sequence(&e),
+            ),
+            (caller, instrument.amount, e.ledger().timestamp()),
+        );
+    }
+
+    /// Mark an unexercised instrument expired once expires_at has passed. Callable by anyone.
+    /// Instruments hold no escrowed tokens, so expiry only releases the owner's claim on the
+    /// commitment value by taking the instrument out of the active state.
+    pub fn expire_instrument(e: Env, instrument_id: String) {
+        let key = DataKey::SecondaryInstrument(instrument_id.clone());
+        let mut instrument = e
+            .storage()
+            .instance()
+            .get::<_, SecondaryInstrument>(&key)
+            .unwrap_or_else(|| fail(&e, TransformationError::TransformationNotFound, "expire_instrument"));
+        if instrument.status != String::from_str(&e, "active") {
+            fail(&e, TransformationError::InvalidState, "expire_instrument");
+        }
+        if instrument.expires_at == 0 || e.ledger().timestamp() < instrument.expires_at {
+            fail(&e, TransformationError::InstrumentNotExpired, "expire_instrument");
+        }
+
+        instrument.status = String::from_str(&e, "expired");
+        e.storage().instance().set(&key, &instrument);
+        e.events().publish(
+            (
+                symbol_short!("SecExpire"),
+                instrument.commitment_id,
+                instrument_id,
+                next_event_sequence(&e),
+            ),
+            (instrument.owner, instrument.amount, e.ledger().timestamp()),
+        );
+    }
+
     /// Add a protocol-specific guarantee to a commitment.
     pub fn add_protocol_guarantee(
         e: Env,
@@ -456,43 +687,80 @@ impl CommitmentTransformationContract {
         require_no_reentrancy(&e);
         set_reentrancy_guard(&e, true);
 
-        let counter: u64 = e
-            .storage()
-            .instance()
-            .get::<_, u64>(&DataKey::TrancheSetCounter)
-            .unwrap_or(0);
-        let guarantee_id = format_tranformation_id(&e, "guar", counter);
-        e.storage()
-            .instance()
-            .set(&DataKey::TrancheSetCounter, &(counter + 1));
+        if !guarantee_terms_valid(&guarantee_type, &terms_hash) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, TransformationError::InvalidGuarantee, "add_protocol_guarantee");
+        }
 
-        let guarantee = ProtocolGuarantee {
-            guarantee_id: guarantee_id.clone(),
-            commitment_id: commitment_id.clone(),
-            guarantee_type: guarantee_type.clone(),
-            terms_hash: terms_hash.clone(),
-            created_at: e.ledger().timestamp(),
-        };
-        e.storage()
-            .instance()
-            .set(&DataKey::ProtocolGuarantee(guarantee_id.clone()), &guarantee);
+        let guarantee_id = store_protocol_guarantee(
+            &e,
+            &caller,
+            &commitment_id,
+            &guarantee_type,
+            &terms_hash,
+        );
 
-        let mut list = e
-            .storage()
-            .instance()
-            .get::<_, Vec<String>>(&DataKey::CommitmentGuarantees(commitment_id.clone()))
-            .unwrap_or(Vec::new(&e));
-        list.push_back(guarantee_id.clone());
-        e.storage()
-            .instance()
-            .set(&DataKey::CommitmentGuarantees(commitment_id.clone()), &list);
+        set_reentrancy_guard(&e, false);
+        guarantee_id
+    }
+
+    /// Split a commitment into tranches and attach a protocol guarantee in one call.
+    /// Both inputs are validated before anything is written, so either both records are
+    /// created or neither is. Returns (transformation_id, guarantee_id).
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_structured_product(
+        e: Env,
+        caller: Address,
+        commitment_id: String,
+        total_value: i128,
+        tranche_share_bps: Vec<u32>,
+        risk_levels: Vec<String>,
+        fee_asset: Address,
+        guarantee_type: String,
+        terms_hash: String,
+    ) -> (String, String) {
+        require_authorized(&e, &caller);
+        require_no_reentrancy(&e);
+        set_reentrancy_guard(&e, true);
+
+        Validation::require_positive(total_value);
+        if !tranche_shares_valid(&e, &tranche_share_bps, &risk_levels) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, TransformationError::InvalidTrancheRatios, "create_structured_product");
+        }
+        if !guarantee_terms_valid(&guarantee_type, &terms_hash) {
+            set_reentrancy_guard(&e, false);
+            fail(&e, TransformationError::InvalidGuarantee, "create_structured_product");
+        }
+
+        let transformation_id = store_tranche_set(
+            &e,
+            &caller,
+            &commitment_id,
+            total_value,
+            &tranche_share_bps,
+            &risk_levels,
+            &fee_asset,
+        );
+        let guarantee_id = store_protocol_guarantee(
+            &e,
+            &caller,
+            &commitment_id,
+            &guarantee_type,
+            &terms_hash,
+        );
 
         set_reentrancy_guard(&e, false);
         e.events().publish(
-            (symbol_short!("GuarAdded"), guarantee_id.clone(), caller),
-            (commitment_id, guarantee_type, terms_hash, e.ledger().timestamp()),
+            (
+                symbol_short!("StructPrd"),
+                commitment_id,
+                transformation_id.clone(),
+                next_event_sequence(&e),
+            ),
+            (caller, guarantee_id.clone(), e.ledger().timestamp()),
         );
-        guarantee_id
+        (transformation_id, guarantee_id)
     }
 
     /// Get tranche set by ID.
@@ -573,6 +841,36 @@ impl CommitmentTransformationContract {
             .unwrap_or(0)
     }
 
+    /// Configuration bundle: (admin, core contract, transformation fee bps, id counter).
+    pub fn get_config(e: Env) -> (Address, Address, u32, u64) {
+        let core = e
+            .storage()
+            .instance()
+            .get::<_, Address>(&DataKey::CoreContract)
+            .unwrap_or_else(|| fail(&e, TransformationError::NotInitialized, "get_config"));
+        let counter = e
+            .storage()
+            .instance()
+            .get::<_, u64>(&DataKey::TrancheSetCounter)
+            .unwrap_or(0);
+        (
+            Self::get_admin(e.clone()),
+            core,
+            Self::get_transformation_fee_bps(e.clone()),
+            counter,
+        )
+    }
+
+    /// Sequence number of the last commitment event (0 before any).
+    /// Commitment events share the topic shape (name, commitment_id, record_id, sequence);
+    /// admin configuration events are not sequenced.
+    pub fn get_event_sequence(e: Env) -> u64 {
+        e.storage()
+            .instance()
+            .get::<_, u64>(&DataKey::EventSequence)
+            .unwrap_or(0)
+    }
+
     /// Set fee recipient (protocol treasury). Admin only.
     pub fn set_fee_recipient(e: Env, caller: Address, recipient: Address) {
         require_admin(&e, &caller);
@@ -583,6 +881,33 @@ impl CommitmentTransformationContract {
         );
     }
 
+    /// Route `insurance_bps` of each transformation fee to `insurance_fund`. Admin only.
+    pub fn set_insurance_fund(e: Env, caller: Address, insurance_fund: Address, insurance_bps: u32) {
+        require_admin(&e, &caller);
+        if insurance_bps > 10000 {
+            fail(&e, TransformationError::InvalidFeeBps, "set_insurance_fund");
+        }
+        e.storage().instance().set(&DataKey::InsuranceFund, &insurance_fund);
+        e.storage().instance().set(&DataKey::InsuranceBps, &insurance_bps);
+        e.events().publish(
+            (symbol_short!("InsurSet"), caller),
+            (insurance_fund, insurance_bps, e.ledger().timestamp()),
+        );
+    }
+
+    /// Get the insurance fund address (optional).
+    pub fn get_insurance_fund(e: Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::InsuranceFund)
+    }
+
+    /// Get the insurance share of transformation fees in basis points.
+    pub fn get_insurance_bps(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get::<_, u32>(&DataKey::InsuranceBps)
+            .unwrap_or(0)
+    }
+
     /// Withdraw collected transformation fees to the configured fee recipient. Admin only.
     pub fn withdraw_fees(e: Env, caller: Address, asset_address: Address, amount: i128) {
         require_admin(&e, &caller);
@@ -621,6 +946,214 @@ impl CommitmentTransformationContract {
             .get::<_, i128>(&DataKey::CollectedFees(asset_address))
             .unwrap_or(0)
     }
+
+    /// Get unreleased collateral escrowed in an asset.
+    pub fn get_escrowed_collateral(e: Env, asset_address: Address) -> i128 {
+        e.storage()
+            .instance()
+            .get::<_, i128>(&DataKey::EscrowedCollateral(asset_address))
+            .unwrap_or(0)
+    }
+
+    /// Transfer the unaccounted balance of `asset_address` (rounding dust, stray
+    /// transfers) to `to`. Escrowed collateral and collected fees are never touched.
+    /// Admin only. Returns the amount swept.
+    pub fn sweep_dust(e: Env, caller: Address, asset_address: Address, to: Address) -> i128 {
+        require_admin(&e, &caller);
+        require_no_reentrancy(&e);
+
+        let contract_address = e.current_contract_address();
+        let token_client = token::Client::new(&e, &asset_address);
+        let liabilities = Self::get_escrowed_collateral(e.clone(), asset_address.clone())
+            + Self::get_collected_fees(e.clone(), asset_address.clone());
+        let surplus = token_client.balance(&contract_address) - liabilities;
+        if surplus <= 0 {
+            fail(&e, TransformationError::NothingToSweep, "sweep_dust");
+        }
+
+        token_client.transfer(&contract_address, &to, &surplus);
+        e.events().publish(
+            (symbol_short!("DustSwept"), caller, to),
+            (asset_address, surplus, e.ledger().timestamp()),
+        );
+        surplus
+    }
+}
+
+fn tranche_shares_valid(e: &Env, tranche_share_bps: &Vec<u32>, risk_levels: &Vec<String>) -> bool {
+    if tranche_share_bps.len() != risk_levels.len() || tranche_share_bps.is_empty() {
+        return false;
+    }
+    if tranche_share_bps.len() > CommitmentTransformationContract::get_max_tranches(e.clone()) {
+        return false;
+    }
+    let mut sum_bps: u32 = 0;
+    for bps in tranche_share_bps.iter() {
+        sum_bps = sum_bps.saturating_add(bps);
+    }
+    sum_bps == 10000
+}
+
+fn guarantee_terms_valid(guarantee_type: &String, terms_hash: &String) -> bool {
+    !guarantee_type.is_empty() && !terms_hash.is_empty()
+}
+
+/// Collect the transformation fee and persist a validated tranche set.
+fn store_tranche_set(
+    e: &Env,
+    caller: &Address,
+    commitment_id: &String,
+    total_value: i128,
+    tranche_share_bps: &Vec<u32>,
+    risk_levels: &Vec<String>,
+    fee_asset: &Address,
+) -> String {
+    let fee_bps = fee_bps_for_commitment(e, commitment_id);
+    let fee_amount = (total_value * fee_bps as i128) / 10000i128;
+
+    // Collect transformation fee from caller when fee_bps > 0; the insurance
+    // share goes straight to the fund, the rest is held for the treasury
+    if fee_amount > 0 {
+        let token_client = token::Client::new(e, fee_asset);
+        let insurance_fund: Option<Address> = e.storage().instance().get(&DataKey::InsuranceFund);
+        let insurance_bps = match insurance_fund {
+            Some(_) => e
+                .storage()
+                .instance()
+                .get::<_, u32>(&DataKey::InsuranceBps)
+                .unwrap_or(0),
+            None => 0,
+        };
+        let (treasury_part, insurance_part) = split_fee(fee_amount, insurance_bps);
+        if treasury_part > 0 {
+            let contract_address = e.current_contract_address();
+            token_client.transfer(caller, &contract_address, &treasury_part);
+            let key = DataKey::CollectedFees(fee_asset.clone());
+            let current: i128 = e.storage().instance().get::<_, i128>(&key).unwrap_or(0);
+            e.storage().instance().set(&key, &(current + treasury_part));
+        }
+        if insurance_part > 0 {
+            if let Some(fund) = insurance_fund {
+                token_client.transfer(caller, &fund, &insurance_part);
+            }
+        }
+    }
+
+    let counter: u64 = e
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKey::TrancheSetCounter)
+        .unwrap_or(0);
+    let transformation_id = format_tranformation_id(e, "tr", counter);
+    e.storage()
+        .instance()
+        .set(&DataKey::TrancheSetCounter, &(counter + 1));
+
+    let mut tranches = Vec::new(e);
+    let net_value = total_value - fee_amount;
+    for (i, (bps, risk)) in tranche_share_bps.iter().zip(risk_levels.iter()).enumerate() {
+        let bps_u32: u32 = bps;
+        let amount = (net_value * bps_u32 as i128) / 10000i128;
+        let tranche_id = format_tranformation_id(e, "t", counter * 10 + i as u64);
+        tranches.push_back(RiskTranche {
+            tranche_id: tranche_id.clone(),
+            commitment_id: commitment_id.clone(),
+            risk_level: risk.clone(),
+            amount,
+            share_bps: bps_u32,
+            created_at: e.ledger().timestamp(),
+        });
+    }
+
+    let set = TrancheSet {
+        transformation_id: transformation_id.clone(),
+        commitment_id: commitment_id.clone(),
+        owner: caller.clone(),
+        total_value,
+        tranches: tranches.clone(),
+        fee_paid: fee_amount,
+        created_at: e.ledger().timestamp(),
+    };
+    e.storage()
+        .instance()
+        .set(&DataKey::TrancheSet(transformation_id.clone()), &set);
+
+    let mut sets = e
+        .storage()
+        .instance()
+        .get::<_, Vec<String>>(&DataKey::CommitmentTrancheSets(commitment_id.clone()))
+        .unwrap_or(Vec::new(e));
+    sets.push_back(transformation_id.clone());
+    e.storage()
+        .instance()
+        .set(&DataKey::CommitmentTrancheSets(commitment_id.clone()), &sets);
+
+    e.events().publish(
+        (
+            symbol_short!("TrCreated"),
+            commitment_id.clone(),
+            transformation_id.clone(),
+            next_event_sequence(e),
+        ),
+        (caller.clone(), total_value, fee_amount, e.ledger().timestamp()),
+    );
+    transformation_id
+}
+
+/// Persist a protocol guarantee for a commitment.
+fn store_protocol_guarantee(
+    e: &Env,
+    caller: &Address,
+    commitment_id: &String,
+    guarantee_type: &String,
+    terms_hash: &String,
+) -> String {
+    let counter: u64 = e
+        .storage()
+        .instance()
+        .get::<_, u64>(&DataKey::TrancheSetCounter)
+        .unwrap_or(0);
+    let guarantee_id = format_tranformation_id(e, "guar", counter);
+    e.storage()
+        .instance()
+        .set(&DataKey::TrancheSetCounter, &(counter + 1));
+
+    let guarantee = ProtocolGuarantee {
+        guarantee_id: guarantee_id.clone(),
+        commitment_id: commitment_id.clone(),
+        guarantee_type: guarantee_type.clone(),
+        terms_hash: terms_hash.clone(),
+        created_at: e.ledger().timestamp(),
+    };
+    e.storage()
+        .instance()
+        .set(&DataKey::ProtocolGuarantee(guarantee_id.clone()), &guarantee);
+
+    let mut list = e
+        .storage()
+        .instance()
+        .get::<_, Vec<String>>(&DataKey::CommitmentGuarantees(commitment_id.clone()))
+        .unwrap_or(Vec::new(e));
+    list.push_back(guarantee_id.clone());
+    e.storage()
+        .instance()
+        .set(&DataKey::CommitmentGuarantees(commitment_id.clone()), &list);
+
+    e.events().publish(
+        (
+            symbol_short!("GuarAdded"),
+            commitment_id.clone(),
+            guarantee_id.clone(),
+            next_event_sequence(e),
+        ),
+        (
+            caller.clone(),
+            guarantee_type.clone(),
+            terms_hash.clone(),
+            e.ledger().timestamp(),
+        ),
+    );
+    guarantee_id
 }
 
 fn format_tranformation_id(e: &Env, prefix: &str, n: u64) -> String {
@@ -1,8 +1,10 @@
 #![cfg(test)]
 
 use super::*;
-use soroban_sdk::testutils::Address as _;
-use soroban_sdk::{vec, Address, Env, String, Vec};
+use soroban_sdk::testutils::{Address as _, Events, Ledger};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short, token, vec, Address, Env, IntoVal, String, Vec,
+};
 
 fn setup(e: &Env) -> (Address, Address, Address) {
     let admin = Address::generate(e);
@@ -11,6 +13,34 @@ fn setup(e: &Env) -> (Address, Address, Address) {
     (admin, core, user)
 }
 
+fn funded_asset(e: &Env, holder: &Address, amount: i128) -> Address {
+    let asset = e
+        .register_stellar_asset_contract_v2(Address::generate(e))
+        .address();
+    token::StellarAssetClient::new(e, &asset).mint(holder, &amount);
+    asset
+}
+
+/// Minimal core stand-in whose commitments tests can set directly
+#[contract]
+pub struct MockCoreContract;
+
+#[contractimpl]
+impl MockCoreContract {
+    pub fn set_commitment(e: Env, commitment_id: String, commitment: Commitment) {
+        e.storage()
+            .instance()
+            .set(&(symbol_short!("cmt"), commitment_id), &commitment);
+    }
+
+    pub fn get_commitment(e: Env, commitment_id: String) -> Commitment {
+        e.storage()
+            .instance()
+            .get(&(symbol_short!("cmt"), commitment_id))
+            .expect("Commitment not found")
+    }
+}
+
 #[test]
 fn test_initialize() {
     let e = Env::default();
@@ -47,6 +77,33 @@ fn test_set_transformation_fee() {
     assert_eq!(client.get_transformation_fee_bps(), 100);
 }
 
+#[test]
+fn test_get_config_matches_individual_getters() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, core, user) = setup(&e);
+    let contract_id = e.register_contract(None, CommitmentTransformationContract);
+    let client = CommitmentTransformationContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &core);
+    assert_eq!(client.get_config(), (admin.clone(), core.clone(), 0, 0));
+
+    client.set_transformation_fee(&admin, &250);
+    client.set_authorized_transformer(&admin, &user, &true);
+    client.collateralize(
+        &user,
+        &String::from_str(&e, "c_1"),
+        &500_000i128,
+        &funded_asset(&e, &user, 500_000),
+    );
+
+    let (cfg_admin, cfg_core, cfg_fee_bps, cfg_counter) = client.get_config();
+    assert_eq!(cfg_admin, client.get_admin());
+    assert_eq!(cfg_core, core);
+    assert_eq!(cfg_fee_bps, client.get_transformation_fee_bps());
+    assert_eq!(cfg_fee_bps, 250);
+    assert_eq!(cfg_counter, 1);
+}
+
 #[test]
 fn test_set_authorized_transformer() {
     let e = Env::default();
@@ -127,6 +184,97 @@ fn test_create_tranches_invalid_ratios() {
     );
 }
 
+fn equal_tranches(e: &Env, count: u32) -> (Vec<u32>, Vec<String>) {
+    let mut shares = Vec::new(e);
+    let mut levels = Vec::new(e);
+    for i in 0..count {
+        // Last tranche absorbs the rounding remainder so shares sum to 10000
+        let share = if i == count - 1 {
+            10000 - (10000 / count) * (count - 1)
+        } else {
+            10000 / count
+        };
+        shares.push_back(share);
+        levels.push_back(String::from_str(e, "senior"));
+    }
+    (shares, levels)
+}
+
+#[test]
+fn test_max_tranches_default_and_boundary() {
+    let e = Env::default();
+    let (client, user) = setup_structured(&e);
+    assert_eq!(client.get_max_tranches(), DEFAULT_MAX_TRANCHES);
+
+    let (shares, levels) = equal_tranches(&e, DEFAULT_MAX_TRANCHES);
+    let id = client.create_tranches(
+        &user,
+        &String::from_str(&e, "c_1"),
+        &1_000_000i128,
+        &shares,
+        &levels,
+        &Address::generate(&e),
+    );
+    assert_eq!(client.get_tranche_set(&id).tranches.len(), DEFAULT_MAX_TRANCHES);
+}
+
+#[test]
+#[should_panic(expected = "Tranche ratios must sum to 100")]
+fn test_create_tranches_over_max_fails() {
+    let e = Env::default();
+    let (client, user) = setup_structured(&e);
+
+    let (shares, levels) = equal_tranches(&e, DEFAULT_MAX_TRANCHES + 1);
+    client.create_tranches(
+        &user,
+        &String::from_str(&e, "c_1"),
+        &1_000_000i128,
+        &shares,
+        &levels,
+        &Address::generate(&e),
+    );
+}
+
+#[test]
+fn test_set_max_tranches() {
+    let e = Env::default();
+    let (client, user) = setup_structured(&e);
+    let admin = client.get_admin();
+    client.set_max_tranches(&admin, &2);
+    assert_eq!(client.get_max_tranches(), 2);
+
+    let commitment_id = String::from_str(&e, "c_1");
+    let (shares, levels) = equal_tranches(&e, 2);
+    client.create_tranches(
+        &user,
+        &commitment_id,
+        &1_000_000i128,
+        &shares,
+        &levels,
+        &Address::generate(&e),
+    );
+
+    let (shares, levels) = equal_tranches(&e, 3);
+    let result = client.try_create_tranches(
+        &user,
+        &commitment_id,
+        &1_000_000i128,
+        &shares,
+        &levels,
+        &Address::generate(&e),
+    );
+    assert!(result.is_err());
+    assert_eq!(client.get_commitment_tranche_sets(&commitment_id).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Invalid amount: must be positive")]
+fn test_set_max_tranches_zero_fails() {
+    let e = Env::default();
+    let (client, _user) = setup_structured(&e);
+    client.set_max_tranches(&client.get_admin(), &0);
+}
+
 #[test]
 fn test_collateralize() {
     let e = Env::default();
@@ -138,9 +286,11 @@ fn test_collateralize() {
     client.set_authorized_transformer(&admin, &user, &true);
 
     let commitment_id = String::from_str(&e, "c_1");
-    let asset = Address::generate(&e);
+    let asset = funded_asset(&e, &user, 500_000);
     let asset_id = client.collateralize(&user, &commitment_id, &500_000i128, &asset);
     assert!(!asset_id.is_empty());
+    assert_eq!(token::Client::new(&e, &asset).balance(&contract_id), 500_000);
+    assert_eq!(token::Client::new(&e, &asset).balance(&user), 0);
 
     let col = client.get_collateralized_asset(&asset_id);
     assert_eq!(col.commitment_id, commitment_id);
@@ -164,7 +314,7 @@ fn test_create_secondary_instrument() {
     let instrument_type = String::from_str(&e, "receivable");
     let amount = 200_000i128;
     let instrument_id =
-        client.create_secondary_instrument(&user, &commitment_id, &instrument_type, &amount);
+        client.create_secondary_instrument(&user, &commitment_id, &instrument_type, &amount, &0);
     assert!(!instrument_id.is_empty());
 
     let inst = client.get_secondary_instrument(&instrument_id);
@@ -172,9 +322,88 @@ fn test_create_secondary_instrument() {
     assert_eq!(inst.owner, user);
     assert_eq!(inst.instrument_type, instrument_type);
     assert_eq!(inst.amount, amount);
+    assert_eq!(inst.expires_at, 0);
+    assert_eq!(inst.status, String::from_str(&e, "active"));
     assert_eq!(client.get_commitment_instruments(&commitment_id).len(), 1);
 }
 
+fn setup_option(e: &Env) -> (CommitmentTransformationContractClient<'_>, Address, String) {
+    e.mock_all_auths();
+    e.ledger().with_mut(|l| l.timestamp = 1_000);
+    let (admin, core, user) = setup(e);
+    let contract_id = e.register_contract(None, CommitmentTransformationContract);
+    let client = CommitmentTransformationContractClient::new(e, &contract_id);
+    client.initialize(&admin, &core);
+    client.set_authorized_transformer(&admin, &user, &true);
+
+    let instrument_id = client.create_secondary_instrument(
+        &user,
+        &String::from_str(e, "c_1"),
+        &String::from_str(e, "option"),
+        &200_000i128,
+        &500,
+    );
+    (client, user, instrument_id)
+}
+
+#[test]
+fn test_exercise_instrument_before_expiry() {
+    let e = Env::default();
+    let (client, user, instrument_id) = setup_option(&e);
+    assert_eq!(client.get_secondary_instrument(&instrument_id).expires_at, 1_500);
+
+    e.ledger().with_mut(|l| l.timestamp = 1_499);
+    client.exercise_instrument(&user, &instrument_id);
+    assert_eq!(
+        client.get_secondary_instrument(&instrument_id).status,
+        String::from_str(&e, "exercised")
+    );
+}
+
+#[test]
+#[should_panic(expected = "Instrument has expired")]
+fn test_exercise_instrument_after_expiry_fails() {
+    let e = Env::default();
+    let (client, user, instrument_id) = setup_option(&e);
+
+    e.ledger().with_mut(|l| l.timestamp = 1_500);
+    client.exercise_instrument(&user, &instrument_id);
+}
+
+#[test]
+fn test_expire_instrument_after_expiry() {
+    let e = Env::default();
+    let (client, _user, instrument_id) = setup_option(&e);
+
+    e.ledger().with_mut(|l| l.timestamp = 1_500);
+    client.expire_instrument(&instrument_id);
+    assert_eq!(
+        client.get_secondary_instrument(&instrument_id).status,
+        String::from_str(&e, "expired")
+    );
+}
+
+#[test]
+#[should_panic(expected = "Instrument has not expired")]
+fn test_expire_instrument_before_expiry_fails() {
+    let e = Env::default();
+    let (client, _user, instrument_id) = setup_option(&e);
+
+    e.ledger().with_mut(|l| l.timestamp = 1_499);
+    client.expire_instrument(&instrument_id);
+}
+
+#[test]
+#[should_panic(expected = "Invalid state for transformation")]
+fn test_expire_exercised_instrument_fails() {
+    let e = Env::default();
+    let (client, user, instrument_id) = setup_option(&e);
+
+    client.exercise_instrument(&user, &instrument_id);
+    e.ledger().with_mut(|l| l.timestamp = 2_000);
+    client.expire_instrument(&instrument_id);
+}
+
 #[test]
 fn test_add_protocol_guarantee() {
     let e = Env::default();
@@ -199,6 +428,112 @@ fn test_add_protocol_guarantee() {
     assert_eq!(client.get_commitment_guarantees(&commitment_id).len(), 1);
 }
 
+#[test]
+#[should_panic(expected = "Guarantee type and terms hash are required")]
+fn test_add_protocol_guarantee_requires_terms() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (admin, core, user) = setup(&e);
+    let contract_id = e.register_contract(None, CommitmentTransformationContract);
+    let client = CommitmentTransformationContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &core);
+    client.set_authorized_transformer(&admin, &user, &true);
+
+    client.add_protocol_guarantee(
+        &user,
+        &String::from_str(&e, "c_1"),
+        &String::from_str(&e, "liquidity_backstop"),
+        &String::from_str(&e, ""),
+    );
+}
+
+fn setup_structured(e: &Env) -> (CommitmentTransformationContractClient<'_>, Address) {
+    e.mock_all_auths();
+    let (admin, core, user) = setup(e);
+    let contract_id = e.register_contract(None, CommitmentTransformationContract);
+    let client = CommitmentTransformationContractClient::new(e, &contract_id);
+    client.initialize(&admin, &core);
+    client.set_authorized_transformer(&admin, &user, &true);
+    (client, user)
+}
+
+#[test]
+fn test_create_structured_product() {
+    let e = Env::default();
+    let (client, user) = setup_structured(&e);
+
+    let commitment_id = String::from_str(&e, "c_1");
+    let (transformation_id, guarantee_id) = client.create_structured_product(
+        &user,
+        &commitment_id,
+        &1_000_000i128,
+        &vec![&e, 7000u32, 3000u32],
+        &vec![
+            &e,
+            String::from_str(&e, "senior"),
+            String::from_str(&e, "equity"),
+        ],
+        &Address::generate(&e),
+        &String::from_str(&e, "liquidity_backstop"),
+        &String::from_str(&e, "0xabc123"),
+    );
+
+    let set = client.get_tranche_set(&transformation_id);
+    assert_eq!(set.tranches.len(), 2);
+    let guar = client.get_protocol_guarantee(&guarantee_id);
+    assert_eq!(guar.commitment_id, commitment_id);
+    assert_eq!(client.get_commitment_tranche_sets(&commitment_id).len(), 1);
+    assert_eq!(client.get_commitment_guarantees(&commitment_id).len(), 1);
+}
+
+#[test]
+fn test_create_structured_product_invalid_tranches_writes_nothing() {
+    let e = Env::default();
+    let (client, user) = setup_structured(&e);
+
+    let commitment_id = String::from_str(&e, "c_1");
+    let result = client.try_create_structured_product(
+        &user,
+        &commitment_id,
+        &1_000_000i128,
+        &vec![&e, 7000u32, 2000u32],
+        &vec![
+            &e,
+            String::from_str(&e, "senior"),
+            String::from_str(&e, "equity"),
+        ],
+        &Address::generate(&e),
+        &String::from_str(&e, "liquidity_backstop"),
+        &String::from_str(&e, "0xabc123"),
+    );
+    assert!(result.is_err());
+    assert_eq!(client.get_commitment_tranche_sets(&commitment_id).len(), 0);
+    assert_eq!(client.get_commitment_guarantees(&commitment_id).len(), 0);
+    assert_eq!(client.get_config().3, 0);
+}
+
+#[test]
+fn test_create_structured_product_invalid_guarantee_writes_nothing() {
+    let e = Env::default();
+    let (client, user) = setup_structured(&e);
+
+    let commitment_id = String::from_str(&e, "c_1");
+    let result = client.try_create_structured_product(
+        &user,
+        &commitment_id,
+        &1_000_000i128,
+        &vec![&e, 10000u32],
+        &vec![&e, String::from_str(&e, "senior")],
+        &Address::generate(&e),
+        &String::from_str(&e, ""),
+        &String::from_str(&e, "0xabc123"),
+    );
+    assert!(result.is_err());
+    assert_eq!(client.get_commitment_tranche_sets(&commitment_id).len(), 0);
+    assert_eq!(client.get_commitment_guarantees(&commitment_id).len(), 0);
+    assert_eq!(client.get_config().3, 0);
+}
+
 #[test]
 #[should_panic(expected = "Unauthorized")]
 fn test_create_tranches_unauthorized() {
@@ -258,6 +593,127 @@ fn test_transformation_with_fee() {
     assert_eq!(set.total_value, total_value);
 }
 
+#[test]
+fn test_transformation_fee_split_with_insurance_fund() {
+    let e = Env::default();
+    let (client, user) = setup_structured(&e);
+    let admin = client.get_admin();
+    let insurance_fund = Address::generate(&e);
+    let fee_asset = funded_asset(&e, &user, 100_000);
+    client.set_transformation_fee(&admin, &100); // 1%
+    client.set_insurance_fund(&admin, &insurance_fund, &2500);
+    assert_eq!(client.get_insurance_fund(), Some(insurance_fund.clone()));
+    assert_eq!(client.get_insurance_bps(), 2500);
+
+    let id = client.create_tranches(
+        &user,
+        &String::from_str(&e, "c_1"),
+        &1_000_000i128,
+        &vec![&e, 10000u32],
+        &vec![&e, String::from_str(&e, "senior")],
+        &fee_asset,
+    );
+
+    // Fee of 10_000 split 75/25 between treasury and insurance
+    let token_client = token::Client::new(&e, &fee_asset);
+    assert_eq!(client.get_tranche_set(&id).fee_paid, 10_000);
+    assert_eq!(client.get_collected_fees(&fee_asset), 7_500);
+    assert_eq!(token_client.balance(&client.address), 7_500);
+    assert_eq!(token_client.balance(&insurance_fund), 2_500);
+    assert_eq!(token_client.balance(&user), 90_000);
+}
+
+#[test]
+#[should_panic(expected = "Fee must be 0-10000 bps")]
+fn test_set_insurance_fund_invalid_bps() {
+    let e = Env::default();
+    let (client, _user) = setup_structured(&e);
+    client.set_insurance_fund(&client.get_admin(), &Address::generate(&e), &10001);
+}
+
+fn mock_core_commitment(e: &Env, core_id: &Address, commitment_id: &str, commitment_type: &str) {
+    let commitment = Commitment {
+        commitment_id: String::from_str(e, commitment_id),
+        owner: Address::generate(e),
+        nft_token_id: 1,
+        rules: CommitmentRules {
+            duration_days: 30,
+            max_loss_percent: 10,
+            commitment_type: String::from_str(e, commitment_type),
+            early_exit_penalty: 5,
+            min_fee_threshold: 0,
+            grace_period_days: 0,
+            max_loss_bps: 0,
+        },
+        amount: 1_000_000,
+        asset_address: Address::generate(e),
+        created_at: 0,
+        expires_at: 30 * 86400,
+        current_value: 1_000_000,
+        status: String::from_str(e, "active"),
+    };
+    e.as_contract(core_id, || {
+        MockCoreContract::set_commitment(e.clone(), commitment.commitment_id.clone(), commitment);
+    });
+}
+
+#[test]
+fn test_fee_for_type_override_and_fallback() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let admin = Address::generate(&e);
+    let user = Address::generate(&e);
+    let core_id = e.register_contract(None, MockCoreContract);
+    let contract_id = e.register_contract(None, CommitmentTransformationContract);
+    let client = CommitmentTransformationContractClient::new(&e, &contract_id);
+    client.initialize(&admin, &core_id);
+    client.set_authorized_transformer(&admin, &user, &true);
+    client.set_transformation_fee(&admin, &100); // 1% flat
+
+    let aggressive = String::from_str(&e, "aggressive");
+    let safe = String::from_str(&e, "safe");
+    client.set_fee_for_type(&admin, &aggressive, &Some(500)); // 5% for aggressive
+    assert_eq!(client.get_fee_for_type(&aggressive), 500);
+    assert_eq!(client.get_fee_for_type(&safe), 100);
+
+    mock_core_commitment(&e, &core_id, "c_aggr", "aggressive");
+    mock_core_commitment(&e, &core_id, "c_safe", "safe");
+    let fee_asset = funded_asset(&e, &user, 100_000);
+
+    let aggr_id = client.create_tranches(
+        &user,
+        &String::from_str(&e, "c_aggr"),
+        &1_000_000i128,
+        &vec![&e, 10000u32],
+        &vec![&e, String::from_str(&e, "senior")],
+        &fee_asset,
+    );
+    let safe_id = client.create_tranches(
+        &user,
+        &String::from_str(&e, "c_safe"),
+        &1_000_000i128,
+        &vec![&e, 10000u32],
+        &vec![&e, String::from_str(&e, "senior")],
+        &fee_asset,
+    );
+
+    assert_eq!(client.get_tranche_set(&aggr_id).fee_paid, 50_000);
+    assert_eq!(client.get_tranche_set(&safe_id).fee_paid, 10_000);
+    assert_eq!(client.get_collected_fees(&fee_asset), 60_000);
+
+    // Clearing the override falls back to the flat fee
+    client.set_fee_for_type(&admin, &aggressive, &None);
+    assert_eq!(client.get_fee_for_type(&aggressive), 100);
+}
+
+#[test]
+#[should_panic(expected = "Fee must be 0-10000 bps")]
+fn test_set_fee_for_type_invalid_bps() {
+    let e = Env::default();
+    let (client, _user) = setup_structured(&e);
+    client.set_fee_for_type(&client.get_admin(), &String::from_str(&e, "aggressive"), &Some(10001));
+}
+
 #[test]
 fn test_transformation_fee_calculation_and_collection() {
     // Test fee calculation: 1% of 1_000_000 = 10_000 (logic only; actual transfer needs token mock)
@@ -304,3 +760,188 @@ fn test_fee_withdraw_requires_recipient() {
     let asset = Address::generate(&e);
     client.withdraw_fees(&admin, &asset, &100i128);
 }
+
+#[test]
+fn test_event_sequence_increments_per_creation() {
+    let e = Env::default();
+    let (client, user) = setup_structured(&e);
+    assert_eq!(client.get_event_sequence(), 0);
+
+    let commitment_id = String::from_str(&e, "c_1");
+    client.create_tranches(
+        &user,
+        &commitment_id,
+        &1_000_000i128,
+        &vec![&e, 10000u32],
+        &vec![&e, String::from_str(&e, "senior")],
+        &Address::generate(&e),
+    );
+    assert_eq!(client.get_event_sequence(), 1);
+
+    let asset = funded_asset(&e, &user, 500_000);
+    let asset_id = client.collateralize(&user, &commitment_id, &500_000i128, &asset);
+    assert_eq!(client.get_event_sequence(), 2);
+
+    let events = e.events().all();
+    let last_event = events.last().unwrap();
+    assert_eq!(
+        last_event.1,
+        vec![
+            &e,
+            symbol_short!("Collater").into_val(&e),
+            commitment_id.into_val(&e),
+            asset_id.into_val(&e),
+            2u64.into_val(&e)
+        ]
+    );
+
+    client.create_secondary_instrument(
+        &user,
+        &commitment_id,
+        &String::from_str(&e, "receivable"),
+        &200_000i128,
+        &0,
+    );
+    client.add_protocol_guarantee(
+        &user,
+        &commitment_id,
+        &String::from_str(&e, "liquidity_backstop"),
+        &String::from_str(&e, "0xabc123"),
+    );
+    assert_eq!(client.get_event_sequence(), 4);
+
+    // Tranche set, guarantee and the combined summary event
+    client.create_structured_product(
+        &user,
+        &commitment_id,
+        &1_000_000i128,
+        &vec![&e, 10000u32],
+        &vec![&e, String::from_str(&e, "senior")],
+        &Address::generate(&e),
+        &String::from_str(&e, "liquidity_backstop"),
+        &String::from_str(&e, "0xdef456"),
+    );
+    assert_eq!(client.get_event_sequence(), 7);
+}
+
+fn setup_collateral<'a>(
+    e: &'a Env,
+    status: &str,
+) -> (CommitmentTransformationContractClient<'a>, Address, Address, String) {
+    e.mock_all_auths();
+    let admin = Address::generate(e);
+    let user = Address::generate(e);
+    let core_id = e.register_contract(None, MockCoreContract);
+    let contract_id = e.register_contract(None, CommitmentTransformationContract);
+    let client = CommitmentTransformationContractClient::new(e, &contract_id);
+    client.initialize(&admin, &core_id);
+    client.set_authorized_transformer(&admin, &user, &true);
+
+    let commitment_id = String::from_str(e, "c_1");
+    let commitment = Commitment {
+        commitment_id: commitment_id.clone(),
+        owner: user.clone(),
+        nft_token_id: 1,
+        rules: CommitmentRules {
+            duration_days: 30,
+            max_loss_percent: 10,
+            commitment_type: String::from_str(e, "safe"),
+            early_exit_penalty: 5,
+            min_fee_threshold: 0,
+            grace_period_days: 0,
+            max_loss_bps: 0,
+        },
+        amount: 1_000_000,
+        asset_address: Address::generate(e),
+        created_at: 0,
+        expires_at: 30 * 86400,
+        current_value: 1_000_000,
+        status: String::from_str(e, status),
+    };
+    e.as_contract(&core_id, || {
+        MockCoreContract::set_commitment(e.clone(), commitment_id.clone(), commitment);
+    });
+
+    let asset = funded_asset(e, &user, 500_000);
+    let asset_id = client.collateralize(&user, &commitment_id, &500_000i128, &asset);
+    (client, user, asset, asset_id)
+}
+
+#[test]
+fn test_release_collateral_after_settlement() {
+    let e = Env::default();
+    let (client, user, asset, asset_id) = setup_collateral(&e, "settled");
+
+    client.release_collateral(&user, &asset_id);
+
+    assert_eq!(token::Client::new(&e, &asset).balance(&user), 500_000);
+    assert!(client.get_collateralized_asset(&asset_id).released);
+}
+
+#[test]
+#[should_panic(expected = "Commitment has not settled")]
+fn test_release_collateral_active_commitment_fails() {
+    let e = Env::default();
+    let (client, user, _asset, asset_id) = setup_collateral(&e, "active");
+    client.release_collateral(&user, &asset_id);
+}
+
+#[test]
+#[should_panic(expected = "Commitment has not settled")]
+fn test_release_collateral_violated_commitment_fails() {
+    let e = Env::default();
+    let (client, user, _asset, asset_id) = setup_collateral(&e, "violated");
+    client.release_collateral(&user, &asset_id);
+}
+
+#[test]
+#[should_panic(expected = "Invalid state for transformation")]
+fn test_release_collateral_twice_fails() {
+    let e = Env::default();
+    let (client, user, _asset, asset_id) = setup_collateral(&e, "settled");
+    client.release_collateral(&user, &asset_id);
+    client.release_collateral(&user, &asset_id);
+}
+
+#[test]
+fn test_sweep_dust_takes_only_unaccounted_balance() {
+    let e = Env::default();
+    let (client, user, asset, asset_id) = setup_collateral(&e, "settled");
+    let admin = client.get_admin();
+    let minter = token::StellarAssetClient::new(&e, &asset);
+    let token_client = token::Client::new(&e, &asset);
+    minter.mint(&user, &100_000);
+    client.set_transformation_fee(&admin, &100);
+    client.create_tranches(
+        &user,
+        &String::from_str(&e, "c_1"),
+        &333_333i128,
+        &vec![&e, 10000u32],
+        &vec![&e, String::from_str(&e, "senior")],
+        &asset,
+    );
+    assert_eq!(client.get_collected_fees(&asset), 3_333);
+    assert_eq!(client.get_escrowed_collateral(&asset), 500_000);
+
+    // Stray remainders land in the contract outside any accounting
+    minter.mint(&client.address, &4);
+    minter.mint(&client.address, &3);
+
+    let treasury = Address::generate(&e);
+    assert_eq!(client.sweep_dust(&admin, &asset, &treasury), 7);
+    assert_eq!(token_client.balance(&treasury), 7);
+    assert_eq!(token_client.balance(&client.address), 503_333);
+
+    // Liabilities are still fully backed
+    client.release_collateral(&user, &asset_id);
+    assert_eq!(client.get_escrowed_collateral(&asset), 0);
+    assert_eq!(token_client.balance(&client.address), 3_333);
+}
+
+#[test]
+#[should_panic(expected = "No unaccounted balance to sweep")]
+fn test_sweep_dust_without_surplus_fails() {
+    let e = Env::default();
+    let (client, _user, asset, _asset_id) = setup_collateral(&e, "active");
+    client.sweep_dust(&client.get_admin(), &asset, &Address::generate(&e));
+}
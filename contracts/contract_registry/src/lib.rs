@@ -0,0 +1,171 @@
+#![no_std]
+
+//! Contract Registry
+//!
+//! Maps well-known contract names (e.g. `core`, `nft`) to deployed addresses so
+//! that contracts can look each other up instead of hardcoding addresses at
+//! initialization. Upgrading a dependency then only requires updating its
+//! registry entry.
+//!
+//! The registry also coordinates protocol-wide storage migrations through
+//! `migrate_all`, which reports a per-contract outcome instead of claiming
+//! atomicity across contracts.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, vec, Address, Env, IntoVal, Symbol, Vec,
+};
+
+/// Registry errors
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum RegistryError {
+    /// Contract not initialized
+    NotInitialized = 1,
+    /// Contract already initialized
+    AlreadyInitialized = 2,
+    /// Caller is not the admin
+    Unauthorized = 3,
+}
+
+/// Storage keys for the registry contract
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Admin address
+    Admin,
+    /// Registered address for a contract name (Symbol -> Address)
+    Contract(Symbol),
+    /// Report of the most recent `migrate_all` run
+    LastMigration,
+}
+
+/// Result of calling `migrate` on one contract
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MigrationOutcome {
+    pub contract: Address,
+    pub success: bool,
+}
+
+/// Record of a `migrate_all` run
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub timestamp: u64,
+    pub outcomes: Vec<MigrationOutcome>,
+}
+
+#[contract]
+pub struct ContractRegistry;
+
+#[contractimpl]
+impl ContractRegistry {
+    /// Initialize the registry with its admin
+    pub fn initialize(e: Env, admin: Address) -> Result<(), RegistryError> {
+        if e.storage().instance().has(&DataKey::Admin) {
+            return Err(RegistryError::AlreadyInitialized);
+        }
+        e.storage().instance().set(&DataKey::Admin, &admin);
+        Ok(())
+    }
+
+    /// Register or replace the address for `name` (admin only)
+    pub fn set_contract(
+        e: Env,
+        caller: Address,
+        name: Symbol,
+        address: Address,
+    ) -> Result<(), RegistryError> {
+        require_admin(&e, &caller)?;
+
+        e.storage()
+            .instance()
+            .set(&DataKey::Contract(name.clone()), &address);
+        e.events()
+            .publish((Symbol::new(&e, "ContractSet"), name), (address,));
+        Ok(())
+    }
+
+    /// Get the address registered for `name`, if any
+    pub fn get_contract(e: Env, name: Symbol) -> Option<Address> {
+        e.storage().instance().get(&DataKey::Contract(name))
+    }
+
+    /// Get the address registered for `name`, or `fallback` when it is not registered
+    pub fn resolve_or_fallback(e: Env, name: Symbol, fallback: Address) -> Address {
+        Self::get_contract(e, name).unwrap_or(fallback)
+    }
+
+    /// Call `migrate(caller, from_version)` on each contract in order (admin only)
+    ///
+    /// Soroban cannot roll back a contract that already migrated, so one
+    /// failure does not stop the run: every contract is attempted and its
+    /// outcome is emitted as `Migrated` and recorded in the report returned
+    /// by `get_last_migration`. `caller` must also be the admin of each
+    /// target contract. Failed contracts can be retried in a later run.
+    pub fn migrate_all(
+        e: Env,
+        caller: Address,
+        contracts: Vec<Address>,
+        from_version: u32,
+    ) -> Result<Vec<MigrationOutcome>, RegistryError> {
+        require_admin(&e, &caller)?;
+
+        let migrate = Symbol::new(&e, "migrate");
+        let mut outcomes = Vec::new(&e);
+        for contract in contracts.iter() {
+            let result = e.try_invoke_contract::<(), soroban_sdk::Error>(
+                &contract,
+                &migrate,
+                vec![&e, caller.into_val(&e), from_version.into_val(&e)],
+            );
+            let success = matches!(result, Ok(Ok(())));
+
+            e.events().publish(
+                (Symbol::new(&e, "Migrated"), contract.clone()),
+                (from_version, success),
+            );
+            outcomes.push_back(MigrationOutcome { contract, success });
+        }
+
+        let report = MigrationReport {
+            from_version,
+            timestamp: e.ledger().timestamp(),
+            outcomes: outcomes.clone(),
+        };
+        e.storage().instance().set(&DataKey::LastMigration, &report);
+
+        Ok(outcomes)
+    }
+
+    /// Get the report of the most recent `migrate_all` run, if any
+    pub fn get_last_migration(e: Env) -> Option<MigrationReport> {
+        e.storage().instance().get(&DataKey::LastMigration)
+    }
+
+    /// Get the admin address
+    pub fn get_admin(e: Env) -> Result<Address, RegistryError> {
+        e.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(RegistryError::NotInitialized)
+    }
+}
+
+fn require_admin(e: &Env, caller: &Address) -> Result<(), RegistryError> {
+    caller.require_auth();
+    let admin: Address = e
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(RegistryError::NotInitialized)?;
+    if *caller != admin {
+        return Err(RegistryError::Unauthorized);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,88 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::{symbol_short, testutils::Address as _, Address, Env};
+
+fn setup(e: &Env) -> (ContractRegistryClient<'_>, Address) {
+    e.mock_all_auths();
+    let admin = Address::generate(e);
+    let contract_id = e.register_contract(None, ContractRegistry);
+    let client = ContractRegistryClient::new(e, &contract_id);
+    client.initialize(&admin);
+    (client, admin)
+}
+
+#[test]
+fn test_initialize_twice_fails() {
+    let e = Env::default();
+    let (client, admin) = setup(&e);
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(
+        client.try_initialize(&admin),
+        Err(Ok(RegistryError::AlreadyInitialized))
+    );
+}
+
+#[test]
+fn test_set_and_get_contract() {
+    let e = Env::default();
+    let (client, admin) = setup(&e);
+    let core = Address::generate(&e);
+
+    assert_eq!(client.get_contract(&symbol_short!("core")), None);
+    client.set_contract(&admin, &symbol_short!("core"), &core);
+    assert_eq!(
+        client.get_contract(&symbol_short!("core")),
+        Some(core.clone())
+    );
+
+    // Re-registering replaces the entry
+    let upgraded = Address::generate(&e);
+    client.set_contract(&admin, &symbol_short!("core"), &upgraded);
+    assert_eq!(client.get_contract(&symbol_short!("core")), Some(upgraded));
+}
+
+#[test]
+fn test_set_contract_requires_admin() {
+    let e = Env::default();
+    let (client, _admin) = setup(&e);
+    let attacker = Address::generate(&e);
+
+    assert_eq!(
+        client.try_set_contract(&attacker, &symbol_short!("core"), &attacker),
+        Err(Ok(RegistryError::Unauthorized))
+    );
+    assert_eq!(client.get_contract(&symbol_short!("core")), None);
+}
+
+#[test]
+fn test_resolve_or_fallback() {
+    let e = Env::default();
+    let (client, admin) = setup(&e);
+    let fallback = Address::generate(&e);
+    let nft = Address::generate(&e);
+
+    assert_eq!(
+        client.resolve_or_fallback(&symbol_short!("nft"), &fallback),
+        fallback
+    );
+    client.set_contract(&admin, &symbol_short!("nft"), &nft);
+    assert_eq!(
+        client.resolve_or_fallback(&symbol_short!("nft"), &fallback),
+        nft
+    );
+}
+
+#[test]
+fn test_migrate_all_requires_admin() {
+    let e = Env::default();
+    let (client, _admin) = setup(&e);
+    let attacker = Address::generate(&e);
+    let target = Address::generate(&e);
+
+    assert_eq!(
+        client.try_migrate_all(&attacker, &soroban_sdk::vec![&e, target], &0),
+        Err(Ok(RegistryError::Unauthorized))
+    );
+    assert_eq!(client.get_last_migration(), None);
+}
@@ -0,0 +1,224 @@
+//! Typed cross-contract call helpers.
+//!
+//! Wraps the `Vec<Val>` argument building and `invoke_contract` /
+//! `try_invoke_contract` plumbing so callers pass typed arguments and a function
+//! name, and get back a typed result. The `invoke*` helpers trap like a plain
+//! `invoke_contract`; the `try_invoke*` helpers report any failure of the callee,
+//! including a result of the wrong type, as `code::CONTRACT_CALL_FAILED`.
+
+use soroban_sdk::{Address, Env, IntoVal, Symbol, TryFromVal, Val, Vec};
+
+use crate::error_codes::code;
+
+fn call<T: TryFromVal<Env, Val>>(e: &Env, contract: &Address, func: &str, args: Vec<Val>) -> T {
+    e.invoke_contract(contract, &Symbol::new(e, func), args)
+}
+
+fn try_call<T: TryFromVal<Env, Val>>(
+    e: &Env,
+    contract: &Address,
+    func: &str,
+    args: Vec<Val>,
+) -> Result<T, u32> {
+    match e.try_invoke_contract::<T, soroban_sdk::Error>(contract, &Symbol::new(e, func), args) {
+        Ok(Ok(value)) => Ok(value),
+        _ => Err(code::CONTRACT_CALL_FAILED),
+    }
+}
+
+/// Call `func` on `contract` with no arguments.
+pub fn invoke0<T: TryFromVal<Env, Val>>(e: &Env, contract: &Address, func: &str) -> T {
+    call(e, contract, func, Vec::new(e))
+}
+
+/// Call `func` on `contract` with one argument.
+pub fn invoke1<A, T>(e: &Env, contract: &Address, func: &str, a: A) -> T
+where
+    A: IntoVal<Env, Val>,
+    T: TryFromVal<Env, Val>,
+{
+    call(e, contract, func, (a,).into_val(e))
+}
+
+/// Call `func` on `contract` with two arguments.
+pub fn invoke2<A, B, T>(e: &Env, contract: &Address, func: &str, a: A, b: B) -> T
+where
+    A: IntoVal<Env, Val>,
+    B: IntoVal<Env, Val>,
+    T: TryFromVal<Env, Val>,
+{
+    call(e, contract, func, (a, b).into_val(e))
+}
+
+/// Call `func` on `contract` with three arguments.
+pub fn invoke3<A, B, C, T>(e: &Env, contract: &Address, func: &str, a: A, b: B, c: C) -> T
+where
+    A: IntoVal<Env, Val>,
+    B: IntoVal<Env, Val>,
+    C: IntoVal<Env, Val>,
+    T: TryFromVal<Env, Val>,
+{
+    call(e, contract, func, (a, b, c).into_val(e))
+}
+
+/// Call `func` on `contract` with no arguments.
+///
+/// # Returns
+/// `Err(code::CONTRACT_CALL_FAILED)` if the call fails or returns another type
+pub fn try_invoke0<T: TryFromVal<Env, Val>>(
+    e: &Env,
+    contract: &Address,
+    func: &str,
+) -> Result<T, u32> {
+    try_call(e, contract, func, Vec::new(e))
+}
+
+/// Call `func` on `contract` with one argument.
+///
+/// # Returns
+/// `Err(code::CONTRACT_CALL_FAILED)` if the call fails or returns another type
+pub fn try_invoke1<A, T>(e: &Env, contract: &Address, func: &str, a: A) -> Result<T, u32>
+where
+    A: IntoVal<Env, Val>,
+    T: TryFromVal<Env, Val>,
+{
+    try_call(e, contract, func, (a,).into_val(e))
+}
+
+/// Call `func` on `contract` with two arguments.
+///
+/// # Returns
+/// `Err(code::CONTRACT_CALL_FAILED)` if the call fails or returns another type
+pub fn try_invoke2<A, B, T>(e: &Env, contract: &Address, func: &str, a: A, b: B) -> Result<T, u32>
+where
+    A: IntoVal<Env, Val>,
+    B: IntoVal<Env, Val>,
+    T: TryFromVal<Env, Val>,
+{
+    try_call(e, contract, func, (a, b).into_val(e))
+}
+
+/// Call `func` on `contract` with three arguments.
+///
+/// # Returns
+/// `Err(code::CONTRACT_CALL_FAILED)` if the call fails or returns another type
+pub fn try_invoke3<A, B, C, T>(
+    e: &Env,
+    contract: &Address,
+    func: &str,
+    a: A,
+    b: B,
+    c: C,
+) -> Result<T, u32>
+where
+    A: IntoVal<Env, Val>,
+    B: IntoVal<Env, Val>,
+    C: IntoVal<Env, Val>,
+    T: TryFromVal<Env, Val>,
+{
+    try_call(e, contract, func, (a, b, c).into_val(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, String};
+
+    /// Callee exercising zero to three arguments and a failing entry point
+    #[contract]
+    struct Callee;
+
+    #[contractimpl]
+    impl Callee {
+        pub fn ping() -> u32 {
+            7
+        }
+
+        pub fn double(x: i128) -> i128 {
+            x * 2
+        }
+
+        pub fn add(a: i128, b: i128) -> i128 {
+            a + b
+        }
+
+        pub fn pick(flag: bool, a: String, b: String) -> String {
+            if flag {
+                a
+            } else {
+                b
+            }
+        }
+
+        pub fn fail(_x: u32) -> u32 {
+            panic!("callee failed")
+        }
+    }
+
+    #[test]
+    fn test_invoke_with_typed_args() {
+        let e = Env::default();
+        let callee = e.register_contract(None, Callee);
+
+        assert_eq!(invoke0::<u32>(&e, &callee, "ping"), 7);
+        assert_eq!(invoke1::<_, i128>(&e, &callee, "double", 21i128), 42);
+        assert_eq!(invoke2::<_, _, i128>(&e, &callee, "add", 2i128, 3i128), 5);
+        let picked: String = invoke3(
+            &e,
+            &callee,
+            "pick",
+            false,
+            String::from_str(&e, "a"),
+            String::from_str(&e, "b"),
+        );
+        assert_eq!(picked, String::from_str(&e, "b"));
+    }
+
+    #[test]
+    fn test_try_invoke_returns_result() {
+        let e = Env::default();
+        let callee = e.register_contract(None, Callee);
+
+        assert_eq!(try_invoke0::<u32>(&e, &callee, "ping"), Ok(7));
+        assert_eq!(try_invoke1::<_, i128>(&e, &callee, "double", 4i128), Ok(8));
+        assert_eq!(try_invoke2::<_, _, i128>(&e, &callee, "add", 1i128, 1i128), Ok(2));
+        assert_eq!(
+            try_invoke3::<_, _, _, String>(
+                &e,
+                &callee,
+                "pick",
+                true,
+                String::from_str(&e, "a"),
+                String::from_str(&e, "b"),
+            ),
+            Ok(String::from_str(&e, "a"))
+        );
+    }
+
+    #[test]
+    fn test_try_invoke_reports_failures() {
+        let e = Env::default();
+        let callee = e.register_contract(None, Callee);
+
+        // Callee panics
+        assert_eq!(
+            try_invoke1::<_, u32>(&e, &callee, "fail", 1u32),
+            Err(code::CONTRACT_CALL_FAILED)
+        );
+        // Wrong argument type
+        assert_eq!(
+            try_invoke1::<_, i128>(&e, &callee, "double", 1u32),
+            Err(code::CONTRACT_CALL_FAILED)
+        );
+        // Result of another type
+        assert_eq!(
+            try_invoke0::<String>(&e, &callee, "ping"),
+            Err(code::CONTRACT_CALL_FAILED)
+        );
+        // Unknown function
+        assert_eq!(
+            try_invoke0::<u32>(&e, &callee, "missing"),
+            Err(code::CONTRACT_CALL_FAILED)
+        );
+    }
+}
@@ -5,6 +5,8 @@ use soroban_sdk::{symbol_short, Env};
 pub mod keys {
     use soroban_sdk::{symbol_short, Symbol};
     pub const EMERGENCY_MODE: Symbol = symbol_short!("EMG_MODE");
+    pub const EMERGENCY_REASON: Symbol = symbol_short!("EMG_RSN");
+    pub const EMERGENCY_SINCE: Symbol = symbol_short!("EMG_AT");
 }
 
 pub struct EmergencyControl;
@@ -34,7 +36,25 @@ impl EmergencyControl {
 
     /// Set emergency mode status
     pub fn set_emergency_mode(e: &Env, enabled: bool) {
-        e.storage().instance().set(&keys::EMERGENCY_MODE, &enabled);
+        Self::set_emergency_mode_with_reason(e, enabled, 0);
+    }
+
+    /// Set emergency mode status, recording a machine-readable reason code
+    ///
+    /// Enabling records the reason and, unless already paused, the pause start
+    /// time. Disabling clears both.
+    pub fn set_emergency_mode_with_reason(e: &Env, enabled: bool, reason_code: u32) {
+        let storage = e.storage().instance();
+        if enabled {
+            if !Self::is_emergency_mode(e) {
+                storage.set(&keys::EMERGENCY_SINCE, &e.ledger().timestamp());
+            }
+            storage.set(&keys::EMERGENCY_REASON, &reason_code);
+        } else {
+            storage.remove(&keys::EMERGENCY_REASON);
+            storage.remove(&keys::EMERGENCY_SINCE);
+        }
+        storage.set(&keys::EMERGENCY_MODE, &enabled);
 
         // Emit event for emergency mode change
         let event_type = if enabled {
@@ -48,4 +68,64 @@ impl EmergencyControl {
             (event_type, e.ledger().timestamp()),
         );
     }
+    /// Get (emergency mode, reason code, pause start timestamp)
+    ///
+    /// Reason and timestamp are 0 when not paused.
+    pub fn get_emergency_status(e: &Env) -> (bool, u32, u64) {
+        let storage = e.storage().instance();
+        (
+            Self::is_emergency_mode(e),
+            storage.get(&keys::EMERGENCY_REASON).unwrap_or(0),
+            storage.get(&keys::EMERGENCY_SINCE).unwrap_or(0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, testutils::Ledger};
+
+    #[contract]
+    struct TestEmergencyContract;
+
+    #[test]
+    fn test_emergency_status_records_reason_and_time() {
+        let e = Env::default();
+        let contract_id = e.register_contract(None, TestEmergencyContract);
+        e.ledger().with_mut(|l| l.timestamp = 1_000);
+
+        e.as_contract(&contract_id, || {
+            assert_eq!(EmergencyControl::get_emergency_status(&e), (false, 0, 0));
+
+            EmergencyControl::set_emergency_mode_with_reason(&e, true, 7);
+            assert!(EmergencyControl::is_emergency_mode(&e));
+            assert_eq!(EmergencyControl::get_emergency_status(&e), (true, 7, 1_000));
+        });
+
+        // Updating the reason keeps the original pause start
+        e.ledger().with_mut(|l| l.timestamp = 2_000);
+        e.as_contract(&contract_id, || {
+            EmergencyControl::set_emergency_mode_with_reason(&e, true, 9);
+            assert_eq!(EmergencyControl::get_emergency_status(&e), (true, 9, 1_000));
+
+            EmergencyControl::set_emergency_mode(&e, false);
+            assert_eq!(EmergencyControl::get_emergency_status(&e), (false, 0, 0));
+        });
+    }
+
+    #[test]
+    fn test_boolean_api_records_zero_reason() {
+        let e = Env::default();
+        let contract_id = e.register_contract(None, TestEmergencyContract);
+        e.ledger().with_mut(|l| l.timestamp = 500);
+
+        e.as_contract(&contract_id, || {
+            EmergencyControl::set_emergency_mode(&e, true);
+            assert_eq!(EmergencyControl::get_emergency_status(&e), (true, 0, 500));
+
+            EmergencyControl::set_emergency_mode_with_reason(&e, false, 3);
+            assert_eq!(EmergencyControl::get_emergency_status(&e), (false, 0, 0));
+        });
+    }
 }
@@ -47,6 +47,24 @@ pub fn net_after_fee_bps(amount: i128, bps: u32) -> i128 {
     amount.checked_sub(fee).expect("Fees: underflow")
 }
 
+/// Split a collected fee between the treasury and an insurance fund.
+///
+/// # Arguments
+/// * `total` - The fee collected (e.g. early exit penalty, transformation fee)
+/// * `insurance_bps` - Share routed to the insurance fund, in basis points (0-10000)
+///
+/// # Returns
+/// `(treasury_part, insurance_part)`. The insurance part rounds down and the
+/// treasury takes the remainder, so the parts always sum to `total`.
+///
+/// # Panics
+/// If `insurance_bps > 10000`.
+pub fn split_fee(total: i128, insurance_bps: u32) -> (i128, i128) {
+    let insurance_part = fee_from_bps(total, insurance_bps);
+    let treasury_part = total.checked_sub(insurance_part).expect("Fees: underflow");
+    (treasury_part, insurance_part)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,6 +101,22 @@ mod tests {
         assert_eq!(net_after_fee_bps(10000, 50), 9950); // 0.5% fee: 10000 - 50 = 9950
     }
 
+    #[test]
+    fn test_split_fee_honors_bps() {
+        assert_eq!(split_fee(1000, 2000), (800, 200)); // 20% to insurance
+        assert_eq!(split_fee(1000, 0), (1000, 0));
+        assert_eq!(split_fee(1000, 10000), (0, 1000));
+    }
+
+    #[test]
+    fn test_split_fee_sums_to_total() {
+        for (total, bps) in [(999, 3333), (1, 5000), (12_345, 1), (0, 2500)] {
+            let (treasury, insurance) = split_fee(total, bps);
+            assert_eq!(treasury + insurance, total);
+        }
+        assert_eq!(split_fee(999, 3333), (667, 332)); // insurance rounds down
+    }
+
     #[test]
     #[should_panic(expected = "bps must be 0-10000")]
     fn test_fee_from_bps_invalid() {
@@ -12,8 +12,12 @@
 //! - Access control patterns
 //! - Event emission patterns
 //! - Rate limiting helpers
+//! - Token transfer helpers
+//! - Typed cross-contract call helpers
 
 pub mod access_control;
+pub mod batch;
+pub mod call;
 pub mod emergency;
 pub mod error_codes;
 pub mod errors;
@@ -23,6 +27,7 @@ pub mod rate_limiting;
 pub mod fees;
 pub mod storage;
 pub mod time;
+pub mod transfer;
 pub mod validation;
 
 #[cfg(test)]
@@ -30,6 +35,7 @@ mod tests;
 
 // Re-export commonly used items
 pub use access_control::*;
+pub use batch::*;
 pub use emergency::EmergencyControl;
 pub use error_codes::*;
 pub use errors::*;
@@ -39,4 +45,5 @@ pub use rate_limiting::*;
 pub use fees::*;
 pub use storage::Storage;
 pub use time::*;
+pub use transfer::*;
 pub use validation::*;
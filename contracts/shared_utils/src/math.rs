@@ -79,6 +79,24 @@ impl SafeMath {
         Self::percent_from(loss, initial)
     }
 
+    /// Calculate loss in basis points: ((initial - current) * 10000) / initial
+    ///
+    /// Same as `loss_percent` at 100x the precision, so a 0.5% loss is 50.
+    ///
+    /// # Arguments
+    /// * `initial` - The initial value
+    /// * `current` - The current value
+    ///
+    /// # Returns
+    /// The loss in basis points as i128 (can be negative if current > initial)
+    pub fn loss_bps(initial: i128, current: i128) -> i128 {
+        if initial == 0 {
+            panic!("Math: cannot calculate loss bps from zero initial value");
+        }
+        let loss = Self::sub(initial, current);
+        Self::div(Self::mul(loss, 10000), initial)
+    }
+
     /// Calculate gain percentage: ((current - initial) * 100) / initial
     ///
     /// # Arguments
@@ -119,6 +137,36 @@ impl SafeMath {
     pub fn penalty_amount(value: i128, penalty_percent: u32) -> i128 {
         Self::percent(value, penalty_percent)
     }
+
+    /// Calculate the penalty amount, then clamp it to an absolute floor and cap
+    ///
+    /// # Arguments
+    /// * `value` - The base value
+    /// * `penalty_percent` - The penalty percentage (0-100)
+    /// * `min_penalty` - Lowest penalty charged, or `None` for no floor
+    /// * `max_penalty` - Highest penalty charged, or `None` for no cap
+    ///
+    /// # Returns
+    /// The clamped penalty amount. A zero percentage stays penalty-free and the
+    /// result never exceeds `value`.
+    pub fn penalty_amount_bounded(
+        value: i128,
+        penalty_percent: u32,
+        min_penalty: Option<i128>,
+        max_penalty: Option<i128>,
+    ) -> i128 {
+        let mut penalty = Self::penalty_amount(value, penalty_percent);
+        if penalty_percent == 0 {
+            return penalty;
+        }
+        if let Some(min) = min_penalty {
+            penalty = penalty.max(min);
+        }
+        if let Some(max) = max_penalty {
+            penalty = penalty.min(max);
+        }
+        penalty.min(value.max(0))
+    }
 }
 
 #[cfg(test)]
@@ -176,6 +224,15 @@ mod tests {
         assert_eq!(SafeMath::loss_percent(1000, 1000), 0);
     }
 
+    #[test]
+    fn test_loss_bps() {
+        assert_eq!(SafeMath::loss_bps(1000, 995), 50);
+        assert_eq!(SafeMath::loss_bps(1000, 900), 1000);
+        assert_eq!(SafeMath::loss_bps(1000, 1000), 0);
+        // Half a percent rounds away in percent precision
+        assert_eq!(SafeMath::loss_percent(1000, 995), 0);
+    }
+
     #[test]
     fn test_gain_percent() {
         assert_eq!(SafeMath::gain_percent(1000, 1100), 10);
@@ -196,4 +253,13 @@ mod tests {
         assert_eq!(SafeMath::penalty_amount(1000, 5), 50);
         assert_eq!(SafeMath::penalty_amount(1000, 0), 0);
     }
+
+    #[test]
+    fn test_penalty_amount_bounded() {
+        assert_eq!(SafeMath::penalty_amount_bounded(1000, 10, None, None), 100);
+        assert_eq!(SafeMath::penalty_amount_bounded(5, 10, Some(2), None), 2);
+        assert_eq!(SafeMath::penalty_amount_bounded(1000, 10, None, Some(40)), 40);
+        assert_eq!(SafeMath::penalty_amount_bounded(1, 10, Some(5), None), 1);
+        assert_eq!(SafeMath::penalty_amount_bounded(1000, 0, Some(5), None), 0);
+    }
 }
@@ -11,6 +11,9 @@
 //! - (RL_CFG, function_symbol) -> (window_seconds: u64, max_calls: u32)
 //! - (RL_STATE, address, function_symbol) -> (window_start: u64, count: u32)
 //! - (RL_EX, address) -> bool
+//!
+//! A blocked call emits `RateLimited` with topics (address, function) and the
+//! ledger timestamp as data.
 
 use soroban_sdk::{Address, Env, Symbol};
 
@@ -80,11 +83,27 @@ impl RateLimiter {
     /// - If no config exists for `function`, this is a no-op.
     /// - If `address` is exempt, this is a no-op.
     /// - Otherwise, maintains a fixed time window based on ledger timestamp.
-    /// - Panics with `"Rate limit exceeded"` when limit is hit.
+    /// - Emits `RateLimited` and panics with `"Rate limit exceeded"` when limit is hit.
+    ///
+    /// The panic rolls back the invocation, event included. Use
+    /// [`RateLimiter::try_check`] to handle the limit without panicking; the
+    /// event only persists if the caller then returns `Ok`, since a contract
+    /// error return rolls it back too.
     pub fn check(e: &Env, address: &Address, function: &Symbol) {
+        if !Self::try_check(e, address, function) {
+            panic!("Rate limit exceeded");
+        }
+    }
+
+    /// Non-panicking variant of [`RateLimiter::check`].
+    ///
+    /// Returns `false` and emits `RateLimited` when the limit is hit, leaving
+    /// the stored window untouched. Returns `true` and counts the call otherwise.
+    /// Like any event, `RateLimited` is discarded if the invocation fails.
+    pub fn try_check(e: &Env, address: &Address, function: &Symbol) -> bool {
         // Exempt addresses bypass rate limits
         if Self::is_exempt(e, address) {
-            return;
+            return true;
         }
 
         // Load configuration; if none, do nothing
@@ -93,7 +112,7 @@ impl RateLimiter {
 
         let (window_seconds, max_calls) = match config {
             Some(cfg) => cfg,
-            None => return,
+            None => return true,
         };
 
         let now = TimeUtils::now(e);
@@ -115,13 +134,18 @@ impl RateLimiter {
         // Enforce count
         let new_count = count.saturating_add(1);
         if new_count > max_calls {
-            panic!("Rate limit exceeded");
+            e.events().publish(
+                (Symbol::new(e, "RateLimited"), address.clone(), function.clone()),
+                now,
+            );
+            return false;
         }
 
         // Persist updated state
         e.storage()
             .instance()
             .set(&state_key, &(window_start, new_count));
+        true
     }
 }
 
@@ -130,8 +154,8 @@ mod tests {
     use super::*;
     use soroban_sdk::{
         contract, contractimpl, symbol_short,
-        testutils::{Address as TestAddress, Ledger},
-        Address, Env, Symbol,
+        testutils::{Address as TestAddress, Events, Ledger},
+        vec, Address, Env, IntoVal, Symbol,
     };
 
     #[contract]
@@ -144,6 +168,11 @@ mod tests {
             RateLimiter::check(&e, &caller, &fn_symbol);
         }
 
+        pub fn checked_call(e: Env, caller: Address) -> bool {
+            let fn_symbol = symbol_short!("limited");
+            RateLimiter::try_check(&e, &caller, &fn_symbol)
+        }
+
         pub fn configure_limit(e: Env, function: Symbol, window_seconds: u64, max_calls: u32) {
             RateLimiter::set_limit(&e, &function, window_seconds, max_calls);
         }
@@ -229,4 +258,61 @@ mod tests {
         client.limited_call(&caller);
         client.limited_call(&caller);
     }
+
+    #[test]
+    fn test_try_check_returns_false_and_emits_event_when_limited() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, TestRateLimitContract);
+        let client = TestRateLimitContractClient::new(&env, &contract_id);
+
+        let caller = <Address as TestAddress>::generate(&env);
+        env.ledger().with_mut(|l| {
+            l.timestamp = 500;
+        });
+
+        // Configure: 1 call per 60 seconds
+        client.configure_limit(&symbol_short!("limited"), &60u64, &1u32);
+
+        assert!(client.checked_call(&caller));
+        assert!(!client.checked_call(&caller));
+
+        let events = env.events().all();
+        let last_event = events.last().unwrap();
+        assert_eq!(last_event.0, contract_id);
+        assert_eq!(
+            last_event.1,
+            vec![
+                &env,
+                Symbol::new(&env, "RateLimited").into_val(&env),
+                caller.into_val(&env),
+                symbol_short!("limited").into_val(&env)
+            ]
+        );
+        let timestamp: u64 = last_event.2.into_val(&env);
+        assert_eq!(timestamp, 500);
+    }
+
+    #[test]
+    fn test_try_check_does_not_count_blocked_calls() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, TestRateLimitContract);
+        let client = TestRateLimitContractClient::new(&env, &contract_id);
+
+        let caller = <Address as TestAddress>::generate(&env);
+
+        // Configure: 1 call per 60 seconds
+        client.configure_limit(&symbol_short!("limited"), &60u64, &1u32);
+
+        env.ledger().with_mut(|l| {
+            l.timestamp = 100;
+        });
+        assert!(client.checked_call(&caller));
+        assert!(!client.checked_call(&caller));
+
+        // Blocked attempts do not extend the window
+        env.ledger().with_mut(|l| {
+            l.timestamp = 160;
+        });
+        assert!(client.checked_call(&caller));
+    }
 }
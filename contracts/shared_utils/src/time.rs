@@ -58,6 +58,48 @@ impl TimeUtils {
         current_time + duration_seconds
     }
 
+    /// Add a number of business days (Monday-Friday) to a timestamp
+    ///
+    /// Weekends are skipped and the time of day is preserved. Starting on a
+    /// weekend counts from the following Monday, so Saturday + 1 is Monday.
+    ///
+    /// # Arguments
+    /// * `e` - The environment
+    /// * `from_ts` - The starting timestamp
+    /// * `days` - Number of business days to add
+    ///
+    /// # Returns
+    /// Timestamp `days` business days after `from_ts`
+    pub fn add_business_days(_e: &Env, from_ts: u64, days: u32) -> u64 {
+        const DAY: u64 = 24 * 60 * 60;
+        if days == 0 {
+            return from_ts;
+        }
+
+        // Counting from a weekend is the same as counting from the Friday before it
+        let mut ts = match Self::weekday(from_ts) {
+            5 => from_ts - DAY,
+            6 => from_ts - 2 * DAY,
+            _ => from_ts,
+        };
+
+        // From a weekday, every 5 business days is exactly one calendar week
+        ts += (days / 5) as u64 * 7 * DAY;
+        for _ in 0..days % 5 {
+            ts += DAY;
+            while Self::weekday(ts) >= 5 {
+                ts += DAY;
+            }
+        }
+        ts
+    }
+
+    /// Day of the week for a unix timestamp (0 = Monday, ..., 6 = Sunday)
+    fn weekday(ts: u64) -> u64 {
+        // 1970-01-01 was a Thursday
+        (ts / (24 * 60 * 60) + 3) % 7
+    }
+
     /// Check if a timestamp has expired (current time >= expiration)
     ///
     /// # Arguments
@@ -155,6 +197,72 @@ mod tests {
         assert_eq!(expiration, 1000 + 86400);
     }
 
+    // 2024-01-05 12:00:00 UTC, a Friday
+    const FRIDAY_NOON: u64 = 1_704_456_000;
+
+    #[test]
+    fn test_add_business_days_spans_weekend() {
+        let env = Env::default();
+        let day = TimeUtils::days_to_seconds(1);
+
+        // Friday + 1 business day is the following Monday
+        assert_eq!(
+            TimeUtils::add_business_days(&env, FRIDAY_NOON, 1),
+            FRIDAY_NOON + 3 * day
+        );
+        // Thursday + 2 business days is Monday
+        assert_eq!(
+            TimeUtils::add_business_days(&env, FRIDAY_NOON - day, 2),
+            FRIDAY_NOON + 3 * day
+        );
+        // Saturday and Sunday + 1 business day are both Monday
+        assert_eq!(
+            TimeUtils::add_business_days(&env, FRIDAY_NOON + day, 1),
+            FRIDAY_NOON + 3 * day
+        );
+        assert_eq!(
+            TimeUtils::add_business_days(&env, FRIDAY_NOON + 2 * day, 1),
+            FRIDAY_NOON + 3 * day
+        );
+        assert_eq!(
+            TimeUtils::add_business_days(&env, FRIDAY_NOON, 0),
+            FRIDAY_NOON
+        );
+    }
+
+    #[test]
+    fn test_add_business_days_multi_week() {
+        let env = Env::default();
+        let day = TimeUtils::days_to_seconds(1);
+        let monday = FRIDAY_NOON + 3 * day;
+
+        // Whole weeks map to 7 calendar days each
+        assert_eq!(
+            TimeUtils::add_business_days(&env, monday, 5),
+            monday + 7 * day
+        );
+        assert_eq!(
+            TimeUtils::add_business_days(&env, monday, 10),
+            monday + 14 * day
+        );
+        // 12 business days from Monday: two weeks, then Tuesday, Wednesday
+        assert_eq!(
+            TimeUtils::add_business_days(&env, monday, 12),
+            monday + 16 * day
+        );
+        // 13 business days from Friday: two weeks to Friday, then three more
+        // business days across the weekend lands on Wednesday
+        assert_eq!(
+            TimeUtils::add_business_days(&env, FRIDAY_NOON, 13),
+            FRIDAY_NOON + 19 * day
+        );
+        // 30 business days from Saturday is six weeks after the previous Friday
+        assert_eq!(
+            TimeUtils::add_business_days(&env, FRIDAY_NOON + day, 30),
+            FRIDAY_NOON + 42 * day
+        );
+    }
+
     #[test]
     fn test_is_expired() {
         let env = Env::default();
@@ -0,0 +1,151 @@
+//! Token transfer helpers that report failure instead of trapping mid-flow.
+//!
+//! `token::Client::transfer` aborts the whole invocation when the token contract
+//! rejects a transfer, so a contract has no chance to clear guards or emit a
+//! meaningful error. These helpers use `try_transfer` and return the standard
+//! error codes from [`crate::error_codes::code`] so callers can decide how to fail.
+
+use soroban_sdk::{token, Address, Env};
+
+use crate::error_codes::code;
+
+/// Where the funds of [`safe_transfer_or_refund`] ended up
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransferOutcome {
+    /// Transferred to the intended recipient
+    Delivered,
+    /// Recipient transfer failed; returned to the refund address instead
+    Refunded,
+}
+
+/// Transfer `amount` of `token` from `from` to `to`.
+///
+/// # Returns
+/// * `Err(code::INVALID_AMOUNT)` if `amount <= 0`
+/// * `Err(code::TRANSFER_FAILED)` if the token contract rejects the transfer
+pub fn safe_transfer(
+    e: &Env,
+    token: &Address,
+    from: &Address,
+    to: &Address,
+    amount: i128,
+) -> Result<(), u32> {
+    if amount <= 0 {
+        return Err(code::INVALID_AMOUNT);
+    }
+    match token::Client::new(e, token).try_transfer(from, to, &amount) {
+        Ok(Ok(())) => Ok(()),
+        _ => Err(code::TRANSFER_FAILED),
+    }
+}
+
+/// Transfer to `to`, falling back to `refund_to` if the recipient transfer fails.
+///
+/// Useful when funds held by a contract must leave it either way, e.g. paying
+/// out to a recipient that may reject the token.
+///
+/// # Returns
+/// The [`TransferOutcome`], or `Err(code::TRANSFER_FAILED)` if both transfers fail.
+pub fn safe_transfer_or_refund(
+    e: &Env,
+    token: &Address,
+    from: &Address,
+    to: &Address,
+    refund_to: &Address,
+    amount: i128,
+) -> Result<TransferOutcome, u32> {
+    match safe_transfer(e, token, from, to, amount) {
+        Ok(()) => Ok(TransferOutcome::Delivered),
+        Err(code::TRANSFER_FAILED) => {
+            safe_transfer(e, token, from, refund_to, amount).map(|_| TransferOutcome::Refunded)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::{contract, contractimpl, testutils::Address as _};
+
+    /// Token stand-in that rejects every transfer to a blocked address
+    #[contract]
+    struct RejectingToken;
+
+    #[contractimpl]
+    impl RejectingToken {
+        pub fn block(e: Env, to: Address) {
+            e.storage().instance().set(&to, &true);
+        }
+
+        pub fn transfer(e: Env, _from: Address, to: Address, _amount: i128) {
+            if e.storage().instance().has(&to) {
+                panic!("transfer rejected");
+            }
+        }
+    }
+
+    fn setup(e: &Env) -> (Address, RejectingTokenClient<'_>) {
+        let token = e.register_contract(None, RejectingToken);
+        let client = RejectingTokenClient::new(e, &token);
+        (token, client)
+    }
+
+    #[test]
+    fn test_safe_transfer_succeeds() {
+        let e = Env::default();
+        let (token, _) = setup(&e);
+        let from = Address::generate(&e);
+        let to = Address::generate(&e);
+
+        assert_eq!(safe_transfer(&e, &token, &from, &to, 100), Ok(()));
+    }
+
+    #[test]
+    fn test_safe_transfer_rejects_non_positive_amount() {
+        let e = Env::default();
+        let (token, _) = setup(&e);
+        let from = Address::generate(&e);
+        let to = Address::generate(&e);
+
+        assert_eq!(safe_transfer(&e, &token, &from, &to, 0), Err(code::INVALID_AMOUNT));
+        assert_eq!(safe_transfer(&e, &token, &from, &to, -5), Err(code::INVALID_AMOUNT));
+    }
+
+    #[test]
+    fn test_safe_transfer_reports_rejected_transfer() {
+        let e = Env::default();
+        let (token, client) = setup(&e);
+        let from = Address::generate(&e);
+        let to = Address::generate(&e);
+        client.block(&to);
+
+        assert_eq!(safe_transfer(&e, &token, &from, &to, 100), Err(code::TRANSFER_FAILED));
+    }
+
+    #[test]
+    fn test_safe_transfer_or_refund() {
+        let e = Env::default();
+        let (token, client) = setup(&e);
+        let from = Address::generate(&e);
+        let to = Address::generate(&e);
+        let refund_to = Address::generate(&e);
+
+        assert_eq!(
+            safe_transfer_or_refund(&e, &token, &from, &to, &refund_to, 100),
+            Ok(TransferOutcome::Delivered)
+        );
+
+        client.block(&to);
+        assert_eq!(
+            safe_transfer_or_refund(&e, &token, &from, &to, &refund_to, 100),
+            Ok(TransferOutcome::Refunded)
+        );
+
+        client.block(&refund_to);
+        assert_eq!(
+            safe_transfer_or_refund(&e, &token, &from, &to, &refund_to, 100),
+            Err(code::TRANSFER_FAILED)
+        );
+    }
+}
@@ -27,6 +27,27 @@ pub struct CompatibilityInfo {
     pub checked_at: u64,
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct CheckpointData {
+    pub id: u32,
+    pub label: String,
+    pub current_version: Version,
+    pub minimum_version: Version,
+    pub version_count: u32,
+    pub timestamp: u64,
+    pub created_by: Address,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct MigrationStatus {
+    pub in_progress: bool,
+    pub started_at: u64,
+    pub completed: bool,
+    pub success: bool,
+}
+
 #[contracttype]
 pub enum DataKey {
     CurrentVersion,
@@ -36,6 +57,12 @@ pub enum DataKey {
     VersionMetadata(Version),
     Compatibility(Version, Version),
     Initialized,
+    CheckpointCount,
+    Checkpoint(u32),
+    MigrationStatus(Version, Version),
+    ActiveMigration,
+    Sunset(Version),
+    Admin,
 }
 
 #[contract]
@@ -99,6 +126,9 @@ impl ContractVersioning {
         // Set version count
         env.storage().instance().set(&DataKey::VersionCount, &1u32);
 
+        // The deployer administers migrations and checkpoints
+        env.storage().instance().set(&DataKey::Admin, &deployer);
+
         // Mark as initialized
         env.storage().instance().set(&initialized_key, &true);
 
@@ -258,9 +288,9 @@ impl ContractVersioning {
             .unwrap();
 
         let min_cmp = Self::compare_versions(env.clone(), version.clone(), min_version);
-        let max_cmp = Self::compare_versions(env.clone(), version, current_version);
+        let max_cmp = Self::compare_versions(env.clone(), version.clone(), current_version);
 
-        min_cmp >= 0 && max_cmp <= 0
+        min_cmp >= 0 && max_cmp <= 0 && !Self::is_past_sunset(&env, &version)
     }
 
     /// Check if current version meets minimum requirement
@@ -308,10 +338,35 @@ impl ContractVersioning {
             .publish((symbol_short!("min_upd"),), (major, minor, patch));
     }
 
-    /// Deprecate a version
+    /// Deprecate a version, ending its support immediately
     pub fn deprecate_version(env: Env, admin: Address, version: Version, reason: String) {
-        admin.require_auth();
+        let now = env.ledger().timestamp();
+        Self::deprecate(&env, admin, version, reason, now);
+    }
+
+    /// Deprecate a version but keep it supported until `sunset_ts`
+    pub fn deprecate_version_with_sunset(
+        env: Env,
+        admin: Address,
+        version: Version,
+        reason: String,
+        sunset_ts: u64,
+    ) {
+        if sunset_ts < env.ledger().timestamp() {
+            panic!("Sunset must not be in the past");
+        }
+        Self::deprecate(&env, admin, version, reason, sunset_ts);
+    }
+
+    /// Get the time after which a deprecated version is no longer supported
+    pub fn get_sunset(env: Env, version: Version) -> Option<u64> {
         Self::require_initialized(&env);
+        env.storage().persistent().get(&DataKey::Sunset(version))
+    }
+
+    fn deprecate(env: &Env, admin: Address, version: Version, reason: String, sunset_ts: u64) {
+        admin.require_auth();
+        Self::require_initialized(env);
 
         let metadata_key = DataKey::VersionMetadata(version.clone());
         let mut metadata: VersionMetadata = env
@@ -326,10 +381,13 @@ impl ContractVersioning {
 
         metadata.deprecated = true;
         env.storage().persistent().set(&metadata_key, &metadata);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Sunset(version.clone()), &sunset_ts);
 
         env.events().publish(
             (symbol_short!("ver_depr"), version.major, version.minor),
-            (version.patch, reason),
+            (version.patch, reason, sunset_ts),
         );
     }
 
@@ -397,6 +455,9 @@ impl ContractVersioning {
     /// Check if client is compatible with current version
     pub fn is_client_compatible(env: Env, client_version: Version) -> bool {
         Self::require_initialized(&env);
+        if Self::is_past_sunset(&env, &client_version) {
+            return false;
+        }
         let current: Version = env
             .storage()
             .instance()
@@ -406,15 +467,45 @@ impl ContractVersioning {
         compatible
     }
 
-    /// Start migration
+    /// Get the admin recorded at initialization
+    pub fn get_admin(env: Env) -> Address {
+        Self::require_initialized(&env);
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic!("Admin not set"))
+    }
+
+    /// Start migration (admin only)
+    ///
+    /// Only one migration may be in progress; the admin ends it with
+    /// `complete_migration`, passing `success = false` to abandon it.
     pub fn start_migration(
         env: Env,
         initiator: Address,
         from_version: Version,
         to_version: Version,
     ) {
-        initiator.require_auth();
-        Self::require_initialized(&env);
+        Self::require_admin(&env, &initiator);
+
+        if env.storage().instance().has(&DataKey::ActiveMigration) {
+            panic!("Migration already in progress");
+        }
+
+        let status = MigrationStatus {
+            in_progress: true,
+            started_at: env.ledger().timestamp(),
+            completed: false,
+            success: false,
+        };
+        env.storage().persistent().set(
+            &DataKey::MigrationStatus(from_version.clone(), to_version.clone()),
+            &status,
+        );
+        env.storage().instance().set(
+            &DataKey::ActiveMigration,
+            &(from_version.clone(), to_version.clone()),
+        );
 
         env.events().publish(
             (symbol_short!("mig_strt"),),
@@ -422,7 +513,7 @@ impl ContractVersioning {
         );
     }
 
-    /// Complete migration
+    /// Complete migration (admin only)
     pub fn complete_migration(
         env: Env,
         executor: Address,
@@ -430,8 +521,23 @@ impl ContractVersioning {
         to_version: Version,
         success: bool,
     ) {
-        executor.require_auth();
-        Self::require_initialized(&env);
+        Self::require_admin(&env, &executor);
+
+        let key = DataKey::MigrationStatus(from_version.clone(), to_version.clone());
+        let mut status: MigrationStatus = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic!("Migration not started"));
+        if !status.in_progress {
+            panic!("Migration not in progress");
+        }
+
+        status.in_progress = false;
+        status.completed = true;
+        status.success = success;
+        env.storage().persistent().set(&key, &status);
+        env.storage().instance().remove(&DataKey::ActiveMigration);
 
         env.events().publish(
             (symbol_short!("mig_done"),),
@@ -439,6 +545,102 @@ impl ContractVersioning {
         );
     }
 
+    /// Record the current version state for audits, returning the checkpoint id (admin only)
+    pub fn create_checkpoint(env: Env, admin: Address, label: String) -> u32 {
+        Self::require_admin(&env, &admin);
+
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::CheckpointCount)
+            .unwrap_or(0);
+
+        let checkpoint = CheckpointData {
+            id,
+            label: label.clone(),
+            current_version: env
+                .storage()
+                .instance()
+                .get(&DataKey::CurrentVersion)
+                .unwrap(),
+            minimum_version: env
+                .storage()
+                .instance()
+                .get(&DataKey::MinimumVersion)
+                .unwrap(),
+            version_count: env
+                .storage()
+                .instance()
+                .get(&DataKey::VersionCount)
+                .unwrap(),
+            timestamp: env.ledger().timestamp(),
+            created_by: admin.clone(),
+        };
+
+        env.storage()
+            .persistent()
+            .set(&DataKey::Checkpoint(id), &checkpoint);
+        env.storage()
+            .instance()
+            .set(&DataKey::CheckpointCount, &(id + 1));
+
+        env.events().publish(
+            (symbol_short!("chkpt"), id),
+            (label, checkpoint.current_version, admin),
+        );
+
+        id
+    }
+
+    /// Get a checkpoint by id
+    pub fn get_checkpoint(env: Env, id: u32) -> CheckpointData {
+        Self::require_initialized(&env);
+        env.storage()
+            .persistent()
+            .get(&DataKey::Checkpoint(id))
+            .unwrap_or_else(|| panic!("Checkpoint not found"))
+    }
+
+    /// List all checkpoints in creation order
+    pub fn list_checkpoints(env: Env) -> Vec<CheckpointData> {
+        Self::require_initialized(&env);
+        let count: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::CheckpointCount)
+            .unwrap_or(0);
+
+        let mut checkpoints = Vec::new(&env);
+        for id in 0..count {
+            if let Some(checkpoint) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, CheckpointData>(&DataKey::Checkpoint(id))
+            {
+                checkpoints.push_back(checkpoint);
+            }
+        }
+        checkpoints
+    }
+
+    /// Get the recorded status of a migration, if it was ever started
+    pub fn get_migration_status(
+        env: Env,
+        from_version: Version,
+        to_version: Version,
+    ) -> Option<MigrationStatus> {
+        Self::require_initialized(&env);
+        env.storage()
+            .persistent()
+            .get(&DataKey::MigrationStatus(from_version, to_version))
+    }
+
+    /// Check whether a migration has been started but not completed
+    pub fn is_migration_in_progress(env: Env) -> bool {
+        Self::require_initialized(&env);
+        env.storage().instance().has(&DataKey::ActiveMigration)
+    }
+
     // ============ Internal Helper Functions ============
 
     fn require_initialized(env: &Env) {
@@ -447,6 +649,25 @@ impl ContractVersioning {
         }
     }
 
+    fn require_admin(env: &Env, caller: &Address) {
+        caller.require_auth();
+        if *caller != Self::get_admin(env.clone()) {
+            panic!("Unauthorized");
+        }
+    }
+
+    /// Whether a deprecated version's sunset has been reached
+    fn is_past_sunset(env: &Env, version: &Version) -> bool {
+        match env
+            .storage()
+            .persistent()
+            .get::<DataKey, u64>(&DataKey::Sunset(version.clone()))
+        {
+            Some(sunset_ts) => env.ledger().timestamp() >= sunset_ts,
+            None => false,
+        }
+    }
+
     fn is_valid_increment(old: &Version, new: &Version) -> bool {
         // New version must be greater
         let cmp = if old.major != new.major {
@@ -516,7 +737,10 @@ impl ContractVersioning {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env, String};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        Address, Env, String,
+    };
 
     #[test]
     fn test_initialization() {
@@ -645,6 +869,91 @@ mod test {
         assert!(client.is_version_deprecated(&version));
     }
 
+    #[test]
+    fn test_deprecation_with_sunset() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, ContractVersioning);
+        let client = ContractVersioningClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.initialize(&admin, &1, &0, &0, &String::from_str(&env, "Initial"));
+        client.update_version(&admin, &1, &1, &0, &String::from_str(&env, "Minor"));
+
+        let v1 = Version {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        };
+        assert!(client.get_sunset(&v1).is_none());
+        client.deprecate_version_with_sunset(
+            &admin,
+            &v1,
+            &String::from_str(&env, "Moving to 1.1"),
+            &5_000,
+        );
+        assert!(client.is_version_deprecated(&v1));
+        assert_eq!(client.get_sunset(&v1), Some(5_000));
+
+        // Still usable during the sunset period
+        env.ledger().with_mut(|li| li.timestamp = 4_999);
+        assert!(client.is_version_supported(&v1));
+        assert!(client.is_client_compatible(&v1));
+
+        env.ledger().with_mut(|li| li.timestamp = 5_000);
+        assert!(!client.is_version_supported(&v1));
+        assert!(!client.is_client_compatible(&v1));
+    }
+
+    #[test]
+    fn test_deprecation_without_sunset_is_immediate() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, ContractVersioning);
+        let client = ContractVersioningClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.initialize(&admin, &1, &0, &0, &String::from_str(&env, "Initial"));
+        client.update_version(&admin, &1, &1, &0, &String::from_str(&env, "Minor"));
+
+        let v1 = Version {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        };
+        assert!(client.is_version_supported(&v1));
+        client.deprecate_version(&admin, &v1, &String::from_str(&env, "Outdated"));
+        assert_eq!(client.get_sunset(&v1), Some(1_000));
+        assert!(!client.is_version_supported(&v1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Sunset must not be in the past")]
+    fn test_deprecation_sunset_in_past() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, ContractVersioning);
+        let client = ContractVersioningClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.initialize(&admin, &1, &0, &0, &String::from_str(&env, "Initial"));
+        let v1 = Version {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        };
+        client.deprecate_version_with_sunset(&admin, &v1, &String::from_str(&env, "Old"), &999);
+    }
+
     #[test]
     fn test_meets_minimum_version() {
         let env = Env::default();
@@ -662,4 +971,269 @@ mod test {
         assert!(client.meets_minimum_version(&1, &0, &0));
         assert!(!client.meets_minimum_version(&3, &0, &0));
     }
+    #[test]
+    fn test_checkpoints_capture_state() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, ContractVersioning);
+        let client = ContractVersioningClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+
+        client.initialize(&admin, &1, &0, &0, &String::from_str(&env, "Initial"));
+        client.update_version(&admin, &1, &1, &0, &String::from_str(&env, "Minor"));
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let first = client.create_checkpoint(&admin, &String::from_str(&env, "Q1 audit"));
+
+        client.update_version(&admin, &2, &0, &0, &String::from_str(&env, "V2"));
+        client.update_minimum_version(&admin, &1, &1, &0);
+        env.ledger().with_mut(|li| li.timestamp = 2_000);
+        let second = client.create_checkpoint(&admin, &String::from_str(&env, "Q2 audit"));
+
+        assert_eq!(first, 0);
+        assert_eq!(second, 1);
+
+        let checkpoint = client.get_checkpoint(&first);
+        assert_eq!(checkpoint.label, String::from_str(&env, "Q1 audit"));
+        assert!(
+            checkpoint.current_version
+                == Version {
+                    major: 1,
+                    minor: 1,
+                    patch: 0
+                }
+        );
+        assert!(
+            checkpoint.minimum_version
+                == Version {
+                    major: 1,
+                    minor: 0,
+                    patch: 0
+                }
+        );
+        assert_eq!(checkpoint.version_count, 2);
+        assert_eq!(checkpoint.timestamp, 1_000);
+
+        let checkpoint = client.get_checkpoint(&second);
+        assert!(
+            checkpoint.current_version
+                == Version {
+                    major: 2,
+                    minor: 0,
+                    patch: 0
+                }
+        );
+        assert!(
+            checkpoint.minimum_version
+                == Version {
+                    major: 1,
+                    minor: 1,
+                    patch: 0
+                }
+        );
+        assert_eq!(checkpoint.version_count, 3);
+        assert_eq!(checkpoint.timestamp, 2_000);
+
+        let checkpoints = client.list_checkpoints();
+        assert_eq!(checkpoints.len(), 2);
+        assert_eq!(
+            checkpoints.get(0).unwrap().label,
+            String::from_str(&env, "Q1 audit")
+        );
+        assert_eq!(
+            checkpoints.get(1).unwrap().label,
+            String::from_str(&env, "Q2 audit")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Checkpoint not found")]
+    fn test_get_missing_checkpoint() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, ContractVersioning);
+        let client = ContractVersioningClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+
+        client.initialize(&admin, &1, &0, &0, &String::from_str(&env, "Initial"));
+        client.get_checkpoint(&0);
+    }
+    #[test]
+    fn test_migration_status_lifecycle() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, ContractVersioning);
+        let client = ContractVersioningClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+
+        client.initialize(&admin, &1, &0, &0, &String::from_str(&env, "Initial"));
+        let v1 = Version {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        };
+        let v2 = Version {
+            major: 2,
+            minor: 0,
+            patch: 0,
+        };
+
+        assert!(!client.is_migration_in_progress());
+        assert!(client.get_migration_status(&v1, &v2).is_none());
+
+        env.ledger().with_mut(|li| li.timestamp = 500);
+        client.start_migration(&admin, &v1, &v2);
+
+        assert!(client.is_migration_in_progress());
+        let status = client.get_migration_status(&v1, &v2).unwrap();
+        assert!(status.in_progress);
+        assert!(!status.completed);
+        assert_eq!(status.started_at, 500);
+
+        client.complete_migration(&admin, &v1, &v2, &true);
+
+        assert!(!client.is_migration_in_progress());
+        let status = client.get_migration_status(&v1, &v2).unwrap();
+        assert!(!status.in_progress);
+        assert!(status.completed);
+        assert!(status.success);
+        assert_eq!(status.started_at, 500);
+    }
+
+    #[test]
+    #[should_panic(expected = "Migration already in progress")]
+    fn test_start_migration_while_in_progress() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, ContractVersioning);
+        let client = ContractVersioningClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+
+        client.initialize(&admin, &1, &0, &0, &String::from_str(&env, "Initial"));
+        let v1 = Version {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        };
+        let v2 = Version {
+            major: 2,
+            minor: 0,
+            patch: 0,
+        };
+        let v3 = Version {
+            major: 3,
+            minor: 0,
+            patch: 0,
+        };
+
+        client.start_migration(&admin, &v1, &v2);
+        client.start_migration(&admin, &v2, &v3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Migration not started")]
+    fn test_complete_migration_not_started() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, ContractVersioning);
+        let client = ContractVersioningClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+
+        client.initialize(&admin, &1, &0, &0, &String::from_str(&env, "Initial"));
+        let v1 = Version {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        };
+        let v2 = Version {
+            major: 2,
+            minor: 0,
+            patch: 0,
+        };
+
+        client.complete_migration(&admin, &v1, &v2, &true);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_start_migration_requires_admin() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, ContractVersioning);
+        let client = ContractVersioningClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+
+        client.initialize(&admin, &1, &0, &0, &String::from_str(&env, "Initial"));
+        assert_eq!(client.get_admin(), admin);
+        let v1 = Version {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        };
+        let v2 = Version {
+            major: 2,
+            minor: 0,
+            patch: 0,
+        };
+
+        // Anyone else would block every later migration
+        client.start_migration(&Address::generate(&env), &v1, &v2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unauthorized")]
+    fn test_create_checkpoint_requires_admin() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, ContractVersioning);
+        let client = ContractVersioningClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+
+        client.initialize(&admin, &1, &0, &0, &String::from_str(&env, "Initial"));
+        client.create_checkpoint(&Address::generate(&env), &String::from_str(&env, "Fake audit"));
+    }
+
+    #[test]
+    fn test_abandoned_migration_frees_the_gate() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, ContractVersioning);
+        let client = ContractVersioningClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+
+        env.mock_all_auths();
+
+        client.initialize(&admin, &1, &0, &0, &String::from_str(&env, "Initial"));
+        let v1 = Version {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        };
+        let v2 = Version {
+            major: 2,
+            minor: 0,
+            patch: 0,
+        };
+
+        client.start_migration(&admin, &v1, &v2);
+        client.complete_migration(&admin, &v1, &v2, &false);
+        assert!(!client.is_migration_in_progress());
+        assert!(!client.get_migration_status(&v1, &v2).unwrap().success);
+
+        client.start_migration(&admin, &v1, &v2);
+        assert!(client.is_migration_in_progress());
+    }
 }
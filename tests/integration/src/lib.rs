@@ -3,11 +3,18 @@
 
 #![cfg(test)]
 
-use commitment_core::{CommitmentCoreContract, CommitmentCoreContractClient, CommitmentRules};
+use commitment_core::{
+    CommitmentCoreContract, CommitmentCoreContractClient, CommitmentRules, TransformationCounts,
+};
 use commitment_nft::{CommitmentNFTContract, CommitmentNFTContractClient};
 use attestation_engine::{AttestationEngineContract, AttestationEngineContractClient};
 use price_oracle::{PriceOracleContract, PriceOracleContractClient};
-use soroban_sdk::{testutils::{Address as _, Ledger}, Address, Env, String, Map};
+use allocation_logic::{AllocationStrategiesContract, AllocationStrategiesContractClient, RiskLevel, Strategy};
+use commitment_transformation::{
+    CommitmentTransformationContract, CommitmentTransformationContractClient,
+};
+use contract_registry::{ContractRegistry, ContractRegistryClient, MigrationOutcome};
+use soroban_sdk::{testutils::{Address as _, Ledger}, token, vec, Address, Env, String, Map};
 
 pub struct IntegrationTestFixture {
     pub env: Env,
@@ -40,7 +47,7 @@ impl IntegrationTestFixture {
         // Deploy Core contract
         let core_contract_id = env.register_contract(None, CommitmentCoreContract);
         let core_client = CommitmentCoreContractClient::new(&env, &core_contract_id);
-        core_client.initialize(&admin, &nft_contract_id);
+        core_client.initialize(&admin, &nft_contract_id, &None);
 
         // Deploy Attestation Engine contract
         let attestation_contract_id = env.register_contract(None, AttestationEngineContract);
@@ -68,6 +75,7 @@ impl IntegrationTestFixture {
             early_exit_penalty: 5,
             min_fee_threshold: 100_0000000,
             grace_period_days: 3,
+            max_loss_bps: 0,
         }
     }
 }
@@ -89,6 +97,8 @@ fn test_create_commitment_with_attestation_flow() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
+        &0,
     );
 
     let commitment = fixture.core_client.get_commitment(&commitment_id);
@@ -128,6 +138,7 @@ fn test_commitment_value_update_with_health_tracking() {
         early_exit_penalty: 5,
         min_fee_threshold: 100_0000000,
         grace_period_days: 3,
+        max_loss_bps: 0,
     };
 
     // Create commitment
@@ -136,10 +147,12 @@ fn test_commitment_value_update_with_health_tracking() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
+        &0,
     );
 
     // Update value in core contract
-    fixture.core_client.update_value(&commitment_id, &1050_0000000);
+    fixture.core_client.update_value(&fixture.admin, &commitment_id, &1050_0000000);
 
     // Record health metrics in attestation engine
     fixture.attestation_client.record_fees(&fixture.admin, &commitment_id, &50_0000000);
@@ -168,6 +181,8 @@ fn test_settlement_flow_end_to_end() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
+        &0,
     );
 
     // Record some fees
@@ -199,6 +214,7 @@ fn test_early_exit_flow_end_to_end() {
         early_exit_penalty: 10,
         min_fee_threshold: 100_0000000,
         grace_period_days: 3,
+        max_loss_bps: 0,
     };
 
     // Create commitment
@@ -207,10 +223,12 @@ fn test_early_exit_flow_end_to_end() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
+        &0,
     );
 
     // Update value
-    fixture.core_client.update_value(&commitment_id, &1100_0000000);
+    fixture.core_client.update_value(&fixture.admin, &commitment_id, &1100_0000000);
 
     // Record attestation for early exit
     let mut data = Map::new(&fixture.env);
@@ -248,6 +266,8 @@ fn test_compliance_verification_flow() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
+        &0,
     );
 
     // Record fees and attest - commitment in good standing
@@ -293,6 +313,8 @@ fn test_gas_single_commitment_creation() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
+        &0,
     );
 }
 
@@ -309,12 +331,14 @@ fn test_gas_multiple_operations() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
+        &0,
     );
 
     // Multiple update operations
-    fixture.core_client.update_value(&commitment_id, &1010_0000000);
-    fixture.core_client.update_value(&commitment_id, &1020_0000000);
-    fixture.core_client.update_value(&commitment_id, &1030_0000000);
+    fixture.core_client.update_value(&fixture.admin, &commitment_id, &1010_0000000);
+    fixture.core_client.update_value(&fixture.admin, &commitment_id, &1020_0000000);
+    fixture.core_client.update_value(&fixture.admin, &commitment_id, &1030_0000000);
 
     // Multiple attestation operations
     fixture.attestation_client.record_fees(&fixture.admin, &commitment_id, &10_0000000);
@@ -342,6 +366,8 @@ fn test_gas_batch_attestations() {
         &1000_0000000,
         &fixture.asset_address,
         &rules,
+        &None,
+        &0,
     );
 
     // Multiple attestations
@@ -367,6 +393,409 @@ fn test_gas_batch_attestations() {
     assert_eq!(attestations.len(), 5);
 }
 
+// ============================================
+// Expiry Reconciliation Tests
+// ============================================
+
+#[test]
+fn test_nft_expiry_matches_core_expiry() {
+    let fixture = IntegrationTestFixture::setup();
+    let env = &fixture.env;
+    env.mock_all_auths_allowing_non_root_auth();
+
+    // A different NFT day length must not leak into commitments minted by core
+    fixture.nft_client.set_seconds_per_day(&fixture.admin, &60);
+    env.ledger().with_mut(|li| {
+        li.timestamp = 1_700_000_000;
+    });
+
+    let asset = env.register_stellar_asset_contract_v2(fixture.admin.clone()).address();
+    token::StellarAssetClient::new(env, &asset).mint(&fixture.owner, &1000_0000000);
+
+    let rules = fixture.create_test_rules();
+    let commitment_id = fixture.core_client.create_commitment(
+        &fixture.owner,
+        &1000_0000000,
+        &asset,
+        &rules,
+        &None,
+        &0,
+    );
+
+    let commitment = fixture.core_client.get_commitment(&commitment_id);
+    let nft = fixture.nft_client.get_metadata(&commitment.nft_token_id);
+
+    assert_eq!(nft.metadata.expires_at, commitment.expires_at);
+    assert_eq!(nft.metadata.created_at, commitment.created_at);
+}
+
+#[test]
+fn test_resolve_commitment_by_nft_token_id() {
+    let fixture = IntegrationTestFixture::setup();
+    let env = &fixture.env;
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let asset = env.register_stellar_asset_contract_v2(fixture.admin.clone()).address();
+    token::StellarAssetClient::new(env, &asset).mint(&fixture.owner, &2000_0000000);
+
+    let rules = fixture.create_test_rules();
+    let first_id = fixture.core_client.create_commitment(
+        &fixture.owner,
+        &1000_0000000,
+        &asset,
+        &rules,
+        &None,
+        &0,
+    );
+    let second_id = fixture.core_client.create_commitment(
+        &fixture.owner,
+        &1000_0000000,
+        &asset,
+        &rules,
+        &None,
+        &0,
+    );
+
+    // A marketplace listing only knows the token id
+    for commitment_id in [first_id, second_id] {
+        let token_id = fixture.core_client.get_commitment(&commitment_id).nft_token_id;
+        let resolved = fixture.core_client.get_commitment_by_nft(&token_id).unwrap();
+        assert_eq!(resolved.commitment_id, commitment_id);
+        assert_eq!(fixture.nft_client.owner_of(&token_id), resolved.owner);
+    }
+    assert!(fixture.core_client.get_commitment_by_nft(&999).is_none());
+}
+
+// ============================================
+// Emergency Recovery Tests
+// ============================================
+
+#[test]
+fn test_emergency_reassign_owner_moves_nft() {
+    let fixture = IntegrationTestFixture::setup();
+    let env = &fixture.env;
+    env.mock_all_auths_allowing_non_root_auth();
+    fixture
+        .nft_client
+        .set_core_contract(&fixture.core_client.address);
+
+    let asset = env.register_stellar_asset_contract_v2(fixture.admin.clone()).address();
+    token::StellarAssetClient::new(env, &asset).mint(&fixture.owner, &1000_0000000);
+
+    let rules = fixture.create_test_rules();
+    let commitment_id = fixture.core_client.create_commitment(
+        &fixture.owner,
+        &1000_0000000,
+        &asset,
+        &rules,
+        &None,
+        &0,
+    );
+    let token_id = fixture.core_client.get_commitment(&commitment_id).nft_token_id;
+
+    let new_owner = Address::generate(env);
+    fixture.core_client.set_emergency_mode(&fixture.admin, &true);
+    fixture
+        .core_client
+        .emergency_reassign_owner(&fixture.admin, &commitment_id, &new_owner, &true);
+
+    assert_eq!(
+        fixture.core_client.get_commitment(&commitment_id).owner,
+        new_owner
+    );
+    assert_eq!(fixture.nft_client.owner_of(&token_id), new_owner);
+    assert_eq!(fixture.nft_client.balance_of(&fixture.owner), 0);
+}
+
+// ============================================
+// Allocation Linkage Tests
+// ============================================
+
+#[test]
+fn test_core_commitment_linked_to_allocation() {
+    let fixture = IntegrationTestFixture::setup();
+    let env = &fixture.env;
+    // Token transfer inside create_commitment authorizes the owner below the root call
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let asset = env.register_stellar_asset_contract_v2(fixture.admin.clone()).address();
+    token::StellarAssetClient::new(env, &asset).mint(&fixture.owner, &1000_0000000);
+
+    let rules = fixture.create_test_rules();
+    let core_commitment_id: String = fixture.core_client.create_commitment(
+        &fixture.owner,
+        &1000_0000000,
+        &asset,
+        &rules,
+        &None,
+        &0,
+    );
+    let commitment = fixture.core_client.get_commitment(&core_commitment_id);
+
+    // Allocation contract points at the same core deployment
+    let allocation_id = env.register_contract(None, AllocationStrategiesContract);
+    let allocation_client = AllocationStrategiesContractClient::new(env, &allocation_id);
+    allocation_client.initialize(&fixture.admin, &fixture.core_client.address);
+    allocation_client.register_pool(&fixture.admin, &0, &RiskLevel::Low, &500, &10_000_0000000);
+
+    let summary = allocation_client.allocate_for_commitment(
+        &fixture.owner,
+        &core_commitment_id,
+        &commitment.amount,
+        &Strategy::Safe,
+    );
+
+    let linked = allocation_client.get_allocation_by_core_id(&core_commitment_id);
+    assert_eq!(linked.commitment_id, summary.commitment_id);
+    assert_eq!(linked.total_allocated, commitment.amount);
+    assert_eq!(
+        allocation_client.get_linked_commitment_id(&core_commitment_id),
+        Some(summary.commitment_id)
+    );
+}
+
+#[test]
+fn test_settlement_releases_linked_allocation() {
+    let fixture = IntegrationTestFixture::setup();
+    let env = &fixture.env;
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let asset = env.register_stellar_asset_contract_v2(fixture.admin.clone()).address();
+    token::StellarAssetClient::new(env, &asset).mint(&fixture.owner, &1000_0000000);
+
+    let rules = fixture.create_test_rules();
+    let core_commitment_id: String = fixture.core_client.create_commitment(
+        &fixture.owner,
+        &1000_0000000,
+        &asset,
+        &rules,
+        &None,
+        &0,
+    );
+    let commitment = fixture.core_client.get_commitment(&core_commitment_id);
+
+    let allocation_id = env.register_contract(None, AllocationStrategiesContract);
+    let allocation_client = AllocationStrategiesContractClient::new(env, &allocation_id);
+    allocation_client.initialize(&fixture.admin, &fixture.core_client.address);
+    allocation_client.register_pool(&fixture.admin, &0, &RiskLevel::Low, &500, &10_000_0000000);
+    allocation_client.allocate_for_commitment(
+        &fixture.owner,
+        &core_commitment_id,
+        &commitment.amount,
+        &Strategy::Safe,
+    );
+    assert_eq!(allocation_client.get_pool(&0).total_liquidity, commitment.amount);
+
+    fixture
+        .core_client
+        .set_allocation_contract(&fixture.admin, &allocation_id);
+
+    env.ledger().with_mut(|li| {
+        li.timestamp = commitment.expires_at + 1;
+    });
+    fixture.core_client.settle(&core_commitment_id);
+
+    // Pool liquidity is released once the commitment settles
+    assert_eq!(allocation_client.get_pool(&0).total_liquidity, 0);
+    assert_eq!(
+        allocation_client
+            .get_allocation_by_core_id(&core_commitment_id)
+            .total_allocated,
+        0
+    );
+    assert_eq!(allocation_client.get_strategy_exposure(&Strategy::Safe), 0);
+}
+
+// ============================================
+// Commitment Detail Aggregation Tests
+// ============================================
+
+#[test]
+fn test_get_commitment_full_with_all_contracts_wired() {
+    let fixture = IntegrationTestFixture::setup();
+    let env = &fixture.env;
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let asset = env.register_stellar_asset_contract_v2(fixture.admin.clone()).address();
+    token::StellarAssetClient::new(env, &asset).mint(&fixture.owner, &1000_0000000);
+
+    let rules = fixture.create_test_rules();
+    let commitment_id = fixture.core_client.create_commitment(
+        &fixture.owner,
+        &1000_0000000,
+        &asset,
+        &rules,
+        &None,
+        &0,
+    );
+    fixture
+        .attestation_client
+        .record_fees(&fixture.admin, &commitment_id, &50_0000000);
+
+    let transformation_id = env.register_contract(None, CommitmentTransformationContract);
+    let transformation_client = CommitmentTransformationContractClient::new(env, &transformation_id);
+    transformation_client.initialize(&fixture.admin, &fixture.core_client.address);
+    transformation_client.create_tranches(
+        &fixture.admin,
+        &commitment_id,
+        &1000_0000000,
+        &vec![env, 7000u32, 3000u32],
+        &vec![
+            env,
+            String::from_str(env, "senior"),
+            String::from_str(env, "equity"),
+        ],
+        &asset,
+    );
+
+    let (commitment, health, transformations) = fixture.core_client.get_commitment_full(
+        &commitment_id,
+        &fixture.attestation_client.address,
+        &transformation_id,
+    );
+
+    assert_eq!(commitment, fixture.core_client.get_commitment(&commitment_id));
+    assert_eq!(health.unwrap().fees_generated, 50_0000000);
+    assert_eq!(
+        transformations,
+        Some(TransformationCounts {
+            tranche_sets: 1,
+            collateral: 0,
+            instruments: 0,
+            guarantees: 0,
+        })
+    );
+}
+
+#[test]
+fn test_slash_for_underperformance_reads_attested_fees() {
+    let fixture = IntegrationTestFixture::setup();
+    let env = &fixture.env;
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let asset = env.register_stellar_asset_contract_v2(fixture.admin.clone()).address();
+    token::StellarAssetClient::new(env, &asset).mint(&fixture.owner, &1000_0000000);
+    let insurance_fund = Address::generate(env);
+    fixture
+        .core_client
+        .set_insurance_fund(&fixture.admin, &insurance_fund, &0);
+    fixture
+        .core_client
+        .set_underperformance_slash_bps(&fixture.admin, &1000); // 10%
+    fixture
+        .core_client
+        .set_attestation_engine(&fixture.admin, &fixture.attestation_client.address);
+
+    // Threshold is 100 tokens; only 50 are attested
+    let rules = fixture.create_test_rules();
+    let commitment_id = fixture.core_client.create_commitment(
+        &fixture.owner,
+        &1000_0000000,
+        &asset,
+        &rules,
+        &None,
+        &0,
+    );
+    fixture
+        .attestation_client
+        .record_fees(&fixture.admin, &commitment_id, &50_0000000);
+
+    let commitment = fixture.core_client.get_commitment(&commitment_id);
+    env.ledger().with_mut(|li| {
+        li.timestamp = commitment.expires_at + 1;
+    });
+
+    let slashed = fixture
+        .core_client
+        .slash_for_underperformance(&fixture.admin, &commitment_id);
+    assert_eq!(slashed, 100_0000000);
+
+    let token = token::Client::new(env, &asset);
+    assert_eq!(token.balance(&insurance_fund), 100_0000000);
+    assert_eq!(token.balance(&fixture.owner), 900_0000000);
+    assert_eq!(
+        fixture.core_client.get_commitment(&commitment_id).status,
+        String::from_str(env, "settled")
+    );
+}
+
+// ============================================
+// Migration Coordination Tests
+// ============================================
+
+#[soroban_sdk::contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum MockMigrationError {
+    InvalidVersion = 1,
+}
+
+/// Stand-in for a protocol contract exposing `migrate(caller, from_version)`
+#[soroban_sdk::contract]
+pub struct MockMigratable;
+
+#[soroban_sdk::contractimpl]
+impl MockMigratable {
+    pub fn migrate(e: Env, caller: Address, from_version: u32) -> Result<(), MockMigrationError> {
+        caller.require_auth();
+        if Self::get_version(e.clone()) != from_version {
+            return Err(MockMigrationError::InvalidVersion);
+        }
+        e.storage()
+            .instance()
+            .set(&soroban_sdk::symbol_short!("version"), &(from_version + 1));
+        Ok(())
+    }
+
+    pub fn get_version(e: Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&soroban_sdk::symbol_short!("version"))
+            .unwrap_or(0)
+    }
+}
+
+#[test]
+fn test_migrate_all_reports_per_contract_outcome() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let registry_id = env.register_contract(None, ContractRegistry);
+    let registry = ContractRegistryClient::new(&env, &registry_id);
+    registry.initialize(&admin);
+
+    let core_id = env.register_contract(None, MockMigratable);
+    let nft_id = env.register_contract(None, MockMigratable);
+    let core = MockMigratableClient::new(&env, &core_id);
+    let nft = MockMigratableClient::new(&env, &nft_id);
+
+    // Core was already migrated on its own; only the NFT is still at v0
+    core.migrate(&admin, &0);
+
+    let outcomes = registry.migrate_all(&admin, &vec![&env, core_id.clone(), nft_id.clone()], &0);
+    assert_eq!(
+        outcomes,
+        vec![
+            &env,
+            MigrationOutcome { contract: core_id.clone(), success: false },
+            MigrationOutcome { contract: nft_id.clone(), success: true },
+        ]
+    );
+    assert_eq!(core.get_version(), 1);
+    assert_eq!(nft.get_version(), 1);
+
+    let report = registry.get_last_migration().unwrap();
+    assert_eq!(report.from_version, 0);
+    assert_eq!(report.outcomes, outcomes);
+
+    // Both contracts now move forward together
+    let outcomes = registry.migrate_all(&admin, &vec![&env, core_id, nft_id], &1);
+    assert!(outcomes.iter().all(|outcome| outcome.success));
+    assert_eq!(core.get_version(), 2);
+    assert_eq!(nft.get_version(), 2);
+}
+
 // ============================================
 // Oracle Integration Tests
 // ============================================